@@ -0,0 +1,91 @@
+use crate::journal::content_hash;
+use crate::manager::get_sibling_file_path;
+use chrono::{DateTime, Local};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// e.g. `default.csv.bak.1`, the latest backup of `default.csv`
+pub fn backup_file_path(data_file: &Path, number: usize) -> PathBuf {
+    get_sibling_file_path(data_file, &format!("bak.{number}"))
+}
+
+// copies the data file to backup 1 before it's rewritten, shifting the older backups up to
+// `count` and removing the rest; nothing is done for an empty file, or if backup 1 has the
+// same content already, e.g. when the file is rewritten without changes in between
+pub fn rotate(data_file: &Path, count: usize) -> io::Result<()> {
+    let content = fs::read(data_file).unwrap_or_default();
+    if content.is_empty() {
+        return Ok(());
+    }
+    let latest = fs::read(backup_file_path(data_file, 1)).ok();
+    if count > 0 && latest.map(|latest| content_hash(&latest)) == Some(content_hash(&content)) {
+        return Ok(());
+    }
+    // a lower count prunes the backups kept before
+    let mut number = count.max(1);
+    while backup_file_path(data_file, number).exists() {
+        number += 1;
+    }
+    for stale in count.max(1)..number {
+        fs::remove_file(backup_file_path(data_file, stale))?;
+    }
+    if count == 0 {
+        return Ok(());
+    }
+    for number in (1..count).rev() {
+        let from = backup_file_path(data_file, number);
+        if from.exists() {
+            fs::rename(from, backup_file_path(data_file, number + 1))?;
+        }
+    }
+    fs::write(backup_file_path(data_file, 1), content)
+}
+
+// the backups there are, the latest first, with the time each was taken
+pub fn list_backups(data_file: &Path) -> Vec<(usize, PathBuf, DateTime<Local>)> {
+    (1..)
+        .map(|number| (number, backup_file_path(data_file, number)))
+        .map_while(|(number, path)| {
+            let modified = fs::metadata(&path).ok()?.modified().ok()?;
+            Some((number, path, DateTime::<Local>::from(modified)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    fn backups(data_file: &Path) -> Vec<String> {
+        list_backups(data_file)
+            .iter()
+            .map(|(_, path, _)| fs::read_to_string(path).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate() {
+        let dir = temp_dir("backup");
+        let data_file = dir.join("default.csv");
+        rotate(&data_file, 3).unwrap();
+        fs::write(&data_file, "").unwrap();
+        rotate(&data_file, 3).unwrap();
+        assert!(backups(&data_file).is_empty());
+
+        for content in ["a", "b", "b", "c", "d"] {
+            fs::write(&data_file, content).unwrap();
+            rotate(&data_file, 3).unwrap();
+        }
+        // the same content twice in a row is kept once
+        assert_eq!(backups(&data_file), vec!["d", "c", "b"]);
+
+        fs::write(&data_file, "e").unwrap();
+        rotate(&data_file, 2).unwrap();
+        assert_eq!(backups(&data_file), vec!["e", "d"]);
+        rotate(&data_file, 0).unwrap();
+        assert!(backups(&data_file).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Local, NaiveDate};
+#[cfg(test)]
+use std::cell::Cell;
+
+// the time everything is measured against, e.g. how long a task is overdue; tests freeze it
+// on their own thread, so that they pass whenever they run
+pub fn now() -> DateTime<Local> {
+    #[cfg(test)]
+    if let Some(now) = FROZEN.with(Cell::get) {
+        return now;
+    }
+    Local::now()
+}
+
+pub fn today() -> NaiveDate {
+    now().date_naive()
+}
+
+#[cfg(test)]
+thread_local! {
+    static FROZEN: Cell<Option<DateTime<Local>>> = const { Cell::new(None) };
+}
+
+// keeps the clock of the thread frozen until it's dropped
+#[cfg(test)]
+pub struct Frozen;
+
+#[cfg(test)]
+impl Drop for Frozen {
+    fn drop(&mut self) {
+        FROZEN.with(|frozen| frozen.set(None));
+    }
+}
+
+// freezes the clock of the thread at the local time, given like `2024-03-04 11:20`
+#[cfg(test)]
+pub fn freeze(time: &str) -> Frozen {
+    use chrono::{NaiveDateTime, TimeZone};
+    let naive = NaiveDateTime::parse_from_str(time, "%F %R").unwrap();
+    let now = Local.from_local_datetime(&naive).unwrap();
+    FROZEN.with(|frozen| frozen.set(Some(now)));
+    Frozen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze() {
+        {
+            let _frozen = freeze("2024-03-04 23:59");
+            assert_eq!(today(), NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+            assert_eq!(now().format("%R").to_string(), "23:59");
+        }
+        assert!((Local::now() - now()).num_seconds().abs() < 5);
+    }
+}
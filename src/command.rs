@@ -1,20 +1,90 @@
-use chrono::{Datelike, Days, Local, NaiveDate};
+use crate::clock;
+use crate::config::{parse_tick_minutes, parse_timeline_hours, StorageKind};
+use crate::pomodoro;
+use crate::stats::DEFAULT_HEATMAP_WEEKS;
+use crate::task::{TaskStatus, PRIORITY_NAMES};
+use chrono::{Datelike, Days, Duration, NaiveDate, NaiveTime, Weekday};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
+// the tasks listed at a time when only a page is asked for without a count
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct ListOption {
     pub date_filter: (DateFilterOp, NaiveDate),
+    // set by a date range, which starts at the date of `date_filter`
+    pub end_date: Option<NaiveDate>,
     pub include_backlog: bool,
     pub is_verbose: bool,
     pub has_timeline: bool,
+    pub tag: Option<String>,
+    pub svg_export: Option<ExportTarget>,
+    // minutes per column of the timeline, overriding the config
+    pub tick_minutes: Option<u32>,
+    // draws the planned and actual periods of every task on a pair of rows
+    pub paired_rows: bool,
+    pub only_prioritized: bool,
+    pub status: Option<TaskStatus>,
+    // only backlog tasks older than the threshold, the oldest first
+    pub only_stale: bool,
+    // lists tasks moved to the archive file as well, which cannot be modified
+    pub include_archived: bool,
+    // deleted tasks are hidden unless asked for
+    pub include_deleted: bool,
+    // prints a json array for scripts instead of rendering the tasks
+    pub is_json: bool,
+    // only tasks whose description contains it case-insensitively, given as `/<text>`
+    pub text: Option<String>,
+    // lists at most this many tasks after filtering and ordering, starting from the page
+    pub limit: Option<usize>,
+    // counted from 1, in units of the limit
+    pub page: usize,
 }
 
-impl ListOption {
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportTarget {
+    pub path: PathBuf,
+    pub overwrite: bool,
+    // hides descriptions of private tasks
+    pub redact: bool,
+}
+
+impl Default for ListOption {
     fn default() -> Self {
         ListOption {
-            date_filter: (DateFilterOp::Equal, Local::now().date_naive()),
+            date_filter: (DateFilterOp::Equal, clock::today()),
+            end_date: None,
             include_backlog: false,
             is_verbose: false,
             has_timeline: false,
+            tag: None,
+            svg_export: None,
+            tick_minutes: None,
+            paired_rows: false,
+            only_prioritized: false,
+            status: None,
+            only_stale: false,
+            include_archived: false,
+            include_deleted: false,
+            is_json: false,
+            text: None,
+            limit: None,
+            page: 1,
+        }
+    }
+}
+
+impl ListOption {
+    // whether the listed dates include today or a later day
+    pub fn reaches_today(&self) -> bool {
+        let today = clock::today();
+        match (self.end_date, self.date_filter) {
+            (Some(end_date), _) => end_date >= today,
+            (None, (DateFilterOp::Earlier, date)) => date > today,
+            (None, (DateFilterOp::EarlierEqual | DateFilterOp::Equal, date)) => date >= today,
+            (None, (DateFilterOp::Later | DateFilterOp::LaterEqual, _)) => true,
         }
     }
 }
@@ -28,89 +98,770 @@ pub enum DateFilterOp {
     LaterEqual,
 }
 
+// wraps a parsed `<op><date>` so it can be typed in a prompt on its own
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DateFilter(pub DateFilterOp, pub NaiveDate);
+
+impl FromStr for DateFilter {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(());
+        }
+        try_parse_date_filter(s)
+            .map(|(op, date)| DateFilter(op, date))
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for DateFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.0.symbol(), self.1.format("%F"))
+    }
+}
+
+impl DateFilter {
+    // e.g. `>= 2024-03-08 (Fri)`
+    pub fn describe(&self) -> String {
+        match self.0 {
+            DateFilterOp::Equal => self.1.format("%F (%a)").to_string(),
+            op => format!("{} {}", op.symbol(), self.1.format("%F (%a)")),
+        }
+    }
+}
+
+impl DateFilterOp {
+    fn symbol(&self) -> &'static str {
+        match self {
+            DateFilterOp::Earlier => "<",
+            DateFilterOp::EarlierEqual => "<=",
+            DateFilterOp::Equal => "",
+            DateFilterOp::Later => ">",
+            DateFilterOp::LaterEqual => ">=",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Arrangement {
+    Immediate,
+    Backlog,
+    // planned today, starting at the time and lasting the minutes
+    Planned(NaiveTime, u32),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    // icalendar, export only
+    Ics,
+    // todo.txt, import only
+    TodoTxt,
+    // the plan of the date, with backlog tasks if the flag is set, export only
+    Markdown(NaiveDate, bool),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReportPeriod {
+    Day,
+    Week,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StatsKind {
+    // how actual durations compare with planned ones
+    Estimates,
+    // completions per day of the weeks
+    Heatmap(u32),
+    // delays of actual starts behind planned ones, over tasks planned on the dates if given
+    Punctuality(Option<DateFilter>),
+    // worked time per tag over tasks started on the dates if given, as csv if asked for
+    Tags(Option<DateFilter>, bool),
+}
+
+// commands on a single task, whose index is picked from a list if omitted
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TaskAction {
+    Start,
+    Complete,
+    Delete,
+    Edit,
+}
+
+impl TaskAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskAction::Start => "start",
+            TaskAction::Complete => "complete",
+            TaskAction::Delete => "delete",
+            TaskAction::Edit => "edit",
+        }
+    }
+}
+
+// a change to a single field of a task, made without prompts
+#[derive(Debug, PartialEq)]
+pub enum TaskEdit {
+    Description(String),
+    // cleared if empty
+    Tags(Vec<String>),
+    Priority(u8),
+    // planned today, starting at the time and lasting the minutes
+    Plan(NaiveTime, u32),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateAction {
+    // saves the task of the index under the name
+    Save(String, usize),
+    List,
+    Delete(String),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Command {
     Empty,
     Quit,
-    Help,
+    // the overview of all commands, or the details of the command
+    Help(Option<String>),
     New,
-    Sort,
+    NewInline(String, Arrangement),
+    // whether to rewrite the data file in the order, instead of only listing in it
+    Sort(bool),
+    Chronic,
     Start(usize),
     Complete(usize),
+    Pause(usize),
+    Resume(usize),
     Delete(usize),
+    Restore(usize),
+    // removes all deleted tasks for good
+    Purge,
+    Note(usize),
+    Show(usize),
     Edit(usize),
+    EditInline(usize, TaskEdit),
+    Pick(TaskAction),
+    TogglePrivate(usize),
     List(ListOption),
+    Search(String),
+    Capture(String),
+    SetHours(u32, u32),
+    // the flag means dry run
+    Maintain(bool),
+    Report(ReportPeriod, NaiveDate),
+    Stats(StatsKind),
+    Export(ExportFormat, ExportTarget),
+    // the flag means replacing all tasks instead of appending
+    Import(ExportFormat, PathBuf, bool),
+    Timings,
+    Config,
+    // the version, where the data lives and how much of it there is
+    Info,
+    // discards the tasks in memory and loads the data file again
+    Reload,
+    // switches to the profile, or lists the profiles if none is given
+    Use(Option<String>),
+    // commits the data file to its git work tree, and pulls and pushes unless the flag is set
+    Sync(bool),
+    // puts the numbered backup in place of the tasks, or lists the backups if none is given
+    RestoreBackup(Option<usize>),
+    // copies the tasks into a store of the kind, which they are kept in from then on
+    Migrate(StorageKind),
+    Postpone(usize, Duration),
+    // moves complete tasks whose actual complete time matches the filter to the archive file
+    Archive(DateFilterOp, NaiveDate),
+    // moves unfinished tasks planned for today or earlier to the date
+    Defer(NaiveDate),
+    Template(TemplateAction),
+    // plans a task from the template of the name
+    NewFromTemplate(String),
+    // changes made to the task, or the latest ones to any task
+    History(Option<usize>),
+    // works on the task for the minutes
+    Pomodoro(usize, u32),
+    // lists the next page of the latest limited listing
+    More,
+    // takes the task out of the first position and puts it at the second one
+    Move(usize, usize),
+    Swap(usize, usize),
+    // merges the second task into the first one, deleting the second
+    Merge(usize, usize),
+    // splits the task into two, completing the first part if it's ongoing
+    Split(usize),
+    // plans backlog tasks into the free time of the date
+    PlanAuto(NaiveDate),
+    // suggests the task to work on next, even if one is ongoing with the flag
+    Next(bool),
+    Flush,
+    // ends the session for the full-screen view, which starts it again on quitting
+    Tui,
+}
+
+impl Command {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Empty => "",
+            Command::Quit => "quit",
+            Command::Help(..) => "help",
+            Command::New | Command::NewInline(..) | Command::NewFromTemplate(..) => "new",
+            Command::Sort(..) => "sort",
+            Command::Chronic => "chronic",
+            Command::Start(..) => "start",
+            Command::Complete(..) => "complete",
+            Command::Pause(..) => "pause",
+            Command::Resume(..) => "resume",
+            Command::Delete(..) => "delete",
+            Command::Restore(..) => "restore",
+            Command::Purge => "purge",
+            Command::Note(..) => "note",
+            Command::Show(..) => "show",
+            Command::Edit(..) | Command::EditInline(..) => "edit",
+            Command::Pick(action) => action.name(),
+            Command::TogglePrivate(..) => "private",
+            Command::List(option) if option.has_timeline => "ll",
+            Command::List(..) => "ls",
+            Command::Search(..) => "grep",
+            Command::Capture(..) => "b",
+            Command::SetHours(..) => "set",
+            Command::Maintain(..) => "maintain",
+            Command::Report(..) => "report",
+            Command::Stats(..) => "stats",
+            Command::Export(..) => "export",
+            Command::Import(..) => "import",
+            Command::Timings => "timings",
+            Command::Config => "config",
+            Command::Info => "info",
+            Command::Reload => "reload",
+            Command::Use(..) => "use",
+            Command::RestoreBackup(..) => "restore-backup",
+            Command::Sync(..) => "sync",
+            Command::Migrate(..) => "migrate",
+            Command::Postpone(..) => "postpone",
+            Command::Archive(..) => "archive",
+            Command::Defer(..) => "defer",
+            Command::Template(..) => "template",
+            Command::History(..) => "log",
+            Command::Pomodoro(..) => "pomo",
+            Command::Next(..) => "next",
+            Command::PlanAuto(..) => "plan",
+            Command::Split(..) => "split",
+            Command::Merge(..) => "merge",
+            Command::More => "more",
+            Command::Move(..) => "mv",
+            Command::Swap(..) => "swap",
+            Command::Flush => "flush",
+            Command::Tui => "tui",
+        }
+    }
+
+    // read-only commands never write the data file, so they could run without the lock
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            Command::Empty
+                | Command::Help(..)
+                | Command::Chronic
+                | Command::Config
+                | Command::Info
+                | Command::Reload
+                | Command::Use(None)
+                | Command::RestoreBackup(None)
+                | Command::Show(..)
+                | Command::List(..)
+                | Command::Search(..)
+                | Command::Maintain(true)
+                | Command::Report(..)
+                | Command::Stats(..)
+                | Command::Export(..)
+                | Command::Template(TemplateAction::List)
+                | Command::History(..)
+                | Command::More
+        )
+    }
+
+    // commands which prompt for input or confirmation, so they cannot run in batch mode
+    pub fn is_interactive(&self) -> bool {
+        matches!(
+            self,
+            Command::New
+                | Command::Note(..)
+                | Command::Edit(..)
+                | Command::Pick(..)
+                | Command::Purge
+                | Command::Defer(..)
+                | Command::Import(_, _, true)
+                | Command::NewFromTemplate(..)
+                | Command::Pomodoro(..)
+                | Command::Next(..)
+                | Command::PlanAuto(..)
+                | Command::Split(..)
+                | Command::Merge(..)
+                | Command::RestoreBackup(Some(..))
+                | Command::Migrate(..)
+                | Command::Tui
+        )
+    }
 }
 
+// the first word of every command, which could be abbreviated as long as it's unambiguous
+pub const COMMAND_NAMES: [&str; 48] = [
+    "archive",
+    "chronic",
+    "complete",
+    "config",
+    "defer",
+    "delete",
+    "edit",
+    "export",
+    "flush",
+    "grep",
+    "help",
+    "import",
+    "info",
+    "ll",
+    "log",
+    "ls",
+    "maintain",
+    "merge",
+    "migrate",
+    "more",
+    "mv",
+    "new",
+    "next",
+    "note",
+    "pause",
+    "plan",
+    "pomo",
+    "postpone",
+    "private",
+    "purge",
+    "quit",
+    "reload",
+    "report",
+    "restore",
+    "restore-backup",
+    "resume",
+    "set",
+    "show",
+    "sort",
+    "split",
+    "start",
+    "stats",
+    "swap",
+    "sync",
+    "template",
+    "timings",
+    "tui",
+    "use",
+];
+
+// short names, which are never taken as abbreviations of longer ones
+const COMMAND_ALIASES: [&str; 8] = ["b", "c", "e", "h", "n", "n!", "q", "s"];
+
 pub fn parse_command(cmd: &str) -> Option<Command> {
-    let cmd = cmd.trim();
+    let cmd = normalize_command_name(cmd.trim());
+    let cmd = cmd.as_str();
     if cmd.is_empty() {
         Some(Command::Empty)
     } else if cmd == "q" || cmd == "quit" {
         Some(Command::Quit)
     } else if cmd == "h" || cmd == "help" {
-        Some(Command::Help)
+        Some(Command::Help(None))
     } else if cmd == "n" || cmd == "new" {
         Some(Command::New)
     } else if cmd == "sort" {
-        Some(Command::Sort)
+        Some(Command::Sort(false))
+    } else if cmd == "sort --persist" {
+        Some(Command::Sort(true))
+    } else if cmd == "chronic" {
+        Some(Command::Chronic)
+    } else if cmd == "more" {
+        Some(Command::More)
+    } else if cmd == "timings" {
+        Some(Command::Timings)
+    } else if cmd == "info" {
+        Some(Command::Info)
+    } else if cmd == "reload" {
+        Some(Command::Reload)
+    } else if cmd == "use" {
+        Some(Command::Use(None))
+    } else if cmd == "tui" {
+        Some(Command::Tui)
+    } else if cmd == "sync" {
+        Some(Command::Sync(false))
+    } else if cmd == "sync --local" {
+        Some(Command::Sync(true))
+    } else if cmd == "restore-backup" {
+        Some(Command::RestoreBackup(None))
+    } else if cmd == "config" {
+        Some(Command::Config)
+    } else if cmd == "log" {
+        Some(Command::History(None))
+    } else if cmd == "plan auto" {
+        Some(Command::PlanAuto(clock::today()))
+    } else if cmd == "next" {
+        Some(Command::Next(false))
+    } else if cmd == "next -f" {
+        Some(Command::Next(true))
+    } else if cmd == "flush" {
+        Some(Command::Flush)
+    } else if cmd == "purge" {
+        Some(Command::Purge)
+    } else if cmd == "maintain" {
+        Some(Command::Maintain(false))
+    } else if cmd == "maintain --dry-run" {
+        Some(Command::Maintain(true))
+    } else if cmd == "archive" {
+        let month_ago = clock::today() - Duration::days(30);
+        Some(Command::Archive(DateFilterOp::Earlier, month_ago))
+    } else if cmd == "defer" {
+        Some(Command::Defer(clock::today() + Duration::days(1)))
+    } else if let Some(action) = try_parse_task_action(cmd) {
+        Some(Command::Pick(action))
     } else {
         let args: Vec<&str> = cmd.split_whitespace().collect();
-        if args[0] == "ls" || args[0] == "ll" {
+        if let Some(pattern) = try_parse_search_pattern(cmd) {
+            Some(Command::Search(pattern))
+        } else if let Some(name) = try_parse_new_from_template(cmd) {
+            Some(Command::NewFromTemplate(name))
+        } else if let Some((description, arrangement)) = try_parse_new_inline(cmd) {
+            Some(Command::NewInline(description, arrangement))
+        } else if let Some(description) = try_parse_capture(cmd) {
+            Some(Command::Capture(description))
+        } else if args[0] == "ls" || args[0] == "ll" {
             try_parse_list_option(&args).map(Command::List)
+        } else if args[0] == "archive" && args.len() == 2 {
+            try_parse_date_filter(args[1]).map(|(op, date)| Command::Archive(op, date))
+        } else if args[0] == "plan" && args.len() == 3 && args[1] == "auto" {
+            try_parse_date(args[2]).map(Command::PlanAuto)
+        } else if args[0] == "defer" && args.len() == 2 {
+            try_parse_date(args[1]).map(Command::Defer)
+        } else if args[0] == "template" {
+            try_parse_template(&args[1..])
+        } else if args[0] == "report" {
+            try_parse_report(&args[1..])
+        } else if args[0] == "stats" {
+            try_parse_stats(&args[1..])
+        } else if args[0] == "export" {
+            try_parse_export(&args[1..])
+        } else if args[0] == "import" {
+            try_parse_import(&args[1..])
         } else if args.len() < 2 {
             None
+        } else if (args[0] == "h" || args[0] == "help") && args.len() == 2 {
+            Some(Command::Help(Some(args[1].to_string())))
+        } else if args[0] == "restore-backup" && args.len() == 2 {
+            args[1]
+                .parse::<usize>()
+                .ok()
+                .map(|number| Command::RestoreBackup(Some(number)))
+        } else if args[0] == "migrate" && args.len() == 2 {
+            StorageKind::parse(args[1]).map(Command::Migrate)
+        } else if args[0] == "use" && args.len() == 2 {
+            Some(Command::Use(Some(args[1].to_string())))
+        } else if args[0] == "set" {
+            try_parse_set(&args[1..])
         } else if args[0] == "s" || args[0] == "start" {
             args[1].parse::<usize>().ok().map(Command::Start)
         } else if args[0] == "c" || args[0] == "complete" {
             args[1].parse::<usize>().ok().map(Command::Complete)
+        } else if args[0] == "pause" {
+            args[1].parse::<usize>().ok().map(Command::Pause)
+        } else if args[0] == "resume" {
+            args[1].parse::<usize>().ok().map(Command::Resume)
         } else if args[0] == "delete" {
             args[1].parse::<usize>().ok().map(Command::Delete)
+        } else if args[0] == "restore" {
+            args[1].parse::<usize>().ok().map(Command::Restore)
+        } else if args[0] == "note" {
+            args[1].parse::<usize>().ok().map(Command::Note)
+        } else if (args[0] == "mv" || args[0] == "swap") && args.len() == 3 {
+            let first = args[1].parse::<usize>().ok()?;
+            let second = args[2].parse::<usize>().ok()?;
+            Some(match args[0] {
+                "mv" => Command::Move(first, second),
+                _ => Command::Swap(first, second),
+            })
+        } else if args[0] == "merge" && args.len() == 3 {
+            let first = args[1].parse::<usize>().ok()?;
+            args[2]
+                .parse::<usize>()
+                .ok()
+                .map(|second| Command::Merge(first, second))
+        } else if args[0] == "split" && args.len() == 2 {
+            args[1].parse::<usize>().ok().map(Command::Split)
+        } else if args[0] == "pomo" {
+            try_parse_pomodoro(&args[1..])
+        } else if args[0] == "log" {
+            args[1]
+                .parse::<usize>()
+                .ok()
+                .map(|index| Command::History(Some(index)))
+        } else if args[0] == "show" {
+            args[1].parse::<usize>().ok().map(Command::Show)
+        } else if (args[0] == "e" || args[0] == "edit") && args.len() > 2 {
+            try_parse_edit_inline(&args[1..])
         } else if args[0] == "e" || args[0] == "edit" {
             args[1].parse::<usize>().ok().map(Command::Edit)
+        } else if args[0] == "postpone" && args.len() == 3 {
+            let index = args[1].parse::<usize>().ok()?;
+            parse_duration(args[2]).map(|duration| Command::Postpone(index, duration))
+        } else if args[0] == "private" {
+            args[1].parse::<usize>().ok().map(Command::TogglePrivate)
         } else {
             None
         }
     }
 }
 
+pub fn try_parse_task_action(cmd: &str) -> Option<TaskAction> {
+    match cmd {
+        "s" | "start" => Some(TaskAction::Start),
+        "c" | "complete" => Some(TaskAction::Complete),
+        "delete" => Some(TaskAction::Delete),
+        "e" | "edit" => Some(TaskAction::Edit),
+        _ => None,
+    }
+}
+
 fn try_parse_list_option(args: &[&str]) -> Option<ListOption> {
-    let mut option = ListOption::default();
-    option.has_timeline = if args[0] == "ls" {
-        false
-    } else if args[0] == "ll" {
-        true
-    } else {
-        return None;
+    let has_timeline = match args[0] {
+        "ls" => false,
+        "ll" => true,
+        _ => return None,
     };
-    if let Some(&arg) = args.get(1) {
-        if let Some(filter) = try_parse_date_filter(arg) {
+    let mut option = ListOption {
+        has_timeline,
+        ..ListOption::default()
+    };
+    let mut args = args[1..].iter();
+    while let Some(&arg) = args.next() {
+        if arg == "svg" || arg == "svg!" {
+            let path = args.next()?;
+            option.svg_export = Some(ExportTarget {
+                path: PathBuf::from(path),
+                overwrite: arg == "svg!",
+                redact: false,
+            });
+        } else if arg == "redact" {
+            option.svg_export.as_mut()?.redact = true;
+        } else if arg == "-r" {
+            match parse_tick_minutes(args.next()?) {
+                Ok(minutes) => option.tick_minutes = Some(minutes),
+                Err(reason) => {
+                    println!("{reason}");
+                    return None;
+                }
+            }
+        } else if let Some(text) = arg.strip_prefix('/').filter(|text| !text.is_empty()) {
+            option.text = Some(text.to_string());
+        } else if arg == "-n" {
+            option.limit = Some(args.next()?.parse::<usize>().ok().filter(|n| *n > 0)?);
+        } else if arg == "--page" {
+            option.page = args.next()?.parse::<usize>().ok().filter(|k| *k > 0)?;
+        } else if arg == "-a" || arg == "archived" {
+            option.include_archived = true;
+        } else if arg == "paired" {
+            option.paired_rows = true;
+        } else if let Some(filter) = try_parse_date_filter(arg) {
             option.date_filter = filter;
-        } else if let Some((include_backlog, is_verbose)) = try_parse_bv(arg) {
+            option.end_date = None;
+        } else if let Some((start, end)) = try_parse_date_range(arg) {
+            option.date_filter = (DateFilterOp::Equal, start);
+            option.end_date = Some(end);
+        } else if let Some((
+            include_backlog,
+            is_verbose,
+            only_prioritized,
+            include_deleted,
+            is_json,
+        )) = try_parse_flags(arg)
+        {
             option.include_backlog = include_backlog;
             option.is_verbose = is_verbose;
+            option.only_prioritized = only_prioritized;
+            option.include_deleted = include_deleted;
+            option.is_json = is_json;
+        } else if let Some(tag) = try_parse_tag(arg) {
+            option.tag = Some(tag);
+        } else if arg == "@stale" {
+            option.status = Some(TaskStatus::Backlog);
+            option.only_stale = true;
+        } else if let Some(status) = try_parse_status(arg) {
+            option.status = Some(status);
         } else {
             return None;
         }
     }
-    if let Some(&arg) = args.get(2) {
-        if let Some((include_backlog, is_verbose)) = try_parse_bv(arg) {
-            option.include_backlog = include_backlog;
-            option.is_verbose = is_verbose;
-        } else if let Some(filter) = try_parse_date_filter(arg) {
-            option.date_filter = filter;
-        } else {
-            return None;
-        }
+    if option.page > 1 && option.limit.is_none() {
+        option.limit = Some(DEFAULT_PAGE_SIZE);
     }
     if option.has_timeline && option.date_filter.0 != DateFilterOp::Equal {
         println!("cannot specify <op> when using `ll`");
         None
+    } else if !option.has_timeline && option.svg_export.is_some() {
+        println!("can only export svg when using `ll`");
+        None
+    } else if !option.has_timeline && option.tick_minutes.is_some() {
+        println!("can only set the resolution when using `ll`");
+        None
+    } else if option.has_timeline && option.only_stale {
+        println!("can only list stale tasks when using `ls`");
+        None
+    } else if !option.has_timeline && option.paired_rows {
+        println!("can only pair rows when using `ll`");
+        None
+    } else if option.has_timeline && option.include_archived {
+        println!("can only include archived tasks when using `ls`");
+        None
+    } else if option.has_timeline && option.is_json {
+        println!("can only output json when using `ls`");
+        None
+    } else if option.is_json && option.include_archived {
+        println!("cannot output archived tasks as json");
+        None
+    } else if option.has_timeline && option.limit.is_some() {
+        println!("can only limit the number of tasks when using `ls`");
+        None
+    } else if option.end_date.is_some() && option.svg_export.is_some() {
+        println!("can only export svg of a single date");
+        None
     } else {
         Some(option)
     }
 }
 
+// the command names starting with the word if it's not a name itself, at least 2 characters
+// long so that a slip of a single letter isn't taken as some command
+fn expand_command_name(word: &str) -> Vec<&'static str> {
+    if word.chars().count() < 2 || COMMAND_NAMES.contains(&word) {
+        return vec![];
+    }
+    COMMAND_NAMES
+        .into_iter()
+        .filter(|name| name.starts_with(word))
+        .collect()
+}
+
+// lowercases the first word and expands it if it's an unambiguous abbreviation, e.g. `Del 2`
+// to `delete 2`, leaving the rest as it is
+fn normalize_command_name(cmd: &str) -> String {
+    let (word, rest) = cmd.split_once(' ').unwrap_or((cmd, ""));
+    let word = word.to_lowercase();
+    let name = match expand_command_name(&word)[..] {
+        [name] => name.to_string(),
+        _ => word,
+    };
+    match rest.is_empty() {
+        true => name,
+        false => format!("{name} {rest}"),
+    }
+}
+
+// number of single character insertions, deletions, substitutions or swaps of adjacent
+// characters between the two, as `strat` is as likely a typo of `start` as `stat`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+// the commands the first word of an invalid command may be meant as: those it's an ambiguous
+// abbreviation of, or else the closest ones by a typo or two, fewer for shorter words
+pub fn suggest_command_names(cmd: &str) -> Vec<&'static str> {
+    let word = cmd.split_whitespace().next().unwrap_or("").to_lowercase();
+    if COMMAND_NAMES.contains(&word.as_str()) || COMMAND_ALIASES.contains(&word.as_str()) {
+        return vec![];
+    }
+    let expanded = expand_command_name(&word);
+    if !expanded.is_empty() {
+        return expanded;
+    }
+    let max_distance = match word.chars().count() {
+        0..=2 => return vec![],
+        3..=5 => 1,
+        _ => 2,
+    };
+    let distances: Vec<(usize, &str)> = COMMAND_NAMES
+        .into_iter()
+        .map(|name| (edit_distance(&word, name), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    let closest = distances.iter().map(|(distance, _)| *distance).min();
+    distances
+        .into_iter()
+        .filter(|(distance, _)| Some(*distance) == closest)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+// e.g. `did you mean `start` or `stats`?`, if there's any command to suggest
+pub fn did_you_mean(cmd: &str) -> Option<String> {
+    let names: Vec<String> = suggest_command_names(cmd)
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect();
+    let (last, rest) = names.split_last()?;
+    Some(match rest {
+        [] => format!("did you mean {last}?"),
+        rest => format!("did you mean {} or {last}?", rest.join(", ")),
+    })
+}
+
+// if a list command fails to parse because of some unrecognized arguments, returns the
+// command without them, so that the date filter could be asked for separately
+pub fn remove_invalid_list_args(cmd: &str) -> Option<String> {
+    let cmd = normalize_command_name(cmd.trim());
+    let args: Vec<&str> = cmd.split_whitespace().collect();
+    if !matches!(args.first(), Some(&"ls") | Some(&"ll")) {
+        return None;
+    }
+    let mut kept = vec![args[0]];
+    let mut has_invalid = false;
+    let mut iter = args[1..].iter();
+    while let Some(&arg) = iter.next() {
+        if arg == "svg" || arg == "svg!" || arg == "-r" || arg == "-n" || arg == "--page" {
+            kept.push(arg);
+            kept.extend(iter.next());
+        } else if arg == "redact"
+            || arg == "paired"
+            || arg == "@stale"
+            || arg == "-a"
+            || arg == "archived"
+            || (arg.starts_with('/') && arg.len() > 1)
+            || try_parse_date_filter(arg).is_some()
+            || try_parse_date_range(arg).is_some()
+            || try_parse_flags(arg).is_some()
+            || try_parse_tag(arg).is_some()
+            || try_parse_status(arg).is_some()
+        {
+            kept.push(arg);
+        } else {
+            has_invalid = true;
+        }
+    }
+    has_invalid.then(|| kept.join(" "))
+}
+
 fn try_parse_date_filter(arg: &str) -> Option<(DateFilterOp, NaiveDate)> {
     assert!(!arg.is_empty());
     if let Some(date) = arg.strip_prefix(">=") {
@@ -126,9 +877,57 @@ fn try_parse_date_filter(arg: &str) -> Option<(DateFilterOp, NaiveDate)> {
     }
 }
 
+// `today`, `yesterday`, `tomorrow`, a weekday like `mon` for the next one or `-mon` for the
+// previous one, both being today if it's that weekday, or `w+1` and `w-1` for the same
+// weekday a week later or earlier
+fn try_parse_relative_date(arg: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match arg {
+        "today" => return Some(today),
+        "yesterday" => return today.pred_opt(),
+        "tomorrow" => return today.succ_opt(),
+        _ => {}
+    }
+    if let Some(weeks) = arg
+        .strip_prefix('w')
+        .filter(|weeks| weeks.starts_with(['+', '-']))
+    {
+        let weeks = weeks.parse::<i32>().ok()?;
+        return today.checked_add_signed(Duration::weeks(weeks as i64));
+    }
+    let (weekday, is_previous) = match arg.strip_prefix('-') {
+        Some(weekday) => (weekday, true),
+        None => (arg, false),
+    };
+    if weekday.len() != 3 {
+        return None;
+    }
+    let weekday = weekday.parse::<Weekday>().ok()?;
+    let days_ahead =
+        (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    match is_previous {
+        true => today.checked_sub_days(Days::new(((7 - days_ahead) % 7) as u64)),
+        false => today.checked_add_days(Days::new(days_ahead as u64)),
+    }
+}
+
+// `<date>..<date>` with both ends included, in either order, or `week` for this week from
+// monday
+fn try_parse_date_range(arg: &str) -> Option<(NaiveDate, NaiveDate)> {
+    if arg == "week" {
+        let monday = clock::today().week(Weekday::Mon).first_day();
+        return Some((monday, monday + Duration::days(6)));
+    }
+    let (start, end) = arg.split_once("..")?;
+    let (start, end) = (try_parse_date(start)?, try_parse_date(end)?);
+    Some((start.min(end), start.max(end)))
+}
+
 fn try_parse_date(arg: &str) -> Option<NaiveDate> {
+    if let Some(date) = try_parse_relative_date(arg, clock::today()) {
+        return Some(date);
+    }
     if arg.len() == 5 && arg.chars().nth(2).unwrap() == '-' {
-        let date = format!("{}-{}", Local::now().year(), arg);
+        let date = format!("{}-{}", clock::now().year(), arg);
         if let Ok(date) = NaiveDate::parse_from_str(&date, "%F") {
             return Some(date);
         }
@@ -137,48 +936,757 @@ fn try_parse_date(arg: &str) -> Option<NaiveDate> {
         Some(date)
     } else if let Ok(offset) = arg.parse::<i32>() {
         if offset.is_positive() {
-            Local::now()
+            clock::now()
                 .date_naive()
                 .checked_add_days(Days::new(offset as u64))
         } else if offset.is_negative() {
-            Local::now()
+            clock::now()
                 .date_naive()
                 .checked_sub_days(Days::new(offset.unsigned_abs() as u64))
         } else {
-            Some(Local::now().date_naive())
+            Some(clock::today())
         }
     } else {
         None
     }
 }
 
-fn try_parse_bv(arg: &str) -> Option<(bool, bool)> {
-    if arg.chars().any(|c| c != 'b' && c != 'v') {
+fn try_parse_flags(arg: &str) -> Option<(bool, bool, bool, bool, bool)> {
+    if arg.chars().any(|c| !"bvpdj".contains(c)) {
         None
     } else {
-        Some((arg.contains('b'), arg.contains('v')))
+        Some((
+            arg.contains('b'),
+            arg.contains('v'),
+            arg.contains('p'),
+            arg.contains('d'),
+            arg.contains('j'),
+        ))
+    }
+}
+
+fn try_parse_search_pattern(cmd: &str) -> Option<String> {
+    let pattern = cmd
+        .strip_prefix('/')
+        .or_else(|| cmd.strip_prefix("grep "))?
+        .trim();
+    (!pattern.is_empty()).then(|| pattern.to_string())
+}
+
+// `n <description> [@backlog|@now|@<hh:mm>+<minutes>]`, which puts the task into backlog by
+// default, or `n! <description>` to start it immediately
+fn try_parse_new_inline(cmd: &str) -> Option<(String, Arrangement)> {
+    if let Some(description) = cmd.strip_prefix("n! ").map(str::trim) {
+        return (!description.is_empty())
+            .then(|| (description.to_string(), Arrangement::Immediate));
+    }
+    let rest = cmd
+        .strip_prefix("n ")
+        .or_else(|| cmd.strip_prefix("new "))?
+        .trim();
+    let (description, arrangement) = if let Some(description) = rest.strip_suffix("@backlog") {
+        (description, Arrangement::Backlog)
+    } else if let Some(description) = rest.strip_suffix("@now") {
+        (description, Arrangement::Immediate)
+    } else if let Some((description, arrangement)) = try_parse_schedule_suffix(rest) {
+        (description, arrangement)
+    } else {
+        (rest, Arrangement::Backlog)
+    };
+    let description = description.trim();
+    (!description.is_empty()).then(|| (description.to_string(), arrangement))
+}
+
+// `n @<name>`, where the name is a single word other than `now` and `backlog`
+fn try_parse_new_from_template(cmd: &str) -> Option<String> {
+    let name = cmd
+        .strip_prefix("n @")
+        .or_else(|| cmd.strip_prefix("new @"))?
+        .trim();
+    (!name.is_empty()
+        && !name.contains(char::is_whitespace)
+        && !name.contains('+')
+        && name != "now"
+        && name != "backlog")
+        .then(|| name.to_string())
+}
+
+// parses `<index> [minutes]`
+fn try_parse_pomodoro(args: &[&str]) -> Option<Command> {
+    let index = args.first()?.parse::<usize>().ok()?;
+    let minutes = match args.get(1) {
+        Some(minutes) => minutes.parse::<u32>().ok().filter(|minutes| *minutes > 0)?,
+        None => pomodoro::DEFAULT_MINUTES,
+    };
+    (args.len() <= 2).then_some(Command::Pomodoro(index, minutes))
+}
+
+fn try_parse_template(args: &[&str]) -> Option<Command> {
+    let action = match args {
+        ["save", name, index] => TemplateAction::Save(name.to_string(), index.parse().ok()?),
+        ["list"] => TemplateAction::List,
+        ["delete", name] => TemplateAction::Delete(name.to_string()),
+        _ => return None,
+    };
+    Some(Command::Template(action))
+}
+
+// a trailing `@14:00+45` plans the task today from 14:00 for 45 minutes
+fn try_parse_schedule_suffix(rest: &str) -> Option<(&str, Arrangement)> {
+    let (description, schedule) = rest.rsplit_once('@')?;
+    if !description.is_empty() && !description.ends_with(char::is_whitespace) {
+        return None;
+    }
+    let (time, minutes) = schedule.split_once('+')?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+    let minutes = minutes.parse::<u32>().ok().filter(|minutes| *minutes > 0)?;
+    Some((description, Arrangement::Planned(time, minutes)))
+}
+
+// `<index> desc <description>`, `<index> tags <tags|->`, `<index> priority <name|0-3>` or
+// `<index> plan <hh:mm>+<minutes>`
+fn try_parse_edit_inline(args: &[&str]) -> Option<Command> {
+    let index = args[0].parse::<usize>().ok()?;
+    let value = args[2..].join(" ");
+    let edit = match args[1] {
+        "desc" => TaskEdit::Description(value),
+        "tags" if value == "-" => TaskEdit::Tags(vec![]),
+        "tags" => TaskEdit::Tags(parse_tags(&value)),
+        "priority" => TaskEdit::Priority(
+            match PRIORITY_NAMES.iter().position(|name| *name == value) {
+                Some(priority) => priority as u8,
+                None => value.parse().ok().filter(|priority| *priority <= 3)?,
+            },
+        ),
+        "plan" if args.len() == 3 => match try_parse_schedule_suffix(&format!("@{value}")) {
+            Some((_, Arrangement::Planned(time, minutes))) => TaskEdit::Plan(time, minutes),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(Command::EditInline(index, edit))
+}
+
+pub fn try_parse_capture(cmd: &str) -> Option<String> {
+    let description = cmd.strip_prefix("b ")?.trim();
+    (!description.is_empty()).then(|| description.to_string())
+}
+
+fn try_parse_set(args: &[&str]) -> Option<Command> {
+    match args {
+        ["hours", start, end] => match parse_timeline_hours(start, end) {
+            Ok((start, end)) => Some(Command::SetHours(start, end)),
+            Err(reason) => {
+                println!("{reason}");
+                None
+            }
+        },
+        _ => None,
+    }
+}
+
+// parses `[week|day] [date]`, which defaults to this week
+fn try_parse_report(args: &[&str]) -> Option<Command> {
+    let (period, args) = match args.first() {
+        Some(&"week") => (ReportPeriod::Week, &args[1..]),
+        Some(&"day") => (ReportPeriod::Day, &args[1..]),
+        _ => (ReportPeriod::Week, args),
+    };
+    let date = match args {
+        [] => clock::today(),
+        [date] => try_parse_date(date)?,
+        _ => return None,
+    };
+    Some(Command::Report(period, date))
+}
+
+fn try_parse_stats(args: &[&str]) -> Option<Command> {
+    let kind = match args {
+        ["estimates"] => StatsKind::Estimates,
+        ["heatmap"] => StatsKind::Heatmap(DEFAULT_HEATMAP_WEEKS),
+        ["heatmap", weeks] => StatsKind::Heatmap(weeks.parse().ok().filter(|weeks| *weeks > 0)?),
+        ["punctuality"] => StatsKind::Punctuality(None),
+        ["punctuality", filter] => StatsKind::Punctuality(Some(filter.parse().ok()?)),
+        ["tags", args @ ..] => {
+            let (mut filter, mut as_csv) = (None, false);
+            for &arg in args {
+                match arg {
+                    "--csv" if !as_csv => as_csv = true,
+                    arg if filter.is_none() => filter = Some(arg.parse().ok()?),
+                    _ => return None,
+                }
+            }
+            StatsKind::Tags(filter, as_csv)
+        }
+        _ => return None,
+    };
+    Some(Command::Stats(kind))
+}
+
+// parses `<format> <path> [-f] [redact]`, or `md [date] [b] <path> [-f] [redact]`
+fn try_parse_export(args: &[&str]) -> Option<Command> {
+    let (format, args) = match args.first() {
+        Some(&"json") => (ExportFormat::Json, &args[1..]),
+        Some(&"ics") => (ExportFormat::Ics, &args[1..]),
+        Some(&"md") => try_parse_markdown_options(&args[1..])?,
+        _ => return None,
+    };
+    let mut target = ExportTarget {
+        path: PathBuf::from(args.first()?),
+        overwrite: false,
+        redact: false,
+    };
+    for &arg in &args[1..] {
+        match arg {
+            "-f" => target.overwrite = true,
+            "redact" => target.redact = true,
+            _ => return None,
+        }
+    }
+    Some(Command::Export(format, target))
+}
+
+// the date and backlog flag before the path, today without backlog by default
+fn try_parse_markdown_options<'a>(args: &'a [&'a str]) -> Option<(ExportFormat, &'a [&'a str])> {
+    let mut date = clock::today();
+    let mut include_backlog = false;
+    let mut rest = args;
+    while rest.len() > 1 {
+        if rest[0] == "b" {
+            include_backlog = true;
+        } else if let Some((DateFilterOp::Equal, filter_date)) = try_parse_date_filter(rest[0]) {
+            date = filter_date;
+        } else {
+            break;
+        }
+        rest = &rest[1..];
+    }
+    Some((ExportFormat::Markdown(date, include_backlog), rest))
+}
+
+// parses `<format> <path> [--replace]`
+fn try_parse_import(args: &[&str]) -> Option<Command> {
+    let format = match args.first() {
+        Some(&"json") => ExportFormat::Json,
+        Some(&"todotxt") => ExportFormat::TodoTxt,
+        _ => return None,
+    };
+    let path = PathBuf::from(args.get(1)?);
+    match &args[2..] {
+        [] => Some(Command::Import(format, path, false)),
+        ["--replace"] => Some(Command::Import(format, path, true)),
+        _ => None,
+    }
+}
+
+// parses `<n>m`, `<n>h` or `<n>d`, negative to move earlier
+pub fn parse_duration(arg: &str) -> Option<Duration> {
+    let unit = arg.chars().last()?;
+    let amount = arg[..arg.len() - unit.len_utf8()].parse::<i64>().ok()?;
+    let unit_millis = match unit {
+        'm' => 60 * 1000,
+        'h' => 60 * 60 * 1000,
+        'd' => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    amount.checked_mul(unit_millis).map(Duration::milliseconds)
+}
+
+fn try_parse_tag(arg: &str) -> Option<String> {
+    arg.strip_prefix('+')
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_string())
+}
+
+fn try_parse_status(arg: &str) -> Option<TaskStatus> {
+    match arg {
+        "@ongoing" => Some(TaskStatus::Ongoing),
+        "@paused" => Some(TaskStatus::Paused),
+        "@overdue" => Some(TaskStatus::Overdue),
+        "@done" => Some(TaskStatus::Complete),
+        "@planned" => Some(TaskStatus::Planned),
+        "@backlog" => Some(TaskStatus::Backlog),
+        _ => None,
+    }
+}
+
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .map(|tag| tag.trim_start_matches('+'))
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+// usage of a command, listed in short by `help` and in full by `help <name>`
+struct CommandHelp {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    // every form of the command with a one-line summary
+    usages: &'static [(&'static str, &'static str)],
+    // explanation and examples shown by `help <name>` only, may be empty
+    details: &'static str,
+}
+
+const COMMAND_HELPS: [CommandHelp; 47] = [
+    CommandHelp {
+        name: "quit",
+        aliases: &["q"],
+        usages: &[("q / quit", "quit arenta")],
+        details: "",
+    },
+    CommandHelp {
+        name: "help",
+        aliases: &["h"],
+        usages: &[
+            ("h / help", "show this message"),
+            ("h <command>", "show the usage of the command in detail with examples"),
+        ],
+        details: "",
+    },
+    CommandHelp {
+        name: "new",
+        aliases: &["n", "n!", "b"],
+        usages: &[
+            ("n / new", "create a new task"),
+            ("n <description> [@backlog|@now]", "create a new task without prompts, in backlog by default"),
+            ("n <description> @<hh:mm>+<minutes>", "plan a new task today, e.g. `n review PR @14:00+45`"),
+            ("n! <description>", "start a new task immediately"),
+            ("b <description>", "put a new task into backlog quickly"),
+        ],
+        details: "\
+without a description, the description, tags, priority and how the task is arranged are asked for one by one
+with a description, the task is created without prompts, put in backlog unless arranged otherwise
+  @backlog puts it in backlog, which is the default, and @now starts it immediately
+  @<hh:mm>+<minutes> plans it today from the time for the minutes
+examples:
+  n Buy milk
+  n review PR @14:00+45
+  n! fix the build
+  b call the bank",
+    },
+    CommandHelp {
+        name: "start",
+        aliases: &["s"],
+        usages: &[("s / start [index]", "start task, picked from a list if index is omitted")],
+        details: "",
+    },
+    CommandHelp {
+        name: "complete",
+        aliases: &["c"],
+        usages: &[("c / complete [index]", "complete task, picked from a list if index is omitted")],
+        details: "",
+    },
+    CommandHelp {
+        name: "edit",
+        aliases: &["e"],
+        usages: &[
+            ("e / edit [index]", "edit task, picked from a list if index is omitted"),
+            ("e <index> desc|tags|priority|plan <value>", "edit a field without prompts, e.g. `e 3 plan 14:00+45` or `e 3 tags -` to clear tags"),
+        ],
+        details: "\
+with only an index, every field is asked for with its current value as the default
+with a field and its value, only that field is changed:
+  desc <description>
+  tags <tags>, separated by commas or spaces, `-` to clear them
+  priority none|low|medium|high
+  plan <hh:mm>+<minutes> plans it today, overlapping tasks are only warned of
+examples:
+  e 3 desc write the quarterly report
+  e 3 tags work,writing
+  e 3 plan 14:00+45",
+    },
+    CommandHelp {
+        name: "note",
+        aliases: &[],
+        usages: &[("note <index>", "append to or replace the note of task, in `$EDITOR` if set")],
+        details: "",
+    },
+    CommandHelp {
+        name: "show",
+        aliases: &[],
+        usages: &[("show <index>", "show everything about task, including its note")],
+        details: "",
+    },
+    CommandHelp {
+        name: "split",
+        aliases: &[],
+        usages: &[("split <index>", "complete the part of ongoing task done so far and go on with the rest as a new task, or split the plan of task by a ratio")],
+        details: "\
+the description of the second part is asked for, and it inherits the tags of task
+an ongoing task is completed now, and the new one takes over what's left of its planned time
+the planned time of any other task is divided by the share of the first part asked for, e.g. 60%",
+    },
+    CommandHelp {
+        name: "mv",
+        aliases: &[],
+        usages: &[("mv <from> <to>", "move task to another position, shifting the tasks in between")],
+        details: "",
+    },
+    CommandHelp {
+        name: "swap",
+        aliases: &[],
+        usages: &[("swap <index> <index>", "exchange the positions of two tasks")],
+        details: "",
+    },
+    CommandHelp {
+        name: "merge",
+        aliases: &[],
+        usages: &[("merge <index> <index>", "merge the second task into the first one after a confirmation, deleting the second")],
+        details: "\
+the descriptions are joined with ` + `, the periods span both tasks, the tags are put together
+and the higher priority is kept; the merged task is complete only if both are",
+    },
+    CommandHelp {
+        name: "next",
+        aliases: &[],
+        usages: &[("next [-f]", "suggest the task to work on next and offer to start it, unless one is ongoing or with -f")],
+        details: "\
+the overdue task or the task planned within 2 hours of the highest priority is suggested,
+or the oldest backlog task if there's none",
+    },
+    CommandHelp {
+        name: "pomo",
+        aliases: &[],
+        usages: &[("pomo <index> [minutes]", "work on task for 25 minutes or the minutes with a countdown, starting it if not yet")],
+        details: "",
+    },
+    CommandHelp {
+        name: "log",
+        aliases: &[],
+        usages: &[("log [index]", "show the changes made to task, or the latest 20 changes to any task")],
+        details: "",
+    },
+    CommandHelp {
+        name: "pause",
+        aliases: &[],
+        usages: &[("pause <index>", "pause ongoing task, the time until resumed isn't counted as worked")],
+        details: "",
+    },
+    CommandHelp {
+        name: "resume",
+        aliases: &[],
+        usages: &[("resume <index>", "resume paused task")],
+        details: "",
+    },
+    CommandHelp {
+        name: "delete",
+        aliases: &[],
+        usages: &[("delete [index]", "delete task, picked from a list if index is omitted")],
+        details: "",
+    },
+    CommandHelp {
+        name: "restore",
+        aliases: &[],
+        usages: &[("restore <index>", "bring back a deleted task")],
+        details: "",
+    },
+    CommandHelp {
+        name: "purge",
+        aliases: &[],
+        usages: &[("purge", "remove all deleted tasks for good after a confirmation")],
+        details: "",
+    },
+    CommandHelp {
+        name: "postpone",
+        aliases: &[],
+        usages: &[("postpone <index> <duration>", "shift the planned time of task, e.g. `postpone 3 30m`, `-2h` or `1d`")],
+        details: "",
+    },
+    CommandHelp {
+        name: "template",
+        aliases: &[],
+        usages: &[
+            ("template save <name> <index>", "save the description, tags, priority and planned length of task as a template"),
+            ("template list", "list saved templates"),
+            ("template delete <name>", "delete the template, e.g. to save it again"),
+            ("n @<name>", "plan a task from the template, asking only when it starts"),
+        ],
+        details: "\
+templates are kept next to the data file, e.g. default.csv.templates, and the start asked for by `n @<name>` is now if left empty
+examples:
+  template save standup 3
+  n @standup",
+    },
+    CommandHelp {
+        name: "plan",
+        aliases: &[],
+        usages: &[("plan auto [date]", "plan backlog tasks into the free working time left of date, today by default, after a confirmation")],
+        details: "\
+backlog tasks of higher priority and then the older ones come first, and the length of each is
+asked for, empty to leave it in backlog; every task takes the earliest free slot long enough for it
+within working hours, or the timeline hours if `working_hours` isn't configured",
+    },
+    CommandHelp {
+        name: "defer",
+        aliases: &[],
+        usages: &[("defer [date]", "move unfinished tasks planned for today or earlier to the same time of date, tomorrow by default")],
+        details: "",
+    },
+    CommandHelp {
+        name: "private",
+        aliases: &[],
+        usages: &[("private <index>", "mark task as private or not, private descriptions are hidden in redacted exports")],
+        details: "",
+    },
+    CommandHelp {
+        name: "sort",
+        aliases: &[],
+        usages: &[("sort [--persist]", "list tasks by priority for the rest of the session, keeping their indices, or reorder the data file with --persist")],
+        details: "",
+    },
+    CommandHelp {
+        name: "chronic",
+        aliases: &[],
+        usages: &[("chronic", "list tasks postponed at least twice, most postponed first")],
+        details: "",
+    },
+    CommandHelp {
+        name: "report",
+        aliases: &[],
+        usages: &[("report [week|day] [date]", "summarize tasks of the week (from monday) or the day of date, this week by default")],
+        details: "",
+    },
+    CommandHelp {
+        name: "stats",
+        aliases: &[],
+        usages: &[
+            ("stats estimates", "compare actual durations of completed tasks with planned ones"),
+            ("stats heatmap [weeks]", "show how many tasks are completed every day of the weeks, 12 by default"),
+            ("stats punctuality [date_filter]", "show how late tasks are started compared with their plans"),
+            ("stats tags [date_filter] [--csv]", "show the time worked per tag, split evenly across the tags of a task"),
+        ],
+        details: "\
+[date_filter] takes the same form as for `ls`, see `h ls`
+examples:
+  stats heatmap 4
+  stats punctuality >=-mon
+  stats tags week --csv",
+    },
+    CommandHelp {
+        name: "export",
+        aliases: &[],
+        usages: &[
+            ("export json <path> [-f] [redact]", "export all tasks as json, `-f` to overwrite an existing file"),
+            ("export ics <path> [-f] [redact]", "export tasks having a planned time as icalendar events"),
+            ("export md [date] [b] <path|-> [-f] [redact]", "export the plan of date, today by default, as a markdown checklist, `-` for stdout"),
+        ],
+        details: "\
+`redact` replaces descriptions of private tasks, marked by `private <index>`
+examples:
+  export json tasks.json -f
+  export md tomorrow b -",
+    },
+    CommandHelp {
+        name: "import",
+        aliases: &[],
+        usages: &[
+            ("import json <path> [--replace]", "append tasks exported as json, skipping duplicates, or replace all tasks with them"),
+            ("import todotxt <path> [--replace]", "import tasks from a todo.txt file, malformed lines into backlog"),
+        ],
+        details: "",
+    },
+    CommandHelp {
+        name: "archive",
+        aliases: &[],
+        usages: &[("archive [date_filter]", "move complete tasks to the archive next to the data file, those completed over 30 days ago by default")],
+        details: "",
+    },
+    CommandHelp {
+        name: "flush",
+        aliases: &[],
+        usages: &[("flush", "write all changes kept in the journal into the data file, which is done on quit as well")],
+        details: "",
+    },
+    CommandHelp {
+        name: "sync",
+        aliases: &[],
+        usages: &[
+            ("sync", "commit the tasks to the git work tree they live in, then pull --rebase and push"),
+            ("sync --local", "commit the tasks only"),
+        ],
+        details: "\
+needs `sync = \"git\"` in the config; the data file is committed along with the archive and
+templates as `arenta: <n> tasks updated`, and a conflict in the data file offers to keep the
+tasks of this machine, those of the upstream, or both merged by task",
+    },
+    CommandHelp {
+        name: "restore-backup",
+        aliases: &[],
+        usages: &[
+            ("restore-backup", "list the backups of the data file with their times and task counts"),
+            ("restore-backup <n>", "put backup n in place of the tasks after a confirmation"),
+        ],
+        details: "\
+a backup is taken whenever the data file is rewritten, e.g. on quit, keeping the latest 3 or
+`backups` in the config; the tasks replaced by a restore become backup 1, so it can be undone",
+    },
+    CommandHelp {
+        name: "migrate",
+        aliases: &[],
+        usages: &[
+            ("migrate sqlite", "copy the tasks of the data file into a sqlite database next to it and keep them there"),
+            ("migrate csv", "copy the tasks of the database back into the data file and keep them there"),
+        ],
+        details: "\
+sets `storage` in the config, leaving the file migrated from as it is; a file holding tasks
+already is replaced after a confirmation; `storage = \"sqlite\"` needs the sqlite3 command",
+    },
+    CommandHelp {
+        name: "reload",
+        aliases: &[],
+        usages: &[("reload", "load the tasks again from the data file, dropping those in memory")],
+        details: "\
+useful when another process has saved tasks in the meantime, e.g. through a synced home
+directory; prints how many tasks were added, removed or changed compared with memory",
+    },
+    CommandHelp {
+        name: "timings",
+        aliases: &[],
+        usages: &[("timings", "list the slowest commands of this session")],
+        details: "",
+    },
+    CommandHelp {
+        name: "config",
+        aliases: &[],
+        usages: &[("config", "print the settings in effect and where they come from")],
+        details: "",
+    },
+    CommandHelp {
+        name: "use",
+        aliases: &[],
+        usages: &[
+            ("use", "list the profiles, marking the active one"),
+            ("use <name>", "switch to another profile, i.e. a separate task list, creating it if new"),
+        ],
+        details: "\
+the tasks of profile <name> are kept in <name>.csv in the data directory with a lock file of their own;
+start arenta with `--profile <name>` to open it right away",
+    },
+    CommandHelp {
+        name: "tui",
+        aliases: &[],
+        usages: &[("tui", "open the full-screen view of the timeline and tasks of today")],
+        details: "\
+j/k select a task, s/c/d start, complete or delete it, / filters by description and q
+returns here; the view refreshes every 30 seconds, start arenta with `--tui` to open it right away",
+    },
+    CommandHelp {
+        name: "info",
+        aliases: &[],
+        usages: &[("info", "print the version, the data and lock files, and how many tasks there are")],
+        details: "",
+    },
+    CommandHelp {
+        name: "maintain",
+        aliases: &[],
+        usages: &[("maintain [--dry-run]", "clean up stale files now, which is done once a day on startup as well")],
+        details: "",
+    },
+    CommandHelp {
+        name: "grep",
+        aliases: &["/"],
+        usages: &[("grep <pattern>", "search all tasks by description, `/<pattern>` for short")],
+        details: "",
+    },
+    CommandHelp {
+        name: "set",
+        aliases: &[],
+        usages: &[("set hours <start> <end>", "set the hours displayed in timeline, e.g. `set hours 10 22`")],
+        details: "",
+    },
+    CommandHelp {
+        name: "ls",
+        aliases: &["ll"],
+        usages: &[
+            ("ls [date_filter] [bvp] [+tag] [@status]", "list tasks, without timeline"),
+            ("ll [date_filter] [bvp] [+tag] [@status]", "list tasks, with timeline"),
+        ],
+        details: "\
+[date_filter] is in format of `<op><date>`
+  <op> could be <, <=, >, >= or empty, which indicates `==`, note that for `ll`, <op> must be empty
+  <date> could in format of mm-dd, yyyy-mm-dd or an integer, which indicates offset to today
+  or today, yesterday, tomorrow, a weekday like `mon` for the next one and `-mon` for the previous one, or `w+1`/`w-1` for a week later/earlier
+  or a range `<date>..<date>` with both ends included, or `week` for this week from monday, for which `ll` shows one timeline per day
+if `b` flag specified, it means display backlog tasks as well
+if `v` flag specified, it means display in verbose mode
+if `p` flag specified, it means display only tasks with a priority
+if `d` flag specified, it means display deleted tasks as well
+if `j` flag specified with `ls`, it means print the tasks as a json array for scripts
+if `-n <count>` specified with `ls`, it means display at most count tasks, and `--page <k>` the k-th page of them, 20 a page by default
+  `more` continues the latest such listing with the next page
+if `+tag` specified, it means display only tasks carrying that tag
+if `/text` specified, it means display only tasks whose description contains text, case-insensitively
+if `@status` specified, it means display only tasks of that status, which could be ongoing, paused, overdue, done, planned or backlog
+  `@stale` with `ls` displays backlog tasks older than `backlog_stale_days`, the oldest first
+  overdue tasks are displayed regardless of date_filter, and so are backlog ones
+  ongoing and paused tasks are displayed if date_filter reaches today or later
+if `-a` or `archived` specified with `ls`, it means display archived tasks as well
+if `svg <path>` specified with `ll`, it means export the timeline as svg (`svg!` to overwrite)
+if `redact` specified after `svg <path>`, it means hide descriptions of private tasks in the export
+if `paired` specified with `ll`, it means draw the planned and actual periods of every task on a pair of rows
+if `-r <minutes>` specified with `ll`, it means draw a column of the timeline per that many minutes, e.g. 5 or 15
+some examples:
+  ls, ls b, ls +1 v, ll, ll -1, ll 2023-01-26 bv, ls +work, ll -1 svg day.svg redact",
+    },
+    CommandHelp {
+        name: "more",
+        aliases: &[],
+        usages: &[("more", "list the next page of the latest listing limited by `ls -n <count>`")],
+        details: "",
+    },
+];
+
+// `  s / start [index]      start task, ...`, with long forms followed by two spaces
+fn format_usage(syntax: &str, summary: &str) -> String {
+    match syntax.len() < 22 {
+        true => format!("  {syntax:<22} {summary}"),
+        false => format!("  {syntax}  {summary}"),
     }
 }
 
 pub fn print_command_usage() {
     println!("commands: ");
-    println!("  q / quit               quit arenta");
-    println!("  h / help               show this message");
-    println!("  n / new                create a new task");
-    println!("  s / start <index>      start task");
-    println!("  c / complete <index>   complete task");
-    println!("  e / edit <index>       edit task");
-    println!("  delete <index>         delete task");
-    println!("  sort                   clean deleted tasks and sort other tasks");
-    println!("  ls [date_filter] [bv]  list tasks, without timeline");
-    println!("  ll [date_filter] [bv]  list tasks, with timeline");
-    println!("    [date_filter] is in format of `<op><date>`");
-    println!("      <op> could be <, <=, >, >= or empty, which indicates `==`, note that for `ll`, <op> must be empty");
-    println!("      <date> could in format of mm-dd, yyyy-mm-dd or an integer, which indicates offset to today");
-    println!("    if `b` flag specified, it means display backlog tasks as well");
-    println!("    if `v` flag specified, it means display in verbose mode");
-    println!("    some examples:");
-    println!("      ls, ls b, ls +1 v, ll, ll -1, ll 2023-01-26 bv");
+    for help in &COMMAND_HELPS {
+        for (syntax, summary) in help.usages {
+            println!("{}", format_usage(syntax, summary));
+        }
+    }
+    println!("type `h <command>` for details and examples, e.g. `h ls`");
+}
+
+// the help of a command by its name, an alias or an unambiguous abbreviation
+fn find_command_help(topic: &str) -> Option<&'static CommandHelp> {
+    let topic = topic.to_lowercase();
+    let expanded = match expand_command_name(&topic)[..] {
+        [name] => name.to_string(),
+        _ => topic,
+    };
+    COMMAND_HELPS
+        .iter()
+        .find(|help| help.name == expanded || help.aliases.contains(&expanded.as_str()))
+}
+
+pub fn print_command_help(topic: &str) {
+    let Some(help) = find_command_help(topic) else {
+        let topics: Vec<&str> = COMMAND_HELPS.iter().map(|help| help.name).collect();
+        println!(
+            "no help on `{topic}`, the topics are: {}",
+            topics.join(", ")
+        );
+        return;
+    };
+    for (syntax, summary) in help.usages {
+        println!("{}", format_usage(syntax, summary));
+    }
+    if !help.aliases.is_empty() {
+        println!("aliases: {}", help.aliases.join(", "));
+    }
+    if !help.details.is_empty() {
+        println!();
+        help.details.lines().for_each(|line| println!("  {line}"));
+    }
 }
 
 #[cfg(test)]
@@ -187,108 +1695,783 @@ mod tests {
 
     #[test]
     fn test_try_parse_date() {
-        assert_eq!(
-            try_parse_date("01-26"),
-            NaiveDate::from_ymd_opt(Local::now().year(), 1, 26)
-        );
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day);
+        // a wednesday
+        let frozen = clock::freeze("2024-03-06 10:00");
+        assert_eq!(try_parse_date("01-26"), date(2024, 1, 26));
         assert_eq!(try_parse_date("01-32"), None);
+        assert_eq!(try_parse_date("2023-01-27"), date(2023, 1, 27));
+        assert_eq!(try_parse_date("+2"), date(2024, 3, 8));
+        assert_eq!(try_parse_date("3"), date(2024, 3, 9));
+        assert_eq!(try_parse_date("-1"), date(2024, 3, 5));
+        assert_eq!(try_parse_date("today"), date(2024, 3, 6));
+        assert_eq!(try_parse_date("tomorrow"), date(2024, 3, 7));
+        assert_eq!(try_parse_date("mon"), date(2024, 3, 11));
+        assert_eq!(try_parse_date("monday"), None);
+        assert_eq!(try_parse_date("w"), None);
+        drop(frozen);
+
+        // offsets count from the day of the clock up to its very last minute
+        let _frozen = clock::freeze("2024-12-31 23:59");
+        assert_eq!(try_parse_date("+1"), date(2025, 1, 1));
+        assert_eq!(try_parse_date("tomorrow"), date(2025, 1, 1));
+        assert_eq!(try_parse_date("-1"), date(2024, 12, 30));
+        assert_eq!(try_parse_date("01-26"), date(2024, 1, 26));
+    }
+
+    #[test]
+    fn test_try_parse_relative_date() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        // a wednesday
+        let today = date(6);
+        let parse = |arg| try_parse_relative_date(arg, today);
+        assert_eq!(parse("today"), Some(today));
+        assert_eq!(parse("yesterday"), Some(date(5)));
+        assert_eq!(parse("tomorrow"), Some(date(7)));
+        assert_eq!(parse("fri"), Some(date(8)));
+        assert_eq!(parse("mon"), Some(date(11)));
+        assert_eq!(parse("Tue"), Some(date(12)));
+        assert_eq!(parse("-mon"), Some(date(4)));
+        assert_eq!(parse("-thu"), NaiveDate::from_ymd_opt(2024, 2, 29));
+        assert_eq!(parse("w+1"), Some(date(13)));
+        assert_eq!(parse("w-1"), NaiveDate::from_ymd_opt(2024, 2, 28));
+        assert_eq!(parse("w1"), None);
+        assert_eq!(parse("w+x"), None);
+        assert_eq!(parse("-1"), None);
+        assert_eq!(parse("bvp"), None);
+        // the weekday of today means today, whichever way
+        assert_eq!(parse("wed"), Some(today));
+        assert_eq!(parse("-wed"), Some(today));
+        let monday = date(4);
+        assert_eq!(try_parse_relative_date("mon", monday), Some(monday));
+        assert_eq!(try_parse_relative_date("sun", monday), Some(date(10)));
         assert_eq!(
-            try_parse_date("2023-01-27"),
-            NaiveDate::from_ymd_opt(2023, 1, 27)
+            try_parse_date_filter(">=mon").map(|(op, date)| (op, date.weekday())),
+            Some((DateFilterOp::LaterEqual, Weekday::Mon))
         );
-        let today = Local::now().date_naive();
-        assert_eq!(try_parse_date("+2"), today.checked_add_days(Days::new(2)));
-        assert_eq!(try_parse_date("3"), today.checked_add_days(Days::new(3)));
-        assert_eq!(try_parse_date("-1"), today.checked_sub_days(Days::new(1)));
     }
 
     #[test]
     fn test_try_parse_date_filter() {
-        let today = Local::now().date_naive();
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let _frozen = clock::freeze("2024-03-01 00:00");
         assert_eq!(
             try_parse_date_filter(">=1"),
-            Some((
-                DateFilterOp::LaterEqual,
-                today.checked_add_days(Days::new(1)).unwrap()
-            ))
+            Some((DateFilterOp::LaterEqual, date(3, 2)))
         );
         assert_eq!(
             try_parse_date_filter(">-2"),
-            Some((
-                DateFilterOp::Later,
-                today.checked_sub_days(Days::new(2)).unwrap()
-            ))
+            Some((DateFilterOp::Later, date(2, 28)))
         );
         assert_eq!(
             try_parse_date_filter("0"),
-            Some((DateFilterOp::Equal, today))
+            Some((DateFilterOp::Equal, date(3, 1)))
         );
         assert_eq!(
             try_parse_date_filter("<4"),
-            Some((
-                DateFilterOp::Earlier,
-                today.checked_add_days(Days::new(4)).unwrap()
-            ))
+            Some((DateFilterOp::Earlier, date(3, 5)))
         );
         assert_eq!(
             try_parse_date_filter("<=-1"),
-            Some((
-                DateFilterOp::EarlierEqual,
-                today.checked_sub_days(Days::new(1)).unwrap()
-            ))
+            Some((DateFilterOp::EarlierEqual, date(2, 29)))
         );
     }
 
     #[test]
-    fn test_parse_command() {
-        assert_eq!(parse_command(""), Some(Command::Empty));
-        assert_eq!(parse_command("  "), Some(Command::Empty));
-        assert_eq!(parse_command("q "), Some(Command::Quit));
-        assert_eq!(parse_command(" quit "), Some(Command::Quit));
-        assert_eq!(parse_command("h"), Some(Command::Help));
-        assert_eq!(parse_command(" help"), Some(Command::Help));
-        assert_eq!(parse_command("n"), Some(Command::New));
-        assert_eq!(parse_command("new"), Some(Command::New));
-        assert_eq!(parse_command("sort "), Some(Command::Sort));
-        assert_eq!(parse_command("s 1"), Some(Command::Start(1)));
-        assert_eq!(parse_command("start 2"), Some(Command::Start(2)));
-        assert_eq!(parse_command("start a"), None);
-        assert_eq!(parse_command("c 1"), Some(Command::Complete(1)));
-        assert_eq!(parse_command("complete 2"), Some(Command::Complete(2)));
-        assert_eq!(parse_command("complete a"), None);
-        assert_eq!(parse_command("d 1"), None);
-        assert_eq!(parse_command("delete 2"), Some(Command::Delete(2)));
-        assert_eq!(parse_command("delete a"), None);
-        assert_eq!(parse_command("e 1"), Some(Command::Edit(1)));
-        assert_eq!(parse_command("edit 2"), Some(Command::Edit(2)));
-        assert_eq!(parse_command("edit a"), None);
+    fn test_try_parse_date_range() {
+        // a wednesday
+        let _frozen = clock::freeze("2024-03-06 10:00");
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
         assert_eq!(
-            parse_command("ls"),
-            Some(Command::List(ListOption::default()))
-        );
-        let date_filter = (
-            DateFilterOp::LaterEqual,
-            Local::now()
-                .date_naive()
-                .checked_add_days(Days::new(1))
-                .unwrap(),
+            try_parse_date_range("2024-03-04..2024-03-08"),
+            Some((
+                NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 8).unwrap()
+            ))
         );
+        assert_eq!(try_parse_date_range("-1..1"), Some((date(5), date(7))));
+        assert_eq!(try_parse_date_range("week"), Some((date(4), date(10))));
         assert_eq!(
-            parse_command("ls >=1"),
-            Some(Command::List(ListOption {
-                date_filter,
-                ..ListOption::default()
-            }))
+            try_parse_date_range("2024-03-08..2024-03-04"),
+            try_parse_date_range("2024-03-04..2024-03-08")
         );
+        assert_eq!(try_parse_date_range("-mon..fri"), Some((date(4), date(8))));
+        assert_eq!(try_parse_date_range("..2024-03-04"), None);
+        assert_eq!(try_parse_date_range("2024-03-04"), None);
         assert_eq!(
-            parse_command("ls >=1 b"),
+            parse_command("ll 2024-03-04..2024-03-08 v"),
             Some(Command::List(ListOption {
-                date_filter,
-                include_backlog: true,
+                date_filter: (
+                    DateFilterOp::Equal,
+                    NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+                ),
+                end_date: NaiveDate::from_ymd_opt(2024, 3, 8),
+                is_verbose: true,
+                has_timeline: true,
                 ..ListOption::default()
             }))
         );
-        assert_eq!(
+        assert_eq!(parse_command("ll week svg out.svg"), None);
+    }
+
+    #[test]
+    fn test_date_filter() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 8).unwrap();
+        assert_eq!(
+            ">=2024-03-08".parse::<DateFilter>(),
+            Ok(DateFilter(DateFilterOp::LaterEqual, date))
+        );
+        assert_eq!("".parse::<DateFilter>(), Err(()));
+        assert_eq!("someday".parse::<DateFilter>(), Err(()));
+        assert_eq!(
+            DateFilter(DateFilterOp::LaterEqual, date).to_string(),
+            ">=2024-03-08"
+        );
+        assert_eq!(
+            DateFilter(DateFilterOp::LaterEqual, date).describe(),
+            ">= 2024-03-08 (Fri)"
+        );
+        assert_eq!(
+            DateFilter(DateFilterOp::Equal, date).describe(),
+            "2024-03-08 (Fri)"
+        );
+    }
+
+    #[test]
+    fn test_parse_command_name_loosely() {
+        assert_eq!(parse_command("Start 3"), Some(Command::Start(3)));
+        assert_eq!(parse_command("LS"), parse_command("ls"));
+        assert_eq!(parse_command("del 2"), Some(Command::Delete(2)));
+        assert_eq!(parse_command("comp 2"), Some(Command::Complete(2)));
+        assert_eq!(parse_command("ed 2"), Some(Command::Edit(2)));
+        // ambiguous ones are left alone
+        assert_eq!(parse_command("st 2"), None);
+        // only the command name is lowercased
+        assert_eq!(
+            parse_command("B Call Alice"),
+            Some(Command::Capture("Call Alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_command_helps() {
+        // every command has help, whether by its name or an alias
+        for name in COMMAND_NAMES {
+            assert!(find_command_help(name).is_some(), "no help on `{name}`");
+        }
+        assert_eq!(find_command_help("ll").unwrap().name, "ls");
+        assert_eq!(find_command_help("N").unwrap().name, "new");
+        assert_eq!(find_command_help("comp").unwrap().name, "complete");
+        assert!(find_command_help("bogus").is_none());
+        let names: Vec<&str> = COMMAND_HELPS.iter().map(|help| help.name).collect();
+        let mut distinct = names.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), names.len());
+        assert_eq!(
+            format_usage("q / quit", "quit arenta"),
+            "  q / quit               quit arenta"
+        );
+        assert_eq!(
+            format_usage("postpone <index> <duration>", "shift"),
+            "  postpone <index> <duration>  shift"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("start", "start"), 0);
+        assert_eq!(edit_distance("strat", "start"), 1);
+        assert_eq!(edit_distance("stat", "start"), 1);
+        assert_eq!(edit_distance("", "ls"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_suggest_command_names() {
+        assert_eq!(suggest_command_names("de 2"), vec!["defer", "delete"]);
+        assert_eq!(suggest_command_names("strat 2"), vec!["start"]);
+        assert_eq!(suggest_command_names("stars"), vec!["start", "stats"]);
+        assert_eq!(suggest_command_names("pasue 2"), vec!["pause"]);
+        assert_eq!(suggest_command_names("compelte 2"), vec!["complete"]);
+        // too far from anything, or too short to tell
+        assert!(suggest_command_names("bogus").is_empty());
+        assert!(suggest_command_names("x").is_empty());
+        assert!(suggest_command_names("lx").is_empty());
+        // valid names with invalid arguments
+        assert!(suggest_command_names("s x").is_empty());
+        assert!(suggest_command_names("delete x").is_empty());
+        assert_eq!(
+            did_you_mean("de 2").as_deref(),
+            Some("did you mean `defer` or `delete`?")
+        );
+        assert_eq!(
+            did_you_mean("pasue 2").as_deref(),
+            Some("did you mean `pause`?")
+        );
+        assert_eq!(did_you_mean("bogus"), None);
+    }
+
+    #[test]
+    fn test_remove_invalid_list_args() {
+        assert_eq!(remove_invalid_list_args("ls"), None);
+        assert_eq!(remove_invalid_list_args("ls -1 bv"), None);
+        assert_eq!(
+            remove_invalid_list_args("ls >=someday bv"),
+            Some("ls bv".to_string())
+        );
+        assert_eq!(
+            remove_invalid_list_args("ll 03-32 svg out.svg"),
+            Some("ll svg out.svg".to_string())
+        );
+        assert_eq!(
+            remove_invalid_list_args("ls someday -n 5"),
+            Some("ls -n 5".to_string())
+        );
+        assert_eq!(remove_invalid_list_args("LL 03-32"), Some("ll".to_string()));
+        assert_eq!(remove_invalid_list_args("s x"), None);
+    }
+
+    #[test]
+    fn test_parse_list_limit() {
+        assert_eq!(
+            parse_command("ls b -n 5"),
+            Some(Command::List(ListOption {
+                include_backlog: true,
+                limit: Some(5),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ls -n 5 --page 3 b"),
+            Some(Command::List(ListOption {
+                include_backlog: true,
+                limit: Some(5),
+                page: 3,
+                ..ListOption::default()
+            }))
+        );
+        // a page without a count has the default size
+        assert_eq!(
+            parse_command("ls --page 2"),
+            Some(Command::List(ListOption {
+                limit: Some(DEFAULT_PAGE_SIZE),
+                page: 2,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ls -n 0"), None);
+        assert_eq!(parse_command("ls -n"), None);
+        assert_eq!(parse_command("ls --page 0"), None);
+        assert_eq!(parse_command("ll -n 5"), None);
+        assert_eq!(parse_command("more"), Some(Command::More));
+        assert_eq!(parse_command("info"), Some(Command::Info));
+        assert!(Command::Info.is_read_only());
+        assert_eq!(parse_command("reload"), Some(Command::Reload));
+        assert_eq!(parse_command("rel"), Some(Command::Reload));
+        assert_eq!(parse_command("use"), Some(Command::Use(None)));
+        assert_eq!(parse_command("tui"), Some(Command::Tui));
+        assert!(Command::Tui.is_interactive());
+        assert!(!Command::Tui.is_read_only());
+        assert_eq!(
+            parse_command("USE Work"),
+            Some(Command::Use(Some("Work".to_string())))
+        );
+        assert!(parse_command("use work home").is_none());
+        assert!(Command::Use(None).is_read_only());
+        assert!(!Command::Use(Some("work".to_string())).is_read_only());
+        assert_eq!(
+            parse_command("restore-backup"),
+            Some(Command::RestoreBackup(None))
+        );
+        assert_eq!(
+            parse_command("restore-backup 2"),
+            Some(Command::RestoreBackup(Some(2)))
+        );
+        assert!(parse_command("restore-backup last").is_none());
+        assert_eq!(parse_command("sync"), Some(Command::Sync(false)));
+        assert_eq!(parse_command("sync --local"), Some(Command::Sync(true)));
+        assert_eq!(
+            parse_command("migrate sqlite"),
+            Some(Command::Migrate(StorageKind::Sqlite))
+        );
+        assert_eq!(
+            parse_command("migrate csv"),
+            Some(Command::Migrate(StorageKind::Csv))
+        );
+        assert!(parse_command("migrate json").is_none());
+        assert!(Command::RestoreBackup(Some(2)).is_interactive());
+    }
+
+    #[test]
+    fn test_parse_list_text() {
+        let _frozen = clock::freeze("2024-03-06 10:00");
+        assert_eq!(
+            parse_command("ls <=0 /invoice b"),
+            Some(Command::List(ListOption {
+                date_filter: (
+                    DateFilterOp::EarlierEqual,
+                    NaiveDate::from_ymd_opt(2024, 3, 6).unwrap()
+                ),
+                include_backlog: true,
+                text: Some("invoice".to_string()),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ll /standup"),
+            Some(Command::List(ListOption {
+                has_timeline: true,
+                text: Some("standup".to_string()),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ls /"), None);
+        assert_eq!(
+            remove_invalid_list_args("ls someday /invoice"),
+            Some("ls /invoice".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration("2h"), Some(Duration::hours(2)));
+        assert_eq!(parse_duration("1d"), Some(Duration::days(1)));
+        assert_eq!(parse_duration("-30m"), Some(Duration::minutes(-30)));
+        assert_eq!(parse_duration("+1h"), Some(Duration::hours(1)));
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("30"), None);
+        assert_eq!(parse_duration("1.5h"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(parse_tags(""), Vec::<String>::new());
+        assert_eq!(parse_tags("work"), vec!["work"]);
+        assert_eq!(
+            parse_tags(" work, +errand;deep-focus "),
+            vec!["work", "errand", "deep-focus"]
+        );
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let _frozen = clock::freeze("2024-03-06 10:00");
+        assert_eq!(parse_command(""), Some(Command::Empty));
+        assert_eq!(parse_command("  "), Some(Command::Empty));
+        assert_eq!(parse_command("q "), Some(Command::Quit));
+        assert_eq!(parse_command(" quit "), Some(Command::Quit));
+        assert_eq!(parse_command("h"), Some(Command::Help(None)));
+        assert_eq!(parse_command(" help"), Some(Command::Help(None)));
+        assert_eq!(
+            parse_command("h ls"),
+            Some(Command::Help(Some("ls".to_string())))
+        );
+        assert_eq!(
+            parse_command("help new"),
+            Some(Command::Help(Some("new".to_string())))
+        );
+        assert_eq!(parse_command("n"), Some(Command::New));
+        assert_eq!(parse_command("new"), Some(Command::New));
+        assert_eq!(
+            parse_command("n Buy milk"),
+            Some(Command::NewInline(
+                "Buy milk".to_string(),
+                Arrangement::Backlog
+            ))
+        );
+        assert_eq!(
+            parse_command("new write report @now"),
+            Some(Command::NewInline(
+                "write report".to_string(),
+                Arrangement::Immediate
+            ))
+        );
+        assert_eq!(
+            parse_command("n call mom @backlog"),
+            Some(Command::NewInline(
+                "call mom".to_string(),
+                Arrangement::Backlog
+            ))
+        );
+        assert_eq!(parse_command("n @now"), None);
+        assert_eq!(
+            parse_command("n! fix the build"),
+            Some(Command::NewInline(
+                "fix the build".to_string(),
+                Arrangement::Immediate
+            ))
+        );
+        assert_eq!(parse_command("n! "), None);
+        assert_eq!(
+            parse_command("n Review PR @14:00+45"),
+            Some(Command::NewInline(
+                "Review PR".to_string(),
+                Arrangement::Planned(NaiveTime::from_hms_opt(14, 0, 0).unwrap(), 45)
+            ))
+        );
+        // not a schedule, so kept in the description
+        assert_eq!(
+            parse_command("n mail bob@14:00+45"),
+            Some(Command::NewInline(
+                "mail bob@14:00+45".to_string(),
+                Arrangement::Backlog
+            ))
+        );
+        assert_eq!(
+            parse_command("n standup @25:00+15"),
+            Some(Command::NewInline(
+                "standup @25:00+15".to_string(),
+                Arrangement::Backlog
+            ))
+        );
+        assert_eq!(parse_command("n @9:30+15"), None);
+        assert_eq!(parse_command("sort "), Some(Command::Sort(false)));
+        assert_eq!(parse_command("sort --persist"), Some(Command::Sort(true)));
+        assert_eq!(parse_command("chronic"), Some(Command::Chronic));
+        assert_eq!(parse_command("timings"), Some(Command::Timings));
+        assert_eq!(parse_command("flush"), Some(Command::Flush));
+        assert_eq!(parse_command("log"), Some(Command::History(None)));
+        assert_eq!(parse_command("log 3"), Some(Command::History(Some(3))));
+        assert_eq!(parse_command("log all"), None);
+        assert_eq!(parse_command("mv 5 0"), Some(Command::Move(5, 0)));
+        assert_eq!(parse_command("swap 1 3"), Some(Command::Swap(1, 3)));
+        assert_eq!(parse_command("mv 5"), None);
+        assert_eq!(parse_command("swap 1 -3"), None);
+        assert_eq!(parse_command("merge 2 5"), Some(Command::Merge(2, 5)));
+        assert_eq!(parse_command("merge 2"), None);
+        assert_eq!(parse_command("merge 2 x"), None);
+        assert_eq!(parse_command("split 2"), Some(Command::Split(2)));
+        assert_eq!(parse_command("split"), None);
+        assert_eq!(parse_command("split 2 3"), None);
+        assert_eq!(
+            parse_command("plan auto"),
+            Some(Command::PlanAuto(date(3, 6)))
+        );
+        assert_eq!(
+            parse_command("plan auto tomorrow"),
+            Some(Command::PlanAuto(date(3, 7)))
+        );
+        assert_eq!(parse_command("plan auto someday"), None);
+        assert_eq!(parse_command("plan"), None);
+        assert_eq!(parse_command("next"), Some(Command::Next(false)));
+        assert_eq!(parse_command("next -f"), Some(Command::Next(true)));
+        assert_eq!(parse_command("next 3"), None);
+        assert!(Command::Next(false).is_interactive());
+        assert_eq!(parse_command("pomo 3"), Some(Command::Pomodoro(3, 25)));
+        assert_eq!(parse_command("pomo 3 50"), Some(Command::Pomodoro(3, 50)));
+        assert_eq!(parse_command("pomo 3 0"), None);
+        assert_eq!(parse_command("pomo 3 50 1"), None);
+        assert_eq!(parse_command("maintain"), Some(Command::Maintain(false)));
+        assert_eq!(
+            parse_command("report"),
+            Some(Command::Report(ReportPeriod::Week, date(3, 6)))
+        );
+        assert_eq!(
+            parse_command("report day -1"),
+            Some(Command::Report(ReportPeriod::Day, date(3, 5)))
+        );
+        assert_eq!(
+            parse_command("report week 2024-03-06"),
+            Some(Command::Report(ReportPeriod::Week, date(3, 6)))
+        );
+        assert_eq!(parse_command("report month"), None);
+        assert_eq!(
+            parse_command("stats estimates"),
+            Some(Command::Stats(StatsKind::Estimates))
+        );
+        assert_eq!(parse_command("stats"), None);
+        assert_eq!(
+            parse_command("stats heatmap"),
+            Some(Command::Stats(StatsKind::Heatmap(12)))
+        );
+        assert_eq!(
+            parse_command("stats heatmap 52"),
+            Some(Command::Stats(StatsKind::Heatmap(52)))
+        );
+        assert_eq!(parse_command("stats heatmap 0"), None);
+        assert_eq!(
+            parse_command("stats punctuality"),
+            Some(Command::Stats(StatsKind::Punctuality(None)))
+        );
+        assert_eq!(
+            parse_command("stats punctuality >=2024-03-04"),
+            Some(Command::Stats(StatsKind::Punctuality(Some(DateFilter(
+                DateFilterOp::LaterEqual,
+                NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+            )))))
+        );
+        assert_eq!(parse_command("stats punctuality soon"), None);
+        assert_eq!(
+            parse_command("stats tags"),
+            Some(Command::Stats(StatsKind::Tags(None, false)))
+        );
+        assert_eq!(
+            parse_command("stats tags --csv 2024-03-04"),
+            Some(Command::Stats(StatsKind::Tags(
+                Some(DateFilter(
+                    DateFilterOp::Equal,
+                    NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+                )),
+                true
+            )))
+        );
+        assert_eq!(parse_command("stats tags --csv --csv"), None);
+        assert_eq!(parse_command("stats tags 2024-03-04 <2024-03-08"), None);
+        assert_eq!(
+            parse_command("export json tasks.json -f redact"),
+            Some(Command::Export(
+                ExportFormat::Json,
+                ExportTarget {
+                    path: PathBuf::from("tasks.json"),
+                    overwrite: true,
+                    redact: true,
+                }
+            ))
+        );
+        assert_eq!(parse_command("export json"), None);
+        assert_eq!(
+            parse_command("import json tasks.json"),
+            Some(Command::Import(
+                ExportFormat::Json,
+                PathBuf::from("tasks.json"),
+                false
+            ))
+        );
+        assert_eq!(
+            parse_command("import json tasks.json --replace"),
+            Some(Command::Import(
+                ExportFormat::Json,
+                PathBuf::from("tasks.json"),
+                true
+            ))
+        );
+        assert_eq!(parse_command("import json tasks.json -f"), None);
+        assert_eq!(parse_command("export xml tasks.xml"), None);
+        assert!(matches!(
+            parse_command("export ics plan.ics redact"),
+            Some(Command::Export(
+                ExportFormat::Ics,
+                ExportTarget { redact: true, .. }
+            ))
+        ));
+        assert_eq!(parse_command("import ics plan.ics"), None);
+        assert_eq!(
+            parse_command("export md -"),
+            Some(Command::Export(
+                ExportFormat::Markdown(date(3, 6), false),
+                ExportTarget {
+                    path: PathBuf::from("-"),
+                    overwrite: false,
+                    redact: false,
+                }
+            ))
+        );
+        assert!(matches!(
+            parse_command("export md 2024-03-04 b plan.md -f"),
+            Some(Command::Export(
+                ExportFormat::Markdown(date, true),
+                ExportTarget { overwrite: true, .. }
+            )) if date == NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+        ));
+        assert_eq!(parse_command("export md <=0 plan.md"), None);
+        assert_eq!(parse_command("export md"), None);
+        assert_eq!(parse_command("import md plan.md"), None);
+        assert_eq!(
+            parse_command("import todotxt todo.txt --replace"),
+            Some(Command::Import(
+                ExportFormat::TodoTxt,
+                PathBuf::from("todo.txt"),
+                true
+            ))
+        );
+        assert_eq!(parse_command("export todotxt todo.txt"), None);
+        assert_eq!(parse_command("export json tasks.json --force"), None);
+        assert_eq!(parse_command("report day 1 2"), None);
+        assert_eq!(
+            parse_command("maintain --dry-run"),
+            Some(Command::Maintain(true))
+        );
+        assert_eq!(parse_command("s 1"), Some(Command::Start(1)));
+        assert_eq!(parse_command("start 2"), Some(Command::Start(2)));
+        assert_eq!(parse_command("start a"), None);
+        assert_eq!(parse_command("c 1"), Some(Command::Complete(1)));
+        assert_eq!(parse_command("complete 2"), Some(Command::Complete(2)));
+        assert_eq!(parse_command("complete a"), None);
+        assert_eq!(parse_command("d 1"), None);
+        assert_eq!(parse_command("delete 2"), Some(Command::Delete(2)));
+        assert_eq!(parse_command("restore 2"), Some(Command::Restore(2)));
+        assert_eq!(parse_command("restore"), None);
+        assert_eq!(parse_command("purge"), Some(Command::Purge));
+        assert_eq!(parse_command("pause 2"), Some(Command::Pause(2)));
+        assert_eq!(
+            parse_command("archive"),
+            Some(Command::Archive(DateFilterOp::Earlier, date(2, 5)))
+        );
+        assert_eq!(
+            parse_command("archive <=-7"),
+            Some(Command::Archive(DateFilterOp::EarlierEqual, date(2, 28)))
+        );
+        assert_eq!(parse_command("archive old"), None);
+        assert_eq!(
+            parse_command("ls -a b"),
+            Some(Command::List(ListOption {
+                include_backlog: true,
+                include_archived: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ll archived"), None);
+        assert_eq!(parse_command("note 2"), Some(Command::Note(2)));
+        assert_eq!(parse_command("show 2"), Some(Command::Show(2)));
+        assert!(Command::Show(2).is_read_only());
+        assert_eq!(parse_command("resume 2"), Some(Command::Resume(2)));
+        assert_eq!(parse_command("delete a"), None);
+        assert_eq!(parse_command("e 1"), Some(Command::Edit(1)));
+        assert_eq!(parse_command("s"), Some(Command::Pick(TaskAction::Start)));
+        assert_eq!(
+            parse_command("complete "),
+            Some(Command::Pick(TaskAction::Complete))
+        );
+        assert_eq!(
+            parse_command("delete"),
+            Some(Command::Pick(TaskAction::Delete))
+        );
+        assert_eq!(parse_command("e"), Some(Command::Pick(TaskAction::Edit)));
+        assert_eq!(parse_command("d"), None);
+        assert_eq!(parse_command("edit 2"), Some(Command::Edit(2)));
+        assert_eq!(
+            parse_command("e 2 desc review  PR"),
+            Some(Command::EditInline(
+                2,
+                TaskEdit::Description("review PR".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_command("e 2 tags +work,home"),
+            Some(Command::EditInline(
+                2,
+                TaskEdit::Tags(vec!["work".to_string(), "home".to_string()])
+            ))
+        );
+        assert_eq!(
+            parse_command("e 2 tags -"),
+            Some(Command::EditInline(2, TaskEdit::Tags(vec![])))
+        );
+        assert_eq!(
+            parse_command("e 2 priority high"),
+            Some(Command::EditInline(2, TaskEdit::Priority(3)))
+        );
+        assert_eq!(
+            parse_command("e 2 priority 1"),
+            Some(Command::EditInline(2, TaskEdit::Priority(1)))
+        );
+        assert_eq!(parse_command("e 2 priority 4"), None);
+        assert_eq!(
+            parse_command("edit 2 plan 14:00+45"),
+            Some(Command::EditInline(
+                2,
+                TaskEdit::Plan(NaiveTime::from_hms_opt(14, 0, 0).unwrap(), 45)
+            ))
+        );
+        assert_eq!(parse_command("e 2 plan 14:00"), None);
+        assert_eq!(parse_command("e 2 color red"), None);
+        assert!(!parse_command("e 2 desc x").unwrap().is_interactive());
+        assert!(parse_command("e 2").unwrap().is_interactive());
+        assert_eq!(parse_command("private 4"), Some(Command::TogglePrivate(4)));
+        assert_eq!(
+            parse_command("postpone 3 30m"),
+            Some(Command::Postpone(3, Duration::minutes(30)))
+        );
+        assert_eq!(
+            parse_command("postpone 3 -2h"),
+            Some(Command::Postpone(3, Duration::hours(-2)))
+        );
+        assert_eq!(parse_command("postpone 3"), None);
+        assert_eq!(parse_command("defer"), Some(Command::Defer(date(3, 7))));
+        assert_eq!(
+            parse_command("defer 2024-03-06"),
+            Some(Command::Defer(date(3, 6)))
+        );
+        assert_eq!(parse_command("defer soon"), None);
+        assert_eq!(
+            parse_command("template save alerts 3"),
+            Some(Command::Template(TemplateAction::Save(
+                "alerts".to_string(),
+                3
+            )))
+        );
+        assert_eq!(
+            parse_command("template list"),
+            Some(Command::Template(TemplateAction::List))
+        );
+        assert_eq!(
+            parse_command("template delete alerts"),
+            Some(Command::Template(TemplateAction::Delete(
+                "alerts".to_string()
+            )))
+        );
+        assert_eq!(parse_command("template save alerts"), None);
+        assert_eq!(
+            parse_command("n @alerts"),
+            Some(Command::NewFromTemplate("alerts".to_string()))
+        );
+        // still a task planned inline
+        assert!(matches!(
+            parse_command("n review @14:00+45"),
+            Some(Command::NewInline(..))
+        ));
+        assert_eq!(parse_command("postpone 3 2w"), None);
+        assert_eq!(parse_command("edit a"), None);
+        assert_eq!(
+            parse_command("grep the Invoice"),
+            Some(Command::Search("the Invoice".to_string()))
+        );
+        assert_eq!(
+            parse_command("/ invoice "),
+            Some(Command::Search("invoice".to_string()))
+        );
+        assert_eq!(
+            parse_command("/invoice"),
+            Some(Command::Search("invoice".to_string()))
+        );
+        assert_eq!(parse_command("grep"), None);
+        assert_eq!(
+            parse_command("b  buy milk "),
+            Some(Command::Capture("buy milk".to_string()))
+        );
+        assert_eq!(parse_command("b"), None);
+        assert_eq!(
+            parse_command("set hours 10 22"),
+            Some(Command::SetHours(10, 22))
+        );
+        assert_eq!(parse_command("set hours 14 2"), None);
+        assert_eq!(parse_command("set hours 10"), None);
+        assert_eq!(parse_command("/"), None);
+        assert_eq!(
+            parse_command("ls"),
+            Some(Command::List(ListOption::default()))
+        );
+        let date_filter = (DateFilterOp::LaterEqual, date(3, 7));
+        assert_eq!(
+            parse_command("ls >=1"),
+            Some(Command::List(ListOption {
+                date_filter,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ls >=1 b"),
+            Some(Command::List(ListOption {
+                date_filter,
+                include_backlog: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
             parse_command("ls bv >=1"),
             Some(Command::List(ListOption {
                 date_filter,
@@ -297,6 +2480,25 @@ mod tests {
                 ..ListOption::default()
             }))
         );
+        assert_eq!(
+            parse_command("ls dv"),
+            Some(Command::List(ListOption {
+                is_verbose: true,
+                include_deleted: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ls <=0 bj"),
+            Some(Command::List(ListOption {
+                date_filter: (DateFilterOp::EarlierEqual, date(3, 6)),
+                include_backlog: true,
+                is_json: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ll j"), None);
+        assert_eq!(parse_command("ls j -a"), None);
         assert_eq!(
             parse_command("ll"),
             Some(Command::List(ListOption {
@@ -308,13 +2510,7 @@ mod tests {
         assert_eq!(
             parse_command("ll b 1"),
             Some(Command::List(ListOption {
-                date_filter: (
-                    DateFilterOp::Equal,
-                    Local::now()
-                        .date_naive()
-                        .checked_add_days(Days::new(1))
-                        .unwrap(),
-                ),
+                date_filter: (DateFilterOp::Equal, date(3, 7),),
                 include_backlog: true,
                 has_timeline: true,
                 ..ListOption::default()
@@ -329,5 +2525,110 @@ mod tests {
                 ..ListOption::default()
             }))
         );
+        assert_eq!(
+            parse_command("ls +work b"),
+            Some(Command::List(ListOption {
+                include_backlog: true,
+                tag: Some("work".to_string()),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ls +1 +work"),
+            Some(Command::List(ListOption {
+                date_filter: (DateFilterOp::Equal, date(3, 7),),
+                tag: Some("work".to_string()),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ls +"), None);
+        assert_eq!(
+            parse_command("ls @overdue v >=1"),
+            Some(Command::List(ListOption {
+                date_filter,
+                is_verbose: true,
+                status: Some(TaskStatus::Overdue),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ll @done"),
+            Some(Command::List(ListOption {
+                has_timeline: true,
+                status: Some(TaskStatus::Complete),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ls @later"), None);
+        assert_eq!(
+            remove_invalid_list_args("ls @ongoing someday"),
+            Some("ls @ongoing".to_string())
+        );
+        assert_eq!(
+            parse_command("ls pb"),
+            Some(Command::List(ListOption {
+                include_backlog: true,
+                only_prioritized: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ll svg! report.svg"),
+            Some(Command::List(ListOption {
+                has_timeline: true,
+                svg_export: Some(ExportTarget {
+                    path: PathBuf::from("report.svg"),
+                    overwrite: true,
+                    redact: false,
+                }),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(
+            parse_command("ll svg report.svg redact"),
+            Some(Command::List(ListOption {
+                has_timeline: true,
+                svg_export: Some(ExportTarget {
+                    path: PathBuf::from("report.svg"),
+                    overwrite: false,
+                    redact: true,
+                }),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ll redact"), None);
+        assert_eq!(parse_command("ll svg"), None);
+        assert_eq!(parse_command("ls svg report.svg"), None);
+        assert_eq!(
+            parse_command("ll -r 5 -1"),
+            Some(Command::List(ListOption {
+                has_timeline: true,
+                tick_minutes: Some(5),
+                date_filter: (DateFilterOp::Equal, date(3, 5)),
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ll -r 7"), None);
+        assert_eq!(parse_command("ll -r"), None);
+        assert_eq!(parse_command("ls -r 15"), None);
+        assert_eq!(
+            parse_command("ll paired b"),
+            Some(Command::List(ListOption {
+                has_timeline: true,
+                paired_rows: true,
+                include_backlog: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ls paired"), None);
+        assert_eq!(
+            parse_command("ls @stale"),
+            Some(Command::List(ListOption {
+                status: Some(TaskStatus::Backlog),
+                only_stale: true,
+                ..ListOption::default()
+            }))
+        );
+        assert_eq!(parse_command("ll @stale"), None);
     }
 }
@@ -0,0 +1,208 @@
+use inquire::autocompletion::{Autocomplete, Replacement};
+use inquire::CustomUserError;
+
+use arenta::command::{try_parse_task_action, COMMAND_NAMES};
+use arenta::manager::is_candidate;
+use arenta::task::Task;
+
+const DESCRIPTION_MAX_LEN: usize = 30;
+
+// completes command names and earlier commands, and task indices after commands on a single
+// task, offering only the tasks the command makes sense for; built anew for every prompt
+#[derive(Clone)]
+pub struct CommandCompleter {
+    // the suggestion shown, e.g. `c 3  write report`, and the input it's completed to
+    suggestions: Vec<(String, String)>,
+    tasks: Vec<Task>,
+    // commands typed before, the latest first without repetition
+    history: Vec<String>,
+}
+
+impl CommandCompleter {
+    pub fn new(tasks: &[Task], history: &[String]) -> Self {
+        let mut distinct: Vec<String> = vec![];
+        for line in history.iter().rev() {
+            if !distinct.contains(line) {
+                distinct.push(line.clone());
+            }
+        }
+        CommandCompleter {
+            suggestions: vec![],
+            tasks: tasks.to_vec(),
+            history: distinct,
+        }
+    }
+
+    fn suggest(&self, input: &str) -> Vec<(String, String)> {
+        if input.trim().is_empty() {
+            return vec![];
+        }
+        // `!` recalls earlier commands containing what follows it
+        if let Some(pattern) = input.strip_prefix('!') {
+            return self
+                .history
+                .iter()
+                .filter(|line| line.contains(pattern.trim()))
+                .map(|line| (line.clone(), line.clone()))
+                .collect();
+        }
+        let recalled = self
+            .history
+            .iter()
+            .filter(|line| line.starts_with(input) && *line != input)
+            .map(|line| (line.clone(), line.clone()));
+        let Some((command, index)) = input.split_once(' ') else {
+            return COMMAND_NAMES
+                .iter()
+                .filter(|name| name.starts_with(input) && **name != input)
+                .map(|name| (name.to_string(), format!("{name} ")))
+                .chain(recalled)
+                .collect();
+        };
+        let Some(action) = try_parse_task_action(command) else {
+            return recalled.collect();
+        };
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| is_candidate(action, task))
+            .map(|(i, task)| (i.to_string(), task))
+            .filter(|(i, _)| i.starts_with(index.trim()) && *i != index.trim())
+            .map(|(i, task)| {
+                (
+                    format!("{command} {i}  {}", truncate(&task.description)),
+                    format!("{command} {i}"),
+                )
+            })
+            .collect()
+    }
+}
+
+fn truncate(description: &str) -> String {
+    match description.chars().count() > DESCRIPTION_MAX_LEN {
+        true => {
+            let truncated: String = description.chars().take(DESCRIPTION_MAX_LEN).collect();
+            format!("{}…", truncated.trim_end())
+        }
+        false => description.to_string(),
+    }
+}
+
+impl Autocomplete for CommandCompleter {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        self.suggestions = self.suggest(input);
+        Ok(self
+            .suggestions
+            .iter()
+            .map(|(suggestion, _)| suggestion.clone())
+            .collect())
+    }
+
+    // the highlighted suggestion, or the only one if none is highlighted
+    fn get_completion(
+        &mut self,
+        _: &str,
+        highlighted: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        let completion = match highlighted {
+            Some(highlighted) => self
+                .suggestions
+                .iter()
+                .find(|(suggestion, _)| *suggestion == highlighted),
+            None if self.suggestions.len() == 1 => self.suggestions.first(),
+            None => None,
+        };
+        Ok(completion.map(|(_, completion)| completion.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completer() -> CommandCompleter {
+        let mut complete = Task::new_backlog_task("done already");
+        complete.complete();
+        let mut deleted = Task::new_backlog_task("deleted");
+        deleted.delete();
+        CommandCompleter::new(
+            &[
+                Task::new_backlog_task("read a very long paper about task management"),
+                Task::new_immediate_task("write report"),
+                complete,
+                deleted,
+            ],
+            &[
+                "ls v".to_string(),
+                "stats tags".to_string(),
+                "ls v".to_string(),
+            ],
+        )
+    }
+
+    fn suggested(input: &str) -> Vec<String> {
+        completer().get_suggestions(input).unwrap()
+    }
+
+    #[test]
+    fn test_suggest_command_names() {
+        assert_eq!(suggested("st"), vec!["start", "stats", "stats tags"]);
+        assert_eq!(suggested("pu"), vec!["purge"]);
+        assert!(suggested("").is_empty());
+        assert_eq!(suggested("ls"), vec!["ls v"]);
+        assert!(suggested("x").is_empty());
+    }
+
+    #[test]
+    fn test_suggest_indices() {
+        // an ongoing task isn't started again, and a complete one isn't completed again
+        assert_eq!(
+            suggested("s "),
+            vec!["s 0  read a very long paper about t…"]
+        );
+        assert_eq!(
+            suggested("complete "),
+            vec![
+                "complete 0  read a very long paper about t…",
+                "complete 1  write report"
+            ]
+        );
+        // deleted tasks are never offered
+        assert_eq!(suggested("e ").len(), 3);
+        assert!(!suggested("delete ")
+            .iter()
+            .any(|s| s.starts_with("delete 3")));
+        // nothing is left to complete once the index is typed
+        assert!(suggested("c 1").is_empty());
+        assert_eq!(suggested("ls "), vec!["ls v"]);
+    }
+
+    #[test]
+    fn test_recall_history() {
+        // the latest first, without repetition
+        assert_eq!(suggested("!"), vec!["ls v", "stats tags"]);
+        assert_eq!(suggested("!tag"), vec!["stats tags"]);
+        assert!(suggested("!grep").is_empty());
+        assert_eq!(suggested("l"), vec!["ll", "log", "ls", "ls v"]);
+    }
+
+    #[test]
+    fn test_completion() {
+        let mut completer = completer();
+        completer.get_suggestions("c ").unwrap();
+        assert_eq!(
+            completer
+                .get_completion("c ", Some("c 1  write report".to_string()))
+                .unwrap(),
+            Some("c 1".to_string())
+        );
+        assert_eq!(completer.get_completion("c ", None).unwrap(), None);
+        completer.get_suggestions("sta").unwrap();
+        assert_eq!(completer.get_completion("sta", None).unwrap(), None);
+        completer.get_suggestions("pur").unwrap();
+        assert_eq!(
+            completer.get_completion("pur", None).unwrap(),
+            Some("purge ".to_string())
+        );
+    }
+}
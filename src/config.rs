@@ -0,0 +1,643 @@
+use crate::working::{format_days, parse_days, DEFAULT_WORKING_DAYS};
+use chrono::format::{Item, StrftimeItems};
+use chrono::Weekday;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_TIMELINE_HOURS: (u32, u32) = (8, 20);
+pub const DEFAULT_SLOW_COMMAND_THRESHOLD: Duration = Duration::from_millis(250);
+pub const DEFAULT_TIME_FORMAT: &str = "%F %R";
+pub const DEFAULT_BACKLOG_STALE_DAYS: u32 = 30;
+pub const DEFAULT_BACKUPS: usize = 3;
+pub const DEFAULT_TRASH_DAYS: u32 = 30;
+
+const KEYS: [&str; 18] = [
+    "data_file",
+    "timeline_start_hour",
+    "timeline_end_hour",
+    "timeline_tick_minutes",
+    "time_format",
+    "color",
+    "plain",
+    "hyperlinks",
+    "slow_command_ms",
+    "color_key",
+    "backlog_stale_days",
+    "working_hours",
+    "working_days",
+    "startup_overview",
+    "backups",
+    "trash_days",
+    "sync",
+    "storage",
+];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ColorMode {
+    // colored decides by itself, e.g. by `NO_COLOR` and `CLICOLOR`
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMode::Auto => "auto",
+            ColorMode::Always => "always",
+            ColorMode::Never => "never",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SyncMode {
+    Off,
+    // the data file is committed to the git work tree it lives in
+    Git,
+}
+
+impl SyncMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncMode::Off => "off",
+            SyncMode::Git => "git",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StorageKind {
+    // the data file itself, with a journal next to it
+    Csv,
+    // a database next to the data file, e.g. `default.db` for `default.csv`
+    Sqlite,
+}
+
+impl StorageKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageKind::Csv => "csv",
+            StorageKind::Sqlite => "sqlite",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(StorageKind::Csv),
+            "sqlite" => Some(StorageKind::Sqlite),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    // the data file, `<data dir>/<profile>.csv` if not set
+    pub data_file: Option<PathBuf>,
+    pub timeline_hours: (u32, u32),
+    // minutes per column of the timeline, which fits the terminal width if not set
+    pub timeline_tick_minutes: Option<u32>,
+    pub time_format: String,
+    pub color: ColorMode,
+    // no escape sequences at all, with statuses told by text
+    pub plain: bool,
+    pub hyperlinks: bool,
+    // commands taking longer than this are reported
+    pub slow_command_threshold: Duration,
+    // a line telling what the colors of the timeline stand for
+    pub color_key: bool,
+    // backlog tasks older than this turn red and are listed by `ls @stale`
+    pub backlog_stale_days: u32,
+    // overdue times and delays count only working time if set
+    pub working_hours: Option<(u32, u32)>,
+    pub working_days: Vec<Weekday>,
+    // a digest of statuses and the timeline of today before the first prompt
+    pub startup_overview: bool,
+    // how many copies of the data file are kept before it's rewritten, none if zero
+    pub backups: usize,
+    // deleted tasks are purged by maintenance this many days after, kept until `purge` if zero
+    pub trash_days: u32,
+    // how `sync` shares the tasks
+    pub sync: SyncMode,
+    // where tasks are kept
+    pub storage: StorageKind,
+    // the file the config is loaded from, and the keys set in it
+    pub origin: Option<PathBuf>,
+    set_keys: Vec<&'static str>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            data_file: None,
+            timeline_hours: DEFAULT_TIMELINE_HOURS,
+            timeline_tick_minutes: None,
+            time_format: DEFAULT_TIME_FORMAT.to_string(),
+            color: ColorMode::Auto,
+            plain: false,
+            hyperlinks: true,
+            slow_command_threshold: DEFAULT_SLOW_COMMAND_THRESHOLD,
+            color_key: true,
+            backlog_stale_days: DEFAULT_BACKLOG_STALE_DAYS,
+            working_hours: None,
+            working_days: DEFAULT_WORKING_DAYS.to_vec(),
+            startup_overview: true,
+            backups: DEFAULT_BACKUPS,
+            trash_days: DEFAULT_TRASH_DAYS,
+            sync: SyncMode::Off,
+            storage: StorageKind::Csv,
+            origin: None,
+            set_keys: vec![],
+        }
+    }
+}
+
+impl Config {
+    // reads `~/.config/arenta/config.toml`, or `~/.arentarc` written by older versions if the
+    // former doesn't exist; unknown keys and invalid values are reported and ignored so that
+    // a typo never prevents arenta from starting
+    pub fn load() -> Self {
+        let config = Config::default();
+        let Some(path) = [get_config_file_path(), get_legacy_config_file_path()]
+            .into_iter()
+            .flatten()
+            .find(|path| path.exists())
+        else {
+            return config;
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return config;
+        };
+        Config::parse(&content, &path)
+    }
+
+    // only a flat subset of toml is supported, i.e. `key = value` lines and comments
+    fn parse(content: &str, path: &Path) -> Self {
+        let mut config = Config {
+            origin: Some(path.to_path_buf()),
+            ..Config::default()
+        };
+        for (line_index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Err(reason) = config.apply_line(line) {
+                eprintln!("{}:{}: {reason}", path.display(), line_index + 1);
+            }
+        }
+        // the hours may be set one by one, so they are checked together at last
+        let (start, end) = config.timeline_hours;
+        if let Err(reason) = parse_timeline_hours(&start.to_string(), &end.to_string()) {
+            eprintln!("{}: {reason}", path.display());
+            config.timeline_hours = DEFAULT_TIMELINE_HOURS;
+            config.set_keys.retain(|key| !key.starts_with("timeline_"));
+        }
+        config
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), String> {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| "expected `key = value`".to_string())?;
+        let value = parse_value(value)?;
+        let key = match key.trim() {
+            "data_file" => {
+                self.data_file = Some(expand_home(&value)?);
+                "data_file"
+            }
+            // written by older versions as `hours = <start> <end>`
+            "hours" => {
+                let hours: Vec<&str> = value.split_whitespace().collect();
+                if hours.len() != 2 {
+                    return Err("expected `hours = <start> <end>`".to_string());
+                }
+                self.timeline_hours = parse_timeline_hours(hours[0], hours[1])?;
+                self.set_keys.push("timeline_start_hour");
+                "timeline_end_hour"
+            }
+            "timeline_start_hour" => {
+                self.timeline_hours.0 = parse_hour(&value)?;
+                "timeline_start_hour"
+            }
+            "timeline_end_hour" => {
+                self.timeline_hours.1 = parse_hour(&value)?;
+                "timeline_end_hour"
+            }
+            "timeline_tick_minutes" => {
+                self.timeline_tick_minutes = Some(parse_tick_minutes(&value)?);
+                "timeline_tick_minutes"
+            }
+            "time_format" => {
+                if value.is_empty()
+                    || StrftimeItems::new(&value).any(|item| matches!(item, Item::Error))
+                {
+                    return Err(format!("invalid time format `{value}`"));
+                }
+                self.time_format = value;
+                "time_format"
+            }
+            "color" => {
+                self.color = match value.as_str() {
+                    "auto" => ColorMode::Auto,
+                    "always" => ColorMode::Always,
+                    "never" => ColorMode::Never,
+                    _ => return Err("expected `color = auto`, `always` or `never`".to_string()),
+                };
+                "color"
+            }
+            "plain" => {
+                self.plain = parse_bool("plain", &value)?;
+                "plain"
+            }
+            "slow_command_ms" => {
+                let ms = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid milliseconds `{value}`"))?;
+                self.slow_command_threshold = Duration::from_millis(ms);
+                "slow_command_ms"
+            }
+            "hyperlinks" => {
+                // `on` and `off` are written by older versions
+                self.hyperlinks = match value.as_str() {
+                    "on" => true,
+                    "off" => false,
+                    value => parse_bool("hyperlinks", value)?,
+                };
+                "hyperlinks"
+            }
+            "backlog_stale_days" => {
+                self.backlog_stale_days = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|days| *days > 0)
+                    .ok_or_else(|| format!("invalid days `{value}`"))?;
+                "backlog_stale_days"
+            }
+            "working_hours" => {
+                let (start, end) = value
+                    .split_once('-')
+                    .ok_or_else(|| "expected `working_hours = <start>-<end>`".to_string())?;
+                self.working_hours = Some(parse_timeline_hours(start.trim(), end.trim())?);
+                "working_hours"
+            }
+            "working_days" => {
+                self.working_days = parse_days(&value)?;
+                "working_days"
+            }
+            "startup_overview" => {
+                self.startup_overview = parse_bool("startup_overview", &value)?;
+                "startup_overview"
+            }
+            "backups" => {
+                self.backups = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid number of backups `{value}`"))?;
+                "backups"
+            }
+            "trash_days" => {
+                self.trash_days = value
+                    .parse::<u32>()
+                    .map_err(|_| format!("invalid days `{value}`"))?;
+                "trash_days"
+            }
+            "sync" => {
+                self.sync = match value.as_str() {
+                    "off" => SyncMode::Off,
+                    "git" => SyncMode::Git,
+                    _ => return Err("expected `sync = off` or `git`".to_string()),
+                };
+                "sync"
+            }
+            "storage" => {
+                self.storage = StorageKind::parse(&value)
+                    .ok_or_else(|| "expected `storage = csv` or `sqlite`".to_string())?;
+                "storage"
+            }
+            "color_key" => {
+                self.color_key = parse_bool("color_key", &value)?;
+                "color_key"
+            }
+            key => return Err(format!("unknown key `{key}`")),
+        };
+        self.set_keys.push(key);
+        Ok(())
+    }
+
+    // always saved as `config.toml` with every setting, which takes over an old `~/.arentarc`
+    pub fn save(&mut self) -> Result<(), String> {
+        let path = get_config_file_path().ok_or("cannot find config directory")?;
+        let mut content = String::new();
+        if let Some(data_file) = &self.data_file {
+            content.push_str(&format!(
+                "data_file = {}\n",
+                quote(&data_file.to_string_lossy())
+            ));
+        }
+        if let Some(minutes) = self.timeline_tick_minutes {
+            content.push_str(&format!("timeline_tick_minutes = {minutes}\n"));
+        }
+        if let Some((start, end)) = self.working_hours {
+            content.push_str(&format!("working_hours = \"{start}-{end}\"\n"));
+        }
+        content.push_str(&format!(
+            "timeline_start_hour = {}\ntimeline_end_hour = {}\ntime_format = {}\ncolor = {}\nplain = {}\nhyperlinks = {}\nslow_command_ms = {}\ncolor_key = {}\nbacklog_stale_days = {}\nworking_days = {}\nstartup_overview = {}\nbackups = {}\ntrash_days = {}\nsync = {}\nstorage = {}\n",
+            self.timeline_hours.0,
+            self.timeline_hours.1,
+            quote(&self.time_format),
+            quote(self.color.name()),
+            self.plain,
+            self.hyperlinks,
+            self.slow_command_threshold.as_millis(),
+            self.color_key,
+            self.backlog_stale_days,
+            quote(&format_days(&self.working_days)),
+            self.startup_overview,
+            self.backups,
+            self.trash_days,
+            quote(self.sync.name()),
+            quote(self.storage.name())
+        ));
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, &content))
+            .map_err(|e| format!("failed to save {}: {e}", path.display()))?;
+        self.set_keys = content
+            .lines()
+            .filter_map(|line| KEYS.iter().copied().find(|key| line.starts_with(key)))
+            .collect();
+        self.origin = Some(path);
+        Ok(())
+    }
+
+    // every setting in effect, together with where it comes from
+    pub fn describe(&self, data_file: &Path) -> Vec<(&'static str, String, String)> {
+        let origin = |key: &str| match (&self.origin, self.set_keys.contains(&key)) {
+            (Some(path), true) => format!("from {}", path.display()),
+            _ => "default".to_string(),
+        };
+        [
+            ("data_file", data_file.display().to_string()),
+            ("timeline_start_hour", self.timeline_hours.0.to_string()),
+            ("timeline_end_hour", self.timeline_hours.1.to_string()),
+            (
+                "timeline_tick_minutes",
+                self.timeline_tick_minutes
+                    .map_or("fit terminal".to_string(), |minutes| minutes.to_string()),
+            ),
+            ("time_format", self.time_format.clone()),
+            ("color", self.color.name().to_string()),
+            ("plain", self.plain.to_string()),
+            ("hyperlinks", self.hyperlinks.to_string()),
+            (
+                "slow_command_ms",
+                self.slow_command_threshold.as_millis().to_string(),
+            ),
+            ("color_key", self.color_key.to_string()),
+            ("backlog_stale_days", self.backlog_stale_days.to_string()),
+            (
+                "working_hours",
+                self.working_hours
+                    .map_or("wall clock".to_string(), |(start, end)| {
+                        format!("{start}-{end}")
+                    }),
+            ),
+            ("working_days", format_days(&self.working_days)),
+            ("startup_overview", self.startup_overview.to_string()),
+            ("backups", self.backups.to_string()),
+            ("trash_days", self.trash_days.to_string()),
+            ("sync", self.sync.name().to_string()),
+            ("storage", self.storage.name().to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key, value, origin(key)))
+        .collect()
+    }
+}
+
+pub fn parse_timeline_hours(start: &str, end: &str) -> Result<(u32, u32), String> {
+    let start = start
+        .parse::<u32>()
+        .map_err(|_| format!("invalid start hour `{start}`"))?;
+    let end = end
+        .parse::<u32>()
+        .map_err(|_| format!("invalid end hour `{end}`"))?;
+    if end > 24 {
+        Err("hours should be within 0-24".to_string())
+    } else if start >= end {
+        Err(
+            "start hour should be earlier than end hour, spanning midnight is not supported"
+                .to_string(),
+        )
+    } else {
+        Ok((start, end))
+    }
+}
+
+// the minutes should divide an hour, so that every hour starts at a column
+pub fn parse_tick_minutes(value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|minutes| (1..=60).contains(minutes) && 60 % minutes == 0)
+        .ok_or_else(|| {
+            format!("invalid tick `{value}`, should be minutes dividing an hour, e.g. 5, 10 or 15")
+        })
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    value
+        .parse::<bool>()
+        .map_err(|_| format!("expected `{key} = true` or `{key} = false`"))
+}
+
+fn parse_hour(value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|hour| *hour <= 24)
+        .ok_or_else(|| format!("invalid hour `{value}`, should be within 0-24"))
+}
+
+// a basic toml string in double quotes, or a bare value such as a number or a boolean,
+// optionally followed by a comment
+fn parse_value(raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    let Some(quoted) = raw.strip_prefix('"') else {
+        let value = raw.split('#').next().unwrap_or_default().trim();
+        return Ok(value.to_string());
+    };
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    loop {
+        match chars.next() {
+            None => return Err(format!("unterminated string `{raw}`")),
+            Some('"') => break,
+            Some('\\') => match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some(c) => return Err(format!("unsupported escape `\\{c}`")),
+                None => return Err(format!("unterminated string `{raw}`")),
+            },
+            Some(c) => value.push(c),
+        }
+    }
+    let rest = chars.as_str().trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected `{rest}` after the string"));
+    }
+    Ok(value)
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn expand_home(path: &str) -> Result<PathBuf, String> {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .ok_or_else(|| "cannot find home directory".to_string()),
+        None if path.is_empty() => Err("the data file should not be empty".to_string()),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+fn get_config_file_path() -> Option<PathBuf> {
+    let mut config_file = dirs::config_dir()?;
+    config_file.push("arenta");
+    config_file.push("config.toml");
+    Some(config_file)
+}
+
+fn get_legacy_config_file_path() -> Option<PathBuf> {
+    let mut config_file = dirs::home_dir()?;
+    config_file.push(".arentarc");
+    Some(config_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeline_hours() {
+        assert_eq!(parse_timeline_hours("10", "22"), Ok((10, 22)));
+        assert_eq!(parse_timeline_hours("0", "24"), Ok((0, 24)));
+        assert!(parse_timeline_hours("14", "2").is_err());
+        assert!(parse_timeline_hours("8", "8").is_err());
+        assert!(parse_timeline_hours("8", "25").is_err());
+        assert!(parse_timeline_hours("a", "20").is_err());
+    }
+
+    #[test]
+    fn test_apply_line() {
+        let mut config = Config::default();
+        assert_eq!(config.apply_line("hours = 10 22"), Ok(()));
+        assert_eq!(config.timeline_hours, (10, 22));
+        assert!(config.apply_line("hours = 10").is_err());
+        assert!(config.apply_line("colors = none").is_err());
+        assert!(config.apply_line("hours").is_err());
+        assert_eq!(config.timeline_hours, (10, 22));
+        assert_eq!(config.apply_line("hyperlinks = off"), Ok(()));
+        assert!(!config.hyperlinks);
+        assert_eq!(
+            config.apply_line("hyperlinks = no"),
+            Err("expected `hyperlinks = true` or `hyperlinks = false`".to_string())
+        );
+        assert_eq!(config.apply_line("plain = true"), Ok(()));
+        assert!(config.plain);
+        assert_eq!(config.apply_line("slow_command_ms = 1000"), Ok(()));
+        assert_eq!(config.slow_command_threshold, Duration::from_secs(1));
+        assert!(config.apply_line("slow_command_ms = 1s").is_err());
+        assert_eq!(config.apply_line("timeline_tick_minutes = 15"), Ok(()));
+        assert_eq!(config.timeline_tick_minutes, Some(15));
+        assert!(config.apply_line("timeline_tick_minutes = 7").is_err());
+        assert!(config.apply_line("timeline_tick_minutes = 0").is_err());
+        assert_eq!(config.apply_line("color_key = false"), Ok(()));
+        assert!(!config.color_key);
+        assert_eq!(config.apply_line("startup_overview = false"), Ok(()));
+        assert!(!config.startup_overview);
+        assert_eq!(config.apply_line("backlog_stale_days = 14"), Ok(()));
+        assert_eq!(config.backlog_stale_days, 14);
+        assert!(config.apply_line("backlog_stale_days = 0").is_err());
+        assert_eq!(config.apply_line("backups = 0"), Ok(()));
+        assert_eq!(config.backups, 0);
+        assert!(config.apply_line("backups = -1").is_err());
+        assert_eq!(config.apply_line("trash_days = 0"), Ok(()));
+        assert_eq!(config.trash_days, 0);
+        assert!(config.apply_line("trash_days = soon").is_err());
+        assert_eq!(config.apply_line("sync = \"git\""), Ok(()));
+        assert_eq!(config.sync, SyncMode::Git);
+        assert!(config.apply_line("sync = svn").is_err());
+        assert_eq!(config.apply_line("storage = \"sqlite\""), Ok(()));
+        assert_eq!(config.storage, StorageKind::Sqlite);
+        assert!(config.apply_line("storage = json").is_err());
+        assert_eq!(config.apply_line("working_hours = \"9-18\""), Ok(()));
+        assert_eq!(config.working_hours, Some((9, 18)));
+        assert!(config.apply_line("working_hours = 18-9").is_err());
+        assert!(config.apply_line("working_hours = 9").is_err());
+        assert_eq!(config.apply_line("working_days = \"mon,wed\""), Ok(()));
+        assert_eq!(config.working_days, vec![Weekday::Mon, Weekday::Wed]);
+        assert!(config.apply_line("working_days = weekdays").is_err());
+
+        assert_eq!(config.apply_line("timeline_start_hour = 7 # early"), Ok(()));
+        assert_eq!(config.timeline_hours, (7, 22));
+        assert!(config.apply_line("timeline_end_hour = 25").is_err());
+        assert_eq!(config.apply_line("color = \"never\""), Ok(()));
+        assert_eq!(config.color, ColorMode::Never);
+        assert!(config.apply_line("color = \"sometimes\"").is_err());
+        assert_eq!(config.apply_line("time_format = \"%m/%d %H:%M\""), Ok(()));
+        assert_eq!(config.time_format, "%m/%d %H:%M");
+        assert!(config.apply_line("time_format = \"%Q\"").is_err());
+        assert_eq!(config.apply_line("data_file = \"/tmp/tasks\""), Ok(()));
+        assert_eq!(config.data_file, Some(PathBuf::from("/tmp/tasks")));
+        assert!(config.apply_line("data_file = \"/tmp/tasks").is_err());
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value(" 42 "), Ok("42".to_string()));
+        assert_eq!(parse_value("true # yes"), Ok("true".to_string()));
+        assert_eq!(parse_value("\"a # b\""), Ok("a # b".to_string()));
+        assert_eq!(
+            parse_value(r#""say \"hi\" \\ bye" # comment"#),
+            Ok(r#"say "hi" \ bye"#.to_string())
+        );
+        assert!(parse_value("\"open").is_err());
+        assert!(parse_value("\"a\" b").is_err());
+        assert!(parse_value(r#""\n""#).is_err());
+        assert_eq!(
+            parse_value(&quote(r#"C:\a "b""#)),
+            Ok(r#"C:\a "b""#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_describe_origins() {
+        let path = PathBuf::from("/home/me/.config/arenta/config.toml");
+        let config = Config::parse(
+            "# arenta\ntimeline_start_hour = 22\ncolor = \"always\"\nbogus = 1\n",
+            &path,
+        );
+        // 22-20 is an invalid range, so both hours fall back to the defaults
+        assert_eq!(config.timeline_hours, DEFAULT_TIMELINE_HOURS);
+        assert_eq!(config.color, ColorMode::Always);
+        let described = config.describe(Path::new("/home/me/.arenta"));
+        let origin_of = |key: &str| {
+            described
+                .iter()
+                .find(|(k, _, _)| *k == key)
+                .map(|(_, value, origin)| (value.as_str(), origin.as_str()))
+                .unwrap()
+        };
+        assert_eq!(
+            origin_of("color"),
+            ("always", "from /home/me/.config/arenta/config.toml")
+        );
+        assert_eq!(origin_of("timeline_start_hour"), ("8", "default"));
+        assert_eq!(origin_of("data_file"), ("/home/me/.arenta", "default"));
+    }
+}
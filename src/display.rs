@@ -0,0 +1,193 @@
+use chrono::Duration;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::OnceLock;
+use unicode_width::UnicodeWidthChar;
+
+use crate::config::{ColorMode, DEFAULT_BACKLOG_STALE_DAYS, DEFAULT_TIME_FORMAT};
+
+const ESC: char = '\x1b';
+
+// like hyperlinks, decided once on startup instead of threaded through every render
+static TIME_FORMAT: OnceLock<String> = OnceLock::new();
+static PLAIN: AtomicBool = AtomicBool::new(false);
+static BACKLOG_STALE_DAYS: AtomicU32 = AtomicU32::new(DEFAULT_BACKLOG_STALE_DAYS);
+
+// `--plain` is given before the config is loaded, so it's only ever turned on here
+pub fn init(color: ColorMode, time_format: &str, plain: bool, backlog_stale_days: u32) {
+    if plain {
+        PLAIN.store(true, Ordering::Relaxed);
+    }
+    let colors = match color {
+        _ if is_plain() => false,
+        ColorMode::Auto => {
+            std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+        }
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    };
+    colored::control::set_override(colors);
+    let _ = TIME_FORMAT.set(time_format.to_string());
+    BACKLOG_STALE_DAYS.store(backlog_stale_days, Ordering::Relaxed);
+}
+
+pub fn set_plain() {
+    PLAIN.store(true, Ordering::Relaxed);
+}
+
+// plain output has no escape sequences, for pipes and scripts
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+// how old a backlog task is when it's taken as stale
+pub fn backlog_stale_days() -> u32 {
+    BACKLOG_STALE_DAYS.load(Ordering::Relaxed)
+}
+
+// the format of times in listings and details, e.g. `%F %R`
+pub fn time_format() -> &'static str {
+    TIME_FORMAT
+        .get()
+        .map_or(DEFAULT_TIME_FORMAT, String::as_str)
+}
+
+// the characters which are displayed, skipping escape sequences for colors (CSI) and
+// hyperlinks (OSC)
+fn visible_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    let mut chars = text.chars().peekable();
+    std::iter::from_fn(move || loop {
+        let c = chars.next()?;
+        if c != ESC {
+            return Some(c);
+        }
+        match chars.next() {
+            // ends with a char in `@` to `~`, e.g. `m` for colors
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // ends with BEL or `ESC \`
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == ESC && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    })
+}
+
+// how many terminal columns the text takes up, where wide characters, e.g. CJK ones, take two
+pub fn display_width(text: &str) -> usize {
+    visible_chars(text).map(|c| c.width().unwrap_or(0)).sum()
+}
+
+// like `{: <width}`, but counts display width instead of chars
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    format!(
+        "{text}{}",
+        " ".repeat(width.saturating_sub(display_width(text)))
+    )
+}
+
+// renders the duration in the largest sensible unit, e.g. `45 minutes`, `3.5 hours`, `2 days`
+// or `3 weeks`, keeping exact minutes below 90 minutes
+pub fn humanize_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    let plural = |count: i64, unit: &str| match count {
+        1 => format!("1 {unit}"),
+        count => format!("{count} {unit}s"),
+    };
+    if minutes < 90 {
+        plural(minutes, "minute")
+    } else if minutes < 48 * 60 {
+        let hours = format!("{:.1}", minutes as f64 / 60.0);
+        format!("{} hours", hours.trim_end_matches(".0"))
+    } else if minutes < 14 * 24 * 60 {
+        plural((minutes as f64 / (24.0 * 60.0)).round() as i64, "day")
+    } else {
+        plural(
+            (minutes as f64 / (7.0 * 24.0 * 60.0)).round() as i64,
+            "week",
+        )
+    }
+}
+
+// e.g. `812 B`, `12.3 KiB` or `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    match unit {
+        "B" => format!("{bytes} B"),
+        unit => format!("{size:.1} {unit}"),
+    }
+}
+
+pub fn strip_ansi(text: &str) -> String {
+    visible_chars(text).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("plain"), 5);
+        assert_eq!(display_width("日本語 ok"), 9);
+        assert_eq!(display_width("\x1b[38;2;255;255;102mongoing\x1b[0m"), 7);
+        assert_eq!(
+            display_width("\x1b]8;;https://x.org\x1b\\https://x.org\x1b]8;;\x1b\\ !"),
+            15
+        );
+        assert_eq!(
+            pad_to_width("\x1b[1m日本\x1b[0m", 6),
+            "\x1b[1m日本\x1b[0m  "
+        );
+        assert_eq!(pad_to_width("too long", 3), "too long");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(12 * 1024 + 300), "12.3 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 / 2), "1.5 MiB");
+    }
+
+    #[test]
+    fn test_humanize_duration() {
+        let humanize = |minutes| humanize_duration(Duration::minutes(minutes));
+        assert_eq!(humanize(0), "0 minutes");
+        assert_eq!(humanize(1), "1 minute");
+        assert_eq!(humanize(89), "89 minutes");
+        assert_eq!(humanize(90), "1.5 hours");
+        assert_eq!(humanize(210), "3.5 hours");
+        assert_eq!(humanize(47 * 60), "47 hours");
+        assert_eq!(humanize(48 * 60), "2 days");
+        assert_eq!(humanize(6 * 24 * 60), "6 days");
+        assert_eq!(humanize(14 * 24 * 60 - 1), "14 days");
+        assert_eq!(humanize(14 * 24 * 60), "2 weeks");
+        assert_eq!(humanize(21 * 24 * 60), "3 weeks");
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32mdone\x1b[0m 日本"), "done 日本");
+        assert_eq!(strip_ansi("\x1b]8;;u\x1b\\link\x1b]8;;\x1b\\"), "link");
+    }
+}
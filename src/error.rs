@@ -0,0 +1,105 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ArentaError {
+    // `context` says what arenta was doing, e.g. "failed to save tasks"
+    Io { context: String, source: io::Error },
+    Parse { row: u64, reason: String },
+    Locked { pid: Option<u32> },
+    Prompt(PromptError),
+    Validation(String),
+}
+
+pub type ArentaResult<T> = Result<T, ArentaError>;
+
+// why a prompt got no answer
+#[derive(Debug)]
+pub enum PromptError {
+    // e.g. esc, which gives up the command at hand
+    Canceled,
+    // e.g. ctrl-c
+    Interrupted,
+    NotTty,
+    Failed(String),
+}
+
+impl ArentaError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        ArentaError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        ArentaError::Validation(message.into())
+    }
+
+    // a fatal error ends the interactive session, e.g. when the terminal is gone or the user
+    // presses ctrl-c, while others only fail the command at hand
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            ArentaError::Prompt(e) if !matches!(e, PromptError::Canceled)
+        )
+    }
+}
+
+impl fmt::Display for ArentaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArentaError::Io { context, source } => write!(f, "{context}: {source}"),
+            ArentaError::Parse { row, reason } => write!(f, "line {row}: {reason}"),
+            ArentaError::Locked { pid: Some(pid) } => write!(
+                f,
+                "lock file has been acquired by another process (pid {pid}) now"
+            ),
+            ArentaError::Locked { pid: None } => {
+                write!(f, "lock file has been acquired by another process now")
+            }
+            ArentaError::Prompt(PromptError::Canceled) => write!(f, "canceled"),
+            ArentaError::Prompt(PromptError::Interrupted) => write!(f, "interrupted"),
+            ArentaError::Prompt(PromptError::NotTty) => {
+                write!(f, "cannot prompt for input, stdin is not a terminal")
+            }
+            ArentaError::Prompt(PromptError::Failed(reason)) => {
+                write!(f, "prompt failed: {reason}")
+            }
+            ArentaError::Validation(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ArentaError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity_and_messages() {
+        assert!(!ArentaError::Prompt(PromptError::Canceled).is_fatal());
+        assert!(ArentaError::Prompt(PromptError::Interrupted).is_fatal());
+        assert!(ArentaError::Prompt(PromptError::NotTty).is_fatal());
+        assert!(!ArentaError::validation("index out of range").is_fatal());
+
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+        assert_eq!(
+            ArentaError::io("failed to save tasks", denied).to_string(),
+            "failed to save tasks: permission denied"
+        );
+        let malformed = ArentaError::Parse {
+            row: 3,
+            reason: "malformed timestamp `yesterday`".to_string(),
+        };
+        assert_eq!(
+            malformed.to_string(),
+            "line 3: malformed timestamp `yesterday`"
+        );
+        assert_eq!(
+            ArentaError::Locked { pid: Some(42) }.to_string(),
+            "lock file has been acquired by another process (pid 42) now"
+        );
+    }
+}
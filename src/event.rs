@@ -0,0 +1,193 @@
+use chrono::{DateTime, Local};
+use csv::WriterBuilder;
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::process;
+
+use crate::clock;
+use crate::json::JsonValue;
+use crate::task::Task;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EventKind {
+    Created,
+    Started,
+    Completed,
+    Edited,
+    Deleted,
+    Restored,
+    WentOverdue,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Started => "started",
+            EventKind::Completed => "completed",
+            EventKind::Edited => "edited",
+            EventKind::Deleted => "deleted",
+            EventKind::Restored => "restored",
+            EventKind::WentOverdue => "overdue",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Event {
+    // unique within a session, so consumers can correlate their records
+    pub id: u64,
+    pub kind: EventKind,
+    pub index: usize,
+    pub description: String,
+    // when the task was created, which tells it apart from others of the same description and
+    // stays the same across renames and moves
+    pub created_at: DateTime<Local>,
+    pub time: DateTime<Local>,
+    // the fields changed by an edit, with the old and new value
+    pub changes: Vec<(String, JsonValue, JsonValue)>,
+}
+
+pub trait Subscriber {
+    fn notify(&mut self, event: &Event);
+}
+
+pub struct EventBus {
+    subscribers: Vec<Box<dyn Subscriber>>,
+    next_id: u64,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus {
+            subscribers: vec![],
+            next_id: 0,
+        }
+    }
+
+    // subscribes the consumers enabled in the user's environment
+    pub fn from_environment() -> Self {
+        let mut bus = EventBus::new();
+        bus.subscribe(Box::new(Notifier));
+        if let Some(path) = std::env::var_os("ARENTA_AUDIT_LOG") {
+            bus.subscribe(Box::new(AuditLogger::new(PathBuf::from(path))));
+        }
+        if let Some(mut hooks_dir) = dirs::home_dir() {
+            hooks_dir.push(".arenta.hooks");
+            if hooks_dir.is_dir() {
+                bus.subscribe(Box::new(HookRunner::new(hooks_dir)));
+            }
+        }
+        bus
+    }
+
+    pub fn subscribe(&mut self, subscriber: Box<dyn Subscriber>) {
+        self.subscribers.push(subscriber);
+    }
+
+    pub fn emit(&mut self, kind: EventKind, index: usize, task: &Task) {
+        self.emit_with_changes(kind, index, task, vec![]);
+    }
+
+    pub fn emit_with_changes(
+        &mut self,
+        kind: EventKind,
+        index: usize,
+        task: &Task,
+        changes: Vec<(String, JsonValue, JsonValue)>,
+    ) {
+        let event = Event {
+            id: self.next_id,
+            kind,
+            index,
+            description: task.description.clone(),
+            created_at: task.created_at,
+            time: clock::now(),
+            changes,
+        };
+        self.next_id += 1;
+        self.subscribers
+            .iter_mut()
+            .for_each(|subscriber| subscriber.notify(&event));
+    }
+}
+
+// tells the user about transitions that happen without a command, e.g. going overdue
+pub struct Notifier;
+
+impl Subscriber for Notifier {
+    fn notify(&mut self, event: &Event) {
+        if event.kind == EventKind::WentOverdue {
+            println!("task {} is overdue now: {}", event.index, event.description);
+        }
+    }
+}
+
+// appends every event as a csv record to the audit log
+pub struct AuditLogger {
+    path: PathBuf,
+}
+
+impl AuditLogger {
+    pub fn new(path: PathBuf) -> Self {
+        AuditLogger { path }
+    }
+}
+
+impl Subscriber for AuditLogger {
+    fn notify(&mut self, event: &Event) {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path);
+        let result = file.map_err(csv::Error::from).and_then(|file| {
+            let mut writer = WriterBuilder::new().from_writer(file);
+            writer.write_record([
+                &event.time.to_rfc3339(),
+                &event.id.to_string(),
+                event.kind.name(),
+                &event.index.to_string(),
+                &event.description,
+            ])?;
+            writer.flush().map_err(csv::Error::from)
+        });
+        if let Err(e) = result {
+            eprintln!("failed to write audit log: {e}");
+        }
+    }
+}
+
+// runs `<hooks_dir>/on-<event>` if it exists, with the event passed in environment variables
+pub struct HookRunner {
+    hooks_dir: PathBuf,
+}
+
+impl HookRunner {
+    pub fn new(hooks_dir: PathBuf) -> Self {
+        HookRunner { hooks_dir }
+    }
+}
+
+impl Subscriber for HookRunner {
+    fn notify(&mut self, event: &Event) {
+        let mut hook = self.hooks_dir.clone();
+        hook.push(format!("on-{}", event.kind.name()));
+        if !hook.is_file() {
+            return;
+        }
+        let status = process::Command::new(&hook)
+            .env("ARENTA_EVENT_ID", event.id.to_string())
+            .env("ARENTA_EVENT", event.kind.name())
+            .env("ARENTA_EVENT_TIME", event.time.to_rfc3339())
+            .env("ARENTA_TASK_INDEX", event.index.to_string())
+            .env("ARENTA_TASK_DESCRIPTION", &event.description)
+            .status();
+        match status {
+            Ok(status) if !status.success() => {
+                eprintln!("hook {} exited with {status}", hook.display())
+            }
+            Err(e) => eprintln!("failed to run hook {}: {e}", hook.display()),
+            _ => (),
+        }
+    }
+}
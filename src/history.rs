@@ -0,0 +1,405 @@
+use crate::event::{Event, EventKind, Subscriber};
+use crate::json::{parse_value, task_to_json, JsonValue};
+use crate::manager::get_sibling_file_path;
+use crate::task::Task;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// derived from other fields or changed along with them, so not worth telling
+const UNTRACKED_FIELDS: [&str; 3] = ["status", "postponed_count", "work_intervals"];
+
+// the fields which differ between the two versions of a task, as they are exported
+pub fn diff_tasks(before: &Task, after: &Task) -> Vec<(String, JsonValue, JsonValue)> {
+    let (JsonValue::Object(old), JsonValue::Object(new)) =
+        (task_to_json(before, false), task_to_json(after, false))
+    else {
+        unreachable!()
+    };
+    old.into_iter()
+        .zip(new)
+        .filter(|((field, old), (_, new))| {
+            !UNTRACKED_FIELDS.contains(&field.as_str()) && old != new
+        })
+        .map(|((field, old), (_, new))| (field, old, new))
+        .collect()
+}
+
+// appends every change made by a command to the history log, one json object per line,
+// without failing the command if it cannot
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    pub fn new(path: PathBuf) -> Self {
+        HistoryLog { path }
+    }
+}
+
+impl Subscriber for HistoryLog {
+    fn notify(&mut self, event: &Event) {
+        if event.kind == EventKind::WentOverdue {
+            return;
+        }
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", event_to_line(event)));
+        if let Err(e) = result {
+            eprintln!("failed to write history log: {e}");
+        }
+    }
+}
+
+fn event_to_line(event: &Event) -> String {
+    let changes = event
+        .changes
+        .iter()
+        .map(|(field, old, new)| {
+            (
+                field.clone(),
+                JsonValue::Array(vec![old.clone(), new.clone()]),
+            )
+        })
+        .collect();
+    JsonValue::Object(vec![
+        (
+            "time".to_string(),
+            JsonValue::String(event.time.to_rfc3339()),
+        ),
+        (
+            "op".to_string(),
+            JsonValue::String(event.kind.name().to_string()),
+        ),
+        ("index".to_string(), JsonValue::Number(event.index as i64)),
+        (
+            "description".to_string(),
+            JsonValue::String(event.description.clone()),
+        ),
+        (
+            "task".to_string(),
+            JsonValue::String(event.created_at.to_rfc3339()),
+        ),
+        ("changes".to_string(), JsonValue::Object(changes)),
+    ])
+    .to_compact_string()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub time: DateTime<Local>,
+    pub op: String,
+    pub index: usize,
+    pub description: String,
+    // the creation time of the task, missing in entries written before it was recorded
+    pub task: Option<DateTime<Local>>,
+    pub changes: Vec<(String, JsonValue, JsonValue)>,
+}
+
+impl Entry {
+    pub fn parse(line: &str) -> Option<Entry> {
+        let value = parse_value(line).ok()?;
+        let string_of = |key: &str| match value.get(key) {
+            Some(JsonValue::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let changes = match value.get("changes") {
+            Some(JsonValue::Object(changes)) => changes
+                .iter()
+                .map(|(field, values)| match values {
+                    JsonValue::Array(values) if values.len() == 2 => {
+                        Some((field.clone(), values[0].clone(), values[1].clone()))
+                    }
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?,
+            _ => vec![],
+        };
+        let parse_time = |time: String| {
+            DateTime::parse_from_rfc3339(&time)
+                .ok()
+                .map(|time| time.with_timezone(&Local))
+        };
+        Some(Entry {
+            time: parse_time(string_of("time")?)?,
+            op: string_of("op")?,
+            index: match value.get("index") {
+                Some(JsonValue::Number(index)) => usize::try_from(*index).ok()?,
+                _ => return None,
+            },
+            description: string_of("description")?,
+            task: string_of("task").and_then(parse_time),
+            changes,
+        })
+    }
+}
+
+// the entries of the history log, after those of the log rotated by maintenance
+pub fn read_entries(log_file: &Path) -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+    for path in [rotated_log_file(log_file), log_file.to_path_buf()] {
+        match fs::read_to_string(&path) {
+            Ok(text) => entries.extend(text.lines().filter_map(Entry::parse)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(entries)
+}
+
+// e.g. `default.csv.log.1`, where maintenance moves the history log once it grows too long
+pub fn rotated_log_file(log_file: &Path) -> PathBuf {
+    get_sibling_file_path(log_file, "1")
+}
+
+// when the tasks in the trash were deleted, by their creation time; a task restored since is
+// left out, as well as one deleted before its creation time was recorded
+pub fn deletion_times(entries: &[Entry]) -> HashMap<DateTime<Local>, DateTime<Local>> {
+    let mut times = HashMap::new();
+    for entry in entries {
+        let Some(task) = entry.task else {
+            continue;
+        };
+        if entry.op == EventKind::Deleted.name() {
+            times.insert(task, entry.time);
+        } else if entry.op == EventKind::Restored.name() {
+            times.remove(&task);
+        }
+    }
+    times
+}
+
+// the index of a task changes on sort, so its history is found by its creation time; entries
+// written before that was recorded fall back to its description, followed back through the
+// renames until it's created
+pub fn history_of<'a>(entries: &'a [Entry], task: &Task) -> Vec<&'a Entry> {
+    let mut description = task.description.clone();
+    let mut history = vec![];
+    for entry in entries.iter().rev() {
+        let is_of_task = match entry.task {
+            Some(created_at) => created_at == task.created_at,
+            None => entry.description == description,
+        };
+        if !is_of_task {
+            continue;
+        }
+        history.push(entry);
+        if entry.op == EventKind::Created.name() {
+            break;
+        }
+        let renamed_from = entry.changes.iter().find_map(|(field, old, _)| match old {
+            JsonValue::String(old) if field == "description" => Some(old.clone()),
+            _ => None,
+        });
+        if let Some(old) = renamed_from {
+            description = old;
+        }
+    }
+    history.reverse();
+    history
+}
+
+pub fn format_json_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "-".to_string(),
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Array(values) => values
+            .iter()
+            .map(format_json_value)
+            .collect::<Vec<_>>()
+            .join(" "),
+        value => value.to_compact_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::at;
+    use chrono::TimeZone;
+
+    fn event(
+        kind: EventKind,
+        description: &str,
+        changes: Vec<(String, JsonValue, JsonValue)>,
+    ) -> Event {
+        Event {
+            id: 0,
+            kind,
+            index: 3,
+            description: description.to_string(),
+            created_at: Local.with_ymd_and_hms(2024, 3, 4, 8, 0, 0).unwrap(),
+            time: Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap(),
+            changes,
+        }
+    }
+
+    fn entry(
+        kind: EventKind,
+        description: &str,
+        changes: Vec<(String, JsonValue, JsonValue)>,
+    ) -> Entry {
+        Entry::parse(&event_to_line(&event(kind, description, changes))).unwrap()
+    }
+
+    #[test]
+    fn test_diff_tasks() {
+        let before = Task::new_planned_task("review", at(4, 9), at(4, 10));
+        let mut after = before.clone();
+        after.reschedule(Some(at(4, 10)), Some(at(4, 11)));
+        after.tags = vec!["work".to_string()];
+        after.update_status();
+        let changes = diff_tasks(&before, &after);
+        let fields: Vec<&str> = changes.iter().map(|(field, ..)| field.as_str()).collect();
+        assert_eq!(fields, vec!["planned_start", "planned_complete", "tags"]);
+        assert_eq!(changes[0].1, JsonValue::String(at(4, 9).to_rfc3339()));
+        assert_eq!(changes[0].2, JsonValue::String(at(4, 10).to_rfc3339()));
+        assert!(diff_tasks(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let changes = vec![(
+            "planned_start".to_string(),
+            JsonValue::Null,
+            JsonValue::String("2024-03-04T10:00:00+08:00".to_string()),
+        )];
+        let event = event(EventKind::Edited, "say \"hi\"", changes.clone());
+        let line = event_to_line(&event);
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            Entry::parse(&line),
+            Some(Entry {
+                time: event.time,
+                op: "edited".to_string(),
+                index: 3,
+                description: "say \"hi\"".to_string(),
+                task: Some(event.created_at),
+                changes,
+            })
+        );
+        assert_eq!(Entry::parse("{\"op\":\"edited\"}"), None);
+        assert_eq!(Entry::parse("not json"), None);
+    }
+
+    #[test]
+    fn test_history_tells_apart_tasks_of_the_same_description() {
+        let mut first = Task::new_backlog_task("standup");
+        first.created_at = at(4, 8);
+        let mut second = first.clone();
+        second.created_at = at(4, 10);
+        let entry_of = |kind: EventKind, task: &Task| {
+            let mut event = event(kind, &task.description, vec![]);
+            event.created_at = task.created_at;
+            Entry::parse(&event_to_line(&event)).unwrap()
+        };
+        let entries = vec![
+            entry_of(EventKind::Created, &first),
+            entry_of(EventKind::Created, &second),
+            entry_of(EventKind::Started, &first),
+            entry_of(EventKind::Completed, &second),
+        ];
+        let ops = |task: &Task| -> Vec<String> {
+            history_of(&entries, task)
+                .iter()
+                .map(|entry| entry.op.clone())
+                .collect()
+        };
+        assert_eq!(ops(&first), ["created", "started"]);
+        assert_eq!(ops(&second), ["created", "completed"]);
+    }
+
+    #[test]
+    fn test_history_follows_renames() {
+        let rename = |old: &str, new: &str| {
+            vec![(
+                "description".to_string(),
+                JsonValue::String(old.to_string()),
+                JsonValue::String(new.to_string()),
+            )]
+        };
+        // written before the task was recorded, so only the description tells
+        let entry = |kind, description, changes| Entry {
+            task: None,
+            ..entry(kind, description, changes)
+        };
+        let entries = vec![
+            entry(EventKind::Created, "draft", vec![]),
+            entry(EventKind::Created, "other", vec![]),
+            entry(
+                EventKind::Edited,
+                "write report",
+                rename("draft", "write report"),
+            ),
+            entry(EventKind::Started, "write report", vec![]),
+            entry(EventKind::Created, "draft", vec![]),
+        ];
+        let history = history_of(&entries, &Task::new_backlog_task("write report"));
+        let ops: Vec<&str> = history.iter().map(|entry| entry.op.as_str()).collect();
+        assert_eq!(ops, vec!["created", "edited", "started"]);
+        assert_eq!(
+            history_of(&entries, &Task::new_backlog_task("draft")).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_deletion_times() {
+        let entry_at = |kind: EventKind, created_at, time| {
+            let mut event = event(kind, "task", vec![]);
+            event.created_at = created_at;
+            event.time = time;
+            Entry::parse(&event_to_line(&event)).unwrap()
+        };
+        let entries = vec![
+            entry_at(EventKind::Deleted, at(4, 1), at(4, 10)),
+            entry_at(EventKind::Deleted, at(4, 2), at(4, 11)),
+            entry_at(EventKind::Restored, at(4, 2), at(4, 12)),
+            entry_at(EventKind::Deleted, at(4, 3), at(4, 13)),
+            entry_at(EventKind::Restored, at(4, 3), at(4, 14)),
+            entry_at(EventKind::Deleted, at(4, 3), at(4, 15)),
+            Entry {
+                task: None,
+                ..entry_at(EventKind::Deleted, at(4, 4), at(4, 16))
+            },
+        ];
+        let times = deletion_times(&entries);
+        assert_eq!(times.len(), 2);
+        assert_eq!(times[&at(4, 1)], at(4, 10));
+        assert_eq!(times[&at(4, 3)], at(4, 15));
+    }
+
+    #[test]
+    fn test_read_entries() {
+        let dir = crate::testing::temp_dir("history-entries");
+        let log_file = dir.join("arenta.log");
+        assert!(read_entries(&log_file).unwrap().is_empty());
+        let line = |description| event_to_line(&event(EventKind::Created, description, vec![]));
+        fs::write(rotated_log_file(&log_file), line("older") + "\n").unwrap();
+        fs::write(&log_file, line("newer") + "\nnot json\n").unwrap();
+        let descriptions: Vec<String> = read_entries(&log_file)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.description)
+            .collect();
+        assert_eq!(descriptions, ["older", "newer"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_json_value() {
+        assert_eq!(format_json_value(&JsonValue::Null), "-");
+        assert_eq!(
+            format_json_value(&JsonValue::Array(vec![
+                JsonValue::String("a".to_string()),
+                JsonValue::String("b".to_string())
+            ])),
+            "a b"
+        );
+        assert_eq!(format_json_value(&JsonValue::Number(2)), "2");
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::IsTerminal;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// like colored's override, decided once on startup instead of threaded through every render
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+const OSC8_START: &str = "\x1b]8;;";
+const OSC8_END: &str = "\x1b\\";
+
+// hyperlinks are never emitted into a pipe, or when colors are turned off
+pub fn init(configured: bool) {
+    let supported = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    ENABLED.store(configured && supported, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// finds bare http(s) urls, leaving out trailing punctuation which usually ends the sentence
+fn find_urls(text: &str) -> Vec<Range<usize>> {
+    let mut urls = vec![];
+    let mut offset = 0;
+    while let Some(start) = ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| text[offset..].find(scheme))
+        .min()
+    {
+        let start = offset + start;
+        let len = text[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(text.len() - start);
+        let url = text[start..start + len].trim_end_matches(|c: char| ".,;:!?)]'\"".contains(c));
+        let end = start + url.len();
+        if !url.ends_with("//") {
+            urls.push(start..end);
+        }
+        offset = end.max(start + 1);
+    }
+    urls
+}
+
+// wraps every url in OSC 8 escape sequences, so that it's clickable in terminals supporting it
+pub fn linkify(text: &str) -> String {
+    let mut linked = String::new();
+    let mut last = 0;
+    for range in find_urls(text) {
+        let url = &text[range.clone()];
+        linked.push_str(&text[last..range.start]);
+        linked.push_str(&format!(
+            "{OSC8_START}{url}{OSC8_END}{url}{OSC8_START}{OSC8_END}"
+        ));
+        last = range.end;
+    }
+    linked.push_str(&text[last..]);
+    linked
+}
+
+pub fn linkify_if_enabled(text: &str) -> String {
+    if is_enabled() {
+        linkify(text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::display_width;
+
+    #[test]
+    fn test_find_urls() {
+        let text = "see https://example.com/a?b=1, and (http://x.org).";
+        let urls: Vec<&str> = find_urls(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(urls, vec!["https://example.com/a?b=1", "http://x.org"]);
+        assert!(find_urls("no links here, nor in https://").is_empty());
+    }
+
+    #[test]
+    fn test_linkify() {
+        let description = "review https://github.com/Gusabary/Arenta/pull/1 today";
+        let linked = linkify(description);
+        assert_eq!(
+            linked,
+            "review \x1b]8;;https://github.com/Gusabary/Arenta/pull/1\x1b\\\
+             https://github.com/Gusabary/Arenta/pull/1\x1b]8;;\x1b\\ today"
+        );
+        assert_eq!(display_width(&linked), description.chars().count());
+        assert_eq!(linkify("plain text"), "plain text");
+    }
+}
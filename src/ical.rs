@@ -0,0 +1,212 @@
+use crate::journal::content_hash;
+use crate::task::{Task, TaskStatus};
+use chrono::{DateTime, Local, Utc};
+
+// content lines longer than this many octets are folded, as required by RFC 5545
+const MAX_LINE_OCTETS: usize = 75;
+
+// a calendar of one event per task having a planned period, returns it together with how
+// many tasks are skipped for lacking one; deleted tasks are left out silently
+pub fn tasks_to_calendar(tasks: &[Task], redact: bool, now: DateTime<Local>) -> (String, usize) {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Gusabary//Arenta//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    let mut skipped = 0;
+    for task in tasks.iter().filter(|task| !task.is_deleted) {
+        let (Some(start), Some(complete)) = (task.planned_start, task.planned_complete) else {
+            skipped += 1;
+            continue;
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event_uid(task)));
+        lines.push(format!("DTSTAMP:{}", format_utc(&now)));
+        lines.push(format!("DTSTART:{}", format_utc(&start)));
+        lines.push(format!("DTEND:{}", format_utc(&complete)));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape_text(task.shared_description(redact))
+        ));
+        let hides_notes = redact && task.is_private;
+        if !task.notes.is_empty() && !hides_notes {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&task.notes)));
+        }
+        if !task.tags.is_empty() {
+            let tags: Vec<String> = task.tags.iter().map(|tag| escape_text(tag)).collect();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        if task.status == TaskStatus::Complete {
+            lines.push("STATUS:COMPLETED".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    let calendar = lines.iter().map(|line| fold_line(line) + "\r\n").collect();
+    (calendar, skipped)
+}
+
+// tasks have no id of their own, so the uid is derived from what identifies a task on
+// import as well, keeping it stable across exports
+fn event_uid(task: &Task) -> String {
+    let key = format!(
+        "{}\n{}",
+        task.description,
+        task.planned_start
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default()
+    );
+    format!("{:016x}@arenta", content_hash(key.as_bytes()))
+}
+
+// in utc, so that no timezone definition is needed
+fn format_utc(datetime: &DateTime<Local>) -> String {
+    datetime
+        .with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// splits the line into chunks of at most 75 octets without breaking a char, every chunk but
+// the first starts with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::at;
+    use chrono::Duration;
+
+    // unfolds the calendar and splits every content line into its name and value, checking
+    // the line endings and lengths on the way
+    fn parse_calendar(calendar: &str) -> Vec<(String, String)> {
+        assert!(calendar.ends_with("\r\n"));
+        let physical: Vec<&str> = calendar.trim_end_matches("\r\n").split("\r\n").collect();
+        let mut logical: Vec<String> = vec![];
+        for line in physical {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{line}");
+            assert!(!line.contains('\n') && !line.contains('\r'));
+            match line.strip_prefix(' ') {
+                Some(continued) => logical.last_mut().unwrap().push_str(continued),
+                None => logical.push(line.to_string()),
+            }
+        }
+        logical
+            .iter()
+            .map(|line| {
+                let (name, value) = line.split_once(':').unwrap();
+                (name.to_string(), value.to_string())
+            })
+            .collect()
+    }
+
+    fn values_in(lines: &[(String, String)], name: &str) -> Vec<String> {
+        lines
+            .iter()
+            .filter(|(n, _)| n == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn unescape_text(text: &str) -> String {
+        text.replace("\\n", "\n")
+            .replace("\\;", ";")
+            .replace("\\,", ",")
+            .replace("\\\\", "\\")
+    }
+
+    #[test]
+    fn test_tasks_to_calendar() {
+        let mut planned = Task::new_planned_task("review; then, merge", at(4, 9), at(4, 10));
+        planned.notes = "a note".to_string();
+        planned.is_private = true;
+        let mut complete = Task::new_planned_task(&"long ".repeat(30), at(4, 13), at(4, 14));
+        complete.start();
+        complete.complete();
+        complete.update_status();
+        complete.tags = vec!["work".to_string(), "q1".to_string()];
+        let mut deleted = Task::new_planned_task("deleted", at(4, 9), at(4, 10));
+        deleted.delete();
+        let tasks = [
+            planned,
+            Task::new_backlog_task("backlog"),
+            complete,
+            deleted,
+        ];
+        let now = at(4, 8);
+
+        let (calendar, skipped) = tasks_to_calendar(&tasks, false, now);
+        assert_eq!(skipped, 1);
+        let lines = parse_calendar(&calendar);
+        assert_eq!(lines.first().unwrap().1, "VCALENDAR");
+        assert_eq!(lines.last().unwrap().1, "VCALENDAR");
+        assert_eq!(values_in(&lines, "BEGIN").len(), 3);
+        assert_eq!(values_in(&lines, "END").len(), 3);
+        assert_eq!(values_in(&lines, "DTSTAMP"), vec![format_utc(&now); 2]);
+        assert_eq!(
+            values_in(&lines, "DTSTART"),
+            vec![format_utc(&at(4, 9)), format_utc(&at(4, 13))]
+        );
+        let offset = Duration::seconds(at(4, 9).offset().local_minus_utc() as i64);
+        let utc_start = (at(4, 9) - offset).format("%Y%m%dT%H%M%SZ").to_string();
+        assert_eq!(values_in(&lines, "DTSTART")[0], utc_start);
+        assert_eq!(
+            values_in(&lines, "SUMMARY")
+                .iter()
+                .map(|summary| unescape_text(summary))
+                .collect::<Vec<_>>(),
+            vec!["review; then, merge".to_string(), "long ".repeat(30)]
+        );
+        assert_eq!(values_in(&lines, "DESCRIPTION"), vec!["a note"]);
+        assert_eq!(values_in(&lines, "CATEGORIES"), vec!["work,q1"]);
+        assert_eq!(values_in(&lines, "STATUS"), vec!["COMPLETED"]);
+        let uids = values_in(&lines, "UID");
+        assert_ne!(uids[0], uids[1]);
+        // exporting again gives the same uids
+        let (again, _) = tasks_to_calendar(&tasks, true, now + Duration::hours(1));
+        let lines = parse_calendar(&again);
+        assert_eq!(values_in(&lines, "UID"), uids);
+        assert_eq!(values_in(&lines, "SUMMARY")[0], "private task");
+        assert!(values_in(&lines, "DESCRIPTION").is_empty());
+    }
+
+    #[test]
+    fn test_fold_line() {
+        assert_eq!(fold_line("short"), "short");
+        let folded = fold_line(&"日".repeat(30));
+        let chunks: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(chunks.len(), 2);
+        // 25 chars of 3 octets each fill up the first line
+        assert_eq!(chunks[0], "日".repeat(25));
+        assert_eq!(chunks[1], format!(" {}", "日".repeat(5)));
+        assert!(chunks.iter().all(|chunk| chunk.len() <= MAX_LINE_OCTETS));
+    }
+}
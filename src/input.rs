@@ -0,0 +1,200 @@
+use chrono::{Duration, NaiveTime};
+
+// longest duration accepted by the planning prompts
+const MAX_DURATION_MINUTES: i64 = 7 * 24 * 60;
+
+// accepts `9`, `9:5`, `09:30`, `930`, `0930`, `2pm` and `2:30pm`
+pub fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    let input = input.trim().to_ascii_lowercase();
+    if input.is_empty() {
+        return Err("please type a time, e.g. 9, 9:30, 0930 or 2pm".to_string());
+    }
+    let (clock, meridiem) = if let Some(clock) = input.strip_suffix("am") {
+        (clock.trim_end(), Some(false))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock.trim_end(), Some(true))
+    } else {
+        (input.as_str(), None)
+    };
+    if !clock.chars().all(|c| c.is_ascii_digit() || c == ':') {
+        return Err(format!(
+            "`{input}` is not a time, e.g. 9, 9:30, 0930 or 2pm"
+        ));
+    }
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (parse_number(hour, 2)?, parse_number(minute, 2)?),
+        None => match clock.len() {
+            1 | 2 => (parse_number(clock, 2)?, 0),
+            3 | 4 => {
+                let (hour, minute) = clock.split_at(clock.len() - 2);
+                (parse_number(hour, 2)?, parse_number(minute, 2)?)
+            }
+            _ => {
+                return Err(format!(
+                    "`{input}` is not a time, e.g. 9, 9:30, 0930 or 2pm"
+                ))
+            }
+        },
+    };
+    if minute > 59 {
+        return Err(format!("minute {minute} is out of range 0-59"));
+    }
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => {
+            return Err(format!("hour {hour} is out of range 1-12 for am/pm"))
+        }
+        Some(is_pm) => hour % 12 + if is_pm { 12 } else { 0 },
+        None if hour > 23 => return Err(format!("hour {hour} is out of range 0-23")),
+        None => hour,
+    };
+    Ok(NaiveTime::from_hms_opt(hour, minute, 0).unwrap())
+}
+
+// accepts plain minutes like `90`, or hours and minutes like `90m`, `2h` and `1h30m`
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if input.is_empty() {
+        return Err("please type a duration, e.g. 45, 90m, 2h or 1h30m".to_string());
+    }
+    let minutes = if input.chars().all(|c| c.is_ascii_digit()) {
+        parse_number(&input, 6)? as i64
+    } else {
+        let mut minutes = 0;
+        let mut rest = input.as_str();
+        let mut units_seen = String::new();
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (amount, unit) = rest.split_at(digits);
+            let unit = unit
+                .chars()
+                .next()
+                .ok_or_else(|| format!("`{amount}` in `{input}` lacks a unit, use h or m"))?;
+            if amount.is_empty() {
+                return Err(format!("`{unit}` in `{input}` lacks an amount"));
+            }
+            if units_seen.contains(unit) {
+                return Err(format!("`{unit}` is given more than once in `{input}`"));
+            }
+            let amount = parse_number(amount, 6)? as i64;
+            minutes += match unit {
+                'h' if units_seen.is_empty() => amount * 60,
+                'h' => return Err(format!("hours should come before minutes in `{input}`")),
+                'm' => amount,
+                unit => return Err(format!("unknown unit `{unit}`, use h or m")),
+            };
+            units_seen.push(unit);
+            rest = &rest[digits + unit.len_utf8()..];
+        }
+        minutes
+    };
+    match minutes {
+        0 => Err("the duration should be at least 1 minute".to_string()),
+        minutes if minutes > MAX_DURATION_MINUTES => {
+            Err("the duration should be at most a week".to_string())
+        }
+        minutes => Ok(Duration::minutes(minutes)),
+    }
+}
+
+fn parse_number(digits: &str, max_len: usize) -> Result<u32, String> {
+    if digits.is_empty() || digits.len() > max_len || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("`{digits}` is not a number"));
+    }
+    Ok(digits.parse().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> Result<NaiveTime, String> {
+        Ok(NaiveTime::from_hms_opt(hour, minute, 0).unwrap())
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("9"), time(9, 0));
+        assert_eq!(parse_time(" 23 "), time(23, 0));
+        assert_eq!(parse_time("9:5"), time(9, 5));
+        assert_eq!(parse_time("09:30"), time(9, 30));
+        assert_eq!(parse_time("930"), time(9, 30));
+        assert_eq!(parse_time("0930"), time(9, 30));
+        assert_eq!(parse_time("2pm"), time(14, 0));
+        assert_eq!(parse_time("2:30 PM"), time(14, 30));
+        assert_eq!(parse_time("12am"), time(0, 0));
+        assert_eq!(parse_time("12pm"), time(12, 0));
+
+        assert_eq!(
+            parse_time("24"),
+            Err("hour 24 is out of range 0-23".to_string())
+        );
+        assert_eq!(
+            parse_time("9:60"),
+            Err("minute 60 is out of range 0-59".to_string())
+        );
+        assert_eq!(
+            parse_time("13pm"),
+            Err("hour 13 is out of range 1-12 for am/pm".to_string())
+        );
+        assert_eq!(
+            parse_time("0am"),
+            Err("hour 0 is out of range 1-12 for am/pm".to_string())
+        );
+        assert_eq!(parse_time("9:"), Err("`` is not a number".to_string()));
+        assert_eq!(
+            parse_time("nine"),
+            Err("`nine` is not a time, e.g. 9, 9:30, 0930 or 2pm".to_string())
+        );
+        assert!(parse_time("éa").is_err());
+        assert!(parse_time("09300").is_err());
+        assert!(parse_time("9:30:00").is_err());
+        assert!(parse_time("").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45"), Ok(Duration::minutes(45)));
+        assert_eq!(parse_duration("90m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::hours(2)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("1H 30M"), Ok(Duration::minutes(90)));
+
+        assert_eq!(
+            parse_duration("0"),
+            Err("the duration should be at least 1 minute".to_string())
+        );
+        assert_eq!(
+            parse_duration("200h"),
+            Err("the duration should be at most a week".to_string())
+        );
+        assert_eq!(
+            parse_duration("2d"),
+            Err("unknown unit `d`, use h or m".to_string())
+        );
+        assert_eq!(
+            parse_duration("1h30"),
+            Err("`30` in `1h30` lacks a unit, use h or m".to_string())
+        );
+        assert_eq!(
+            parse_duration("30m1h"),
+            Err("hours should come before minutes in `30m1h`".to_string())
+        );
+        assert_eq!(
+            parse_duration("1h1h"),
+            Err("`h` is given more than once in `1h1h`".to_string())
+        );
+        assert_eq!(
+            parse_duration("h"),
+            Err("`h` in `h` lacks an amount".to_string())
+        );
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration("-30m").is_err());
+        assert!(parse_duration("").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::manager::{get_sibling_file_path, record_to_task};
+use crate::task::Task;
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+// the journal is compacted into the data file once it grows over this
+pub const JOURNAL_MAX_RECORDS: usize = 500;
+
+// every change is appended as `put,<index>,<columns of the task>,$` or `del,<index>,$`, where
+// the trailing `$` tells a complete record from one cut short by a crash; the first record
+// `base,<hash>` names the content of the data file the journal applies to
+const RECORD_END: &str = "$";
+
+pub struct Journal {
+    path: PathBuf,
+    base_hash: u64,
+    // how many records have been applied since the data file was written
+    pub records: usize,
+    // bytes up to the end of the last record applied, which the next record is written after
+    // so that a damaged rest is dropped
+    applied_len: u64,
+}
+
+// FNV-1a, which is plenty to tell whether the data file has been rewritten
+pub fn content_hash(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Journal {
+    pub fn new(data_file: &Path, base_hash: u64) -> Self {
+        Journal {
+            path: get_sibling_file_path(data_file, "journal"),
+            base_hash,
+            records: 0,
+            applied_len: 0,
+        }
+    }
+
+    // applies the journal onto the tasks loaded from the data file, returns a warning if some
+    // records are ignored; a journal written for another data file content is stale, which
+    // happens if arenta is killed right after compacting
+    pub fn replay(&mut self, tasks: &mut Vec<Task>) -> Option<String> {
+        let content = fs::read(&self.path).ok()?;
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(content.as_slice());
+        let mut record = StringRecord::new();
+        if !reader.read_record(&mut record).ok()?
+            || record.len() != 2
+            || &record[0] != "base"
+            || record[1].parse::<u64>().ok() != Some(self.base_hash)
+        {
+            return None;
+        }
+        self.applied_len = reader.position().byte();
+        for line in 2.. {
+            match reader
+                .read_record(&mut record)
+                .map_err(|e| e.to_string())
+                .and_then(|has_record| match has_record {
+                    true => apply_record(&record, tasks).map(|()| true),
+                    false => Ok(false),
+                }) {
+                Ok(false) => break,
+                Ok(true) => {
+                    self.records += 1;
+                    self.applied_len = reader.position().byte();
+                }
+                Err(reason) => {
+                    return Some(format!(
+                        "{}:{line}: {reason}, ignored the rest of the journal",
+                        self.path.display()
+                    ))
+                }
+            }
+        }
+        None
+    }
+
+    // the rows are appended in a single write, which is synced before returning
+    pub fn append(&mut self, rows: &[(usize, Vec<String>)]) -> io::Result<()> {
+        let records = rows.iter().map(|(index, row)| {
+            ["put".to_string(), index.to_string()]
+                .into_iter()
+                .chain(row.iter().cloned())
+                .collect()
+        });
+        self.write(records.collect())
+    }
+
+    // the task at the index is removed, moving those after it up
+    pub fn delete(&mut self, index: usize) -> io::Result<()> {
+        self.write(vec![vec!["del".to_string(), index.to_string()]])
+    }
+
+    fn write(&mut self, records: Vec<Vec<String>>) -> io::Result<()> {
+        let mut writer = WriterBuilder::new().flexible(true).from_writer(vec![]);
+        if self.records == 0 {
+            writer.write_record(["base", &self.base_hash.to_string()])?;
+        }
+        for record in &records {
+            writer.write_record(record.iter().map(String::as_str).chain([RECORD_END]))?;
+        }
+        let content = writer
+            .into_inner()
+            .map_err(|e| io::Error::new(e.error().kind(), e.error().to_string()))?;
+        // starts over if there is no applied record, e.g. the journal file is stale
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(self.records == 0)
+            .open(&self.path)?;
+        if self.records > 0 {
+            file.set_len(self.applied_len)?;
+            file.seek(SeekFrom::Start(self.applied_len))?;
+        }
+        file.write_all(&content)?;
+        file.sync_all()?;
+        self.records += records.len();
+        self.applied_len = file.stream_position()?;
+        Ok(())
+    }
+
+    // called after the data file is rewritten with all changes
+    pub fn reset(&mut self, base_hash: u64) -> io::Result<()> {
+        self.base_hash = base_hash;
+        self.records = 0;
+        self.applied_len = 0;
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn apply_record(record: &StringRecord, tasks: &mut Vec<Task>) -> Result<(), String> {
+    if record.len() < 3 || &record[record.len() - 1] != RECORD_END {
+        return Err("incomplete record".to_string());
+    }
+    let index = record[1]
+        .parse::<usize>()
+        .map_err(|_| format!("invalid index `{}`", &record[1]))?;
+    match &record[0] {
+        "put" => (),
+        "del" if index < tasks.len() => {
+            tasks.remove(index);
+            return Ok(());
+        }
+        "del" => return Err(format!("index {index} out of range")),
+        kind => return Err(format!("unknown record `{kind}`")),
+    }
+    let columns: StringRecord = record.iter().skip(2).take(record.len() - 3).collect();
+    let task = record_to_task(&columns)?;
+    match index {
+        index if index < tasks.len() => tasks[index] = task,
+        index if index == tasks.len() => tasks.push(task),
+        index => return Err(format!("index {index} out of range")),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::task_to_row;
+    use crate::testing::temp_dir;
+
+    fn journal_in(name: &str, base_hash: u64) -> (PathBuf, Journal) {
+        let dir = temp_dir(&format!("journal-{name}"));
+        let journal = Journal::new(&dir.join("arenta"), base_hash);
+        (dir, journal)
+    }
+
+    fn descriptions(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.description.as_str()).collect()
+    }
+
+    #[test]
+    fn test_append_and_replay() {
+        let (dir, mut journal) = journal_in("replay", 42);
+        let mut updated = Task::new_backlog_task("b, updated");
+        updated.tags = vec!["work".to_string()];
+        journal.append(&[(1, task_to_row(&updated))]).unwrap();
+        journal
+            .append(&[(2, task_to_row(&Task::new_backlog_task("c")))])
+            .unwrap();
+        assert_eq!(journal.records, 2);
+
+        let base = || vec![Task::new_backlog_task("a"), Task::new_backlog_task("b")];
+        let mut tasks = base();
+        let mut replayed = Journal::new(&dir.join("arenta"), 42);
+        assert_eq!(replayed.replay(&mut tasks), None);
+        assert_eq!(replayed.records, 2);
+        assert_eq!(descriptions(&tasks), vec!["a", "b, updated", "c"]);
+        assert_eq!(tasks[1].tags, vec!["work"]);
+
+        // a journal for another content of the data file is stale
+        let mut tasks = base();
+        let mut stale = Journal::new(&dir.join("arenta"), 43);
+        assert_eq!(stale.replay(&mut tasks), None);
+        assert_eq!(stale.records, 0);
+        assert_eq!(descriptions(&tasks), vec!["a", "b"]);
+
+        // tasks after a deleted one move up
+        let mut deleted = Journal::new(&dir.join("arenta"), 42);
+        let mut tasks = base();
+        deleted.replay(&mut tasks);
+        deleted.delete(0).unwrap();
+        let mut tasks = base();
+        assert_eq!(
+            Journal::new(&dir.join("arenta"), 42).replay(&mut tasks),
+            None
+        );
+        assert_eq!(descriptions(&tasks), vec!["b, updated", "c"]);
+
+        replayed.reset(7).unwrap();
+        assert!(!dir.join("arenta.journal").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_replay_ignores_record_cut_short() {
+        let (dir, mut journal) = journal_in("truncated", 42);
+        journal
+            .append(&[
+                (0, task_to_row(&Task::new_backlog_task("a2"))),
+                (1, task_to_row(&Task::new_backlog_task("b"))),
+            ])
+            .unwrap();
+        let path = dir.join("arenta.journal");
+        let content = fs::read_to_string(&path).unwrap();
+        // as if killed while writing the tags of the last record
+        let cut = content.trim_end().len() - ",0,0,false,$".len() - 1;
+        fs::write(&path, &content[..cut]).unwrap();
+
+        let mut tasks = vec![Task::new_backlog_task("a")];
+        let mut replayed = Journal::new(&dir.join("arenta"), 42);
+        let warning = replayed.replay(&mut tasks).unwrap();
+        assert!(warning.ends_with(":3: incomplete record, ignored the rest of the journal"));
+        assert_eq!(descriptions(&tasks), vec!["a2"]);
+
+        // the next record takes the place of the one cut short
+        replayed
+            .append(&[(1, task_to_row(&Task::new_backlog_task("c")))])
+            .unwrap();
+        let mut tasks = vec![Task::new_backlog_task("a")];
+        assert_eq!(
+            Journal::new(&dir.join("arenta"), 42).replay(&mut tasks),
+            None
+        );
+        assert_eq!(descriptions(&tasks), vec!["a2", "c"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b""), 0xcbf29ce484222325);
+        assert_ne!(content_hash(b"a,b\n"), content_hash(b"a,c\n"));
+    }
+}
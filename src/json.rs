@@ -0,0 +1,700 @@
+use crate::task::Task;
+use chrono::{DateTime, Local};
+use csv::StringRecord;
+use std::iter::Peekable;
+use std::str::Chars;
+
+// just enough json for exporting and importing tasks
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub fn to_pretty_string(&self) -> String {
+        let mut output = String::new();
+        self.write_pretty(&mut output, 0);
+        output.push('\n');
+        output
+    }
+
+    // on a single line, as the history log keeps one value per line
+    pub fn to_compact_string(&self) -> String {
+        match self {
+            JsonValue::Array(values) => {
+                let values: Vec<String> = values.iter().map(JsonValue::to_compact_string).collect();
+                format!("[{}]", values.join(","))
+            }
+            JsonValue::Object(members) => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|(key, value)| {
+                        let mut member = String::new();
+                        write_escaped(&mut member, key);
+                        format!("{member}:{}", value.to_compact_string())
+                    })
+                    .collect();
+                format!("{{{}}}", members.join(","))
+            }
+            scalar => {
+                let mut output = String::new();
+                scalar.write_pretty(&mut output, 0);
+                output
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn write_pretty(&self, output: &mut String, depth: usize) {
+        const INDENT: &str = "  ";
+        match self {
+            JsonValue::Null => output.push_str("null"),
+            JsonValue::Bool(b) => output.push_str(&b.to_string()),
+            JsonValue::Number(n) => output.push_str(&n.to_string()),
+            JsonValue::String(s) => write_escaped(output, s),
+            JsonValue::Array(values) if values.is_empty() => output.push_str("[]"),
+            JsonValue::Array(values) => {
+                output.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    output.push_str(&INDENT.repeat(depth + 1));
+                    value.write_pretty(output, depth + 1);
+                    output.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                output.push_str(&INDENT.repeat(depth));
+                output.push(']');
+            }
+            JsonValue::Object(members) if members.is_empty() => output.push_str("{}"),
+            JsonValue::Object(members) => {
+                output.push_str("{\n");
+                for (i, (key, value)) in members.iter().enumerate() {
+                    output.push_str(&INDENT.repeat(depth + 1));
+                    write_escaped(output, key);
+                    output.push_str(": ");
+                    value.write_pretty(output, depth + 1);
+                    output.push_str(if i + 1 < members.len() { ",\n" } else { "\n" });
+                }
+                output.push_str(&INDENT.repeat(depth));
+                output.push('}');
+            }
+        }
+    }
+}
+
+fn write_escaped(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser {
+            chars: text.chars().peekable(),
+            line: 1,
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        Err(format!("line {}: {message}", self.line))
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => self.error(&format!("expected `{expected}`, found `{c}`")),
+            None => self.error(&format!("expected `{expected}`, found end of input")),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('n') => self.parse_literal("null", JsonValue::Null),
+            Some('t') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some('f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some('"') => self.parse_string().map(JsonValue::String),
+            Some('[') => self
+                .parse_array(|parser| parser.parse_value())
+                .map(JsonValue::Array),
+            Some('{') => self.parse_object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => self.error(&format!("unexpected `{c}`")),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, String> {
+        for expected in literal.chars() {
+            if self.next() != Some(expected) {
+                return self.error(&format!("invalid literal, expected `{literal}`"));
+            }
+        }
+        Ok(value)
+    }
+
+    // only integers are supported, which is all a task needs
+    fn parse_number(&mut self) -> Result<JsonValue, String> {
+        let mut number = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == '-' || c.is_ascii_alphanumeric() || c == '.' || c == '+' {
+                number.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+        match number.parse::<i64>() {
+            Ok(n) => Ok(JsonValue::Number(n)),
+            Err(_) => self.error(&format!("unsupported number `{number}`")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.next() {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('/') => s.push('/'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('u') => s.push(self.parse_unicode_escape()?),
+                    _ => return self.error("invalid escape in string"),
+                },
+                Some(c) => s.push(c),
+                None => return self.error("unterminated string"),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, String> {
+        let hex: String = (0..4).filter_map(|_| self.next()).collect();
+        match u32::from_str_radix(&hex, 16) {
+            Ok(code) if hex.len() == 4 => Ok(code),
+            _ => self.error(&format!("invalid unicode escape `{hex}`")),
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, String> {
+        let mut code = self.parse_hex4()?;
+        // a character outside the basic plane is escaped as a surrogate pair
+        if (0xd800..0xdc00).contains(&code) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return self.error("unpaired surrogate in string");
+            }
+            let low = self.parse_hex4()?;
+            code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+        }
+        match char::from_u32(code) {
+            Some(c) => Ok(c),
+            None => self.error("invalid unicode escape"),
+        }
+    }
+
+    fn parse_array<T>(
+        &mut self,
+        mut parse_element: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        self.expect('[')?;
+        let mut elements = vec![];
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(elements);
+        }
+        loop {
+            elements.push(parse_element(self)?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(elements),
+                _ => return self.error("expected `,` or `]` in array"),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, String> {
+        self.expect('{')?;
+        let mut members = vec![];
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(members)),
+                _ => return self.error("expected `,` or `}` in object"),
+            }
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Ok(()),
+            Some(_) => self.error("unexpected trailing characters"),
+        }
+    }
+}
+
+// parses a top-level array, returning every element with the line it starts on
+pub fn parse_array_with_lines(text: &str) -> Result<Vec<(usize, JsonValue)>, String> {
+    let mut parser = Parser::new(text);
+    let elements = parser.parse_array(|parser| {
+        parser.skip_whitespace();
+        let line = parser.line;
+        parser.parse_value().map(|value| (line, value))
+    })?;
+    parser.expect_end()?;
+    Ok(elements)
+}
+
+pub fn parse_value(text: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser::new(text);
+    let value = parser.parse_value()?;
+    parser.expect_end()?;
+    Ok(value)
+}
+
+// converts an exported task back into the columns of the data file, so that it's validated
+// exactly like a line of the data file, the status is ignored as it's always recalculated
+pub fn json_to_record(value: &JsonValue) -> Result<StringRecord, String> {
+    let JsonValue::Object(members) = value else {
+        return Err("expected an object".to_string());
+    };
+    let get = |key: &str| {
+        members
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    };
+    let string_of = |key: &str| match get(key) {
+        None | Some(JsonValue::Null) => Ok(String::new()),
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(JsonValue::Bool(b)) => Ok(b.to_string()),
+        Some(JsonValue::Number(n)) => Ok(n.to_string()),
+        Some(_) => Err(format!("invalid `{key}`")),
+    };
+    let description = match get("description") {
+        Some(JsonValue::String(description)) => description.clone(),
+        _ => return Err("missing description".to_string()),
+    };
+    let tags = match get("tags") {
+        None | Some(JsonValue::Null) => vec![],
+        Some(JsonValue::Array(tags)) => tags
+            .iter()
+            .map(|tag| match tag {
+                JsonValue::String(tag) => Ok(tag.clone()),
+                _ => Err("invalid `tags`".to_string()),
+            })
+            .collect::<Result<Vec<String>, String>>()?,
+        Some(_) => return Err("invalid `tags`".to_string()),
+    };
+    // `[[start, end], ...]` with a null end for the open interval
+    let work_intervals = match get("work_intervals") {
+        None | Some(JsonValue::Null) => String::new(),
+        Some(JsonValue::Array(intervals)) => intervals
+            .iter()
+            .map(|interval| match interval {
+                JsonValue::Array(ends) => match ends.as_slice() {
+                    [JsonValue::String(start), JsonValue::String(end)] => {
+                        Ok(format!("{start}/{end}"))
+                    }
+                    [JsonValue::String(start), JsonValue::Null] => Ok(format!("{start}/")),
+                    _ => Err("invalid `work_intervals`".to_string()),
+                },
+                _ => Err("invalid `work_intervals`".to_string()),
+            })
+            .collect::<Result<Vec<String>, String>>()?
+            .join(";"),
+        Some(_) => return Err("invalid `work_intervals`".to_string()),
+    };
+    let is_deleted = match get("is_deleted") {
+        None => "false".to_string(),
+        _ => string_of("is_deleted")?,
+    };
+    Ok(StringRecord::from(vec![
+        description,
+        string_of("planned_start")?,
+        string_of("planned_complete")?,
+        string_of("actual_start")?,
+        string_of("actual_complete")?,
+        is_deleted,
+        tags.join(";"),
+        string_of("postponed_count")?,
+        string_of("priority")?,
+        string_of("is_private")?,
+        work_intervals,
+        string_of("notes")?,
+        string_of("created_at")?,
+    ]))
+}
+
+fn datetime_opt_to_json(datetime_opt: &Option<DateTime<Local>>) -> JsonValue {
+    datetime_opt.map_or(JsonValue::Null, |dt| JsonValue::String(dt.to_rfc3339()))
+}
+
+pub fn task_to_json(task: &Task, redact: bool) -> JsonValue {
+    JsonValue::Object(vec![
+        (
+            "description".to_string(),
+            JsonValue::String(task.shared_description(redact).to_string()),
+        ),
+        (
+            "status".to_string(),
+            JsonValue::String(task.status.name().to_string()),
+        ),
+        ("is_deleted".to_string(), JsonValue::Bool(task.is_deleted)),
+        (
+            "planned_start".to_string(),
+            datetime_opt_to_json(&task.planned_start),
+        ),
+        (
+            "planned_complete".to_string(),
+            datetime_opt_to_json(&task.planned_complete),
+        ),
+        (
+            "actual_start".to_string(),
+            datetime_opt_to_json(&task.actual_start),
+        ),
+        (
+            "actual_complete".to_string(),
+            datetime_opt_to_json(&task.actual_complete),
+        ),
+        (
+            "tags".to_string(),
+            JsonValue::Array(
+                task.tags
+                    .iter()
+                    .map(|tag| JsonValue::String(tag.clone()))
+                    .collect(),
+            ),
+        ),
+        (
+            "postponed_count".to_string(),
+            JsonValue::Number(task.postponed_count as i64),
+        ),
+        (
+            "priority".to_string(),
+            JsonValue::Number(task.priority as i64),
+        ),
+        ("is_private".to_string(), JsonValue::Bool(task.is_private)),
+        (
+            "notes".to_string(),
+            JsonValue::String(match redact && task.is_private {
+                true => String::new(),
+                false => task.notes.clone(),
+            }),
+        ),
+        (
+            "work_intervals".to_string(),
+            JsonValue::Array(
+                task.work_intervals
+                    .iter()
+                    .map(|(start, end)| {
+                        JsonValue::Array(vec![
+                            datetime_opt_to_json(&Some(*start)),
+                            datetime_opt_to_json(end),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        (
+            "created_at".to_string(),
+            datetime_opt_to_json(&Some(task.created_at)),
+        ),
+    ])
+}
+
+// what `ls j` prints, the listed tasks with their index and status
+pub fn listed_tasks_to_json(tasks: &[(usize, &Task)]) -> JsonValue {
+    JsonValue::Array(
+        tasks
+            .iter()
+            .map(|(index, task)| {
+                JsonValue::Object(vec![
+                    ("index".to_string(), JsonValue::Number(*index as i64)),
+                    (
+                        "description".to_string(),
+                        JsonValue::String(task.description.clone()),
+                    ),
+                    (
+                        "status".to_string(),
+                        JsonValue::String(task.status.name().to_string()),
+                    ),
+                    (
+                        "planned_start".to_string(),
+                        datetime_opt_to_json(&task.planned_start),
+                    ),
+                    (
+                        "planned_complete".to_string(),
+                        datetime_opt_to_json(&task.planned_complete),
+                    ),
+                    (
+                        "actual_start".to_string(),
+                        datetime_opt_to_json(&task.actual_start),
+                    ),
+                    (
+                        "actual_complete".to_string(),
+                        datetime_opt_to_json(&task.actual_complete),
+                    ),
+                    ("is_deleted".to_string(), JsonValue::Bool(task.is_deleted)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskStatus;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_to_pretty_string() {
+        let value = JsonValue::Array(vec![
+            JsonValue::Object(vec![
+                (
+                    "name".to_string(),
+                    JsonValue::String("say \"hi\"\n".to_string()),
+                ),
+                ("tags".to_string(), JsonValue::Array(vec![])),
+                ("count".to_string(), JsonValue::Number(-3)),
+                ("done".to_string(), JsonValue::Bool(true)),
+            ]),
+            JsonValue::Null,
+        ]);
+        assert_eq!(
+            value.to_pretty_string(),
+            concat!(
+                "[\n",
+                "  {\n",
+                "    \"name\": \"say \\\"hi\\\"\\n\",\n",
+                "    \"tags\": [],\n",
+                "    \"count\": -3,\n",
+                "    \"done\": true\n",
+                "  },\n",
+                "  null\n",
+                "]\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_compact_string_round_trip() {
+        let value = JsonValue::Object(vec![
+            (
+                "op".to_string(),
+                JsonValue::String("say \"hi\"\n".to_string()),
+            ),
+            (
+                "values".to_string(),
+                JsonValue::Array(vec![
+                    JsonValue::Number(-1),
+                    JsonValue::Null,
+                    JsonValue::Bool(true),
+                ]),
+            ),
+            ("empty".to_string(), JsonValue::Object(vec![])),
+        ]);
+        let line = value.to_compact_string();
+        assert_eq!(
+            line,
+            r#"{"op":"say \"hi\"\n","values":[-1,null,true],"empty":{}}"#
+        );
+        assert_eq!(parse_value(&line), Ok(value.clone()));
+        assert_eq!(
+            value.get("values"),
+            Some(&JsonValue::Array(vec![
+                JsonValue::Number(-1),
+                JsonValue::Null,
+                JsonValue::Bool(true)
+            ]))
+        );
+        assert_eq!(value.get("missing"), None);
+        assert!(parse_value("{} {}").is_err());
+    }
+
+    #[test]
+    fn test_parse_array_with_lines() {
+        let text =
+            "[\n  {\"a\": [1, -2], \"b\": null},\n\n  \"\\u00e9\\ud83d\\ude00\\n\",\n  true\n]\n";
+        assert_eq!(
+            parse_array_with_lines(text),
+            Ok(vec![
+                (
+                    2,
+                    JsonValue::Object(vec![
+                        (
+                            "a".to_string(),
+                            JsonValue::Array(vec![JsonValue::Number(1), JsonValue::Number(-2)])
+                        ),
+                        ("b".to_string(), JsonValue::Null),
+                    ])
+                ),
+                (4, JsonValue::String("é😀\n".to_string())),
+                (5, JsonValue::Bool(true)),
+            ])
+        );
+        assert_eq!(parse_array_with_lines("[]"), Ok(vec![]));
+        assert_eq!(
+            parse_array_with_lines("[\n1,\n2.5]"),
+            Err("line 3: unsupported number `2.5`".to_string())
+        );
+        assert_eq!(
+            parse_array_with_lines("[1] 2"),
+            Err("line 1: unexpected trailing characters".to_string())
+        );
+        assert!(parse_array_with_lines("{}").is_err());
+        assert!(parse_array_with_lines("[\"open").is_err());
+        assert!(parse_array_with_lines("[1,]").is_err());
+    }
+
+    #[test]
+    fn test_task_json_round_trip() {
+        let mut task = Task::new_backlog_task("say \"hi\"");
+        task.planned_start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).single();
+        task.planned_complete = Local.with_ymd_and_hms(2024, 3, 4, 10, 0, 0).single();
+        task.tags = vec!["work".to_string(), "home".to_string()];
+        task.priority = 2;
+        task.work_intervals = vec![(task.planned_start.unwrap(), None)];
+        task.notes = "line 1\nline 2".to_string();
+        let text = JsonValue::Array(vec![task_to_json(&task, false)]).to_pretty_string();
+        let entries = parse_array_with_lines(&text).unwrap();
+        let record = json_to_record(&entries[0].1).unwrap();
+        assert_eq!(
+            record,
+            StringRecord::from(vec![
+                "say \"hi\"".to_string(),
+                task.planned_start.unwrap().to_rfc3339(),
+                task.planned_complete.unwrap().to_rfc3339(),
+                String::new(),
+                String::new(),
+                "false".to_string(),
+                "work;home".to_string(),
+                "0".to_string(),
+                "2".to_string(),
+                "false".to_string(),
+                format!("{}/", task.planned_start.unwrap().to_rfc3339()),
+                "line 1\nline 2".to_string(),
+                task.created_at.to_rfc3339(),
+            ])
+        );
+        assert!(json_to_record(&JsonValue::Object(vec![])).is_err());
+        assert!(json_to_record(&JsonValue::Null).is_err());
+    }
+
+    #[test]
+    fn test_listed_tasks_to_json() {
+        let start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let mut planned = Task::new_planned_task("say \"hi\"", start, start);
+        planned.status = TaskStatus::Overdue;
+        let mut deleted = Task::new_backlog_task("gone");
+        deleted.delete();
+        let line = listed_tasks_to_json(&[(2, &planned), (5, &deleted)]).to_compact_string();
+        assert!(!line.contains('\n') && !line.contains('\x1b'));
+        let JsonValue::Array(tasks) = parse_value(&line).unwrap() else {
+            panic!("{line}");
+        };
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].get("index"), Some(&JsonValue::Number(2)));
+        assert_eq!(
+            tasks[0].get("description"),
+            Some(&JsonValue::String("say \"hi\"".to_string()))
+        );
+        assert_eq!(
+            tasks[0].get("status"),
+            Some(&JsonValue::String("overdue".to_string()))
+        );
+        assert_eq!(
+            tasks[0].get("planned_start"),
+            Some(&JsonValue::String(start.to_rfc3339()))
+        );
+        assert_eq!(tasks[0].get("actual_complete"), Some(&JsonValue::Null));
+        assert_eq!(tasks[0].get("is_deleted"), Some(&JsonValue::Bool(false)));
+        assert_eq!(tasks[1].get("is_deleted"), Some(&JsonValue::Bool(true)));
+        assert_eq!(listed_tasks_to_json(&[]).to_compact_string(), "[]");
+    }
+
+    #[test]
+    fn test_task_to_json() {
+        let mut task = Task::new_backlog_task("call ACME");
+        task.planned_start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).single();
+        task.planned_complete = Local.with_ymd_and_hms(2024, 3, 4, 10, 0, 0).single();
+        task.tags = vec!["work".to_string()];
+        task.is_private = true;
+        task.notes = "ask for ACME's invoice".to_string();
+        let json = task_to_json(&task, false).to_pretty_string();
+        assert!(json.contains("\"description\": \"call ACME\""));
+        assert!(json.contains("\"status\": \"backlog\""));
+        assert!(json.contains(&format!(
+            "\"planned_start\": \"{}\"",
+            task.planned_start.unwrap().to_rfc3339()
+        )));
+        assert!(json.contains("\"actual_start\": null"));
+        assert!(json.contains("\"tags\": [\n    \"work\"\n  ]"));
+
+        let redacted = task_to_json(&task, true).to_pretty_string();
+        assert!(!redacted.contains("ACME"));
+        assert!(redacted.contains("\"description\": \"private task\""));
+    }
+}
@@ -0,0 +1,77 @@
+//! The logic of arenta, a terminal-based daily task manager, for building other frontends on.
+//!
+//! A [`Manager`] works on the data file of a [`Profile`] the way a session does, saving every
+//! change right away. Whatever a command asks the user goes through a [`Prompter`], so a
+//! manager without a terminal is given [`NoPrompter`] or one of its own:
+//!
+//! ```
+//! # let dir = std::env::temp_dir().join(format!("arenta-doc-{}-lib", std::process::id()));
+//! # std::fs::create_dir_all(&dir).unwrap();
+//! use arenta::{parse_command, Command, Config, Manager, NoPrompter, Profile, Task, TaskStatus};
+//!
+//! let profile = Profile { name: None, data_file: dir.join("tasks.csv") };
+//! let mut manager = Manager::new(Config::default(), profile, Box::new(NoPrompter)).unwrap();
+//! let index = manager.add_task(Task::new_immediate_task("write report")).unwrap();
+//! manager.complete(index).unwrap();
+//!
+//! let Some(Command::List(option)) = parse_command("ls") else { unreachable!() };
+//! let listed = manager.list(&option);
+//! assert_eq!(listed[0].status, TaskStatus::Complete);
+//!
+//! // commands typed in a session work as well, printing what they do
+//! let command = parse_command("n water the plants").unwrap();
+//! assert!(manager.execute_once(&command));
+//! assert_eq!(manager.tasks().len(), 2);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+//!
+//! The `cli` feature, on by default, only builds the `arenta` binary; a library user can leave
+//! its terminal dependencies out with `default-features = false`.
+
+mod backup;
+mod clock;
+pub mod command;
+pub mod config;
+pub mod display;
+pub mod error;
+mod event;
+mod history;
+mod hyperlink;
+mod ical;
+mod input;
+mod journal;
+mod json;
+pub mod lock;
+mod maintenance;
+pub mod manager;
+mod markdown;
+mod paths;
+pub mod pomodoro;
+pub mod profile;
+pub mod prompt;
+mod prompt_history;
+mod report;
+mod schedule;
+mod stats;
+pub mod storage;
+mod sync;
+pub mod task;
+mod template;
+#[cfg(test)]
+mod testing;
+mod timeline;
+pub mod timing;
+mod todotxt;
+mod working;
+
+pub use command::{parse_command, Command, ListOption};
+pub use config::Config;
+pub use error::{ArentaError, ArentaResult};
+pub use manager::Manager;
+pub use profile::Profile;
+pub use prompt::{NoPrompter, Prompter};
+pub use storage::TaskStore;
+pub use task::{Task, TaskStatus};
+
+// taken from the manifest, so that it never goes stale
+pub const VERSION: &str = concat!("v", env!("CARGO_PKG_VERSION"));
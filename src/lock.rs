@@ -0,0 +1,205 @@
+use crate::error::{ArentaError, ArentaResult};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// the lock file contains the pid of the process holding it, and is removed when the guard is
+// dropped, which also happens when unwinding from a panic
+pub struct Lock {
+    path: PathBuf,
+    is_released: bool,
+}
+
+impl Lock {
+    pub fn acquire(path: &Path) -> ArentaResult<Lock> {
+        match Lock::try_create(path) {
+            Err(ArentaError::Locked { pid: Some(pid) }) if !is_running(pid) => {
+                eprintln!(
+                    "removed a stale lock file left behind by process {pid}, which is no longer running"
+                );
+                fs::remove_file(path)
+                    .map_err(|e| ArentaError::io("failed to remove the stale lock file", e))?;
+                Lock::try_create(path)
+            }
+            result => result,
+        }
+    }
+
+    fn try_create(path: &Path) -> ArentaResult<Lock> {
+        match File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)
+        {
+            Ok(mut file) => {
+                // without the pid a lock left behind can't be told stale, but it's still a lock
+                let _ = write!(file, "{}", std::process::id());
+                remove_on_termination(path.to_path_buf());
+                Ok(Lock {
+                    path: path.to_path_buf(),
+                    is_released: false,
+                })
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(ArentaError::Locked {
+                pid: read_holder_pid(path),
+            }),
+            Err(e) => Err(ArentaError::io("failed to acquire lock file", e)),
+        }
+    }
+
+    // retries until the lock is acquired or the timeout elapses
+    pub fn acquire_with_timeout(path: &Path, timeout: Duration) -> ArentaResult<Lock> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match Lock::acquire(path) {
+                Err(ArentaError::Locked { .. }) if Instant::now() < deadline => {
+                    thread::sleep(Duration::from_millis(50))
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // like dropping the guard, but reports a failure to remove the lock file
+    pub fn release(mut self) -> io::Result<()> {
+        self.is_released = true;
+        remove_if_owned(&self.path)
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if !self.is_released {
+            let _ = remove_if_owned(&self.path);
+        }
+    }
+}
+
+pub fn read_holder_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// never removes a lock file which has been taken over by another process meanwhile
+fn remove_if_owned(path: &Path) -> io::Result<()> {
+    match read_holder_pid(path) {
+        Some(pid) if pid != std::process::id() => Ok(()),
+        _ => fs::remove_file(path),
+    }
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return true;
+    };
+    if pid <= 0 {
+        return true;
+    }
+    // signal 0 only checks whether the process exists, EPERM means it's owned by another user
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+// lock files taken by this process over time, e.g. one per profile switched to; those
+// released meanwhile are left alone by `remove_if_owned` as they're gone or taken over
+#[cfg(unix)]
+static TAKEN_LOCK_FILES: std::sync::Mutex<Vec<PathBuf>> = std::sync::Mutex::new(vec![]);
+
+// ctrl-c while a command is running, or a kill, ends the process without unwinding, so the
+// lock files are removed from a thread waiting for these signals instead
+#[cfg(unix)]
+fn remove_on_termination(path: PathBuf) {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let Ok(mut taken) = TAKEN_LOCK_FILES.lock() else {
+        return;
+    };
+    let is_waiting = !taken.is_empty();
+    taken.push(path);
+    if is_waiting {
+        return;
+    }
+    let Ok(mut signals) = Signals::new([SIGINT, SIGTERM, SIGHUP]) else {
+        return;
+    };
+    thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            if let Ok(taken) = TAKEN_LOCK_FILES.lock() {
+                taken.iter().for_each(|path| {
+                    let _ = remove_if_owned(path);
+                });
+            }
+            std::process::exit(128 + signal);
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn remove_on_termination(_path: PathBuf) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    #[test]
+    fn test_acquire_and_release() {
+        let dir = temp_dir("lock");
+        let path = dir.join("lock");
+
+        let lock = Lock::acquire(&path).unwrap();
+        assert_eq!(read_holder_pid(&path), Some(std::process::id()));
+        match Lock::acquire_with_timeout(&path, Duration::from_millis(100)) {
+            Err(ArentaError::Locked { pid }) => assert_eq!(pid, Some(std::process::id())),
+            _ => panic!("lock should be held"),
+        }
+        lock.release().unwrap();
+        Lock::acquire(&path).unwrap().release().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lock_is_removed_when_dropped() {
+        let dir = temp_dir("lock-drop");
+        let path = dir.join("lock");
+        let result = std::panic::catch_unwind(|| {
+            let _lock = Lock::acquire(&path).unwrap();
+            panic!("as if the loader panicked");
+        });
+        assert!(result.is_err());
+        assert!(!path.exists());
+
+        // a lock file taken over by another process is left alone
+        let lock = Lock::acquire(&path).unwrap();
+        fs::write(&path, "1").unwrap();
+        drop(lock);
+        assert_eq!(read_holder_pid(&path), Some(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_stale_lock_is_reclaimed() {
+        let dir = temp_dir("lock-stale");
+        let path = dir.join("lock");
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        exited.wait().unwrap();
+        fs::write(&path, exited.id().to_string()).unwrap();
+        assert!(!is_running(exited.id()));
+        assert!(is_running(std::process::id()));
+
+        let lock = Lock::acquire(&path).unwrap();
+        assert_eq!(read_holder_pid(&path), Some(std::process::id()));
+        lock.release().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,52 +1,286 @@
 use std::env;
 use std::error::Error;
-use std::fs::File;
+use std::fs;
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+use std::time::Duration;
 
-mod command;
-mod manager;
-mod task;
-mod timeline;
+use arenta::config::Config;
+use arenta::error::{ArentaError, ArentaResult};
+use arenta::lock::Lock;
+use arenta::manager::Manager;
+use arenta::profile::{Profile, DEFAULT_PROFILE};
+use arenta::prompt::Prompter;
+use arenta::{command, display, VERSION};
+use terminal::TerminalPrompter;
+
+mod completion;
+mod terminal;
+mod tui;
 
 fn print_version() {
-    const VERSION: &str = "v1.0.2";
     println!("arenta {VERSION}");
 }
 
 fn print_usage() {
     println!("arenta - A terminal-based daily task management tool with minimal overhead");
-    println!("usage: arenta [-hv]");
+    println!("usage: arenta [-hv] [--plain] [--read-only] [--tui] [--profile <name>] [-e <command>] [--batch <file|-> [--keep-going]]");
+    println!("  -e <command>  execute a single command and exit, e.g. arenta -e \"n Buy milk\"");
+    println!("  --batch <file|->  execute the commands of the file or stdin one per line, stopping at the first failure");
+    println!("  --keep-going  with --batch, execute the rest of the commands after a failure");
+    println!("  --plain       print no colors or other escape sequences, telling statuses by text");
+    println!("  --profile <name>  work on the task list of the profile, `default` if not given");
+    println!("  --read-only   open a session which only looks at tasks, beside another one holding the lock");
+    println!(
+        "  --tui         open the session in the full-screen view, as if `tui` were typed first"
+    );
+}
+
+// mutating one-shot commands exit with EX_TEMPFAIL when the lock is still held after waiting
+const LOCK_HELD_EXIT_CODE: u8 = 75;
+const LOCK_WAIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// the session goes on once the full-screen view of `tui` is quit; returns the profile to
+// switch to, like `start_loop`
+fn run_session(manager: &mut Manager, mut is_tui_requested: bool) -> Option<String> {
+    loop {
+        if is_tui_requested {
+            if let Err(e) = tui::run(manager) {
+                eprintln!("failed to open the full-screen view: {e}");
+            }
+        }
+        let next = manager.start_loop();
+        is_tui_requested = manager.take_tui_request();
+        if !is_tui_requested {
+            return next;
+        }
+    }
 }
 
-fn arenta_loop() -> Result<(), Box<dyn Error>> {
-    let mut lock_file = dirs::home_dir().unwrap();
-    lock_file.push(".arenta.lock");
+// a read-only session neither takes the lock nor saves tasks, so it can be opened beside
+// another session to look at the plan
+fn arenta_read_only_loop(mut profile: String, mut is_tui_requested: bool) {
+    loop {
+        let config = Config::load();
+        let manager = Profile::resolve(&config, &profile).and_then(|resolved| {
+            Manager::new_read_only(config, resolved, Box::new(TerminalPrompter::new()))
+        });
+        match manager.map(|mut manager| run_session(&mut manager, is_tui_requested)) {
+            Ok(Some(next)) => {
+                profile = next;
+                is_tui_requested = false;
+            }
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("{e}");
+                return;
+            }
+        }
+    }
+}
+
+// `use` ends a session, which is followed by another one on the profile switched to
+fn arenta_loop(mut profile: String, mut is_tui_requested: bool) -> Result<(), Box<dyn Error>> {
+    loop {
+        let config = Config::load();
+        let resolved = match Profile::resolve(&config, &profile) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                eprintln!("{e}");
+                return Ok(());
+            }
+        };
+        let lock = match Lock::acquire(&resolved.lock_file()) {
+            Ok(lock) => lock,
+            Err(e @ ArentaError::Locked { .. }) => {
+                eprintln!("{e}");
+                let read_only =
+                    TerminalPrompter::new().confirm("open a read-only session instead?", true);
+                if let Ok(true) = read_only {
+                    arenta_read_only_loop(profile, is_tui_requested);
+                }
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                return Ok(());
+            }
+        };
+        let next = match Manager::new(config, resolved, Box::new(TerminalPrompter::new())) {
+            Ok(mut manager) => run_session(&mut manager, is_tui_requested),
+            Err(e) => {
+                eprintln!("{e}");
+                None
+            }
+        };
+        lock.release()?;
+        match next {
+            Some(next) => {
+                profile = next;
+                is_tui_requested = false;
+            }
+            None => return Ok(()),
+        }
+    }
+}
 
-    if let Err(..) = File::options()
-        .read(true)
-        .write(true)
-        .create_new(true)
-        .open(lock_file.as_path())
-    {
-        eprintln!("lock file has been acquired by another process now");
-        return Ok(());
+fn execute_with(manager: ArentaResult<Manager>, command: &command::Command) -> bool {
+    match manager {
+        Ok(mut manager) => manager.execute_once(command),
+        Err(e) => {
+            eprintln!("{e}");
+            false
+        }
     }
+}
 
-    let mut manager = manager::Manager::new();
-    manager.start_loop();
+// read-only commands bypass the lock and read a snapshot of the data file, so that they
+// never block on an interactive session; mutating commands wait shortly for the lock
+fn arenta_execute(command_line: &str, profile: &str) -> Result<ExitCode, Box<dyn Error>> {
+    let Some(command) = command::parse_command(command_line) else {
+        match command::did_you_mean(command_line) {
+            Some(suggestion) => eprintln!("invalid command, {suggestion}"),
+            None => eprintln!("invalid command, use `arenta -h` to show usage"),
+        }
+        return Ok(ExitCode::FAILURE);
+    };
+    if let command::Command::Use(Some(name)) = &command {
+        eprintln!(
+            "`use` only switches profiles in a session, try `arenta --profile {name} -e <command>`"
+        );
+        return Ok(ExitCode::FAILURE);
+    }
+    if command == command::Command::Tui {
+        eprintln!("`tui` only opens in a session, try `arenta --tui`");
+        return Ok(ExitCode::FAILURE);
+    }
+    let config = Config::load();
+    let resolved = match Profile::resolve(&config, profile) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("{e}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let succeeded = if command.is_read_only() {
+        execute_with(
+            Manager::new_read_only(config, resolved, Box::new(TerminalPrompter::new())),
+            &command,
+        )
+    } else {
+        let lock = match Lock::acquire_with_timeout(&resolved.lock_file(), LOCK_WAIT_TIMEOUT) {
+            Ok(lock) => lock,
+            Err(e) => {
+                eprintln!("{e}");
+                return Ok(ExitCode::from(LOCK_HELD_EXIT_CODE));
+            }
+        };
+        let succeeded = execute_with(
+            Manager::new(config, resolved, Box::new(TerminalPrompter::new())),
+            &command,
+        );
+        lock.release()?;
+        succeeded
+    };
+    Ok(if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
 
-    std::fs::remove_file(lock_file.as_path())?;
-    Ok(())
+// like mutating one-shot commands, but the commands are read before waiting for the lock
+fn arenta_batch(source: &str, keep_going: bool, profile: &str) -> Result<ExitCode, Box<dyn Error>> {
+    let lines = match source {
+        "-" => io::stdin().lock().lines().collect::<Result<Vec<_>, _>>(),
+        path => fs::read_to_string(path).map(|content| content.lines().map(String::from).collect()),
+    };
+    let lines = match lines {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("failed to read {source}: {e}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let config = Config::load();
+    let resolved = match Profile::resolve(&config, profile) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("{e}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let lock = match Lock::acquire_with_timeout(&resolved.lock_file(), LOCK_WAIT_TIMEOUT) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("{e}");
+            return Ok(ExitCode::from(LOCK_HELD_EXIT_CODE));
+        }
+    };
+    let succeeded = match Manager::new(config, resolved, Box::new(TerminalPrompter::new())) {
+        Ok(mut manager) => manager.run_batch(&lines, keep_going),
+        Err(e) => {
+            eprintln!("{e}");
+            false
+        }
+    };
+    lock.release()?;
+    Ok(if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() == 1 {
-        arenta_loop()
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let mut args: Vec<String> = env::args().collect();
+    if let Some(position) = args.iter().position(|arg| arg == "--plain") {
+        args.remove(position);
+        display::set_plain();
+    }
+    let profile = match args.iter().position(|arg| arg == "--profile") {
+        Some(position) if position + 1 < args.len() => {
+            args.remove(position);
+            args.remove(position)
+        }
+        Some(_) => {
+            print_usage();
+            return Ok(ExitCode::FAILURE);
+        }
+        None => DEFAULT_PROFILE.to_string(),
+    };
+    let keep_going = match args.iter().position(|arg| arg == "--keep-going") {
+        Some(position) => {
+            args.remove(position);
+            true
+        }
+        None => false,
+    };
+    let is_tui_requested = match args.iter().position(|arg| arg == "--tui") {
+        Some(position) => {
+            args.remove(position);
+            true
+        }
+        None => false,
+    };
+    if args.len() == 3 && args[1] == "--batch" && !is_tui_requested {
+        return arenta_batch(&args[2], keep_going, &profile);
+    } else if keep_going {
+        print_usage();
+        return Ok(ExitCode::FAILURE);
+    } else if args.len() == 1 {
+        arenta_loop(profile, is_tui_requested)?;
+    } else if args.len() == 2 && args[1] == "--read-only" {
+        arenta_read_only_loop(profile, is_tui_requested);
+    } else if is_tui_requested {
+        print_usage();
+        return Ok(ExitCode::FAILURE);
     } else if args.len() == 2 && args[1] == "-v" {
         print_version();
-        Ok(())
+    } else if args.len() == 3 && args[1] == "-e" {
+        return arenta_execute(&args[2], &profile);
     } else {
         print_usage();
-        Ok(())
     }
+    Ok(ExitCode::SUCCESS)
 }
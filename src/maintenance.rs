@@ -0,0 +1,419 @@
+use crate::backup::{self, backup_file_path};
+use crate::clock;
+use crate::config::{Config, StorageKind};
+use crate::history::{deletion_times, read_entries, rotated_log_file};
+use crate::manager::get_sibling_file_path;
+use crate::storage::{open_store, store_file_path, CsvFile, TaskStore};
+use crate::task::Task;
+use chrono::{Duration, NaiveDate};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// the audit log and the history log are rotated once they grow over this
+const LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+pub struct MaintenanceTargets {
+    pub data_file: PathBuf,
+    pub audit_log: Option<PathBuf>,
+    pub storage: StorageKind,
+    pub backups: usize,
+    pub trash_days: u32,
+}
+
+impl MaintenanceTargets {
+    pub fn from_environment(data_file: &Path, config: &Config) -> Self {
+        MaintenanceTargets {
+            data_file: data_file.to_path_buf(),
+            audit_log: std::env::var_os("ARENTA_AUDIT_LOG").map(PathBuf::from),
+            storage: config.storage,
+            backups: config.backups,
+            trash_days: config.trash_days,
+        }
+    }
+
+    // records the date of the last maintenance pass
+    fn stamp_file(&self) -> PathBuf {
+        get_sibling_file_path(&self.data_file, "maintained")
+    }
+
+    fn store_file(&self) -> PathBuf {
+        store_file_path(self.storage, &self.data_file)
+    }
+
+    fn history_log(&self) -> PathBuf {
+        get_sibling_file_path(&self.data_file, "log")
+    }
+}
+
+// every step is independent, so a failing one is reported and the others still run
+struct Step {
+    planned: &'static str,
+    done: &'static str,
+    // returns whether there is anything to do, and only does it if `dry_run` is unset
+    run: fn(&MaintenanceTargets, bool) -> Result<bool, String>,
+}
+
+// the trash is purged before the history log telling when tasks were deleted is rotated
+const STEPS: [Step; 6] = [
+    Step {
+        planned: "remove stale temporary file",
+        done: "removed stale temporary file",
+        run: remove_stale_temporary_file,
+    },
+    Step {
+        planned: "compact journal",
+        done: "compacted journal",
+        run: compact_journal,
+    },
+    Step {
+        planned: "purge expired trash",
+        done: "purged expired trash",
+        run: purge_expired_trash,
+    },
+    Step {
+        planned: "prune old backups",
+        done: "pruned old backups",
+        run: prune_backups,
+    },
+    Step {
+        planned: "rotate audit log",
+        done: "rotated audit log",
+        run: rotate_audit_log,
+    },
+    Step {
+        planned: "rotate history log",
+        done: "rotated history log",
+        run: rotate_history_log,
+    },
+];
+
+// runs the maintenance pass at most once per day
+pub fn run_if_due(targets: &MaintenanceTargets, today: NaiveDate) {
+    let last_run = fs::read_to_string(targets.stamp_file()).unwrap_or_default();
+    if last_run.trim() == today.format("%F").to_string() {
+        return;
+    }
+    let summary = run(targets, today, false);
+    if !summary.is_empty() {
+        println!("maintenance: {}", summary.join(", "));
+    }
+}
+
+// returns what has been done, or would be done in a dry run
+pub fn run(targets: &MaintenanceTargets, today: NaiveDate, dry_run: bool) -> Vec<&'static str> {
+    let mut summary = vec![];
+    for step in &STEPS {
+        match (step.run)(targets, dry_run) {
+            Ok(true) => summary.push(if dry_run { step.planned } else { step.done }),
+            Ok(false) => {}
+            Err(e) => eprintln!("maintenance: failed to {}: {e}", step.planned),
+        }
+    }
+    if !dry_run {
+        if let Err(e) = fs::write(targets.stamp_file(), today.format("%F\n").to_string()) {
+            eprintln!("maintenance: failed to record the last run: {e}");
+        }
+    }
+    summary
+}
+
+// left behind if arenta is killed while writing the data file
+fn remove_stale_temporary_file(
+    targets: &MaintenanceTargets,
+    dry_run: bool,
+) -> Result<bool, String> {
+    let tmp_file = get_sibling_file_path(&targets.data_file, "tmp");
+    if !tmp_file.exists() {
+        return Ok(false);
+    }
+    if !dry_run {
+        fs::remove_file(&tmp_file).map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
+
+// folds the journal into the data file, as a session does on quit; corrupt lines fail it, as
+// they are left for a session to ask about
+fn compact_journal(targets: &MaintenanceTargets, dry_run: bool) -> Result<bool, String> {
+    let journal = get_sibling_file_path(&targets.data_file, "journal");
+    if targets.storage != StorageKind::Csv || !journal.exists() {
+        return Ok(false);
+    }
+    let mut store = CsvFile::new(&targets.data_file);
+    let tasks = store.load_all().map_err(|e| e.to_string())?;
+    if store.pending() == 0 {
+        return Ok(false);
+    }
+    if !dry_run {
+        save_tasks(targets, &mut store, &tasks)?;
+    }
+    Ok(true)
+}
+
+// deleted tasks are purged once they have been in the trash for `trash_days`, as told by the
+// history log; those deleted before it tells are kept until `purge`
+fn purge_expired_trash(targets: &MaintenanceTargets, dry_run: bool) -> Result<bool, String> {
+    if targets.trash_days == 0 {
+        return Ok(false);
+    }
+    let mut store =
+        open_store(targets.storage, &targets.store_file()).map_err(|e| e.to_string())?;
+    let tasks = store.load_all().map_err(|e| e.to_string())?;
+    if !tasks.iter().any(|task| task.is_deleted) {
+        return Ok(false);
+    }
+    let entries = read_entries(&targets.history_log()).map_err(|e| e.to_string())?;
+    let deleted_at = deletion_times(&entries);
+    let expiry = clock::now() - Duration::days(targets.trash_days as i64);
+    let is_expired = |task: &Task| {
+        task.is_deleted
+            && deleted_at
+                .get(&task.created_at)
+                .is_some_and(|time| *time <= expiry)
+    };
+    if !tasks.iter().any(is_expired) {
+        return Ok(false);
+    }
+    if !dry_run {
+        let kept: Vec<Task> = tasks.into_iter().filter(|task| !is_expired(task)).collect();
+        save_tasks(targets, store.as_mut(), &kept)?;
+    }
+    Ok(true)
+}
+
+// the file is backed up before it's rewritten, as a session does, and left alone if it can't be
+fn save_tasks(
+    targets: &MaintenanceTargets,
+    store: &mut dyn TaskStore,
+    tasks: &[Task],
+) -> Result<(), String> {
+    backup::rotate(&targets.store_file(), targets.backups)
+        .map_err(|e| format!("failed to back up {}: {e}", targets.store_file().display()))?;
+    store.save_all(tasks).map_err(|e| e.to_string())
+}
+
+// backups beyond the count kept, e.g. after `backups` is lowered, which are otherwise only
+// pruned the next time the data file is rewritten
+fn prune_backups(targets: &MaintenanceTargets, dry_run: bool) -> Result<bool, String> {
+    let store_file = targets.store_file();
+    let stale: Vec<PathBuf> = (targets.backups + 1..)
+        .map(|number| backup_file_path(&store_file, number))
+        .take_while(|path| path.exists())
+        .collect();
+    if !dry_run {
+        for path in &stale {
+            fs::remove_file(path).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(!stale.is_empty())
+}
+
+fn rotate_audit_log(targets: &MaintenanceTargets, dry_run: bool) -> Result<bool, String> {
+    match &targets.audit_log {
+        Some(audit_log) => rotate_log(audit_log, dry_run),
+        None => Ok(false),
+    }
+}
+
+fn rotate_history_log(targets: &MaintenanceTargets, dry_run: bool) -> Result<bool, String> {
+    rotate_log(&targets.history_log(), dry_run)
+}
+
+// keeps a single rotated log as `<log>.1`, which `log` still reads for the history log
+fn rotate_log(log: &Path, dry_run: bool) -> Result<bool, String> {
+    let Ok(metadata) = fs::metadata(log) else {
+        return Ok(false);
+    };
+    if metadata.len() <= LOG_MAX_BYTES {
+        return Ok(false);
+    }
+    if !dry_run {
+        fs::rename(log, rotated_log_file(log)).map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, EventKind, Subscriber};
+    use crate::history::HistoryLog;
+    use crate::manager::load_tasks_from_file;
+    use crate::testing::temp_dir;
+
+    fn targets_in(dir: &Path) -> MaintenanceTargets {
+        MaintenanceTargets {
+            data_file: dir.join("arenta"),
+            audit_log: Some(dir.join("audit.log")),
+            storage: StorageKind::Csv,
+            backups: 3,
+            trash_days: 30,
+        }
+    }
+
+    fn descriptions(data_file: &Path) -> Vec<String> {
+        load_tasks_from_file(data_file)
+            .tasks
+            .into_iter()
+            .map(|task| task.description)
+            .collect()
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = temp_dir("maintenance");
+        let targets = targets_in(&dir);
+        let today = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        fs::write(dir.join("arenta.tmp"), "half-written").unwrap();
+        fs::write(
+            dir.join("audit.log"),
+            vec![b'x'; LOG_MAX_BYTES as usize + 1],
+        )
+        .unwrap();
+
+        assert_eq!(
+            run(&targets, today, true),
+            vec!["remove stale temporary file", "rotate audit log"]
+        );
+        assert!(dir.join("arenta.tmp").exists());
+        assert!(!dir.join("audit.log.1").exists());
+        assert!(!dir.join("arenta.maintained").exists());
+
+        assert_eq!(
+            run(&targets, today, false),
+            vec!["removed stale temporary file", "rotated audit log"]
+        );
+        assert!(!dir.join("arenta.tmp").exists());
+        assert!(dir.join("audit.log.1").exists());
+        assert!(!dir.join("audit.log").exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("arenta.maintained")).unwrap(),
+            "2024-03-04\n"
+        );
+        assert!(run(&targets, today, false).is_empty());
+
+        // a failing step doesn't stop the others
+        fs::remove_file(dir.join("audit.log.1")).unwrap();
+        fs::create_dir_all(dir.join("audit.log.1").join("occupied")).unwrap();
+        fs::write(
+            dir.join("audit.log"),
+            vec![b'x'; LOG_MAX_BYTES as usize + 1],
+        )
+        .unwrap();
+        fs::write(dir.join("arenta.tmp"), "").unwrap();
+        assert_eq!(
+            run(&targets, today, false),
+            vec!["removed stale temporary file"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact_journal() {
+        let dir = temp_dir("maintenance-journal");
+        let targets = targets_in(&dir);
+        assert_eq!(compact_journal(&targets, false), Ok(false));
+        let mut store = CsvFile::new(&targets.data_file);
+        store.save_all(&[Task::new_backlog_task("a")]).unwrap();
+        store.append(&[Task::new_backlog_task("b")]).unwrap();
+        let journal = get_sibling_file_path(&targets.data_file, "journal");
+        assert!(journal.exists());
+
+        assert_eq!(compact_journal(&targets, true), Ok(true));
+        assert!(journal.exists());
+        assert_eq!(compact_journal(&targets, false), Ok(true));
+        assert!(!journal.exists());
+        assert_eq!(descriptions(&targets.data_file), ["a", "b"]);
+        assert_eq!(
+            descriptions(&backup_file_path(&targets.data_file, 1)),
+            ["a"]
+        );
+        assert_eq!(compact_journal(&targets, false), Ok(false));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_purge_expired_trash() {
+        let _frozen = clock::freeze("2024-03-31 12:00");
+        let dir = temp_dir("maintenance-trash");
+        let mut targets = targets_in(&dir);
+        let mut tasks = vec![];
+        for (description, days_created_ago) in [("expired", 50), ("recent", 40), ("live", 30)] {
+            let mut task = Task::new_backlog_task(description);
+            task.created_at = clock::now() - Duration::days(days_created_ago);
+            task.is_deleted = description != "live";
+            tasks.push(task);
+        }
+        // deleted before the history log told, so kept until `purge`
+        let mut untold = Task::new_backlog_task("untold");
+        untold.is_deleted = true;
+        tasks.push(untold);
+        CsvFile::new(&targets.data_file).save_all(&tasks).unwrap();
+        let mut history = HistoryLog::new(targets.history_log());
+        for (task, days_ago) in [(&tasks[0], 31), (&tasks[1], 10)] {
+            history.notify(&Event {
+                id: 0,
+                kind: EventKind::Deleted,
+                index: 0,
+                description: task.description.clone(),
+                created_at: task.created_at,
+                time: clock::now() - Duration::days(days_ago),
+                changes: vec![],
+            });
+        }
+
+        targets.trash_days = 0;
+        assert_eq!(purge_expired_trash(&targets, false), Ok(false));
+        targets.trash_days = 30;
+        assert_eq!(purge_expired_trash(&targets, true), Ok(true));
+        assert_eq!(descriptions(&targets.data_file).len(), 4);
+        assert_eq!(purge_expired_trash(&targets, false), Ok(true));
+        assert_eq!(
+            descriptions(&targets.data_file),
+            ["recent", "live", "untold"]
+        );
+        assert_eq!(
+            descriptions(&backup_file_path(&targets.data_file, 1)).len(),
+            4
+        );
+        assert_eq!(purge_expired_trash(&targets, false), Ok(false));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_backups() {
+        let dir = temp_dir("maintenance-backups");
+        let mut targets = targets_in(&dir);
+        for number in 1..=5 {
+            fs::write(backup_file_path(&targets.data_file, number), "").unwrap();
+        }
+        assert_eq!(prune_backups(&targets, true), Ok(true));
+        assert!(backup_file_path(&targets.data_file, 5).exists());
+        assert_eq!(prune_backups(&targets, false), Ok(true));
+        assert!(backup_file_path(&targets.data_file, 3).exists());
+        assert!(!backup_file_path(&targets.data_file, 4).exists());
+        assert!(!backup_file_path(&targets.data_file, 5).exists());
+        assert_eq!(prune_backups(&targets, false), Ok(false));
+        targets.backups = 0;
+        assert_eq!(prune_backups(&targets, false), Ok(true));
+        assert!(!backup_file_path(&targets.data_file, 1).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_history_log() {
+        let dir = temp_dir("maintenance-history");
+        let targets = targets_in(&dir);
+        let history_log = targets.history_log();
+        assert_eq!(rotate_history_log(&targets, false), Ok(false));
+        fs::write(&history_log, "{}\n").unwrap();
+        assert_eq!(rotate_history_log(&targets, false), Ok(false));
+        fs::write(&history_log, vec![b'x'; LOG_MAX_BYTES as usize + 1]).unwrap();
+        assert_eq!(rotate_history_log(&targets, true), Ok(true));
+        assert!(history_log.exists());
+        assert_eq!(rotate_history_log(&targets, false), Ok(true));
+        assert!(!history_log.exists());
+        assert!(rotated_log_file(&history_log).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
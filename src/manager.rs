@@ -1,354 +1,3561 @@
-use crate::command::{parse_command, print_command_usage, Command, DateFilterOp, ListOption};
-use crate::task::{Task, TaskStatus};
-use crate::timeline::Timeline;
-use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone};
-use csv::{ReaderBuilder, StringRecord, Writer};
-use inquire::error::InquireResult;
-use inquire::{
-    ui::{RenderConfig, Styled},
-    CustomType, DateSelect, Select, Text,
+use crate::backup;
+use crate::clock;
+use crate::command::{
+    did_you_mean, parse_command, parse_tags, print_command_help, print_command_usage,
+    remove_invalid_list_args, try_parse_capture, Arrangement, Command, DateFilter, DateFilterOp,
+    ExportFormat, ExportTarget, ListOption, ReportPeriod, StatsKind, TaskAction, TaskEdit,
+    TemplateAction,
 };
-use std::cmp::Ordering;
-use std::path::PathBuf;
+use crate::config::{ColorMode, Config, StorageKind, SyncMode};
+use crate::display::{self, format_size, humanize_duration, pad_to_width};
+use crate::error::{ArentaError, ArentaResult, PromptError};
+use crate::event::{EventBus, EventKind};
+use crate::history::{diff_tasks, format_json_value, history_of, read_entries, HistoryLog};
+use crate::hyperlink;
+use crate::ical::tasks_to_calendar;
+use crate::input::{parse_duration, parse_time};
+use crate::journal::{content_hash, JOURNAL_MAX_RECORDS};
+use crate::json::{
+    json_to_record, listed_tasks_to_json, parse_array_with_lines, task_to_json, JsonValue,
+};
+use crate::lock::read_holder_pid;
+use crate::maintenance::{self, MaintenanceTargets};
+use crate::markdown::plan_to_markdown;
+use crate::profile::{self, list_profiles, lock_file_path, Profile};
+use crate::prompt::{Prompter, SelectPrompt, TextPrompt};
+use crate::prompt_history::PromptHistory;
+use crate::report::build_report;
+use crate::schedule::{free_slots, pack_into_slots, round_up_to_five_minutes, Interval};
+use crate::stats::{
+    estimate_stats, punctuality_stats, render_heatmap, render_tag_times, tag_times_to_csv,
+    time_per_tag,
+};
+use crate::storage::{open_store, store_file_path, CsvFile, SqliteFile, TaskStore};
+use crate::sync::{split_conflict, PullError, Repo};
+use crate::task::{
+    compare_date, render_verbose_header, Task, TaskStatus, WorkInterval, PRIORITY_HIGH,
+    PRIORITY_NAMES,
+};
+use crate::template::{load_templates, save_templates, validate_name, Template};
+use crate::timeline::{render_color_key, Timeline};
+use crate::timing::{format_duration, Stopwatch};
+use crate::todotxt::line_to_task;
+use crate::working::{self, WorkingHours};
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+    Utc, Weekday,
+};
+use colored::Colorize;
+use csv::{ByteRecord, ReaderBuilder, StringRecord, WriterBuilder};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub struct Manager {
     tasks: Vec<Task>,
+    file_path: PathBuf,
+    config: Config,
+    events: EventBus,
+    // a read-only manager never writes the data file, as it doesn't hold the lock
+    is_read_only: bool,
+    // name and duration of every command executed in this session
+    timings: Vec<(&'static str, std::time::Duration)>,
+    // set by `sort`, which orders listings without touching the order in the data file
+    is_listed_by_priority: bool,
+    // the next page of the latest limited listing which has more, for `more`
+    next_page: Option<ListOption>,
+    // tasks as they are saved, to find out what to put into the store
+    persisted_rows: Vec<Vec<String>>,
+    // where tasks are saved, the data file itself unless `storage` says otherwise
+    store: Box<dyn TaskStore>,
+    // none if the data file is set in the config instead
+    profile: Option<String>,
+    // set by `use`, which ends the session to start another one on the profile
+    next_profile: Option<String>,
+    // set by `tui`, which ends the session for the frontend to open its full-screen view
+    is_tui_requested: bool,
+    // the files as they were when loaded or last saved by this process; any other change
+    // comes from another process, whose changes a save would clobber
+    files_stamp: FilesStamp,
+    // answers whatever the commands ask, e.g. the terminal
+    prompter: Box<dyn Prompter>,
+}
+
+/// What `ll` shows for a day, see [`Manager::day_view`].
+pub struct DayView {
+    pub timeline: Vec<String>,
+    /// The index of every task on the timeline along with its row.
+    pub rows: Vec<(usize, String)>,
+    /// The count of tasks in each status, e.g. `2 complete, 1 ongoing`.
+    pub summary: String,
+}
+
+// decided once the config is loaded, left alone by tests which load managers on their own
+fn init_output(config: &Config) {
+    display::init(
+        config.color,
+        &config.time_format,
+        config.plain,
+        config.backlog_stale_days,
+    );
+    working::init(config.working_hours.map(|hours| WorkingHours {
+        hours,
+        days: config.working_days.clone(),
+    }));
+}
+
+pub(crate) struct LoadResult {
+    pub(crate) tasks: Vec<Task>,
+    // identifies the content of the data file, which the journal applies to
+    pub(crate) base_hash: u64,
+    // each skipped record comes along with its raw content, so it can be quarantined
+    pub(crate) skipped: Vec<(ArentaError, String)>,
+    // the format of a file written by a newer arenta, which this one may not save faithfully
+    pub(crate) newer_version: Option<u32>,
+}
+
+impl LoadResult {
+    // a file of a newer format is only read, so that its newer fields aren't written away
+    pub(crate) fn check_version(&self) -> ArentaResult<()> {
+        match self.newer_version {
+            Some(version) => Err(ArentaError::validation(format!(
+                "the data file is of format {version}, written by a newer arenta, while this one \
+                 only knows format {FORMAT_VERSION}; upgrade arenta, or open it with --read-only"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+// a data file merged by git outside of `sync` may be left with conflict markers, which would
+// make every line in between corrupt; the file as it was is kept as the latest backup
+fn resolve_conflict_markers(
+    prompter: &mut dyn Prompter,
+    file_path: &Path,
+    config: &Config,
+    is_read_only: bool,
+) -> ArentaResult<()> {
+    let content = fs::read(file_path).unwrap_or_default();
+    let Some([(first_label, first), (second_label, second)]) =
+        split_conflict(&String::from_utf8_lossy(&content))
+    else {
+        return Ok(());
+    };
+    eprintln!("{} has git conflict markers", file_path.display());
+    if is_read_only {
+        eprintln!("the lines between them are skipped, resolve them in a session of its own");
+        return Ok(());
+    }
+    let tasks = resolve_conflict(
+        prompter,
+        [&first_label, &second_label],
+        parse_tasks(first.as_bytes()).tasks,
+        parse_tasks(second.as_bytes()).tasks,
+    )?;
+    let save_error = |e| {
+        ArentaError::io(
+            format!("failed to resolve the conflict in {}", file_path.display()),
+            e,
+        )
+    };
+    backup::rotate(file_path, config.backups.max(1)).map_err(save_error)?;
+    write_tasks_to_file(file_path, &tasks).map_err(save_error)?;
+    Ok(())
+}
+
+fn stamp(file_path: &Path) -> io::Result<(u64, Option<SystemTime>)> {
+    let metadata = fs::metadata(file_path)?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+// the stamps of the data file and its journal, which tell whether tasks have been saved
+type FilesStamp = [Option<(u64, Option<SystemTime>)>; 2];
+
+fn files_stamp(file_path: &Path) -> FilesStamp {
+    [
+        stamp(file_path).ok(),
+        stamp(&get_sibling_file_path(file_path, "journal")).ok(),
+    ]
 }
 
-fn get_arenta_file_path() -> PathBuf {
-    let mut arenta_file = dirs::home_dir().unwrap();
-    arenta_file.push(".arenta");
-    arenta_file
+// reads the whole file at once, retrying once if it's modified during reading, so that
+// readers not holding the lock still get a consistent snapshot
+fn read_snapshot(file_path: &Path) -> io::Result<Vec<u8>> {
+    let mut content = vec![];
+    for _ in 0..2 {
+        let before = stamp(file_path)?;
+        content = fs::read(file_path)?;
+        if stamp(file_path)? == before {
+            break;
+        }
+    }
+    Ok(content)
+}
+
+pub(crate) fn load_tasks_from_file(file_path: &Path) -> LoadResult {
+    parse_tasks(&read_snapshot(file_path).unwrap_or_default())
 }
 
-fn load_tasks_from_file() -> Vec<Task> {
-    let reader = ReaderBuilder::new()
+fn parse_tasks(content: &[u8]) -> LoadResult {
+    let mut result = LoadResult {
+        tasks: vec![],
+        base_hash: content_hash(content),
+        skipped: vec![],
+        newer_version: None,
+    };
+    // the tasks of rows without a creation time, with the hashes of those rows
+    let mut uncreated = vec![];
+    let mut reader = ReaderBuilder::new()
         .has_headers(false)
-        .from_path(get_arenta_file_path().as_path());
-    if reader.is_err() {
-        return vec![];
-    }
-    fn record_to_task(record: StringRecord) -> Task {
-        assert_eq!(record.len(), 6);
-        let planned_start = datetime_opt_from_string(record.get(1).unwrap());
-        let planned_complete = datetime_opt_from_string(record.get(2).unwrap());
-        let actual_start = datetime_opt_from_string(record.get(3).unwrap());
-        let actual_complete = datetime_opt_from_string(record.get(4).unwrap());
-        if planned_start.is_some() != planned_complete.is_some() {
-            panic!("planned start and complete should always come in pair");
-        }
-        if planned_start.is_some() && planned_start.unwrap() > planned_complete.unwrap() {
-            panic!("planned start shouldn't be later than planned complete");
-        }
-        if actual_start.is_some()
-            && actual_complete.is_some()
-            && actual_start.unwrap() > actual_complete.unwrap()
-        {
-            panic!("actual start shouldn't be later than actual complete");
+        .flexible(true)
+        .from_reader(content);
+    for (record_index, byte_record) in reader.byte_records().enumerate() {
+        let byte_record = match byte_record {
+            Ok(byte_record) => byte_record,
+            Err(e) => {
+                let row = e
+                    .position()
+                    .map_or(record_index as u64 + 1, |pos| pos.line());
+                let error = ArentaError::Parse {
+                    row,
+                    reason: e.to_string(),
+                };
+                result.skipped.push((error, String::new()));
+                continue;
+            }
+        };
+        let row = byte_record
+            .position()
+            .map_or(record_index as u64 + 1, |pos| pos.line());
+        if record_index == 0 && byte_record.get(0) == Some(FORMAT_MARKER.as_bytes()) {
+            result.newer_version = byte_record
+                .get(1)
+                .and_then(|version| std::str::from_utf8(version).ok()?.parse().ok())
+                .filter(|version| *version > FORMAT_VERSION);
+            continue;
+        }
+        let task = StringRecord::from_byte_record(byte_record.clone())
+            .map_err(|_| "record is not valid utf-8".to_string())
+            .and_then(|record| record_to_task(&record))
+            .map_err(|reason| ArentaError::Parse { row, reason });
+        match task {
+            Ok(task) => {
+                if byte_record.get(12).is_none_or(<[u8]>::is_empty) {
+                    uncreated.push((result.tasks.len(), content_hash(byte_record.as_slice())));
+                }
+                result.tasks.push(task);
+            }
+            Err(error) => result
+                .skipped
+                .push((error, byte_record_to_raw(&byte_record))),
+        }
+    }
+    stamp_uncreated_tasks(&mut result.tasks, &uncreated);
+    result
+}
+
+// rows written before creation times were kept are given one from their content, so that
+// they're the same tasks wherever and whenever the file is parsed, e.g. on another machine
+// sharing it or on reload: the earliest time of the row, else of the file, as tasks were
+// created before they were planned or started, plus the hash of the row in nanoseconds to
+// tell apart rows of the same times
+fn stamp_uncreated_tasks(tasks: &mut [Task], uncreated: &[(usize, u64)]) {
+    fn earliest_time(task: &Task) -> Option<DateTime<Local>> {
+        [
+            task.planned_start,
+            task.planned_complete,
+            task.actual_start,
+            task.actual_complete,
+        ]
+        .into_iter()
+        .chain(task.work_intervals.iter().map(|(start, _)| Some(*start)))
+        .flatten()
+        .min()
+    }
+    if uncreated.is_empty() {
+        return;
+    }
+    let is_uncreated = |index: usize| uncreated.iter().any(|(other, _)| *other == index);
+    // a file of nothing but timeless rows has no time to go by, so they're taken as created
+    // long ago
+    let earliest_of_file = tasks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, task)| match is_uncreated(index) {
+            true => earliest_time(task),
+            false => Some(task.created_at),
+        })
+        .min()
+        .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap().with_timezone(&Local));
+    for &(index, hash) in uncreated {
+        let created_at = earliest_time(&tasks[index]).unwrap_or(earliest_of_file);
+        tasks[index].created_at = created_at
+            .with_nanosecond((hash % 1_000_000_000) as u32)
+            .unwrap_or(created_at);
+    }
+}
+
+// columns after these are kept in `Task::extras`, as they may be added by a newer version
+pub(crate) const KNOWN_COLUMNS: usize = 13;
+
+// the data file starts with a `#arenta,<version>` row, files written before versioning have
+// no such row and may lack the deletion column
+const FORMAT_MARKER: &str = "#arenta";
+const FORMAT_VERSION: u32 = 2;
+
+// planned tasks starting within this many hours are suggested by `next`
+const NEXT_TASK_WINDOW_HOURS: i64 = 2;
+
+pub fn record_to_task(record: &StringRecord) -> Result<Task, String> {
+    if record.len() < 5 {
+        return Err(format!(
+            "expected at least 5 columns, found {}",
+            record.len()
+        ));
+    }
+    let planned_start = datetime_opt_from_string(&record[1])?;
+    let planned_complete = datetime_opt_from_string(&record[2])?;
+    let actual_start = datetime_opt_from_string(&record[3])?;
+    let actual_complete = datetime_opt_from_string(&record[4])?;
+    if planned_start.is_some() != planned_complete.is_some() {
+        return Err("planned start and complete should always come in pair".to_string());
+    }
+    if let (Some(start), Some(complete)) = (planned_start, planned_complete) {
+        if start > complete {
+            return Err("planned start shouldn't be later than planned complete".to_string());
         }
-        Task {
-            description: record.get(0).unwrap().to_string(),
-            planned_start,
-            planned_complete,
-            actual_start,
-            actual_complete,
-            status: TaskStatus::Planned,
-            is_deleted: record.get(5).unwrap().parse::<bool>().unwrap(),
+    }
+    if let (Some(start), Some(complete)) = (actual_start, actual_complete) {
+        if start > complete {
+            return Err("actual start shouldn't be later than actual complete".to_string());
         }
     }
-    reader
-        .unwrap()
-        .records()
-        .map(|result| record_to_task(result.unwrap()))
-        .collect::<Vec<_>>()
+    let is_deleted = match record.get(5) {
+        None => false,
+        Some(flag) => flag
+            .parse::<bool>()
+            .map_err(|_| format!("invalid deletion flag `{flag}`"))?,
+    };
+    Ok(Task {
+        description: record[0].to_string(),
+        planned_start,
+        planned_complete,
+        actual_start,
+        actual_complete,
+        status: TaskStatus::Planned,
+        is_deleted,
+        tags: record.get(6).map(parse_tags).unwrap_or_default(),
+        postponed_count: match record.get(7) {
+            None | Some("") => 0,
+            Some(count) => count
+                .parse::<u32>()
+                .map_err(|_| format!("invalid postponed count `{count}`"))?,
+        },
+        priority: match record.get(8) {
+            None | Some("") => 0,
+            Some(priority) => priority
+                .parse::<u8>()
+                .ok()
+                .filter(|&priority| priority <= PRIORITY_HIGH)
+                .ok_or_else(|| format!("invalid priority `{priority}`"))?,
+        },
+        is_private: match record.get(9) {
+            None | Some("") => false,
+            Some(flag) => flag
+                .parse::<bool>()
+                .map_err(|_| format!("invalid private flag `{flag}`"))?,
+        },
+        work_intervals: work_intervals_from_string(record.get(10).unwrap_or_default())?,
+        notes: record.get(11).unwrap_or_default().to_string(),
+        // rows written before creation times were kept are taken as created on load, which
+        // `parse_tasks` then replaces with a time derived from the row
+        created_at: datetime_opt_from_string(record.get(12).unwrap_or_default())?
+            .unwrap_or_else(clock::now),
+        extras: record
+            .iter()
+            .skip(KNOWN_COLUMNS)
+            .map(String::from)
+            .collect(),
+    })
+}
+
+fn byte_record_to_raw(byte_record: &ByteRecord) -> String {
+    let mut writer = WriterBuilder::new().flexible(true).from_writer(vec![]);
+    if writer.write_byte_record(byte_record).is_err() {
+        return String::new();
+    }
+    writer
+        .into_inner()
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default()
+}
+
+// e.g. `~/.arenta` with suffix `corrupt` becomes `~/.arenta.corrupt`
+pub fn get_sibling_file_path(file_path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(suffix);
+    file_path.with_file_name(file_name)
+}
+
+fn handle_skipped_records(
+    prompter: &mut dyn Prompter,
+    file_path: &Path,
+    skipped: &[(ArentaError, String)],
+) -> ArentaResult<()> {
+    let corrupt_file_path = get_sibling_file_path(file_path, "corrupt");
+    eprintln!(
+        "{} corrupt line(s) found in {}:",
+        skipped.len(),
+        file_path.display()
+    );
+    skipped.iter().for_each(|(error, _)| eprintln!("  {error}"));
+    let continue_option = format!(
+        "continue, moving corrupt lines to {}",
+        corrupt_file_path.display()
+    );
+    let options = [continue_option.as_str(), "abort without touching the file"];
+    let option = select_option(prompter, "how to deal with them", &options)?;
+    if !option.starts_with("continue") {
+        return Err(ArentaError::validation(format!(
+            "aborted, {} is left untouched",
+            file_path.display()
+        )));
+    }
+    let quarantined = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&corrupt_file_path)
+        .and_then(|mut file| {
+            skipped
+                .iter()
+                .try_for_each(|(_, raw)| file.write_all(raw.as_bytes()))
+        });
+    quarantined.map_err(|e| ArentaError::io("failed to quarantine corrupt lines", e))?;
+    println!("corrupt lines moved to {}", corrupt_file_path.display());
+    Ok(())
 }
 
 impl Manager {
-    pub fn new() -> Self {
-        Manager {
-            tasks: load_tasks_from_file(),
+    pub fn new(
+        config: Config,
+        profile: Profile,
+        prompter: Box<dyn Prompter>,
+    ) -> ArentaResult<Self> {
+        init_output(&config);
+        let mut events = EventBus::from_environment();
+        events.subscribe(Box::new(HistoryLog::new(get_sibling_file_path(
+            &profile.data_file,
+            "log",
+        ))));
+        let mut manager = Self::load(profile.data_file, config, events, false, prompter)?;
+        manager.profile = profile.name;
+        maintenance::run_if_due(
+            &MaintenanceTargets::from_environment(&manager.file_path, &manager.config),
+            clock::today(),
+        );
+        manager.reload_maintained_tasks();
+        Ok(manager)
+    }
+
+    pub fn new_read_only(
+        config: Config,
+        profile: Profile,
+        prompter: Box<dyn Prompter>,
+    ) -> ArentaResult<Self> {
+        init_output(&config);
+        let mut manager = Self::load(profile.data_file, config, EventBus::new(), true, prompter)?;
+        manager.profile = profile.name;
+        Ok(manager)
+    }
+
+    pub(crate) fn load(
+        file_path: PathBuf,
+        config: Config,
+        events: EventBus,
+        is_read_only: bool,
+        mut prompter: Box<dyn Prompter>,
+    ) -> ArentaResult<Self> {
+        let store_path = store_file_path(config.storage, &file_path);
+        let files_stamp = files_stamp(&store_path);
+        let (store, mut tasks, skipped, journal_warning): (Box<dyn TaskStore>, _, _, _) =
+            match config.storage {
+                StorageKind::Csv => {
+                    resolve_conflict_markers(prompter.as_mut(), &file_path, &config, is_read_only)?;
+                    let mut store = CsvFile::new(&file_path);
+                    let (result, warning) = store.load();
+                    match result.newer_version {
+                        Some(version) if is_read_only => eprintln!(
+                            "the data file is of format {version}, written by a newer arenta, \
+                             some of its fields may not show"
+                        ),
+                        _ => result.check_version()?,
+                    }
+                    (Box::new(store), result.tasks, result.skipped, warning)
+                }
+                StorageKind::Sqlite => {
+                    let mut store = SqliteFile::new(&store_path)?;
+                    let tasks = store.load_all()?;
+                    if !store_path.exists() {
+                        hint_migration(&file_path, &store_path);
+                    }
+                    (Box::new(store), tasks, vec![], None)
+                }
+            };
+        if let Some(warning) = &journal_warning {
+            eprintln!("{warning}");
+        }
+        // statuses derived at load time are not transitions, so don't emit events for them
+        tasks.iter_mut().for_each(|task| task.update_status());
+        hyperlink::init(
+            config.hyperlinks && config.color != ColorMode::Never && !display::is_plain(),
+        );
+        let mut manager = Manager {
+            persisted_rows: tasks_to_rows(&tasks),
+            tasks,
+            file_path,
+            config,
+            events,
+            is_read_only,
+            timings: vec![],
+            is_listed_by_priority: false,
+            next_page: None,
+            store,
+            files_stamp,
+            profile: None,
+            next_profile: None,
+            is_tui_requested: false,
+            prompter,
+        };
+        if !skipped.is_empty() && is_read_only {
+            eprintln!("{} corrupt line(s) skipped:", skipped.len());
+            skipped.iter().for_each(|(error, _)| eprintln!("  {error}"));
+        } else if !skipped.is_empty() {
+            handle_skipped_records(manager.prompter.as_mut(), &manager.file_path, &skipped)?;
+            manager.compact_tasks_or_warn();
+        }
+        // a damaged journal is compacted away, so that nothing is left to warn about
+        if journal_warning.is_some() && !is_read_only {
+            manager.compact_tasks_or_warn();
+        }
+        Ok(manager)
+    }
+
+    /// The tasks in the order of the data file, deleted ones included; their positions are
+    /// the indices the other methods take.
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    /// Appends the task and saves it, returning its index.
+    ///
+    /// ```
+    /// # let dir = std::env::temp_dir().join(format!("arenta-doc-{}-add", std::process::id()));
+    /// # std::fs::create_dir_all(&dir).unwrap();
+    /// use arenta::{Config, Manager, NoPrompter, Profile, Task};
+    ///
+    /// let profile = Profile { name: None, data_file: dir.join("tasks.csv") };
+    /// let mut manager = Manager::new(Config::default(), profile, Box::new(NoPrompter)).unwrap();
+    /// let index = manager.add_task(Task::new_backlog_task("water the plants")).unwrap();
+    /// assert_eq!(manager.tasks()[index].description, "water the plants");
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn add_task(&mut self, task: Task) -> ArentaResult<usize> {
+        self.tasks.push(task);
+        self.dump_tasks()?;
+        let index = self.tasks.len() - 1;
+        self.emit(EventKind::Created, index);
+        Ok(index)
+    }
+
+    /// Starts the task, failing if it's ongoing, paused, complete or deleted.
+    pub fn start(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let task = &mut self.tasks[index];
+        task.update_status();
+        // starting again would move the start past an earlier completion
+        if !is_candidate(TaskAction::Start, task) {
+            return Err(ArentaError::validation(
+                match (task.is_deleted, task.status) {
+                    (true, _) => format!("task {index} is deleted, `restore {index}` first"),
+                    (false, TaskStatus::Paused) => {
+                        format!("task {index} is paused, `resume {index}` to go on")
+                    }
+                    (false, status) => format!("task {index} is {} already", status.name()),
+                },
+            ));
+        }
+        task.start();
+        self.dump_tasks()?;
+        self.emit(EventKind::Started, index);
+        Ok(())
+    }
+
+    /// Completes the task, starting it as well if it never was.
+    ///
+    /// ```
+    /// # let dir = std::env::temp_dir().join(format!("arenta-doc-{}-complete", std::process::id()));
+    /// # std::fs::create_dir_all(&dir).unwrap();
+    /// use arenta::{Config, Manager, NoPrompter, Profile, Task, TaskStatus};
+    ///
+    /// let profile = Profile { name: None, data_file: dir.join("tasks.csv") };
+    /// let mut manager = Manager::new(Config::default(), profile, Box::new(NoPrompter)).unwrap();
+    /// let index = manager.add_task(Task::new_immediate_task("write report")).unwrap();
+    /// manager.complete(index).unwrap();
+    /// assert_eq!(manager.tasks()[index].status, TaskStatus::Complete);
+    /// assert!(manager.complete(index + 1).is_err());
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn complete(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        self.tasks[index].complete();
+        self.dump_tasks()?;
+        self.emit(EventKind::Completed, index);
+        Ok(())
+    }
+
+    /// Marks the task deleted, which hides it from listings until it's restored or purged.
+    pub fn delete(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        self.tasks[index].delete();
+        self.dump_tasks()?;
+        self.emit(EventKind::Deleted, index);
+        Ok(())
+    }
+
+    /// The tasks `ls` would show for the option, in the same order.
+    ///
+    /// ```
+    /// # let dir = std::env::temp_dir().join(format!("arenta-doc-{}-list", std::process::id()));
+    /// # std::fs::create_dir_all(&dir).unwrap();
+    /// use arenta::{parse_command, Command, Config, Manager, NoPrompter, Profile, Task};
+    ///
+    /// let profile = Profile { name: None, data_file: dir.join("tasks.csv") };
+    /// let mut manager = Manager::new(Config::default(), profile, Box::new(NoPrompter)).unwrap();
+    /// manager.add_task(Task::new_immediate_task("write report")).unwrap();
+    /// manager.add_task(Task::new_backlog_task("water the plants")).unwrap();
+    ///
+    /// let Some(Command::List(option)) = parse_command("ls b") else { unreachable!() };
+    /// let listed = manager.list(&option);
+    /// assert_eq!(listed.len(), 2);
+    /// assert_eq!(listed[1].description, "water the plants");
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn list(&mut self, option: &ListOption) -> Vec<&Task> {
+        self.update_status_of_all_tasks();
+        self.matching_tasks(option)
+            .into_iter()
+            .map(|(_, task)| task)
+            .collect()
+    }
+
+    /// The timeline of the first day of the option and the tasks on it the way `ll` shows
+    /// them, as lines for a frontend laying them out on its own; the timeline fits the width
+    /// if given. A read-only manager picks up what the other session saved first.
+    pub fn day_view(&mut self, option: &ListOption, width: Option<usize>) -> DayView {
+        if self.is_read_only {
+            self.load_snapshot();
+        }
+        self.update_status_of_all_tasks();
+        let tasks = self.timeline_tasks(option);
+        let timeline = self
+            .new_timeline(&tasks, option.date_filter.1, option, width)
+            .render();
+        let rows = tasks
+            .iter()
+            .enumerate()
+            .map(|(timeline_index, &(index, task))| {
+                let row = task.render_with_highlight(
+                    index,
+                    Some(timeline_index_to_char(timeline_index)),
+                    option.is_verbose,
+                    option.text.as_deref(),
+                );
+                (index, row)
+            })
+            .collect();
+        DayView {
+            timeline,
+            rows,
+            summary: summarize_statuses(&tasks),
         }
     }
 
-    pub fn start_loop(&mut self) {
-        inquire::set_global_render_config(get_render_config());
+    /// Writes all tasks into another store, e.g. to keep a copy elsewhere.
+    pub fn save_to(&self, store: &mut dyn TaskStore) -> ArentaResult<()> {
+        store.save_all(&self.tasks)
+    }
+
+    // returns the profile to switch to, if the session ends by `use`
+    pub fn start_loop(&mut self) -> Option<String> {
         self.update_status_of_all_tasks();
+        let mut history = PromptHistory::load(get_sibling_file_path(&self.file_path, "history"));
+        if self.config.startup_overview {
+            self.print_overview();
+        }
+        let prompt = match self.is_read_only {
+            true => "arenta (read-only)>",
+            false => "arenta>",
+        };
         loop {
-            let command = Text::new("arenta>").prompt();
-            if command.is_err() {
+            let Ok(line) = self
+                .prompter
+                .command_line(prompt, &self.tasks, history.entries())
+            else {
                 eprintln!("command error, exit");
                 break;
+            };
+            history.push(&line);
+            if let Err(reason) = history.save() {
+                eprintln!("{reason}");
             }
-            let command = parse_command(&command.unwrap());
-            if command.is_none() {
-                println!("invalid command, type `h` to show usage");
-                continue;
+            let pasted_lines = self.prompter.pending_lines();
+            // the other session may have changed tasks since the last command
+            if self.is_read_only {
+                self.load_snapshot();
             }
-            match self.dispatch_command(&command.unwrap()) {
-                Err(..) => {
-                    eprintln!("command error, exit");
+            let result = if pasted_lines.is_empty() {
+                self.execute_line(&line)
+            } else {
+                let mut lines = vec![line];
+                lines.extend(pasted_lines);
+                self.execute_pasted_lines(lines)
+            };
+            match result {
+                Ok(true) => break,
+                Ok(false) => (),
+                Err(e) if e.is_fatal() => {
+                    eprintln!("{e}, exit");
                     break;
                 }
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+        if !self.is_read_only && !self.is_flushed() {
+            self.compact_tasks_or_warn();
+        }
+        self.next_profile.take()
+    }
+
+    /// Whether the session ended by `tui`, which the frontend answers by opening its
+    /// full-screen view before starting the session again; cleared by asking.
+    pub fn take_tui_request(&mut self) -> bool {
+        std::mem::take(&mut self.is_tui_requested)
+    }
+
+    // replaces the tasks in memory with what's saved now, journal included
+    fn load_snapshot(&mut self) {
+        self.files_stamp = files_stamp(&self.store_file_path());
+        match self.store.load_all() {
+            Ok(tasks) => {
+                self.persisted_rows = tasks_to_rows(&tasks);
+                self.tasks = tasks;
+            }
+            Err(e) => eprintln!("{e}, tasks are left as they were"),
+        }
+        self.update_status_of_all_tasks();
+    }
+
+    // maintenance may rewrite the tasks, e.g. compacting the journal or purging the trash, which
+    // are loaded again then; tasks changed but not saved are left to the check before saving
+    fn reload_maintained_tasks(&mut self) {
+        let stamp = files_stamp(&self.store_file_path());
+        if stamp == self.files_stamp || tasks_to_rows(&self.tasks) != self.persisted_rows {
+            return;
+        }
+        match self.store.load_all() {
+            Ok(mut tasks) => {
+                // the statuses were derived before, so they are no transitions
+                tasks.iter_mut().for_each(Task::update_status);
+                self.persisted_rows = tasks_to_rows(&tasks);
+                self.tasks = tasks;
+                self.files_stamp = stamp;
+            }
+            Err(e) => eprintln!("{e}, tasks are left as they were"),
+        }
+    }
+
+    // what `ll` would show, if there's anything on the timeline of today
+    fn print_overview(&mut self) {
+        let today = clock::today();
+        println!("{}", digest_of_today(&self.tasks, today));
+        let has_times_today = self.tasks.iter().any(|task| {
+            !task.is_deleted
+                && [task.planned_start, task.actual_start]
+                    .iter()
+                    .flatten()
+                    .any(|time| time.date_naive() == today)
+        });
+        if !has_times_today {
+            return;
+        }
+        let option = ListOption {
+            has_timeline: true,
+            ..ListOption::default()
+        };
+        if let Err(e) = self.list_tasks_with_timeline(&option) {
+            eprintln!("{e}");
+        }
+    }
+
+    // executes a single command non-interactively, returns whether it succeeded
+    pub fn execute_once(&mut self, command: &Command) -> bool {
+        self.update_status_of_all_tasks();
+        match self.dispatch_command(command) {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!("{e}");
+                false
+            }
+        }
+    }
+
+    // runs the commands one per line without prompts, echoing each of them, and stops at the
+    // first failure unless asked to keep going; returns whether all of them succeeded
+    pub fn run_batch(&mut self, lines: &[String], keep_going: bool) -> bool {
+        self.update_status_of_all_tasks();
+        let mut succeeded = true;
+        for (line_index, line) in lines.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            println!("{} {line}", "arenta>".dimmed());
+            let result = match parse_command(line) {
+                None => Err(ArentaError::validation(match did_you_mean(line) {
+                    Some(suggestion) => format!("invalid command, {suggestion}"),
+                    None => "invalid command".to_string(),
+                })),
+                Some(command) if command.is_interactive() => Err(ArentaError::validation(format!(
+                    "`{}` asks for input, which batch mode cannot answer",
+                    command.name()
+                ))),
+                Some(command) => self.dispatch_command(&command),
+            };
+            match result {
                 Ok(true) => break,
-                Ok(false) => (),
+                Ok(false) => continue,
+                Err(e) => eprintln!("line {}: {e}", line_index + 1),
+            }
+            succeeded = false;
+            if !keep_going {
+                break;
+            }
+        }
+        if !self.is_flushed() {
+            self.compact_tasks_or_warn();
+        }
+        succeeded
+    }
+
+    fn check_index(&self, index: usize) -> ArentaResult<()> {
+        match index < self.tasks.len() {
+            true => Ok(()),
+            false => Err(ArentaError::validation("index out of range")),
+        }
+    }
+
+    // returns true if the loop should quit
+    fn execute_line(&mut self, line: &str) -> ArentaResult<bool> {
+        if let Some(command) = parse_command(line) {
+            return self.dispatch_command(&command);
+        }
+        if let Some(line) = remove_invalid_list_args(line) {
+            let filter = self.prompter.text(
+                TextPrompt::new("date filter:")
+                    .with_help("<op><date>, <op> could be <, <=, >, >= or empty, <date> could be mm-dd, yyyy-mm-dd or an offset to today")
+                    .with_validator(|input| match input.parse::<DateFilter>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("please type a valid date filter, e.g. 03-08, >=-1 or <2024-03-08".to_string()),
+                    })
+                    .with_formatter(|input| match input.parse::<DateFilter>() {
+                        Ok(filter) => filter.describe(),
+                        Err(_) => input.to_string(),
+                    }),
+            );
+            return match filter.map(|filter| filter.parse::<DateFilter>()) {
+                Ok(Ok(filter)) => self.execute_line(&format!("{line} {filter}")),
+                Ok(Err(_)) | Err(ArentaError::Prompt(PromptError::Canceled)) => Ok(false),
+                Err(e) => Err(e),
+            };
+        }
+        match did_you_mean(line) {
+            Some(suggestion) => println!("invalid command, {suggestion}"),
+            None => println!("invalid command, type `h` to show usage"),
+        }
+        Ok(false)
+    }
+
+    fn execute_pasted_lines(&mut self, lines: Vec<String>) -> ArentaResult<bool> {
+        let lines: Vec<String> = lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| try_parse_capture(line).unwrap_or_else(|| line.to_string()))
+            .collect();
+        if lines.len() <= 1 {
+            return self.execute_line(lines.first().map_or("", |line| line.as_str()));
+        }
+        println!("{} lines pasted:", lines.len());
+        lines.iter().for_each(|line| println!("  {line}"));
+        let confirmed = self.prompter.confirm(
+            &format!("put them into backlog as {} tasks?", lines.len()),
+            true,
+        )?;
+        if confirmed {
+            lines
+                .iter()
+                .for_each(|line| self.tasks.push(Task::new_backlog_task(line)));
+            self.dump_tasks()?;
+            let first_index = self.tasks.len() - lines.len();
+            (first_index..self.tasks.len()).for_each(|index| self.emit(EventKind::Created, index));
+            println!("task {}-{} created", first_index, self.tasks.len() - 1);
+            Ok(false)
+        } else {
+            println!(
+                "only the first line is executed as a command, the other {} are discarded",
+                lines.len() - 1
+            );
+            self.execute_line(&lines[0])
+        }
+    }
+
+    // measures how long the command takes, excluding the time waiting at prompts
+    fn dispatch_command(&mut self, command: &Command) -> ArentaResult<bool> {
+        if self.is_read_only
+            && !command.is_read_only()
+            && !matches!(command, Command::Quit | Command::Use(..) | Command::Tui)
+        {
+            return Err(ArentaError::validation(format!(
+                "read-only session, `{}` is not available",
+                command.name()
+            )));
+        }
+        let stopwatch = Stopwatch::start();
+        let result = self.run_command(command);
+        let elapsed = stopwatch.elapsed();
+        if !matches!(command, Command::Empty | Command::Quit) {
+            self.timings.push((command.name(), elapsed));
+            if elapsed >= self.config.slow_command_threshold {
+                println!(
+                    "{}",
+                    format!(
+                        "(took {} — consider `archive` to move old complete tasks away, or `purge` to remove deleted tasks)",
+                        format_duration(elapsed)
+                    )
+                    .dimmed()
+                );
             }
         }
+        result
     }
 
-    fn dispatch_command(&mut self, command: &Command) -> InquireResult<bool> {
+    fn run_command(&mut self, command: &Command) -> ArentaResult<bool> {
         match command {
             Command::Empty => return Ok(false),
             Command::Quit => return Ok(true),
-            Command::Help => print_command_usage(),
+            Command::Help(None) => print_command_usage(),
+            Command::Help(Some(topic)) => print_command_help(topic),
             Command::New => self.new_task()?,
-            Command::Sort => self.sort_tasks(),
-            Command::Start(index) => self.start_task(*index),
-            Command::Complete(index) => self.complete_task(*index),
-            Command::Delete(index) => self.delete_task(*index),
+            Command::NewInline(description, arrangement) => {
+                self.new_task_inline(description, *arrangement)?
+            }
+            Command::Sort(false) => self.list_by_priority(),
+            Command::Sort(true) => self.sort_tasks()?,
+            Command::Chronic => self.list_chronic_tasks(),
+            Command::Start(index) => self.start_task(*index)?,
+            Command::Complete(index) => self.complete_task(*index)?,
+            Command::Pause(index) => self.pause_task(*index)?,
+            Command::Resume(index) => self.resume_task(*index)?,
+            Command::Delete(index) => self.delete_task(*index)?,
+            Command::Restore(index) => self.restore_task(*index)?,
+            Command::Purge => self.purge_tasks()?,
+            Command::Note(index) => self.edit_note(*index)?,
+            Command::Show(index) => self.show_task(*index)?,
             Command::Edit(index) => self.edit_task(*index)?,
+            Command::EditInline(index, edit) => self.edit_task_inline(*index, edit)?,
+            Command::Pick(action) => self.pick_task(*action)?,
+            Command::TogglePrivate(index) => self.toggle_private(*index)?,
             Command::List(list_option) => match list_option.has_timeline {
-                true => self.list_tasks_with_timeline(list_option),
+                true => self.list_tasks_with_timeline(list_option)?,
                 false => self.list_tasks(list_option),
             },
+            Command::More => match self.next_page.take() {
+                Some(option) => self.list_tasks(&option),
+                None => {
+                    return Err(ArentaError::validation(
+                        "no listing to continue, limit one with `ls -n <count>` first",
+                    ))
+                }
+            },
+            Command::Search(pattern) => self.search_tasks(pattern),
+            Command::Capture(description) => self.capture_task(description)?,
+            Command::SetHours(start, end) => self.set_timeline_hours(*start, *end),
+            Command::Maintain(dry_run) => self.maintain(*dry_run),
+            Command::Report(period, date) => self.report(*period, *date),
+            Command::Stats(kind) => self.print_stats(*kind),
+            Command::Export(format, target) => self.export_tasks(*format, target)?,
+            Command::Import(format, path, replace) => self.import_tasks(*format, path, *replace)?,
+            Command::Timings => self.list_timings(),
+            Command::Config => self.print_config(),
+            Command::Info => self.print_info(),
+            Command::Reload => self.reload_tasks(),
+            Command::RestoreBackup(number) => self.restore_backup(*number)?,
+            Command::Sync(is_local) => self.sync_tasks(*is_local)?,
+            Command::Migrate(kind) => self.migrate_tasks(*kind)?,
+            Command::Use(None) => self.print_profiles(),
+            Command::Use(Some(name)) => return self.switch_profile(name),
+            Command::Tui => {
+                self.is_tui_requested = true;
+                return Ok(true);
+            }
+            Command::Postpone(index, duration) => self.postpone_task(*index, *duration)?,
+            Command::Flush => self.flush_tasks()?,
+            Command::Archive(op, date) => self.archive_tasks(*op, *date)?,
+            Command::Defer(date) => self.defer_tasks(*date)?,
+            Command::Template(action) => match action {
+                TemplateAction::Save(name, index) => self.save_template(name, *index)?,
+                TemplateAction::List => self.list_templates()?,
+                TemplateAction::Delete(name) => self.delete_template(name)?,
+            },
+            Command::NewFromTemplate(name) => self.new_task_from_template(name)?,
+            Command::History(index) => self.print_history(*index)?,
+            Command::Pomodoro(index, minutes) => self.run_pomodoro(*index, *minutes)?,
+            Command::Next(force) => self.suggest_next_task(*force)?,
+            Command::PlanAuto(date) => self.plan_backlog_tasks(*date)?,
+            Command::Split(index) => self.split_task(*index)?,
+            Command::Merge(first, second) => self.merge_tasks(*first, *second)?,
+            Command::Move(from, to) => self.move_task(*from, *to)?,
+            Command::Swap(first, second) => self.swap_tasks(*first, *second)?,
         }
         Ok(false)
     }
 
-    fn new_task(&mut self) -> InquireResult<()> {
-        let description = Text::new("description:").prompt()?;
-        let options = vec!["start immediately", "put into backlog", "plan to..."];
-        let option = Select::new("how to arrange this task", options)
-            .without_help_message()
-            .prompt()?;
-        match option {
-            "start immediately" => self.tasks.push(Task::new_immediate_task(&description)),
-            "put into backlog" => self.tasks.push(Task::new_backlog_task(&description)),
+    fn new_task(&mut self) -> ArentaResult<()> {
+        let description = self.prompter.text(TextPrompt::new("description:"))?;
+        let tags = self.prompter.text(
+            TextPrompt::new("tags:").with_help("separated by spaces, press enter if no tags"),
+        )?;
+        let priority = get_priority_input(self.prompter.as_mut(), 0)?;
+        let options = ["start immediately", "put into backlog", "plan to..."];
+        let option = select_option(self.prompter.as_mut(), "how to arrange this task", &options)?;
+        let mut task = match option {
+            "start immediately" => Task::new_immediate_task(&description),
+            "put into backlog" => Task::new_backlog_task(&description),
             "plan to..." => {
-                let (planned_start, planned_complete) = get_planned_pair()?;
-                self.tasks.push(Task::new_planned_task(
-                    &description,
-                    planned_start.unwrap(),
-                    planned_complete.unwrap(),
-                ));
+                let (planned_start, planned_complete) = get_planned_pair(self.prompter.as_mut())?;
+                if !self.confirm_despite_conflicts(planned_start, planned_complete, None)? {
+                    println!("task not created");
+                    return Ok(());
+                }
+                Task::new_planned_task(&description, planned_start, planned_complete)
             }
             _ => unreachable!(),
-        }
-        self.dump_tasks();
+        };
+        task.tags = parse_tags(&tags);
+        task.priority = priority;
+        self.tasks.push(task);
+        self.dump_tasks()?;
+        self.emit(EventKind::Created, self.tasks.len() - 1);
         println!("task {} created", self.tasks.len() - 1);
         Ok(())
     }
 
-    fn sort_tasks(&mut self) {
-        self.clean_deleted_tasks();
-        self.update_status_of_all_tasks();
-        self.tasks.sort_by(|ta, tb| {
-            if ta.has_higher_priority_than(tb) {
-                Ordering::Less
-            } else {
-                Ordering::Greater
+    fn new_task_inline(&mut self, description: &str, arrangement: Arrangement) -> ArentaResult<()> {
+        match arrangement {
+            Arrangement::Immediate => self.tasks.push(Task::new_immediate_task(description)),
+            Arrangement::Backlog => self.tasks.push(Task::new_backlog_task(description)),
+            Arrangement::Planned(time, minutes) => {
+                let datetime = NaiveDateTime::new(clock::today(), time);
+                let start = resolve_local_datetime(Local.from_local_datetime(&datetime), datetime)?;
+                let complete = start + Duration::minutes(minutes as i64);
+                self.tasks
+                    .push(Task::new_planned_task(description, start, complete));
             }
-        });
-        self.dump_tasks();
-        println!("all tasks sorted");
+        }
+        self.dump_tasks()?;
+        self.emit(EventKind::Created, self.tasks.len() - 1);
+        println!("task {} created", self.tasks.len() - 1);
+        Ok(())
     }
 
-    fn start_task(&mut self, index: usize) {
-        if self.tasks.len() <= index {
-            eprintln!("index out of range");
-        } else {
-            self.tasks[index].start();
-            self.dump_tasks();
-            println!("task {index} started");
-        }
+    fn capture_task(&mut self, description: &str) -> ArentaResult<()> {
+        let index = self.add_task(Task::new_backlog_task(description))?;
+        println!("task {index} created");
+        Ok(())
     }
 
-    fn complete_task(&mut self, index: usize) {
-        if self.tasks.len() <= index {
-            eprintln!("index out of range");
-        } else {
-            self.tasks[index].complete();
-            self.dump_tasks();
-            println!("task {index} completed");
+    fn print_info(&self) {
+        let field = |name: &str, value: String| {
+            println!("{}{value}", pad_to_width(name, 12).dimmed());
+        };
+        println!("arenta {}", crate::VERSION);
+        if let Some(profile) = &self.profile {
+            field("profile", profile.clone());
         }
+        let size = |path: &Path| match fs::metadata(path) {
+            Ok(metadata) => format_size(metadata.len()),
+            Err(_) => "not created yet".to_string(),
+        };
+        field(
+            "data file",
+            format!("{} ({})", self.file_path.display(), size(&self.file_path)),
+        );
+        if self.config.storage == StorageKind::Sqlite {
+            let store_path = self.store_file_path();
+            field(
+                "database",
+                format!(
+                    "{} ({}), which tasks are kept in",
+                    store_path.display(),
+                    size(&store_path)
+                ),
+            );
+        }
+        let deleted = self.tasks.iter().filter(|task| task.is_deleted).count();
+        let archived = load_tasks_from_file(&self.archive_file_path()).tasks.len();
+        field(
+            "tasks",
+            format!(
+                "{} live, {deleted} deleted, {archived} archived",
+                self.tasks.len() - deleted
+            ),
+        );
+        let lock_file = lock_file_path(&self.file_path);
+        let holder = match read_holder_pid(&lock_file) {
+            Some(pid) if pid == std::process::id() => {
+                format!("held by process {pid}, this one")
+            }
+            Some(pid) => format!("held by process {pid}"),
+            None => "not held".to_string(),
+        };
+        field("lock file", format!("{} ({holder})", lock_file.display()));
     }
 
-    fn delete_task(&mut self, index: usize) {
-        if self.tasks.len() <= index {
-            eprintln!("index out of range");
-        } else {
-            self.tasks[index].delete();
-            self.dump_tasks();
-            println!("task {index} deleted");
+    fn print_profiles(&self) {
+        let Some(active) = &self.profile else {
+            println!("no profiles, as `data_file` is set in the config");
+            return;
+        };
+        let mut profiles = list_profiles(&self.file_path);
+        if !profiles.contains(active) {
+            profiles.push(active.clone());
+            profiles.sort();
+        }
+        for name in profiles {
+            match name == *active {
+                true => println!("* {name}"),
+                false => println!("  {name}"),
+            }
         }
     }
 
-    fn edit_task(&mut self, index: usize) -> InquireResult<()> {
-        if self.tasks.len() <= index {
-            eprintln!("index out of range");
-        } else {
-            let task = &mut self.tasks[index];
-            let new_description = Text::new("description:")
-                .with_placeholder(&task.description)
-                .with_help_message("press enter if don't update description")
-                .prompt()?;
-            if !new_description.is_empty() {
-                task.description = new_description
-            }
-            match get_edit_operation("planned start and complete time") {
-                EditOperation::Ignore => (),
-                EditOperation::Reset => (task.planned_start, task.planned_complete) = (None, None),
-                EditOperation::Update => {
-                    (task.planned_start, task.planned_complete) = get_planned_pair()?
-                }
+    // the session ends here, and main starts another one on the profile
+    fn switch_profile(&mut self, name: &str) -> ArentaResult<bool> {
+        profile::validate_name(name)?;
+        match &self.profile {
+            None => Err(ArentaError::validation(
+                "profiles are not available while `data_file` is set in the config",
+            )),
+            Some(active) if active == name => {
+                println!("already in profile `{name}`");
+                Ok(false)
             }
-            match get_edit_operation("actual start time") {
-                EditOperation::Ignore => (),
-                EditOperation::Reset => task.actual_start = None,
-                EditOperation::Update => {
-                    task.actual_start = Some(get_datetime_input("actual start")?)
-                }
-            }
-            match get_edit_operation("actual complete time") {
-                EditOperation::Ignore => (),
-                EditOperation::Reset => task.actual_complete = None,
-                EditOperation::Update => {
-                    task.actual_complete = Some(get_datetime_input("actual complete")?)
-                }
+            Some(_) => {
+                println!("switching to profile `{name}`");
+                self.next_profile = Some(name.to_string());
+                Ok(true)
             }
-            task.update_status();
-            self.dump_tasks();
-            println!("task {index} edited");
         }
-        Ok(())
     }
 
-    fn list_tasks(&mut self, option: &ListOption) {
-        self.update_status_of_all_tasks();
-        render_header_if_verbose_list(option);
-        self.tasks
+    // the profile is named in listings only once there's more than one, so that tasks of one
+    // are never taken for those of another
+    fn print_profile_footer(&self) {
+        let Some(active) = &self.profile else {
+            return;
+        };
+        if list_profiles(&self.file_path)
             .iter()
-            .enumerate()
-            .filter(|(_, task)| task.satisfy(option))
-            .for_each(|(index, task)| task.render(index, None, option.is_verbose));
+            .any(|name| name != active)
+        {
+            println!("{}", format!("profile: {active}").dimmed());
+        }
     }
 
-    fn list_tasks_with_timeline(&mut self, option: &ListOption) {
-        self.update_status_of_all_tasks();
-        let tasks: Vec<(usize, &Task)> = self
-            .tasks
-            .iter()
-            .enumerate()
-            .filter(|(_, task)| task.satisfy(option))
-            .take(26)
-            .collect();
-        let (op, date) = option.date_filter;
-        assert_eq!(op, DateFilterOp::Equal);
-        Timeline::new(&tasks, date).draw();
-        println!();
-        render_header_if_verbose_list(option);
-        tasks
-            .iter()
-            .enumerate()
-            .for_each(|(timeline_index, &(index, task))| {
-                task.render(
-                    index,
-                    Some(timeline_index_to_char(timeline_index)),
-                    option.is_verbose,
-                )
-            });
+    fn print_config(&self) {
+        for (key, value, origin) in self.config.describe(&self.file_path) {
+            println!("{} = {value}  {}", key, origin.dimmed());
+        }
     }
 
-    fn clean_deleted_tasks(&mut self) {
-        self.tasks.retain(|task| !task.is_deleted);
+    fn set_timeline_hours(&mut self, start: u32, end: u32) {
+        self.config.timeline_hours = (start, end);
+        match self.config.save() {
+            Ok(()) => println!("timeline hours set to {start}-{end}"),
+            Err(e) => eprintln!("timeline hours set to {start}-{end} for this session only, {e}"),
+        }
     }
 
-    fn update_status_of_all_tasks(&mut self) {
-        self.tasks.iter_mut().for_each(|task| task.update_status());
-    }
-
-    fn dump_tasks(&mut self) {
-        let mut writer = Writer::from_path(get_arenta_file_path().as_path()).unwrap();
-        self.tasks.iter().for_each(|task| {
-            writer
-                .write_record([
-                    &task.description,
-                    &datetime_opt_to_string(&task.planned_start),
-                    &datetime_opt_to_string(&task.planned_complete),
-                    &datetime_opt_to_string(&task.actual_start),
-                    &datetime_opt_to_string(&task.actual_complete),
-                    &task.is_deleted.to_string(),
-                ])
-                .unwrap()
-        });
-        writer.flush().unwrap();
+    fn list_by_priority(&mut self) {
+        self.is_listed_by_priority = true;
+        println!("tasks are listed by priority from now on, their indices unchanged");
     }
-}
 
-fn render_header_if_verbose_list(option: &ListOption) {
-    if !option.is_verbose {
-        return;
+    fn sort_tasks(&mut self) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        self.tasks.sort_by(compare_for_sort);
+        self.dump_tasks()?;
+        println!("all tasks sorted");
+        Ok(())
     }
-    let indent = if option.has_timeline { 7 } else { 4 };
-    println!(
-        "{}{: <35}{: <20}{: <20}{: <20}{: <20}description",
-        " ".repeat(indent),
-        "status",
-        "planned start",
-        "planned complete",
-        "actual start",
-        "actual complete"
-    );
-    println!("{}", "-".repeat(126 + indent));
-}
 
-pub fn timeline_index_to_char(index: usize) -> char {
-    char::from_u32('a' as u32 + index as u32).unwrap()
-}
+    fn start_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.start(index)?;
+        println!("task {index} started");
+        Ok(())
+    }
 
-fn datetime_opt_to_string(datetime_opt: &Option<DateTime<Local>>) -> String {
-    datetime_opt.map_or("".to_string(), |dt| dt.to_rfc3339())
-}
+    fn complete_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        if self.tasks[index].is_untracked() {
+            return self.start_untracked_task(index);
+        }
+        self.complete(index)?;
+        println!("task {index} completed");
+        Ok(())
+    }
 
-fn datetime_opt_from_string(s: &str) -> Option<DateTime<Local>> {
-    if s.is_empty() {
-        None
-    } else {
-        Some(
-            DateTime::parse_from_rfc3339(s)
-                .unwrap()
-                .with_timezone(&Local),
-        )
+    // completing a task completed without a start again would move its completion to now,
+    // so it's started at its completion instead, which leaves it taking no time
+    fn start_untracked_task(&mut self, index: usize) -> ArentaResult<()> {
+        let confirmed = self.prompter.confirm(
+            &format!(
+                "task {index} is complete but was never started, start it when it was completed?"
+            ),
+            true,
+        )?;
+        if !confirmed {
+            println!("task {index} is left untracked");
+            return Ok(());
+        }
+        let before = self.tasks[index].clone();
+        let task = &mut self.tasks[index];
+        task.actual_start = task.actual_complete;
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        println!("task {index} started when it was completed");
+        Ok(())
     }
-}
 
-fn get_render_config() -> RenderConfig {
-    RenderConfig {
-        prompt_prefix: Styled::new(""),
-        answered_prompt_prefix: Styled::new(""),
-        ..RenderConfig::default()
+    fn pause_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let task = &mut self.tasks[index];
+        task.update_status();
+        if task.status != TaskStatus::Ongoing {
+            return Err(ArentaError::validation(format!(
+                "task {index} is not ongoing"
+            )));
+        }
+        task.pause();
+        self.dump_tasks()?;
+        println!("task {index} paused");
+        Ok(())
     }
-}
 
-fn get_datetime_input(hint: &str) -> InquireResult<DateTime<Local>> {
-    let date = DateSelect::new(&format!("{hint} date:"))
-        .with_help_message("select a date")
-        .prompt()?;
-    let time = CustomType::<NaiveTime>::new(&format!("{hint} time:"))
-        .with_parser(&|time| NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| ()))
-        .with_formatter(&|time| time.format("%H:%M").to_string())
-        .with_error_message("please type a valid time.")
-        .with_help_message("time in %H:%M format")
-        .prompt()?;
-    let datetime = NaiveDateTime::new(date, time);
-    Ok(Local.from_local_datetime(&datetime).unwrap())
+    fn resume_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let task = &mut self.tasks[index];
+        task.update_status();
+        if task.status != TaskStatus::Paused {
+            return Err(ArentaError::validation(format!(
+                "task {index} is not paused"
+            )));
+        }
+        task.resume();
+        self.dump_tasks()?;
+        println!("task {index} resumed");
+        Ok(())
+    }
+
+    // the pomodoro is counted as worked through the open work interval of the task, which is
+    // started or resumed for it if needed
+    fn run_pomodoro(&mut self, index: usize, minutes: u32) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let task = &mut self.tasks[index];
+        task.update_status();
+        match task.status {
+            TaskStatus::Complete => {
+                return Err(ArentaError::validation(format!("task {index} is complete")))
+            }
+            TaskStatus::Ongoing => (),
+            TaskStatus::Paused => {
+                task.resume();
+                self.dump_tasks()?;
+                println!("task {index} resumed");
+            }
+            _ => {
+                task.start();
+                self.dump_tasks()?;
+                self.emit(EventKind::Started, index);
+                println!("task {index} started");
+            }
+        }
+        println!("press q or ctrl-c to stop early");
+        let label = format!("on task {index}: {}", self.tasks[index].description);
+        let duration = std::time::Duration::from_secs(minutes as u64 * 60);
+        match self.prompter.count_down(duration, &label) {
+            true => println!(
+                "\x07{minutes} minutes done, time for a break, `pause {index}` to stop counting it"
+            ),
+            false => println!("pomodoro stopped, task {index} is still ongoing"),
+        }
+        Ok(())
+    }
+
+    fn suggest_next_task(&mut self, force: bool) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        let ongoing = self
+            .tasks
+            .iter()
+            .position(|task| !task.is_deleted && task.status == TaskStatus::Ongoing);
+        if let (Some(index), false) = (ongoing, force) {
+            println!(
+                "you're already working on: {index}. {}, `next -f` to suggest another task",
+                self.tasks[index].description
+            );
+            return Ok(());
+        }
+        let Some(index) = pick_next_task(&self.tasks, clock::now()) else {
+            println!("nothing to work on next");
+            return Ok(());
+        };
+        print!("{}", self.tasks[index].render_detail(index));
+        if self.prompter.confirm("start it now?", true)? {
+            self.start_task(index)?;
+        }
+        Ok(())
+    }
+
+    // an ongoing task is completed now and the rest goes on as a new ongoing task, while the
+    // plan of any other task is split by a ratio; the second part inherits the tags either way
+    fn split_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        self.update_status_of_all_tasks();
+        let original = &self.tasks[index];
+        if original.is_deleted || original.status == TaskStatus::Complete {
+            return Err(ArentaError::validation(format!(
+                "task {index} is {}, there's nothing left to split",
+                if original.is_deleted {
+                    "deleted"
+                } else {
+                    "complete"
+                }
+            )));
+        }
+        let description = self.prompter.text(
+            TextPrompt::new("description of the second part:").with_validator(|input| match input
+                .trim()
+                .is_empty()
+            {
+                true => Err("the description cannot be empty".to_string()),
+                false => Ok(()),
+            }),
+        )?;
+        let description = description.trim();
+        let before = original.clone();
+        let mut first = original.clone();
+        let is_ongoing = first.status == TaskStatus::Ongoing;
+        let mut second = match is_ongoing {
+            true => Task::new_immediate_task(description),
+            false => Task::new_backlog_task(description),
+        };
+        if let (Some(start), Some(complete)) = (first.planned_start, first.planned_complete) {
+            let at = match is_ongoing {
+                true => clock::now(),
+                false => {
+                    let percent = prompt_parsed(
+                        self.prompter.as_mut(),
+                        "share of the first part:",
+                        "a percentage of the planned time, e.g. 60 or 60%",
+                        parse_split_percent,
+                    )?;
+                    start + (complete - start) * percent as i32 / 100
+                }
+            };
+            let ((_, at), (_, complete)) = split_window(start, complete, at);
+            first.planned_complete = Some(at);
+            // an expired plan leaves nothing to the second part
+            if at < complete {
+                second.planned_start = Some(at);
+                second.planned_complete = Some(complete);
+            }
+        }
+        if is_ongoing {
+            first.complete();
+        }
+        second.tags = first.tags.clone();
+        first.update_status();
+        second.update_status();
+        self.tasks[index] = first;
+        self.tasks.push(second);
+        let new_index = self.tasks.len() - 1;
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        if is_ongoing {
+            self.emit(EventKind::Completed, index);
+        }
+        self.emit(EventKind::Created, new_index);
+        match is_ongoing {
+            true => println!("task {index} completed, task {new_index} started for the rest"),
+            false => println!("task {index} split, task {new_index} created for the second part"),
+        }
+        Ok(())
+    }
+
+    fn move_task(&mut self, from: usize, to: usize) -> ArentaResult<()> {
+        self.check_index(from)?;
+        self.check_index(to)?;
+        let task = self.tasks.remove(from);
+        self.tasks.insert(to, task);
+        self.dump_tasks()?;
+        println!("task {from} moved to {to}");
+        self.print_positions(from.min(to)..=from.max(to));
+        Ok(())
+    }
+
+    fn swap_tasks(&mut self, first: usize, second: usize) -> ArentaResult<()> {
+        self.check_index(first)?;
+        self.check_index(second)?;
+        self.tasks.swap(first, second);
+        self.dump_tasks()?;
+        println!("tasks {first} and {second} swapped");
+        self.print_positions([first.min(second), first.max(second)]);
+        Ok(())
+    }
+
+    // the tasks at the positions after reordering
+    fn print_positions(&self, positions: impl IntoIterator<Item = usize>) {
+        for index in positions {
+            println!("  {index}. {}", self.tasks[index].description);
+        }
+    }
+
+    fn merge_tasks(&mut self, first: usize, second: usize) -> ArentaResult<()> {
+        self.check_index(first)?;
+        self.check_index(second)?;
+        if first == second {
+            return Err(ArentaError::validation("cannot merge a task with itself"));
+        }
+        if let Some(index) = [first, second]
+            .into_iter()
+            .find(|index| self.tasks[*index].is_deleted)
+        {
+            return Err(ArentaError::validation(format!(
+                "task {index} is deleted, `restore {index}` first to merge it"
+            )));
+        }
+        let merged = merged_task(&self.tasks[first], &self.tasks[second]);
+        print!("{}", merged.render_detail(first));
+        let confirmed = self.prompter.confirm(
+            &format!("merge task {second} into task {first} like this?"),
+            true,
+        )?;
+        if !confirmed {
+            println!("merge canceled");
+            return Ok(());
+        }
+        let before = std::mem::replace(&mut self.tasks[first], merged);
+        self.tasks[second].delete();
+        self.dump_tasks()?;
+        self.emit_edited(first, &before);
+        self.emit(EventKind::Deleted, second);
+        println!("task {second} merged into task {first} and deleted");
+        Ok(())
+    }
+
+    fn edit_note(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let before = self.tasks[index].clone();
+        let current = before.notes.clone();
+        let replace = current.is_empty()
+            || select_option(self.prompter.as_mut(), "note:", &["append", "replace"])? == "replace";
+        let input =
+            get_multiline_input(self.prompter.as_mut(), if replace { &current } else { "" })?;
+        let input = input.trim_end();
+        self.tasks[index].notes = match (replace, current.is_empty(), input.is_empty()) {
+            (true, _, _) | (false, true, _) => input.to_string(),
+            (false, false, true) => current,
+            (false, false, false) => format!("{current}\n{input}"),
+        };
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        println!("note of task {index} saved");
+        Ok(())
+    }
+
+    fn show_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        self.update_status_of_all_tasks();
+        print!("{}", self.tasks[index].render_detail(index));
+        Ok(())
+    }
+
+    fn delete_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.delete(index)?;
+        println!("task {index} deleted, `restore {index}` to bring it back");
+        Ok(())
+    }
+
+    fn restore_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        if !self.tasks[index].is_deleted {
+            return Err(ArentaError::validation(format!(
+                "task {index} is not deleted"
+            )));
+        }
+        self.tasks[index].restore();
+        self.dump_tasks()?;
+        self.emit(EventKind::Restored, index);
+        println!("task {index} restored");
+        Ok(())
+    }
+
+    fn purge_tasks(&mut self) -> ArentaResult<()> {
+        let count = self.tasks.iter().filter(|task| task.is_deleted).count();
+        if count == 0 {
+            println!("no deleted tasks to purge");
+            return Ok(());
+        }
+        let confirmed = self.prompter.confirm(
+            &format!("remove {count} deleted task(s) for good? they cannot be restored any more"),
+            false,
+        )?;
+        if !confirmed {
+            println!("purge canceled");
+            return Ok(());
+        }
+        self.clean_deleted_tasks();
+        self.dump_tasks()?;
+        println!("{count} deleted task(s) purged, task indices may have changed");
+        Ok(())
+    }
+
+    fn pick_task(&mut self, action: TaskAction) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        let candidates: Vec<Candidate> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| is_candidate(action, task))
+            .map(|(index, task)| Candidate::new(index, task))
+            .collect();
+        if candidates.is_empty() {
+            println!("no task to {}", action.name());
+            return Ok(());
+        }
+        let picked = self.prompter.select(
+            SelectPrompt::new(&format!("{} task:", action.name()), &candidates)
+                .with_help("type to filter, enter to pick"),
+        )?;
+        let picked = &candidates[picked];
+        match action {
+            TaskAction::Start => self.start_task(picked.index),
+            TaskAction::Complete => self.complete_task(picked.index),
+            TaskAction::Delete => self.delete_task(picked.index),
+            TaskAction::Edit => self.edit_task(picked.index),
+        }
+    }
+
+    fn postpone_task(&mut self, index: usize, duration: Duration) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let before = self.tasks[index].clone();
+        let task = &mut self.tasks[index];
+        let (Some(start), Some(complete)) = (task.planned_start, task.planned_complete) else {
+            return Err(ArentaError::validation(format!(
+                "task {index} has no planned time to shift"
+            )));
+        };
+        let (Some(start), Some(complete)) = (
+            start.checked_add_signed(duration),
+            complete.checked_add_signed(duration),
+        ) else {
+            return Err(ArentaError::validation(
+                "cannot shift the planned time that far",
+            ));
+        };
+        task.reschedule(Some(start), Some(complete));
+        task.update_status();
+        println!(
+            "task {index} planned from {} to {}",
+            start.format("%F %R"),
+            complete.format("%F %R")
+        );
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        Ok(())
+    }
+
+    fn templates_file_path(&self) -> PathBuf {
+        get_sibling_file_path(&self.file_path, "templates")
+    }
+
+    fn save_template(&mut self, name: &str, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        validate_name(name).map_err(ArentaError::Validation)?;
+        let template =
+            Template::from_task(name, &self.tasks[index]).map_err(ArentaError::Validation)?;
+        let path = self.templates_file_path();
+        let mut templates = load_templates(&path)?;
+        if templates.iter().any(|template| template.name == name) {
+            return Err(ArentaError::validation(format!(
+                "template `{name}` already exists, delete it first to save it again"
+            )));
+        }
+        templates.push(template);
+        save_templates(&path, &templates)?;
+        println!("template `{name}` saved from task {index}, use `n @{name}` to plan it");
+        Ok(())
+    }
+
+    fn list_templates(&self) -> ArentaResult<()> {
+        let templates = load_templates(&self.templates_file_path())?;
+        if templates.is_empty() {
+            println!("no templates, use `template save <name> <index>` to save one");
+        }
+        for template in templates {
+            let mut line = format!(
+                "  {} {} for {}",
+                pad_to_width(&format!("@{}", template.name), 12).bold(),
+                template.description,
+                humanize_duration(Duration::minutes(template.minutes))
+            );
+            if !template.tags.is_empty() {
+                line.push_str(&format!("  {}", template.tags.join(" ").dimmed()));
+            }
+            println!("{line}");
+        }
+        Ok(())
+    }
+
+    fn delete_template(&mut self, name: &str) -> ArentaResult<()> {
+        let path = self.templates_file_path();
+        let mut templates = load_templates(&path)?;
+        let count = templates.len();
+        templates.retain(|template| template.name != name);
+        if templates.len() == count {
+            return Err(ArentaError::validation(format!(
+                "no template named `{name}`"
+            )));
+        }
+        save_templates(&path, &templates)?;
+        println!("template `{name}` deleted");
+        Ok(())
+    }
+
+    fn new_task_from_template(&mut self, name: &str) -> ArentaResult<()> {
+        let templates = load_templates(&self.templates_file_path())?;
+        let Some(template) = templates.iter().find(|template| template.name == name) else {
+            return Err(ArentaError::validation(format!(
+                "no template named `{name}`, see `template list`"
+            )));
+        };
+        let start = match prompt_parsed(
+            self.prompter.as_mut(),
+            "planned start:",
+            "a time today like 14:30, an offset like +30m, `cal` to pick a date, or empty for now",
+            parse_template_start,
+        )? {
+            Some(start) => start,
+            None => get_datetime_input(self.prompter.as_mut(), "planned start")?,
+        };
+        let mut task = template.instantiate(start);
+        task.validate_periods().map_err(ArentaError::Validation)?;
+        let (planned_start, planned_complete) = (start, task.planned_complete.unwrap());
+        if !self.confirm_despite_conflicts(planned_start, planned_complete, None)? {
+            println!("task not created");
+            return Ok(());
+        }
+        task.update_status();
+        self.tasks.push(task);
+        self.dump_tasks()?;
+        self.emit(EventKind::Created, self.tasks.len() - 1);
+        println!(
+            "task {} created from template `{name}`",
+            self.tasks.len() - 1
+        );
+        Ok(())
+    }
+
+    fn defer_tasks(&mut self, date: NaiveDate) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        let deferrals = plan_deferrals(&self.tasks, clock::today(), date)?;
+        if deferrals.is_empty() {
+            println!("no unfinished task planned for today or earlier");
+            return Ok(());
+        }
+        for (index, start, complete) in &deferrals {
+            let task = &self.tasks[*index];
+            println!(
+                "  {index}. {}: {} ~ {} -> {} ~ {}",
+                task.description,
+                task.planned_start.unwrap().format("%F %R"),
+                task.planned_complete.unwrap().format("%F %R"),
+                start.format("%F %R"),
+                complete.format("%F %R")
+            );
+        }
+        let confirmed = self.prompter.confirm(
+            &format!("defer these {} task(s) to {date}?", deferrals.len()),
+            true,
+        )?;
+        if !confirmed {
+            println!("defer canceled");
+            return Ok(());
+        }
+        let mut befores = vec![];
+        for (index, start, complete) in &deferrals {
+            befores.push(self.tasks[*index].clone());
+            let task = &mut self.tasks[*index];
+            task.reschedule(Some(*start), Some(*complete));
+            task.update_status();
+        }
+        self.dump_tasks()?;
+        for ((index, _, _), before) in deferrals.iter().zip(&befores) {
+            self.emit_edited(*index, before);
+        }
+        println!("{} task(s) deferred to {date}", deferrals.len());
+        Ok(())
+    }
+
+    // the working hours of the date, or the hours of the timeline if they aren't configured,
+    // from now on if it's today
+    fn schedule_window(&self, date: NaiveDate) -> ArentaResult<Interval> {
+        let hours = match working::working_hours() {
+            Some(working_hours) if !working_hours.days.contains(&date.weekday()) => {
+                return Err(ArentaError::validation(format!(
+                    "{date} is not a working day"
+                )))
+            }
+            Some(working_hours) => working_hours.hours,
+            None => self.config.timeline_hours,
+        };
+        let at_hour = |hour: u32| {
+            let datetime = date.and_hms_opt(0, 0, 0).unwrap() + Duration::hours(hour as i64);
+            resolve_local_datetime(Local.from_local_datetime(&datetime), datetime)
+        };
+        let start = at_hour(hours.0)?.max(round_up_to_five_minutes(clock::now()));
+        Ok((start, at_hour(hours.1)?))
+    }
+
+    fn plan_backlog_tasks(&mut self, date: NaiveDate) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        let window = self.schedule_window(date)?;
+        if window.0 >= window.1 {
+            println!("no working time left on {date}");
+            return Ok(());
+        }
+        let mut backlog: Vec<usize> = (0..self.tasks.len())
+            .filter(|index| {
+                let task = &self.tasks[*index];
+                !task.is_deleted && task.status == TaskStatus::Backlog
+            })
+            .collect();
+        if backlog.is_empty() {
+            println!("no backlog tasks to plan");
+            return Ok(());
+        }
+        // of higher priority first, then the oldest first
+        backlog.sort_by_key(|index| {
+            let task = &self.tasks[*index];
+            (std::cmp::Reverse(task.priority), task.created_at)
+        });
+        let busy: Vec<Interval> = self
+            .tasks
+            .iter()
+            .filter(|task| !task.is_deleted && task.status != TaskStatus::Complete)
+            .filter_map(|task| Some((task.planned_start?, task.planned_complete?)))
+            .collect();
+        let mut slots = free_slots(window, &busy);
+        let mut plans: Vec<(usize, Interval)> = vec![];
+        let mut unscheduled = vec![];
+        for index in backlog {
+            // no need to ask once the free time is used up
+            if slots.is_empty() {
+                unscheduled.push(index);
+                continue;
+            }
+            let estimate = prompt_parsed(
+                self.prompter.as_mut(),
+                &format!("{index}. {} will take:", self.tasks[index].description),
+                "minutes, or e.g. 90m, 2h or 1h30m, empty to leave it in backlog",
+                parse_estimate,
+            )?;
+            match estimate.and_then(|estimate| pack_into_slots(&mut slots, &[estimate])[0]) {
+                Some(plan) => plans.push((index, plan)),
+                None => unscheduled.push(index),
+            }
+        }
+        if plans.is_empty() {
+            println!("no backlog task fits into the free time of {date}");
+            return Ok(());
+        }
+        plans.sort_by_key(|(_, plan)| *plan);
+        self.render_proposed_schedule(date, &plans, &unscheduled);
+        let confirmed = self
+            .prompter
+            .confirm(&format!("plan these {} task(s)?", plans.len()), true)?;
+        if !confirmed {
+            println!("plan canceled");
+            return Ok(());
+        }
+        let mut befores = vec![];
+        for (index, (start, complete)) in &plans {
+            befores.push(self.tasks[*index].clone());
+            let task = &mut self.tasks[*index];
+            task.reschedule(Some(*start), Some(*complete));
+            task.update_status();
+        }
+        self.dump_tasks()?;
+        for ((index, _), before) in plans.iter().zip(&befores) {
+            self.emit_edited(*index, before);
+        }
+        println!(
+            "{} task(s) planned on {date}, {} left in backlog",
+            plans.len(),
+            unscheduled.len()
+        );
+        Ok(())
+    }
+
+    // the timeline of the date as if the plans were made, while nothing is changed yet
+    fn render_proposed_schedule(
+        &self,
+        date: NaiveDate,
+        plans: &[(usize, Interval)],
+        unscheduled: &[usize],
+    ) {
+        let mut proposed = self.tasks.clone();
+        for (index, (start, complete)) in plans {
+            proposed[*index].reschedule(Some(*start), Some(*complete));
+            proposed[*index].update_status();
+        }
+        let mut tasks: Vec<(usize, &Task)> = proposed
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| {
+                !task.is_deleted
+                    && task
+                        .planned_start
+                        .is_some_and(|start| start.date_naive() == date)
+            })
+            .collect();
+        tasks.truncate(26);
+        let mut timeline = Timeline::new(&tasks, date, self.config.timeline_hours);
+        if let Some(width) = self.prompter.width() {
+            timeline.set_available_width(width);
+        }
+        timeline.draw();
+        println!();
+        for (index, (start, complete)) in plans {
+            println!(
+                "  {index}. {}: {} ~ {}",
+                self.tasks[*index].description,
+                start.format("%R"),
+                complete.format("%R")
+            );
+        }
+        if !unscheduled.is_empty() {
+            println!("unscheduled:");
+            for index in unscheduled {
+                println!("  {index}. {}", self.tasks[*index].description);
+            }
+        }
+    }
+
+    fn toggle_private(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let before = self.tasks[index].clone();
+        let task = &mut self.tasks[index];
+        task.is_private = !task.is_private;
+        let is_private = task.is_private;
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        match is_private {
+            true => println!("task {index} is private now"),
+            false => println!("task {index} is not private any more"),
+        }
+        Ok(())
+    }
+
+    fn report(&mut self, period: ReportPeriod, date: NaiveDate) {
+        self.update_status_of_all_tasks();
+        let (first, last) = match period {
+            ReportPeriod::Day => (date, date),
+            ReportPeriod::Week => {
+                let week = date.week(Weekday::Mon);
+                (week.first_day(), week.last_day())
+            }
+        };
+        build_report(&self.tasks, first, last).render(&self.tasks);
+    }
+
+    fn print_stats(&mut self, kind: StatsKind) {
+        self.update_status_of_all_tasks();
+        match kind {
+            StatsKind::Estimates => match estimate_stats(&self.tasks) {
+                Some(stats) => stats.render(&self.tasks),
+                None => println!("no completed task has both a planned and an actual period"),
+            },
+            StatsKind::Heatmap(weeks) => render_heatmap(&self.tasks, clock::today(), weeks),
+            StatsKind::Punctuality(filter) => {
+                match punctuality_stats(&self.tasks, filter, clock::now(), working::working_hours())
+                {
+                    Some(stats) => stats.render(&self.tasks),
+                    None => println!("no task planned on the dates has been started"),
+                }
+            }
+            StatsKind::Tags(filter, as_csv) => {
+                let times = time_per_tag(&self.tasks, filter, clock::now());
+                match (times.is_empty(), as_csv) {
+                    (_, true) => print!("{}", tag_times_to_csv(&times)),
+                    (true, false) => println!("no task started on the dates"),
+                    (false, false) => render_tag_times(&times),
+                }
+            }
+        }
+    }
+
+    fn export_tasks(&mut self, format: ExportFormat, target: &ExportTarget) -> ArentaResult<()> {
+        // `-` is for piping into another command
+        let to_stdout = target.path == Path::new("-");
+        if !to_stdout && target.path.exists() && !target.overwrite {
+            return Err(ArentaError::validation(format!(
+                "{} already exists, use `-f` to overwrite it",
+                target.path.display()
+            )));
+        }
+        let (content, exported, skipped) = match format {
+            ExportFormat::Json => (
+                JsonValue::Array(
+                    self.tasks
+                        .iter()
+                        .map(|task| task_to_json(task, target.redact))
+                        .collect(),
+                )
+                .to_pretty_string(),
+                self.tasks.len(),
+                0,
+            ),
+            ExportFormat::Ics => {
+                self.update_status_of_all_tasks();
+                let (calendar, skipped) =
+                    tasks_to_calendar(&self.tasks, target.redact, clock::now());
+                let deleted = self.tasks.iter().filter(|task| task.is_deleted).count();
+                (calendar, self.tasks.len() - deleted - skipped, skipped)
+            }
+            ExportFormat::Markdown(date, include_backlog) => {
+                self.update_status_of_all_tasks();
+                let (markdown, exported) =
+                    plan_to_markdown(&self.tasks, date, include_backlog, target.redact);
+                (markdown, exported, 0)
+            }
+            ExportFormat::TodoTxt => {
+                return Err(ArentaError::validation(
+                    "exporting todotxt is not supported",
+                ))
+            }
+        };
+        if to_stdout {
+            print!("{content}");
+            return Ok(());
+        }
+        fs::write(&target.path, content)
+            .map_err(|e| ArentaError::io("failed to export tasks", e))?;
+        match skipped {
+            0 => println!("{exported} tasks exported to {}", target.path.display()),
+            skipped => println!(
+                "{exported} tasks exported to {}, skipped {skipped} without a planned time",
+                target.path.display()
+            ),
+        }
+        Ok(())
+    }
+
+    fn import_tasks(
+        &mut self,
+        format: ExportFormat,
+        path: &Path,
+        replace: bool,
+    ) -> ArentaResult<()> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| ArentaError::io(format!("failed to read {}", path.display()), e))?;
+        let mut tasks = vec![];
+        let mut invalid_lines = vec![];
+        match format {
+            ExportFormat::Json => {
+                let entries = parse_array_with_lines(&text).map_err(ArentaError::Validation)?;
+                for (line, value) in entries {
+                    match json_to_record(&value).and_then(|record| record_to_task(&record)) {
+                        Ok(mut task) => {
+                            task.update_status();
+                            tasks.push(task);
+                        }
+                        Err(reason) => {
+                            eprintln!("line {line}: {reason}");
+                            invalid_lines.push(line);
+                        }
+                    }
+                }
+            }
+            // malformed lines are still imported, into backlog
+            ExportFormat::TodoTxt => {
+                for (index, line) in text.lines().enumerate() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let (task, malformed) = line_to_task(line, self.config.timeline_hours);
+                    if let Some(reason) = malformed {
+                        eprintln!("line {}: {reason}, imported into backlog", index + 1);
+                    }
+                    tasks.push(task);
+                }
+            }
+            ExportFormat::Ics | ExportFormat::Markdown(..) => {
+                return Err(ArentaError::validation(
+                    "importing is only supported for json and todotxt",
+                ))
+            }
+        }
+        if replace {
+            let confirmed = self.prompter.confirm(
+                &format!(
+                    "replace all {} tasks with the imported ones?",
+                    self.tasks.len()
+                ),
+                false,
+            )?;
+            if !confirmed {
+                println!("import canceled");
+                return Ok(());
+            }
+            self.tasks.clear();
+        }
+        let (imported, skipped) = merge_imported_tasks(&mut self.tasks, tasks);
+        if imported > 0 || replace {
+            self.dump_tasks()?;
+        }
+        println!("{}", describe_import(imported, skipped, &invalid_lines));
+        Ok(())
+    }
+
+    fn list_timings(&mut self) {
+        const SLOWEST_COUNT: usize = 10;
+        let mut timings = self.timings.clone();
+        timings.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+        println!("slowest commands of this session:");
+        timings
+            .iter()
+            .take(SLOWEST_COUNT)
+            .for_each(|(name, elapsed)| println!("  {: <8}{name}", format_duration(*elapsed)));
+    }
+
+    fn maintain(&mut self, dry_run: bool) {
+        let targets = MaintenanceTargets::from_environment(&self.file_path, &self.config);
+        let summary = maintenance::run(&targets, clock::today(), dry_run);
+        self.reload_maintained_tasks();
+        match (summary.is_empty(), dry_run) {
+            (true, _) => println!("nothing to maintain"),
+            (false, true) => println!("maintenance would: {}", summary.join(", ")),
+            (false, false) => println!("maintenance: {}", summary.join(", ")),
+        }
+    }
+
+    fn edit_task(&mut self, index: usize) -> ArentaResult<()> {
+        self.check_index(index)?;
+        // edited on a copy, so an invalid edit leaves the task as it is
+        let mut task = self.tasks[index].clone();
+        let new_description = self.prompter.text(
+            TextPrompt::new("description:")
+                .with_placeholder(&task.description)
+                .with_help("press enter if don't update description"),
+        )?;
+        if !new_description.is_empty() {
+            task.description = new_description
+        }
+        let new_tags = self.prompter.text(
+            TextPrompt::new("tags:")
+                .with_placeholder(&task.tags.join(" "))
+                .with_help("press enter if don't update tags, `-` to clear them"),
+        )?;
+        if new_tags.trim() == "-" {
+            task.tags.clear();
+        } else if !new_tags.is_empty() {
+            task.tags = parse_tags(&new_tags);
+        }
+        task.priority = get_priority_input(self.prompter.as_mut(), task.priority)?;
+        match get_edit_operation(self.prompter.as_mut(), "planned start and complete time")? {
+            EditOperation::Ignore => (),
+            EditOperation::Reset => (task.planned_start, task.planned_complete) = (None, None),
+            EditOperation::Update => {
+                let (planned_start, planned_complete) = get_planned_pair(self.prompter.as_mut())?;
+                if !self.confirm_despite_conflicts(planned_start, planned_complete, Some(index))? {
+                    println!("task {index} is not edited");
+                    return Ok(());
+                }
+                task.reschedule(Some(planned_start), Some(planned_complete));
+            }
+        }
+        let actual_period = (task.actual_start, task.actual_complete);
+        match get_edit_operation(self.prompter.as_mut(), "actual start time")? {
+            EditOperation::Ignore => (),
+            EditOperation::Reset => task.actual_start = None,
+            EditOperation::Update => {
+                task.actual_start =
+                    Some(get_datetime_input(self.prompter.as_mut(), "actual start")?)
+            }
+        }
+        match get_edit_operation(self.prompter.as_mut(), "actual complete time")? {
+            EditOperation::Ignore => (),
+            EditOperation::Reset => task.actual_complete = None,
+            EditOperation::Update => {
+                task.actual_complete = Some(get_datetime_input(
+                    self.prompter.as_mut(),
+                    "actual complete",
+                )?)
+            }
+        }
+        // pauses recorded within the old actual period no longer apply
+        if (task.actual_start, task.actual_complete) != actual_period {
+            task.work_intervals.clear();
+        }
+        task.validate_periods().map_err(|reason| {
+            ArentaError::validation(format!("{reason}, task {index} is not edited"))
+        })?;
+        task.update_status();
+        let before = std::mem::replace(&mut self.tasks[index], task);
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        println!("task {index} edited");
+        Ok(())
+    }
+
+    // a single field changed without prompts, where conflicts of a new plan are only warned of
+    fn edit_task_inline(&mut self, index: usize, edit: &TaskEdit) -> ArentaResult<()> {
+        self.check_index(index)?;
+        let mut task = self.tasks[index].clone();
+        match edit {
+            TaskEdit::Description(description) => task.description = description.clone(),
+            TaskEdit::Tags(tags) => task.tags = tags.clone(),
+            TaskEdit::Priority(priority) => task.priority = *priority,
+            TaskEdit::Plan(time, minutes) => {
+                let datetime = NaiveDateTime::new(clock::today(), *time);
+                let start = resolve_local_datetime(Local.from_local_datetime(&datetime), datetime)?;
+                let complete = start + Duration::minutes(*minutes as i64);
+                self.warn_conflicts(start, complete, Some(index));
+                task.reschedule(Some(start), Some(complete));
+            }
+        }
+        task.validate_periods().map_err(|reason| {
+            ArentaError::validation(format!("{reason}, task {index} is not edited"))
+        })?;
+        task.update_status();
+        let before = std::mem::replace(&mut self.tasks[index], task);
+        self.dump_tasks()?;
+        self.emit_edited(index, &before);
+        println!("task {index} edited");
+        Ok(())
+    }
+
+    // warns about other tasks planned within the period, true if there are any
+    fn warn_conflicts(
+        &self,
+        start: DateTime<Local>,
+        complete: DateTime<Local>,
+        excluded: Option<usize>,
+    ) -> bool {
+        let conflicts = find_conflicts(&self.tasks, start, complete, excluded);
+        for (index, overlap) in &conflicts {
+            let message = format!(
+                "overlaps with task {index} ({}) for {}",
+                self.tasks[*index].description,
+                humanize_duration(*overlap)
+            );
+            println!("{}", message.yellow());
+        }
+        !conflicts.is_empty()
+    }
+
+    // warns about other tasks planned within the period, true if there are none or the user
+    // plans it anyway
+    fn confirm_despite_conflicts(
+        &mut self,
+        start: DateTime<Local>,
+        complete: DateTime<Local>,
+        excluded: Option<usize>,
+    ) -> ArentaResult<bool> {
+        if !self.warn_conflicts(start, complete, excluded) {
+            return Ok(true);
+        }
+        self.prompter.confirm("plan anyway?", false)
+    }
+
+    // the tasks a listing shows with their indices, in the order shown
+    fn matching_tasks(&self, option: &ListOption) -> Vec<(usize, &Task)> {
+        let mut tasks: Vec<(usize, &Task)> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.satisfy(option))
+            .collect();
+        if self.is_listed_by_priority {
+            sort_by_priority(&mut tasks);
+        }
+        if option.only_stale {
+            let now = clock::now();
+            tasks.retain(|(_, task)| task.is_stale(now, self.config.backlog_stale_days));
+            tasks.sort_by_key(|(_, task)| task.created_at);
+        }
+        tasks
+    }
+
+    fn list_tasks(&mut self, option: &ListOption) {
+        self.update_status_of_all_tasks();
+        let mut tasks = self.matching_tasks(option);
+        if option.is_json {
+            if let Some(limit) = option.limit {
+                let range = page_range(tasks.len(), limit, option.page);
+                tasks = tasks[range].to_vec();
+            }
+            println!("{}", listed_tasks_to_json(&tasks).to_compact_string());
+            return;
+        }
+        let mut archived_tasks = match option.include_archived {
+            true => load_tasks_from_file(&self.archive_file_path()).tasks,
+            false => vec![],
+        };
+        archived_tasks.iter_mut().for_each(Task::update_status);
+        let archived: Vec<(usize, &Task)> = archived_tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.satisfy(option))
+            .collect();
+        if tasks.is_empty() && archived.is_empty() {
+            println!("no tasks match");
+            self.print_profile_footer();
+            return;
+        }
+        // archived tasks come after the others, on the same pages
+        let total = tasks.len() + archived.len();
+        let range = match option.limit {
+            Some(limit) => page_range(total, limit, option.page),
+            None => 0..total,
+        };
+        render_header_if_verbose_list(option);
+        for position in range.clone() {
+            let row = match position.checked_sub(tasks.len()) {
+                None => tasks[position].1.render_with_highlight(
+                    tasks[position].0,
+                    None,
+                    option.is_verbose,
+                    option.text.as_deref(),
+                ),
+                Some(position) => archived[position]
+                    .1
+                    .render_archived(option.is_verbose, option.text.as_deref()),
+            };
+            println!("{row}");
+        }
+        let summary = summarize_statuses(&[tasks, archived.clone()].concat());
+        match archived.len() {
+            0 => println!("{summary}"),
+            count => println!("{summary}, of which {count} archived"),
+        }
+        self.next_page = None;
+        if option.limit.is_none() {
+            self.print_profile_footer();
+            return;
+        }
+        match range.is_empty() {
+            true => println!("no more tasks on page {}", option.page),
+            false if range.end < total => {
+                println!(
+                    "showing {} of {total}, `more` for the next page",
+                    range.len()
+                );
+                self.next_page = Some(ListOption {
+                    page: option.page + 1,
+                    ..option.clone()
+                });
+            }
+            false => println!("showing {} of {total}", range.len()),
+        }
+        self.print_profile_footer();
+    }
+
+    // the tasks of a timeline, which has a letter for each of at most 26
+    fn timeline_tasks(&self, option: &ListOption) -> Vec<(usize, &Task)> {
+        let mut tasks: Vec<(usize, &Task)> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.satisfy(option))
+            .collect();
+        if self.is_listed_by_priority {
+            sort_by_priority(&mut tasks);
+        }
+        tasks.truncate(26);
+        tasks
+    }
+
+    fn new_timeline<'a>(
+        &self,
+        tasks: &'a Vec<(usize, &'a Task)>,
+        day: NaiveDate,
+        option: &ListOption,
+        width: Option<usize>,
+    ) -> Timeline<'a> {
+        let mut timeline = Timeline::new(tasks, day, self.config.timeline_hours);
+        if let Some(width) = width {
+            timeline.set_available_width(width);
+        }
+        if let Some(minutes) = option.tick_minutes.or(self.config.timeline_tick_minutes) {
+            timeline.set_tick_minutes(minutes);
+        }
+        if option.paired_rows {
+            timeline.set_paired_rows();
+        }
+        timeline
+    }
+
+    fn list_tasks_with_timeline(&mut self, option: &ListOption) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        let tasks = self.timeline_tasks(option);
+        let (op, date) = option.date_filter;
+        if op != DateFilterOp::Equal {
+            return Err(ArentaError::validation(
+                "the timeline shows a single date, remove the comparison from the date filter",
+            ));
+        }
+        if let Some(svg_export) = &option.svg_export {
+            return export_timeline_svg(&tasks, date, self.config.timeline_hours, svg_export);
+        }
+        // every day of a range gets a timeline of its own, sharing the letters of tasks
+        let end_date = option.end_date.unwrap_or(date);
+        for day in date.iter_days().take_while(|day| *day <= end_date) {
+            if day != date {
+                println!();
+            }
+            self.new_timeline(&tasks, day, option, self.prompter.width())
+                .draw();
+        }
+        // the key is useless without colors
+        if self.config.color_key && colored::control::SHOULD_COLORIZE.should_colorize() {
+            println!("{}", render_color_key());
+        }
+        println!();
+        if tasks.is_empty() {
+            println!("no tasks match");
+            return Ok(());
+        }
+        render_header_if_verbose_list(option);
+        tasks
+            .iter()
+            .enumerate()
+            .for_each(|(timeline_index, &(index, task))| {
+                let row = task.render_with_highlight(
+                    index,
+                    Some(timeline_index_to_char(timeline_index)),
+                    option.is_verbose,
+                    option.text.as_deref(),
+                );
+                println!("{row}");
+            });
+        println!("{}", summarize_statuses(&tasks));
+        Ok(())
+    }
+
+    fn list_chronic_tasks(&mut self) {
+        self.update_status_of_all_tasks();
+        let mut tasks: Vec<(usize, &Task)> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| !task.is_deleted && task.postponed_count >= 2)
+            .collect();
+        if tasks.is_empty() {
+            println!("no task has been postponed more than once");
+            return;
+        }
+        tasks.sort_by_key(|(_, task)| std::cmp::Reverse(task.postponed_count));
+        tasks
+            .iter()
+            .for_each(|(index, task)| println!("{}", task.render(*index, None, false)));
+    }
+
+    fn search_tasks(&mut self, pattern: &str) {
+        self.update_status_of_all_tasks();
+        let mut found = false;
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| !task.is_deleted && task.matches(pattern))
+            .for_each(|(index, task)| {
+                found = true;
+                println!(
+                    "{}",
+                    task.render_with_highlight(index, None, false, Some(pattern))
+                );
+            });
+        if !found {
+            println!("no task matches `{pattern}`");
+        }
+    }
+
+    fn clean_deleted_tasks(&mut self) {
+        self.tasks.retain(|task| !task.is_deleted);
+    }
+
+    fn update_status_of_all_tasks(&mut self) {
+        let went_overdue: Vec<usize> = self
+            .tasks
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, task)| {
+                let old_status = task.status;
+                task.update_status();
+                (old_status != TaskStatus::Overdue
+                    && task.status == TaskStatus::Overdue
+                    && !task.is_deleted)
+                    .then_some(index)
+            })
+            .collect();
+        went_overdue
+            .into_iter()
+            .for_each(|index| self.emit(EventKind::WentOverdue, index));
+    }
+
+    fn emit(&mut self, kind: EventKind, index: usize) {
+        self.events.emit(kind, index, &self.tasks[index]);
+    }
+
+    fn emit_edited(&mut self, index: usize, before: &Task) {
+        let changes = diff_tasks(before, &self.tasks[index]);
+        self.events
+            .emit_with_changes(EventKind::Edited, index, &self.tasks[index], changes);
+    }
+
+    fn history_file_path(&self) -> PathBuf {
+        get_sibling_file_path(&self.file_path, "log")
+    }
+
+    fn print_history(&self, index: Option<usize>) -> ArentaResult<()> {
+        const RECENT_COUNT: usize = 20;
+        if let Some(index) = index {
+            self.check_index(index)?;
+        }
+        let entries = read_entries(&self.history_file_path())
+            .map_err(|e| ArentaError::io("failed to read history", e))?;
+        let entries = match index {
+            Some(index) => history_of(&entries, &self.tasks[index]),
+            None => entries.iter().rev().take(RECENT_COUNT).rev().collect(),
+        };
+        if entries.is_empty() {
+            println!("no history yet");
+        }
+        for entry in entries {
+            println!(
+                "{}  {} {}. {}",
+                entry
+                    .time
+                    .format(display::time_format())
+                    .to_string()
+                    .dimmed(),
+                pad_to_width(&entry.op, 9),
+                entry.index,
+                entry.description
+            );
+            for (field, old, new) in &entry.changes {
+                println!(
+                    "    {field}: {} -> {}",
+                    format_json_value(old),
+                    format_json_value(new)
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // puts the changed tasks into the store, e.g. appends them to the journal, or saves all
+    // tasks anew if more than one task is removed or the journal has grown too long
+    fn dump_tasks(&mut self) -> ArentaResult<()> {
+        if self.is_read_only {
+            return Err(ArentaError::validation(
+                "cannot save tasks in read-only mode",
+            ));
+        }
+        if files_stamp(&self.store_file_path()) != self.files_stamp {
+            return self.resolve_changed_files();
+        }
+        let rows = tasks_to_rows(&self.tasks);
+        if rows == self.persisted_rows {
+            return Ok(());
+        }
+        let removed = removed_index(&self.persisted_rows, &rows);
+        let changed: Vec<(usize, &Task)> = rows
+            .iter()
+            .enumerate()
+            .filter(|(index, row)| self.persisted_rows.get(*index) != Some(row))
+            .map(|(index, _)| (index, &self.tasks[index]))
+            .collect();
+        let records = match removed {
+            Some(_) => 1,
+            None => changed.len(),
+        };
+        if (removed.is_none() && rows.len() < self.persisted_rows.len())
+            || self.store.pending() + records > JOURNAL_MAX_RECORDS
+        {
+            return self.rewrite_tasks();
+        }
+        match removed {
+            Some(index) => self.store.delete(index)?,
+            None => self.store.upsert(&changed)?,
+        }
+        self.persisted_rows = rows;
+        self.files_stamp = files_stamp(&self.store_file_path());
+        Ok(())
+    }
+
+    // rewrites the data file with all tasks and clears the journal
+    fn compact_tasks(&mut self) -> ArentaResult<()> {
+        if self.is_read_only {
+            return Err(ArentaError::validation(
+                "cannot save tasks in read-only mode",
+            ));
+        }
+        if files_stamp(&self.store_file_path()) != self.files_stamp {
+            return self.resolve_changed_files();
+        }
+        self.rewrite_tasks()
+    }
+
+    fn rewrite_tasks(&mut self) -> ArentaResult<()> {
+        let store_path = self.store_file_path();
+        // a failed backup is no reason to lose the changes
+        if let Err(e) = backup::rotate(&store_path, self.config.backups) {
+            eprintln!("failed to back up {}: {e}", store_path.display());
+        }
+        self.store.save_all(&self.tasks)?;
+        self.persisted_rows = tasks_to_rows(&self.tasks);
+        self.files_stamp = files_stamp(&store_path);
+        Ok(())
+    }
+
+    fn store_file_path(&self) -> PathBuf {
+        store_file_path(self.config.storage, &self.file_path)
+    }
+
+    // another process has saved tasks since, e.g. through a synced home directory, so either
+    // its changes or those made here are lost
+    fn resolve_changed_files(&mut self) -> ArentaResult<()> {
+        let store_path = self.store_file_path();
+        eprintln!(
+            "{} has been changed by another process since it was loaded",
+            store_path.display()
+        );
+        let options = vec![
+            "overwrite it with the tasks here",
+            "reload it, dropping the change just made",
+        ];
+        let option = select_option(self.prompter.as_mut(), "how to deal with it", &options)
+            .map_err(|_| {
+                ArentaError::validation(format!(
+                    "{} is left untouched, `reload` to load it again",
+                    store_path.display()
+                ))
+            })?;
+        if option.starts_with("overwrite") {
+            return self.rewrite_tasks();
+        }
+        self.reload_tasks();
+        Err(ArentaError::validation("the change is dropped"))
+    }
+
+    // the tasks replaced become the latest backup themselves, so that restoring can be undone
+    fn restore_backup(&mut self, number: Option<usize>) -> ArentaResult<()> {
+        let backups = backup::list_backups(&self.store_file_path());
+        if backups.is_empty() {
+            println!("no backups yet, one is taken whenever the data file is rewritten");
+            return Ok(());
+        }
+        let load_backup = |path: &Path| open_store(self.config.storage, path)?.load_all();
+        for (index, path, time) in &backups {
+            let count = match load_backup(path) {
+                Ok(tasks) => format!("{} task(s)", tasks.len()),
+                Err(_) => "unreadable".to_string(),
+            };
+            println!("{index}. {}  {count}", time.format(display::time_format()));
+        }
+        let Some(number) = number else {
+            println!("`restore-backup <n>` to restore one of them");
+            return Ok(());
+        };
+        let Some((_, path, _)) = backups.iter().find(|(index, ..)| *index == number) else {
+            return Err(ArentaError::validation(format!("no backup {number}")));
+        };
+        let tasks = load_backup(path).map_err(|e| {
+            ArentaError::validation(format!(
+                "backup {number} is unreadable, {e}, restore it by hand from {}",
+                path.display()
+            ))
+        })?;
+        let confirmed = self.prompter.confirm(
+            &format!(
+                "replace the {} task(s) here with the {} of backup {number}?",
+                self.tasks.len(),
+                tasks.len()
+            ),
+            false,
+        )?;
+        if !confirmed {
+            println!("restore canceled");
+            return Ok(());
+        }
+        // the journal is merged first, so the backup of the tasks replaced is complete
+        if !self.is_flushed() {
+            self.compact_tasks()?;
+        }
+        self.tasks = tasks;
+        self.compact_tasks()?;
+        self.update_status_of_all_tasks();
+        println!(
+            "backup {number} restored, the tasks replaced are kept as backup 1, task indices may have changed"
+        );
+        Ok(())
+    }
+
+    // the tasks are copied from the file of the other kind, the one loaded here if that's the
+    // kind in use, and kept in the new store from then on
+    fn migrate_tasks(&mut self, kind: StorageKind) -> ArentaResult<()> {
+        let from = match kind {
+            StorageKind::Csv => StorageKind::Sqlite,
+            StorageKind::Sqlite => StorageKind::Csv,
+        };
+        let from_path = store_file_path(from, &self.file_path);
+        let to_path = store_file_path(kind, &self.file_path);
+        let tasks = match self.config.storage == from {
+            true => self.tasks.clone(),
+            false => open_store(from, &from_path)?.load_all()?,
+        };
+        let existing = match self.config.storage == kind {
+            true => self.tasks.len(),
+            false => open_store(kind, &to_path)?
+                .load_all()
+                .map_or(0, |tasks| tasks.len()),
+        };
+        if existing > 0 {
+            let confirmed = self.prompter.confirm(
+                &format!(
+                    "{} holds {existing} task(s) already, replace them with the {} of {}?",
+                    to_path.display(),
+                    tasks.len(),
+                    from_path.display()
+                ),
+                false,
+            )?;
+            if !confirmed {
+                println!("migration canceled");
+                return Ok(());
+            }
+        }
+        if let Err(e) = backup::rotate(&to_path, self.config.backups) {
+            eprintln!("failed to back up {}: {e}", to_path.display());
+        }
+        let mut store = open_store(kind, &to_path)?;
+        store.save_all(&tasks)?;
+        self.store = store;
+        self.config.storage = kind;
+        self.persisted_rows = tasks_to_rows(&tasks);
+        self.tasks = tasks;
+        self.files_stamp = files_stamp(&to_path);
+        self.update_status_of_all_tasks();
+        println!(
+            "{} task(s) migrated to {}, {} is left as it was",
+            self.tasks.len(),
+            to_path.display(),
+            from_path.display()
+        );
+        match self.config.save() {
+            Ok(()) => println!("storage set to {}", kind.name()),
+            Err(e) => eprintln!("storage set to {} for this session only, {e}", kind.name()),
+        }
+        Ok(())
+    }
+
+    fn reload_tasks(&mut self) {
+        let before = std::mem::take(&mut self.tasks);
+        self.load_snapshot();
+        let (added, removed, changed) = compare_task_sets(&before, &self.tasks);
+        println!(
+            "{} tasks reloaded, {added} added, {removed} removed, {changed} changed",
+            self.tasks.len()
+        );
+    }
+
+    // commits the data file and the files next to it that are worth sharing, then pulls and
+    // pushes; the data file is compacted first, as the journal is local to each machine
+    fn sync_tasks(&mut self, is_local: bool) -> ArentaResult<()> {
+        if self.config.sync != SyncMode::Git {
+            return Err(ArentaError::validation(
+                "sync is off, set `sync = \"git\"` in the config",
+            ));
+        }
+        if self.config.storage != StorageKind::Csv {
+            return Err(ArentaError::validation(
+                "sync needs `storage = \"csv\"`, as git can't merge a database, `migrate csv` first",
+            ));
+        }
+        if !self.is_flushed() {
+            self.compact_tasks()?;
+        }
+        let repo = Repo::discover(&self.file_path).map_err(ArentaError::validation)?;
+        let committed = repo.committed(&self.file_path).unwrap_or_default();
+        let (added, removed, changed) =
+            compare_task_sets(&parse_tasks(&committed).tasks, &self.tasks);
+        let mut paths = vec![self.file_path.clone()];
+        paths.extend(
+            [self.archive_file_path(), self.templates_file_path()]
+                .into_iter()
+                .filter(|path| path.exists()),
+        );
+        let message = format!("arenta: {} tasks updated", added + removed + changed);
+        match repo
+            .commit(&paths, &message)
+            .map_err(ArentaError::validation)?
+        {
+            true => println!("committed `{message}`"),
+            false => println!("nothing to commit"),
+        }
+        if is_local {
+            return Ok(());
+        }
+        let Some(upstream) = repo.upstream() else {
+            println!("no upstream branch, committed locally only");
+            return Ok(());
+        };
+        let mut pulled = repo.pull_rebase();
+        while let Err(PullError::Conflicts(files)) = &pulled {
+            if !files.iter().all(|file| is_same_file(file, &self.file_path)) {
+                repo.abort_rebase();
+                let files: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+                return Err(ArentaError::validation(format!(
+                    "pulling {upstream} conflicts in {}, which are left for git to resolve by hand",
+                    files.join(", ")
+                )));
+            }
+            let (mine, theirs) = repo.conflict_sides(&self.file_path);
+            let resolved = resolve_conflict(
+                self.prompter.as_mut(),
+                ["this machine", upstream.as_str()],
+                parse_tasks(&mine).tasks,
+                parse_tasks(&theirs).tasks,
+            );
+            let tasks = match resolved {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    repo.abort_rebase();
+                    return Err(e);
+                }
+            };
+            write_tasks_to_file(&self.file_path, &tasks).map_err(|e| self.save_error(e))?;
+            pulled = repo.continue_rebase(&self.file_path);
+        }
+        if let Err(PullError::Failed(reason)) = pulled {
+            repo.abort_rebase();
+            return Err(ArentaError::validation(format!(
+                "failed to pull {upstream}: {reason}"
+            )));
+        }
+        // the pull has rewritten the data file
+        let before = std::mem::take(&mut self.tasks);
+        self.load_snapshot();
+        let (added, removed, changed) = compare_task_sets(&before, &self.tasks);
+        println!("pulled {upstream}, {added} added, {removed} removed, {changed} changed");
+        repo.push()
+            .map_err(|reason| ArentaError::validation(format!("failed to push: {reason}")))?;
+        println!("pushed to {upstream}");
+        Ok(())
+    }
+
+    // used where there is no command to fail, e.g. on quit
+    fn compact_tasks_or_warn(&mut self) {
+        if let Err(e) = self.compact_tasks() {
+            eprintln!("{e}");
+        }
+    }
+
+    // the changes are kept in memory, so the next change retries saving them
+    fn save_error(&self, e: io::Error) -> ArentaError {
+        ArentaError::io(
+            format!(
+                "failed to save tasks, {} is left untouched",
+                self.file_path.display()
+            ),
+            e,
+        )
+    }
+
+    // whether the data file alone holds all tasks as they are in memory
+    fn is_flushed(&self) -> bool {
+        self.store.pending() == 0 && tasks_to_rows(&self.tasks) == self.persisted_rows
+    }
+
+    fn archive_file_path(&self) -> PathBuf {
+        get_sibling_file_path(&self.file_path, "archive")
+    }
+
+    fn archive_tasks(&mut self, op: DateFilterOp, date: NaiveDate) -> ArentaResult<()> {
+        self.update_status_of_all_tasks();
+        let should_archive = |task: &Task| {
+            !task.is_deleted
+                && task.status == TaskStatus::Complete
+                && compare_date(&task.actual_complete, op, &date)
+        };
+        let rows: Vec<Vec<String>> = self
+            .tasks
+            .iter()
+            .filter(|task| should_archive(task))
+            .map(task_to_row)
+            .collect();
+        if rows.is_empty() {
+            println!("no complete tasks to archive");
+            return Ok(());
+        }
+        // archived first, so a failure in between leaves tasks in both files rather than none
+        let archive_file_path = self.archive_file_path();
+        append_rows_to_file(&archive_file_path, &rows).map_err(|e| {
+            ArentaError::io(
+                format!("failed to archive tasks to {}", archive_file_path.display()),
+                e,
+            )
+        })?;
+        self.tasks.retain(|task| !should_archive(task));
+        self.dump_tasks()?;
+        println!(
+            "{} task(s) archived to {}, task indices may have changed",
+            rows.len(),
+            archive_file_path.display()
+        );
+        Ok(())
+    }
+
+    fn flush_tasks(&mut self) -> ArentaResult<()> {
+        if self.is_flushed() {
+            println!("nothing to flush");
+            return Ok(());
+        }
+        self.compact_tasks()?;
+        println!("journal compacted into {}", self.file_path.display());
+        Ok(())
+    }
+}
+
+pub fn task_to_row(task: &Task) -> Vec<String> {
+    let known_columns = [
+        task.description.clone(),
+        datetime_opt_to_string(&task.planned_start),
+        datetime_opt_to_string(&task.planned_complete),
+        datetime_opt_to_string(&task.actual_start),
+        datetime_opt_to_string(&task.actual_complete),
+        task.is_deleted.to_string(),
+        task.tags.join(";"),
+        task.postponed_count.to_string(),
+        task.priority.to_string(),
+        task.is_private.to_string(),
+        work_intervals_to_string(&task.work_intervals),
+        task.notes.clone(),
+        task.created_at.to_rfc3339(),
+    ];
+    known_columns
+        .into_iter()
+        .chain(task.extras.iter().cloned())
+        .collect()
+}
+
+fn tasks_to_rows(tasks: &[Task]) -> Vec<Vec<String>> {
+    tasks.iter().map(task_to_row).collect()
+}
+
+// both lists are valid on their own, so one of them is kept as it is, or all tasks of the
+// first are kept with those of the second only it lacks appended
+fn resolve_conflict(
+    prompter: &mut dyn Prompter,
+    labels: [&str; 2],
+    first: Vec<Task>,
+    second: Vec<Task>,
+) -> ArentaResult<Vec<Task>> {
+    let union = union_of_tasks(&first, &second);
+    let options = vec![
+        format!("keep {} ({} tasks)", labels[0], first.len()),
+        format!("keep {} ({} tasks)", labels[1], second.len()),
+        format!("merge both by task identity ({} tasks)", union.len()),
+    ];
+    let option = prompter.select(SelectPrompt::new(
+        "the data file has conflicting changes",
+        &options,
+    ))?;
+    Ok(match option {
+        0 => first,
+        1 => second,
+        _ => union,
+    })
+}
+
+// a task in both is kept as it is in the first
+fn union_of_tasks(first: &[Task], second: &[Task]) -> Vec<Task> {
+    let mut tasks = first.to_vec();
+    tasks.extend(
+        second
+            .iter()
+            .filter(|task| {
+                !first
+                    .iter()
+                    .any(|other| other.created_at == task.created_at)
+            })
+            .cloned(),
+    );
+    tasks
+}
+
+// the index of the single row missing from the saved ones, if that's all that has changed
+fn removed_index(saved: &[Vec<String>], rows: &[Vec<String>]) -> Option<usize> {
+    if rows.len() + 1 != saved.len() {
+        return None;
+    }
+    let index = saved
+        .iter()
+        .zip(rows)
+        .position(|(saved, row)| saved != row)
+        .unwrap_or(rows.len());
+    (saved[index + 1..] == rows[index..]).then_some(index)
+}
+
+// tasks kept in a database that isn't created yet may still be in the data file
+fn hint_migration(data_file: &Path, store_path: &Path) {
+    let count = load_tasks_from_file(data_file).tasks.len();
+    if count > 0 {
+        eprintln!(
+            "{} holds no tasks yet, `migrate sqlite` to bring over the {count} task(s) of {}",
+            store_path.display(),
+            data_file.display()
+        );
+    }
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+// tasks are told apart by their creation time, as they have no ids; returns how many are
+// added, removed and changed from the first tasks to the second
+fn compare_task_sets(before: &[Task], after: &[Task]) -> (usize, usize, usize) {
+    fn find<'a>(tasks: &'a [Task], task: &Task) -> Option<&'a Task> {
+        tasks
+            .iter()
+            .find(|other| other.created_at == task.created_at)
+    }
+    let added = after
+        .iter()
+        .filter(|task| find(before, task).is_none())
+        .count();
+    let removed = before
+        .iter()
+        .filter(|task| find(after, task).is_none())
+        .count();
+    let changed = after
+        .iter()
+        .filter_map(|task| find(before, task).map(|old| (old, task)))
+        .filter(|(old, task)| task_to_row(old) != task_to_row(task))
+        .count();
+    (added, removed, changed)
+}
+
+// writes into a temporary sibling file and renames it over the data file at last,
+// so the data file is never left half-written, returns the hash of the written content
+pub(crate) fn write_tasks_to_file(file_path: &Path, tasks: &[Task]) -> io::Result<u64> {
+    let mut writer = WriterBuilder::new().flexible(true).from_writer(vec![]);
+    writer.write_record([FORMAT_MARKER, &FORMAT_VERSION.to_string()])?;
+    for task in tasks {
+        writer.write_record(task_to_row(task))?;
+    }
+    let content = writer
+        .into_inner()
+        .map_err(|e| io::Error::new(e.error().kind(), e.error().to_string()))?;
+    let tmp_file_path = get_sibling_file_path(file_path, "tmp");
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_file_path)?;
+        file.write_all(&content)?;
+        file.sync_all()?;
+        fs::rename(&tmp_file_path, file_path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_file_path);
+    }
+    result.map(|()| content_hash(&content))
+}
+
+// appends the rows to the file, leaving existing ones as they are
+fn append_rows_to_file(file_path: &Path, rows: &[Vec<String>]) -> io::Result<()> {
+    let mut writer = WriterBuilder::new().flexible(true).from_writer(vec![]);
+    if fs::metadata(file_path).map_or(true, |metadata| metadata.len() == 0) {
+        writer.write_record([FORMAT_MARKER, &FORMAT_VERSION.to_string()])?;
+    }
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    let content = writer
+        .into_inner()
+        .map_err(|e| io::Error::new(e.error().kind(), e.error().to_string()))?;
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(file_path)?;
+    file.write_all(&content)?;
+    file.sync_all()
+}
+
+// tasks other than the excluded one planned to overlap the period, with how long they overlap,
+// touching ends are no conflict and complete tasks are no longer in the way
+fn find_conflicts(
+    tasks: &[Task],
+    start: DateTime<Local>,
+    complete: DateTime<Local>,
+    excluded: Option<usize>,
+) -> Vec<(usize, Duration)> {
+    tasks
+        .iter()
+        .enumerate()
+        .filter(|(index, task)| {
+            Some(*index) != excluded && !task.is_deleted && task.status != TaskStatus::Complete
+        })
+        .filter_map(|(index, task)| {
+            let overlap = complete.min(task.planned_complete?) - start.max(task.planned_start?);
+            (overlap > Duration::zero()).then_some((index, overlap))
+        })
+        .collect()
+}
+
+// the index of a task and its new planned pair
+type Deferral = (usize, DateTime<Local>, DateTime<Local>);
+
+// the new planned pair of every planned or overdue task starting today or earlier, keeping the
+// time of day and length; tasks planned for later days are left as they are
+fn plan_deferrals(
+    tasks: &[Task],
+    today: NaiveDate,
+    date: NaiveDate,
+) -> ArentaResult<Vec<Deferral>> {
+    if date <= today {
+        return Err(ArentaError::validation(format!(
+            "can only defer to a date after today, not {date}"
+        )));
+    }
+    let mut deferrals = vec![];
+    for (index, task) in tasks.iter().enumerate() {
+        if task.is_deleted || !matches!(task.status, TaskStatus::Planned | TaskStatus::Overdue) {
+            continue;
+        }
+        let (Some(start), Some(complete)) = (task.planned_start, task.planned_complete) else {
+            continue;
+        };
+        if start.date_naive() > today {
+            continue;
+        }
+        let datetime = NaiveDateTime::new(date, start.time());
+        let new_start = resolve_local_datetime(Local.from_local_datetime(&datetime), datetime)?;
+        deferrals.push((index, new_start, new_start + (complete - start)));
+    }
+    Ok(deferrals)
+}
+
+// appends the imported tasks unless one with the same description and planned start already
+// exists, returns how many are appended and skipped
+fn merge_imported_tasks(tasks: &mut Vec<Task>, imported: Vec<Task>) -> (usize, usize) {
+    let total = imported.len();
+    let mut appended = 0;
+    for task in imported {
+        if tasks.iter().any(|existing| {
+            existing.description == task.description && existing.planned_start == task.planned_start
+        }) {
+            continue;
+        }
+        tasks.push(task);
+        appended += 1;
+    }
+    (appended, total - appended)
+}
+
+fn describe_import(imported: usize, skipped: usize, invalid_lines: &[usize]) -> String {
+    let mut summary = format!("imported {imported} tasks, skipped {skipped} duplicates");
+    if !invalid_lines.is_empty() {
+        let lines: Vec<String> = invalid_lines.iter().map(|line| line.to_string()).collect();
+        match invalid_lines.len() {
+            1 => summary.push_str(&format!(", 1 invalid entry on line {}", lines[0])),
+            n => summary.push_str(&format!(
+                ", {n} invalid entries on lines {}",
+                lines.join(", ")
+            )),
+        }
+    }
+    summary
+}
+
+fn export_timeline_svg(
+    tasks: &Vec<(usize, &Task)>,
+    date: NaiveDate,
+    hours: (u32, u32),
+    svg_export: &ExportTarget,
+) -> ArentaResult<()> {
+    if svg_export.path.exists() && !svg_export.overwrite {
+        return Err(ArentaError::validation(format!(
+            "{} already exists, use `svg!` to overwrite it",
+            svg_export.path.display()
+        )));
+    }
+    let svg = Timeline::new(tasks, date, hours).render_svg(svg_export.redact);
+    fs::write(&svg_export.path, svg)
+        .map_err(|e| ArentaError::io("failed to export timeline", e))?;
+    println!("timeline exported to {}", svg_export.path.display());
+    Ok(())
+}
+
+// a task offered by `pick_task`, displayed like `3. write report [ongoing]`
+struct Candidate {
+    index: usize,
+    label: String,
+}
+
+impl Candidate {
+    fn new(index: usize, task: &Task) -> Self {
+        Candidate {
+            index,
+            label: format!("{index}. {} [{}]", task.description, task.status.name()),
+        }
+    }
+}
+
+impl std::fmt::Display for Candidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+// only offers tasks which the action makes sense for
+pub fn is_candidate(action: TaskAction, task: &Task) -> bool {
+    !task.is_deleted
+        && match action {
+            TaskAction::Start => !matches!(
+                task.status,
+                TaskStatus::Ongoing | TaskStatus::Paused | TaskStatus::Complete
+            ),
+            TaskAction::Complete => task.status != TaskStatus::Complete,
+            TaskAction::Delete | TaskAction::Edit => true,
+        }
+}
+
+// the positions of the page among all listed tasks, empty past the last page
+fn page_range(total: usize, limit: usize, page: usize) -> std::ops::Range<usize> {
+    let start = limit.saturating_mul(page - 1).min(total);
+    start..start.saturating_add(limit).min(total)
+}
+
+// e.g. `7 tasks: 2 ongoing, 1 overdue, 3 planned, 1 complete`, leaving out statuses of no task
+fn summarize_statuses(tasks: &[(usize, &Task)]) -> String {
+    let count_of = |status| {
+        tasks
+            .iter()
+            .filter(|(_, task)| !task.is_deleted && task.status == status)
+            .count()
+    };
+    let deleted = tasks.iter().filter(|(_, task)| task.is_deleted).count();
+    let parts: Vec<String> = [
+        TaskStatus::Ongoing,
+        TaskStatus::Paused,
+        TaskStatus::Overdue,
+        TaskStatus::Planned,
+        TaskStatus::Complete,
+        TaskStatus::Backlog,
+    ]
+    .map(|status| (count_of(status), status.name()))
+    .into_iter()
+    .chain([(deleted, "deleted")])
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, name)| format!("{count} {name}"))
+    .collect();
+    let noun = if tasks.len() == 1 { "task" } else { "tasks" };
+    format!("{} {noun}: {}", tasks.len(), parts.join(", "))
+}
+
+// the overdue or soon planned task of the highest priority, or the oldest backlog task if
+// there's none; statuses are expected to be up to date
+fn pick_next_task(tasks: &[Task], now: DateTime<Local>) -> Option<usize> {
+    let soon = now + Duration::hours(NEXT_TASK_WINDOW_HOURS);
+    let candidates = || {
+        tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| !task.is_deleted)
+    };
+    candidates()
+        .filter(|(_, task)| match task.status {
+            TaskStatus::Overdue => true,
+            TaskStatus::Planned => task.planned_start.is_some_and(|start| start <= soon),
+            _ => false,
+        })
+        .min_by(|(_, a), (_, b)| a.compare_priority(b))
+        .or_else(|| {
+            candidates()
+                .filter(|(_, task)| task.status == TaskStatus::Backlog)
+                .min_by_key(|(_, task)| task.created_at)
+        })
+        .map(|(index, _)| index)
+}
+
+// e.g. `3 overdue, 1 ongoing, 4 planned today, 6 in backlog`, leaving out what there's none of
+fn digest_of_today(tasks: &[Task], today: NaiveDate) -> String {
+    let count_of = |status, only_today: bool| {
+        tasks
+            .iter()
+            .filter(|task| !task.is_deleted && task.status == status)
+            .filter(|task| {
+                !only_today
+                    || task
+                        .planned_start
+                        .is_some_and(|start| start.date_naive() == today)
+            })
+            .count()
+    };
+    let parts: Vec<String> = [
+        (count_of(TaskStatus::Overdue, false), "overdue"),
+        (count_of(TaskStatus::Ongoing, false), "ongoing"),
+        (count_of(TaskStatus::Paused, false), "paused"),
+        (count_of(TaskStatus::Planned, true), "planned today"),
+        (count_of(TaskStatus::Backlog, false), "in backlog"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, name)| format!("{count} {name}"))
+    .collect();
+    match parts.is_empty() {
+        true => "nothing to do today".to_string(),
+        false => parts.join(", "),
+    }
+}
+
+// deleted tasks are kept until purged, out of the way
+fn compare_for_sort(ta: &Task, tb: &Task) -> std::cmp::Ordering {
+    ta.is_deleted
+        .cmp(&tb.is_deleted)
+        .then_with(|| ta.compare_priority(tb))
+}
+
+fn sort_by_priority(tasks: &mut [(usize, &Task)]) {
+    tasks.sort_by(|(_, ta), (_, tb)| compare_for_sort(ta, tb));
+}
+
+fn render_header_if_verbose_list(option: &ListOption) {
+    if option.is_verbose {
+        println!(
+            "{}",
+            render_verbose_header(if option.has_timeline { 7 } else { 4 })
+        );
+    }
+}
+
+pub fn timeline_index_to_char(index: usize) -> char {
+    char::from_u32('a' as u32 + index as u32).unwrap()
+}
+
+fn datetime_opt_to_string(datetime_opt: &Option<DateTime<Local>>) -> String {
+    datetime_opt.map_or("".to_string(), |dt| dt.to_rfc3339())
+}
+
+// e.g. `<start>/<end>;<start>/`, where the last interval is still open
+fn work_intervals_to_string(intervals: &[WorkInterval]) -> String {
+    intervals
+        .iter()
+        .map(|(start, end)| format!("{}/{}", start.to_rfc3339(), datetime_opt_to_string(end)))
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+fn work_intervals_from_string(s: &str) -> Result<Vec<WorkInterval>, String> {
+    if s.is_empty() {
+        return Ok(vec![]);
+    }
+    s.split(';')
+        .map(|interval| {
+            let (start, end) = interval
+                .split_once('/')
+                .ok_or_else(|| format!("malformed work interval `{interval}`"))?;
+            let start = datetime_opt_from_string(start)?
+                .ok_or_else(|| format!("malformed work interval `{interval}`"))?;
+            let end = datetime_opt_from_string(end)?;
+            if end.is_some_and(|end| end < start) {
+                return Err(format!("work interval `{interval}` ends before it starts"));
+            }
+            Ok((start, end))
+        })
+        .collect()
+}
+
+fn datetime_opt_from_string(s: &str) -> Result<Option<DateTime<Local>>, String> {
+    if s.is_empty() {
+        Ok(None)
+    } else {
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| Some(dt.with_timezone(&Local)))
+            .map_err(|_| format!("malformed timestamp `{s}`"))
+    }
+}
+
+fn get_datetime_input(prompter: &mut dyn Prompter, hint: &str) -> ArentaResult<DateTime<Local>> {
+    let date = prompter.date(&format!("{hint} date:"))?;
+    let time = prompt_parsed(
+        prompter,
+        &format!("{hint} time:"),
+        "e.g. 9, 9:30, 0930 or 2pm",
+        parse_time,
+    )?;
+    let datetime = NaiveDateTime::new(date, time);
+    resolve_local_datetime(Local.from_local_datetime(&datetime), datetime)
+}
+
+// opens `$VISUAL` or `$EDITOR` on the initial text if either is set, otherwise reads lines
+// until an empty one
+fn get_multiline_input(prompter: &mut dyn Prompter, initial: &str) -> ArentaResult<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_default();
+    let mut editor_args = editor.split_whitespace();
+    let Some(program) = editor_args.next() else {
+        if !initial.is_empty() {
+            println!("{}", "the note is replaced with the lines typed".dimmed());
+        }
+        let mut lines = vec![];
+        loop {
+            let line = prompter.text(TextPrompt::new(">").with_help("an empty line to finish"))?;
+            if line.is_empty() {
+                return Ok(lines.join("\n"));
+            }
+            lines.push(line);
+        }
+    };
+    let path = env::temp_dir().join(format!("arenta-note-{}.txt", std::process::id()));
+    fs::write(&path, initial).map_err(|e| ArentaError::io("failed to prepare the note", e))?;
+    let status = std::process::Command::new(program)
+        .args(editor_args)
+        .arg(&path)
+        .status();
+    let content = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    match status {
+        Ok(status) if status.success() => {
+            content.map_err(|e| ArentaError::io("failed to read the note", e))
+        }
+        Ok(status) => Err(ArentaError::validation(format!(
+            "`{editor}` exited with {status}, note is left untouched"
+        ))),
+        Err(e) => Err(ArentaError::io(format!("failed to run `{editor}`"), e)),
+    }
+}
+
+// re-prompts with the reason until the input parses
+fn prompt_parsed<T: 'static>(
+    prompter: &mut dyn Prompter,
+    message: &str,
+    help: &str,
+    parse: fn(&str) -> Result<T, String>,
+) -> ArentaResult<T> {
+    let input = prompter.text(
+        TextPrompt::new(message)
+            .with_help(help)
+            .with_validator(move |input| parse(input).map(|_| ())),
+    )?;
+    parse(&input).map_err(ArentaError::validation)
+}
+
+// a time skipped by a daylight saving transition doesn't exist, and one repeated by it
+// resolves to its first occurrence
+fn resolve_local_datetime(
+    result: LocalResult<DateTime<Local>>,
+    datetime: NaiveDateTime,
+) -> ArentaResult<DateTime<Local>> {
+    result.earliest().ok_or_else(|| {
+        ArentaError::validation(format!(
+            "{} doesn't exist in the local time zone",
+            datetime.format("%F %R")
+        ))
+    })
+}
+
+// most tasks are planned for today, so the start is typed as a time today or an offset to
+// now, while the calendar is only brought up on demand
+fn get_planned_start_input(prompter: &mut dyn Prompter) -> ArentaResult<DateTime<Local>> {
+    let start = prompt_parsed(
+        prompter,
+        "planned start:",
+        "a time today like 14:30, an offset like +30m, or empty / `cal` to pick a date",
+        parse_planned_start,
+    )?;
+    match start {
+        Some(start) => Ok(start),
+        None => get_datetime_input(prompter, "planned start"),
+    }
+}
+
+// like the planned start of a new task, except that it defaults to now
+fn parse_template_start(input: &str) -> Result<Option<DateTime<Local>>, String> {
+    match input.trim() {
+        "" => Ok(Some(clock::now())),
+        input => parse_planned_start(input),
+    }
+}
+
+fn parse_planned_start(input: &str) -> Result<Option<DateTime<Local>>, String> {
+    parse_planned_start_at(input, clock::now())
+}
+
+// `None` means picking from the calendar
+fn parse_planned_start_at(
+    input: &str,
+    now: DateTime<Local>,
+) -> Result<Option<DateTime<Local>>, String> {
+    let input = input.trim();
+    if input.is_empty() || input == "cal" {
+        return Ok(None);
+    }
+    if let Some(offset) = input.strip_prefix('+') {
+        return parse_duration(offset).map(|offset| Some(now + offset));
+    }
+    let datetime = NaiveDateTime::new(now.date_naive(), parse_time(input)?);
+    resolve_local_datetime(Local.from_local_datetime(&datetime), datetime)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+fn get_planned_pair(
+    prompter: &mut dyn Prompter,
+) -> ArentaResult<(DateTime<Local>, DateTime<Local>)> {
+    let start_dt = get_planned_start_input(prompter)?;
+    let duration = prompt_parsed(
+        prompter,
+        "planned time to take:",
+        "minutes, or e.g. 90m, 2h or 1h30m",
+        parse_duration,
+    )?;
+    let complete_dt = start_dt + duration;
+    Ok((start_dt, complete_dt))
+}
+
+// the periods of both tasks put together, and a time missing from either one is taken from
+// the other, except that the merged task is complete only if both are
+fn merged_task(first: &Task, second: &Task) -> Task {
+    fn earlier<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+    let mut merged = first.clone();
+    merged.description = format!("{} + {}", first.description, second.description);
+    merged.planned_start = earlier(first.planned_start, second.planned_start);
+    merged.planned_complete = match (first.planned_complete, second.planned_complete) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+    merged.actual_start = earlier(first.actual_start, second.actual_start);
+    merged.actual_complete = first
+        .actual_complete
+        .zip(second.actual_complete)
+        .map(|(a, b)| a.max(b));
+    for tag in &second.tags {
+        if !merged.has_tag(tag) {
+            merged.tags.push(tag.clone());
+        }
+    }
+    merged.priority = first.priority.max(second.priority);
+    // the open interval of an unfinished task stays the last one
+    merged
+        .work_intervals
+        .extend(second.work_intervals.iter().copied());
+    merged
+        .work_intervals
+        .sort_by_key(|(start, end)| (end.is_none(), *start));
+    merged.notes = match (first.notes.is_empty(), second.notes.is_empty()) {
+        (false, false) => format!("{}\n{}", first.notes, second.notes),
+        _ => format!("{}{}", first.notes, second.notes),
+    };
+    merged.update_status();
+    merged
+}
+
+// e.g. `60` or `60%`, leaving something to both parts
+fn parse_split_percent(input: &str) -> Result<u32, String> {
+    let input = input.trim();
+    let percent = input
+        .strip_suffix('%')
+        .unwrap_or(input)
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("`{input}` is not a percentage"))?;
+    match (1..100).contains(&percent) {
+        true => Ok(percent),
+        false => Err("the percentage should be between 1 and 99".to_string()),
+    }
 }
 
-type PlannedPairResult = InquireResult<(Option<DateTime<Local>>, Option<DateTime<Local>>)>;
-fn get_planned_pair() -> PlannedPairResult {
-    let start_dt = get_datetime_input("planned start")?;
-    let duration = CustomType::<usize>::new("planned time to take (in minutes):").prompt()?;
-    let complete_dt = start_dt + Duration::minutes(duration as i64);
-    Ok((Some(start_dt), Some(complete_dt)))
+// the two parts of the window divided at the time, which is kept within the window
+fn split_window(
+    start: DateTime<Local>,
+    complete: DateTime<Local>,
+    at: DateTime<Local>,
+) -> (Interval, Interval) {
+    let at = at.clamp(start, complete.max(start));
+    ((start, at), (at, complete.max(start)))
+}
+
+// empty means leaving the task in backlog
+fn parse_estimate(input: &str) -> Result<Option<Duration>, String> {
+    match input.trim() {
+        "" => Ok(None),
+        input => match parse_duration(input)? {
+            duration if duration > Duration::zero() => Ok(Some(duration)),
+            _ => Err("the estimate should be longer than 0 minutes".to_string()),
+        },
+    }
+}
+
+// the option picked, for selects whose options are told apart by their text
+fn select_option<'a>(
+    prompter: &mut dyn Prompter,
+    message: &str,
+    options: &[&'a str],
+) -> ArentaResult<&'a str> {
+    let index = prompter.select(SelectPrompt::new(message, options))?;
+    Ok(options[index])
+}
+
+fn get_priority_input(prompter: &mut dyn Prompter, current: u8) -> ArentaResult<u8> {
+    let priority = prompter.select(
+        SelectPrompt::new("priority:", &PRIORITY_NAMES).with_starting_cursor(current as usize),
+    )?;
+    Ok(priority as u8)
 }
 
 enum EditOperation {
@@ -357,16 +3564,1293 @@ enum EditOperation {
     Update,
 }
 
-fn get_edit_operation(hint: &str) -> EditOperation {
-    let options = vec!["don't update", "reset", "update to..."];
-    let option = Select::new(&format!("update {hint}?"), options)
-        .without_help_message()
-        .prompt()
-        .unwrap();
-    match option {
+fn get_edit_operation(prompter: &mut dyn Prompter, hint: &str) -> ArentaResult<EditOperation> {
+    let options = ["don't update", "reset", "update to..."];
+    let option = select_option(prompter, &format!("update {hint}?"), &options)?;
+    Ok(match option {
         "don't update" => EditOperation::Ignore,
         "reset" => EditOperation::Reset,
         "update to..." => EditOperation::Update,
         _ => unreachable!(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::strip_ansi;
+    use crate::event::{AuditLogger, HookRunner};
+    use crate::prompt::NoPrompter;
+    use crate::testing::{at, load_manager, temp_dir};
+    use chrono::Timelike;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn record(fields: &[&str]) -> StringRecord {
+        StringRecord::from(fields.to_vec())
+    }
+
+    #[test]
+    fn test_record_to_task() {
+        let task = record_to_task(&record(&[
+            "task",
+            "2023-01-26T09:00:00+08:00",
+            "2023-01-26T10:00:00+08:00",
+            "",
+            "",
+            "false",
+        ]))
+        .unwrap();
+        assert_eq!(task.description, "task");
+        assert!(task.tags.is_empty());
+        assert!(!task.is_private);
+
+        let task = record_to_task(&record(&[
+            "task", "", "", "", "", "false", "work", "0", "0", "true",
+        ]))
+        .unwrap();
+        assert!(task.is_private);
+        assert_eq!(
+            record_to_task(&record(&[
+                "task", "", "", "", "", "false", "", "", "", "yes"
+            ]))
+            .unwrap_err(),
+            "invalid private flag `yes`"
+        );
+
+        assert_eq!(
+            record_to_task(&record(&["task", "", "", ""])).unwrap_err(),
+            "expected at least 5 columns, found 4"
+        );
+        assert_eq!(
+            record_to_task(&record(&["task", "yesterday", "", "", "", "false"])).unwrap_err(),
+            "malformed timestamp `yesterday`"
+        );
+        assert_eq!(
+            record_to_task(&record(&[
+                "task",
+                "2023-01-26T09:00:00+08:00",
+                "",
+                "",
+                "",
+                "false"
+            ]))
+            .unwrap_err(),
+            "planned start and complete should always come in pair"
+        );
+        assert_eq!(
+            record_to_task(&record(&[
+                "task",
+                "",
+                "",
+                "2023-01-26T10:00:00+08:00",
+                "2023-01-26T09:00:00+08:00",
+                "false"
+            ]))
+            .unwrap_err(),
+            "actual start shouldn't be later than actual complete"
+        );
+        assert_eq!(
+            record_to_task(&record(&["task", "", "", "", "", "maybe"])).unwrap_err(),
+            "invalid deletion flag `maybe`"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_audit_log_and_hooks_observe_same_events() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("events");
+        let hooks_dir = dir.join("hooks");
+        std::fs::create_dir(&hooks_dir).unwrap();
+        let hook_output = dir.join("hooks.log");
+        for event in ["started", "completed", "deleted", "overdue"] {
+            let hook = hooks_dir.join(format!("on-{event}"));
+            std::fs::write(
+                &hook,
+                format!(
+                    "#!/bin/sh\necho \"$ARENTA_EVENT_ID,$ARENTA_EVENT,$ARENTA_TASK_INDEX\" >> {}\n",
+                    hook_output.display()
+                ),
+            )
+            .unwrap();
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let audit_log = dir.join("audit.log");
+        let mut events = EventBus::new();
+        events.subscribe(Box::new(AuditLogger::new(audit_log.clone())));
+        events.subscribe(Box::new(HookRunner::new(hooks_dir)));
+
+        let mut manager = Manager::load(
+            dir.join("arenta"),
+            Config::default(),
+            events,
+            false,
+            Box::new(NoPrompter),
+        )
+        .unwrap();
+        manager.tasks.push(Task::new_backlog_task("first"));
+        manager.tasks.push(Task::new_backlog_task("second"));
+        let past = clock::now() - Duration::hours(1);
+        manager
+            .tasks
+            .push(Task::new_planned_task("third", past, past));
+        manager.tasks[2].status = TaskStatus::Planned;
+
+        manager.start_task(0).unwrap();
+        manager.complete_task(0).unwrap();
+        manager.delete_task(1).unwrap();
+        manager.update_status_of_all_tasks();
+        // a second status update must not report the same transition again
+        manager.update_status_of_all_tasks();
+
+        let audit_records: Vec<String> = std::fs::read_to_string(&audit_log)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                line.split(',')
+                    .skip(1)
+                    .take(3)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect();
+        let hook_records: Vec<String> = std::fs::read_to_string(&hook_output)
+            .unwrap()
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(
+            audit_records,
+            vec!["0,started,0", "1,completed,0", "2,deleted,1", "3,overdue,2"]
+        );
+        assert_eq!(hook_records, audit_records);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_tasks_to_file_keeps_previous_file_on_error() {
+        let dir = temp_dir("atomic");
+        let file_path = dir.join("arenta");
+        let tasks = vec![Task::new_backlog_task("first")];
+        write_tasks_to_file(&file_path, &tasks).unwrap();
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert!(content.starts_with("#arenta,2\nfirst,"));
+        assert!(!get_sibling_file_path(&file_path, "tmp").exists());
+
+        // make the temporary file impossible to create
+        fs::create_dir(get_sibling_file_path(&file_path, "tmp")).unwrap();
+        let tasks = vec![Task::new_backlog_task("second")];
+        assert!(write_tasks_to_file(&file_path, &tasks).is_err());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), content);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_work_intervals_columns() {
+        let start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let intervals = vec![
+            (start, Some(start + Duration::hours(1))),
+            (start + Duration::hours(2), None),
+        ];
+        let column = work_intervals_to_string(&intervals);
+        assert_eq!(column.matches(';').count(), 1);
+        assert!(column.ends_with('/'));
+        assert_eq!(work_intervals_from_string(&column), Ok(intervals));
+        assert_eq!(work_intervals_from_string(""), Ok(vec![]));
+        assert!(work_intervals_from_string("yesterday/").is_err());
+        assert!(work_intervals_from_string(&start.to_rfc3339()).is_err());
+        let inverted = format!(
+            "{}/{}",
+            start.to_rfc3339(),
+            (start - Duration::hours(1)).to_rfc3339()
+        );
+        assert!(work_intervals_from_string(&inverted)
+            .unwrap_err()
+            .ends_with("ends before it starts"));
+    }
+
+    #[test]
+    fn test_unknown_columns_survive_rewriting() {
+        let dir = temp_dir("extras");
+        let file_path = dir.join("arenta");
+        // the known columns are followed by two unknown ones
+        let known = "task,,,,,false,work,0,0,false".to_string() + &",".repeat(KNOWN_COLUMNS - 10);
+        fs::write(
+            &file_path,
+            known + ",from the future,\"a, b\"\nplain,,,,,false\n",
+        )
+        .unwrap();
+        let mut tasks = load_tasks_from_file(&file_path).tasks;
+        assert_eq!(tasks[0].extras, vec!["from the future", "a, b"]);
+        assert!(tasks[1].extras.is_empty());
+
+        tasks[0].start();
+        tasks[0].priority = 2;
+        write_tasks_to_file(&file_path, &tasks).unwrap();
+        let tasks = load_tasks_from_file(&file_path).tasks;
+        assert_eq!(tasks[0].priority, 2);
+        assert!(tasks[0].actual_start.is_some());
+        assert_eq!(tasks[0].work_intervals.len(), 1);
+        assert_eq!(tasks[0].extras, vec!["from the future", "a, b"]);
+        assert!(fs::read_to_string(&file_path)
+            .unwrap()
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with(",from the future,\"a, b\""));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_every_field_survives_rewriting() {
+        let dir = temp_dir("round-trip");
+        let file_path = dir.join("arenta");
+        let at =
+            |hour: u32, minute: u32| Local.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap();
+        let task = Task {
+            description: "write \"report\", then review".to_string(),
+            planned_start: Some(at(9, 0)),
+            planned_complete: Some(at(10, 30)),
+            actual_start: Some(at(9, 15)),
+            actual_complete: Some(at(11, 0)),
+            status: TaskStatus::Complete,
+            is_deleted: true,
+            tags: vec!["work".to_string(), "q1".to_string()],
+            postponed_count: 3,
+            priority: PRIORITY_HIGH,
+            is_private: true,
+            work_intervals: vec![(at(9, 15), Some(at(10, 0))), (at(10, 20), Some(at(11, 0)))],
+            notes: "first line\nsecond, line".to_string(),
+            created_at: at(8, 0),
+            extras: vec!["from the future".to_string()],
+        };
+        let plain = Task::new_backlog_task("plain");
+        write_tasks_to_file(&file_path, &[task.clone(), plain.clone()]).unwrap();
+        assert!(fs::read_to_string(&file_path)
+            .unwrap()
+            .starts_with("#arenta,2\n"));
+
+        let result = load_tasks_from_file(&file_path);
+        assert!(result.skipped.is_empty());
+        let mut tasks = result.tasks;
+        tasks.iter_mut().for_each(Task::update_status);
+        let mut expected = task;
+        expected.update_status();
+        assert_eq!(tasks[0], expected);
+        write_tasks_to_file(&file_path, &tasks).unwrap();
+        let mut reloaded = load_tasks_from_file(&file_path).tasks;
+        reloaded.iter_mut().for_each(Task::update_status);
+        assert_eq!(tasks, reloaded);
+        assert_eq!(tasks[0].description, "write \"report\", then review");
+        assert!(tasks[0].is_deleted);
+        assert_eq!(tasks[0].work_intervals.len(), 2);
+        assert_eq!(tasks[0].notes, "first line\nsecond, line");
+        assert_eq!(tasks[0].created_at, at(8, 0));
+        assert_eq!(tasks[1], plain);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_files_without_version_still_load() {
+        let dir = temp_dir("unversioned");
+        let file_path = dir.join("arenta");
+        fs::write(
+            &file_path,
+            "old task,2023-01-26T09:00:00+08:00,2023-01-26T10:00:00+08:00,,\n#arenta,,,,,true\n",
+        )
+        .unwrap();
+        let result = load_tasks_from_file(&file_path);
+        assert!(result.skipped.is_empty());
+        assert_eq!(result.tasks.len(), 2);
+        assert!(!result.tasks[0].is_deleted);
+        // a task described as the marker is only taken as the header on the first row
+        assert_eq!(result.tasks[1].description, "#arenta");
+        assert!(result.tasks[1].is_deleted);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_newer_format_is_only_read() {
+        let dir = temp_dir("newer-format");
+        let file_path = dir.join("arenta");
+        fs::write(
+            &file_path,
+            "#arenta,3\ntask,2023-01-26T09:00:00+08:00,2023-01-26T10:00:00+08:00,,\n",
+        )
+        .unwrap();
+        let result = load_tasks_from_file(&file_path);
+        assert_eq!(result.newer_version, Some(3));
+        assert_eq!(result.tasks.len(), 1);
+        let Err(e) = load_manager(&file_path, false) else {
+            panic!("a newer format is loaded for writing")
+        };
+        assert!(e.to_string().contains("format 3"));
+        assert_eq!(load_manager(&file_path, true).unwrap().tasks().len(), 1);
+
+        write_tasks_to_file(&file_path, &result.tasks).unwrap();
+        assert_eq!(load_tasks_from_file(&file_path).newer_version, None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let at =
+            |hour: u32, minute: u32| Local.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap();
+        let planned = |start: u32, complete: u32| {
+            Task::new_planned_task("task", at(start, 0), at(complete, 0))
+        };
+        let mut complete = planned(9, 12);
+        complete.start();
+        complete.complete();
+        complete.update_status();
+        let mut deleted = planned(9, 12);
+        deleted.delete();
+        let tasks = vec![
+            planned(8, 10),
+            planned(11, 12),
+            planned(9, 10),
+            complete,
+            deleted,
+            Task::new_backlog_task("backlog"),
+            planned(10, 11),
+        ];
+        assert_eq!(
+            find_conflicts(&tasks, at(9, 30), at(11, 0), Some(2)),
+            vec![(0, Duration::minutes(30)), (6, Duration::hours(1))]
+        );
+        // touching ends
+        assert!(find_conflicts(&tasks, at(12, 0), at(13, 0), None).is_empty());
+        assert!(find_conflicts(&tasks, at(7, 0), at(8, 0), None).is_empty());
+    }
+
+    #[test]
+    fn test_plan_deferrals() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let planned = |day: u32, status: TaskStatus| Task {
+            status,
+            ..Task::new_planned_task("task", at(day, 9), at(day, 11))
+        };
+        let mut deleted = planned(4, TaskStatus::Planned);
+        deleted.delete();
+        let tasks = vec![
+            planned(4, TaskStatus::Planned),
+            planned(2, TaskStatus::Overdue),
+            planned(5, TaskStatus::Planned),
+            planned(4, TaskStatus::Ongoing),
+            Task::new_backlog_task("backlog"),
+            deleted,
+        ];
+        let tomorrow = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        assert_eq!(
+            plan_deferrals(&tasks, today, tomorrow).unwrap(),
+            vec![(0, at(5, 9), at(5, 11)), (1, at(5, 9), at(5, 11))]
+        );
+        assert!(plan_deferrals(&tasks, today, today).is_err());
+    }
+
+    #[test]
+    fn test_merge_imported_tasks() {
+        let planned = |description: &str, hour: u32| {
+            let start = Local.with_ymd_and_hms(2024, 3, 4, hour, 0, 0).unwrap();
+            Task::new_planned_task(description, start, start + Duration::hours(1))
+        };
+        let mut tasks = vec![planned("standup", 9), Task::new_backlog_task("read")];
+        let imported = vec![
+            planned("standup", 9),
+            planned("standup", 10),
+            Task::new_backlog_task("read"),
+            Task::new_backlog_task("write"),
+            Task::new_backlog_task("write"),
+        ];
+        assert_eq!(merge_imported_tasks(&mut tasks, imported), (2, 3));
+        assert_eq!(tasks.len(), 4);
+        assert_eq!(tasks[2].planned_start.unwrap().hour(), 10);
+        assert_eq!(tasks[3].description, "write");
+    }
+
+    #[test]
+    fn test_describe_import() {
+        assert_eq!(
+            describe_import(12, 3, &[7]),
+            "imported 12 tasks, skipped 3 duplicates, 1 invalid entry on line 7"
+        );
+        assert_eq!(
+            describe_import(0, 0, &[2, 9]),
+            "imported 0 tasks, skipped 0 duplicates, 2 invalid entries on lines 2, 9"
+        );
+        assert_eq!(
+            describe_import(1, 0, &[]),
+            "imported 1 tasks, skipped 0 duplicates"
+        );
+    }
+
+    #[test]
+    fn test_changes_are_journaled_until_flushed() {
+        let dir = temp_dir("journal");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(
+            &file_path,
+            &[Task::new_backlog_task("a"), Task::new_backlog_task("b")],
+        )
+        .unwrap();
+        let base = fs::read_to_string(&file_path).unwrap();
+
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.start_task(1).unwrap();
+        manager.capture_task("c").unwrap();
+        // nothing changes, so nothing is written
+        manager.dump_tasks().unwrap();
+        assert_eq!(manager.store.pending(), 2);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), base);
+
+        let mut manager = load_manager(&file_path, false).unwrap();
+        assert_eq!(manager.tasks.len(), 3);
+        assert!(manager.tasks[1].actual_start.is_some());
+        manager.flush_tasks().unwrap();
+        assert!(!get_sibling_file_path(&file_path, "journal").exists());
+        assert_eq!(load_tasks_from_file(&file_path).tasks.len(), 3);
+
+        // removing a single task is journaled, while removing more rewrites the data file
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.delete_task(0).unwrap();
+        manager.clean_deleted_tasks();
+        manager.dump_tasks().unwrap();
+        assert_eq!(manager.store.pending(), 2);
+        let reloaded = load_manager(&file_path, false).unwrap();
+        assert_eq!(reloaded.tasks.len(), 2);
+        assert_eq!(reloaded.tasks[0].description, "b");
+        manager.delete_task(0).unwrap();
+        manager.delete_task(1).unwrap();
+        manager.clean_deleted_tasks();
+        manager.dump_tasks().unwrap();
+        assert!(load_tasks_from_file(&file_path).tasks.is_empty());
+        assert!(!get_sibling_file_path(&file_path, "journal").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_paused_task_is_resumed_rather_than_started() {
+        let dir = temp_dir("restart-paused");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(&file_path, &[Task::new_backlog_task("x")]).unwrap();
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.start(0).unwrap();
+        manager.run_command(&Command::Pause(0)).unwrap();
+        let paused = manager.tasks[0].clone();
+        let Err(e) = manager.start(0) else {
+            panic!("a paused task is started again")
+        };
+        assert_eq!(e.to_string(), "task 0 is paused, `resume 0` to go on");
+        assert_eq!(manager.tasks[0], paused);
+        manager.run_command(&Command::Resume(0)).unwrap();
+        assert_eq!(manager.tasks[0].actual_start, paused.actual_start);
+        assert_eq!(manager.tasks[0].work_intervals[0], paused.work_intervals[0]);
+        assert_eq!(manager.tasks[0].work_intervals.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_completed_task_is_not_started_again() {
+        let dir = temp_dir("restart");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(&file_path, &[Task::new_immediate_task("x")]).unwrap();
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.complete(0).unwrap();
+        let completed = manager.tasks[0].clone();
+        let Err(e) = manager.start(0) else {
+            panic!("a completed task is started again")
+        };
+        assert_eq!(e.to_string(), "task 0 is complete already");
+        assert_eq!(manager.tasks[0], completed);
+        manager.delete(0).unwrap();
+        assert!(manager.start(0).is_err());
+
+        manager.dump_tasks().unwrap();
+        manager.compact_tasks().unwrap();
+        let result = load_tasks_from_file(&file_path);
+        assert!(result.skipped.is_empty());
+        assert_eq!(result.tasks[0].actual_complete, completed.actual_complete);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tasks_are_reloaded_after_maintenance() {
+        let dir = temp_dir("maintained");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(&file_path, &[Task::new_backlog_task("a")]).unwrap();
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.capture_task("b").unwrap();
+        assert_eq!(manager.store.pending(), 1);
+
+        manager.run_command(&Command::Maintain(false)).unwrap();
+        assert_eq!(manager.store.pending(), 0);
+        assert!(!get_sibling_file_path(&file_path, "journal").exists());
+        // saved without taking the compacted file for a change made elsewhere
+        manager.capture_task("c").unwrap();
+        let descriptions: Vec<String> = load_tasks_from_file(&file_path)
+            .tasks
+            .into_iter()
+            .map(|task| task.description)
+            .collect();
+        assert_eq!(descriptions, ["a", "b"]);
+        assert_eq!(manager.store.pending(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tasks_kept_in_sqlite() {
+        let dir = temp_dir("sqlite");
+        let file_path = dir.join("arenta.csv");
+        let load = || {
+            let mut config = Config::default();
+            config.storage = StorageKind::Sqlite;
+            Manager::load(
+                file_path.clone(),
+                config,
+                EventBus::new(),
+                false,
+                Box::new(NoPrompter),
+            )
+        };
+        let Ok(mut manager) = load() else {
+            eprintln!("sqlite3 is missing, skipped");
+            fs::remove_dir_all(&dir).unwrap();
+            return;
+        };
+        manager.capture_task("a").unwrap();
+        manager.capture_task("b").unwrap();
+        manager.capture_task("c").unwrap();
+        manager.start_task(1).unwrap();
+        manager.delete_task(0).unwrap();
+        manager.clean_deleted_tasks();
+        manager.dump_tasks().unwrap();
+        manager.tasks[1].description = "c, renamed".to_string();
+        manager.dump_tasks().unwrap();
+        assert!(manager.is_flushed());
+        assert!(!file_path.exists());
+
+        let reloaded = load().unwrap();
+        assert_eq!(
+            tasks_to_rows(&reloaded.tasks),
+            tasks_to_rows(&manager.tasks)
+        );
+        assert!(reloaded.tasks[0].actual_start.is_some());
+        assert_eq!(reloaded.tasks[1].description, "c, renamed");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_removed_index() {
+        let rows = |names: &[&str]| -> Vec<Vec<String>> {
+            names.iter().map(|name| vec![name.to_string()]).collect()
+        };
+        let saved = rows(&["a", "b", "c"]);
+        assert_eq!(removed_index(&saved, &rows(&["a", "c"])), Some(1));
+        assert_eq!(removed_index(&saved, &rows(&["a", "b"])), Some(2));
+        assert_eq!(removed_index(&saved, &rows(&["b", "c"])), Some(0));
+        assert_eq!(removed_index(&saved, &rows(&["b", "a"])), None);
+        assert_eq!(removed_index(&saved, &rows(&["a"])), None);
+        assert_eq!(removed_index(&saved, &saved), None);
+    }
+
+    #[test]
+    fn test_read_only_session_follows_the_file() {
+        let dir = temp_dir("read-only");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(&file_path, &[Task::new_backlog_task("a")]).unwrap();
+        let base = fs::read_to_string(&file_path).unwrap();
+
+        let mut viewer = load_manager(&file_path, true).unwrap();
+        let error = viewer.dispatch_command(&Command::Start(0)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "read-only session, `start` is not available"
+        );
+        assert!(viewer.tasks[0].actual_start.is_none());
+        assert!(viewer.dispatch_command(&Command::Info).is_ok());
+
+        // changes journaled by the session holding the lock show up after loading again
+        let mut writer = load_manager(&file_path, false).unwrap();
+        writer.capture_task("b").unwrap();
+        viewer.load_snapshot();
+        assert_eq!(viewer.tasks.len(), 2);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), base);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_changes_by_another_process_are_noticed() {
+        let dir = temp_dir("stamp");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(&file_path, &[Task::new_backlog_task("a")]).unwrap();
+        let mut first = load_manager(&file_path, false).unwrap();
+        let mut second = load_manager(&file_path, false).unwrap();
+        first.capture_task("b").unwrap();
+        assert_eq!(files_stamp(&file_path), first.files_stamp);
+        assert_ne!(files_stamp(&file_path), second.files_stamp);
+
+        second.reload_tasks();
+        assert_eq!(second.tasks.len(), 2);
+        assert_eq!(files_stamp(&file_path), second.files_stamp);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reload_keeps_legacy_rows() {
+        let dir = temp_dir("legacy-reload");
+        let file_path = dir.join("arenta");
+        fs::write(&file_path, LEGACY_ROWS).unwrap();
+        let mut manager = load_manager(&file_path, true).unwrap();
+        let before = manager.tasks.clone();
+        manager.load_snapshot();
+        assert_eq!(compare_task_sets(&before, &manager.tasks), (0, 0, 0));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compare_task_sets() {
+        let kept = Task::new_backlog_task("kept");
+        let mut edited = Task::new_backlog_task("edited");
+        edited.created_at -= Duration::minutes(1);
+        let mut removed = Task::new_backlog_task("removed");
+        removed.created_at -= Duration::minutes(2);
+        let before = vec![kept.clone(), edited.clone(), removed];
+        edited.description = "edited again".to_string();
+        let mut added = Task::new_backlog_task("added");
+        added.created_at += Duration::minutes(1);
+        assert_eq!(
+            compare_task_sets(&before, &[kept, edited, added]),
+            (1, 1, 1)
+        );
+        assert_eq!(compare_task_sets(&before, &before), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_union_of_tasks() {
+        let shared = Task::new_backlog_task("shared");
+        let mut edited = shared.clone();
+        edited.description = "shared, edited".to_string();
+        let mut mine = Task::new_backlog_task("mine");
+        mine.created_at -= Duration::minutes(1);
+        let mut theirs = Task::new_backlog_task("theirs");
+        theirs.created_at += Duration::minutes(1);
+        let union = union_of_tasks(&[shared, mine], &[theirs, edited]);
+        let descriptions: Vec<&str> = union.iter().map(|t| t.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["shared", "mine", "theirs"]);
+    }
+
+    // rows of the 5 columns written before creation times were kept
+    const LEGACY_ROWS: &str = "old,2023-01-26T09:00:00+08:00,2023-01-26T10:00:00+08:00,,\n\
+                               backlog,,,,\n\
+                               another backlog,,,,\n";
+
+    #[test]
+    fn test_legacy_rows_are_the_same_tasks_on_every_machine() {
+        let dir = temp_dir("legacy-sync");
+        let file_path = dir.join("arenta");
+        // this machine edits a task and rewrites the file, the other one parses it afresh
+        let mut mine = parse_tasks(LEGACY_ROWS.as_bytes()).tasks;
+        mine[1].description = "backlog, edited".to_string();
+        write_tasks_to_file(&file_path, &mine).unwrap();
+        let mine = load_tasks_from_file(&file_path).tasks;
+        let mut theirs = parse_tasks(LEGACY_ROWS.as_bytes()).tasks;
+        let mut added = Task::new_backlog_task("added");
+        added.created_at = clock::now();
+        theirs.push(added);
+
+        // as the commit message of sync counts them
+        let committed = parse_tasks(LEGACY_ROWS.as_bytes()).tasks;
+        assert_eq!(compare_task_sets(&committed, &mine), (0, 0, 1));
+        let union = union_of_tasks(&mine, &theirs);
+        let descriptions: Vec<&str> = union.iter().map(|t| t.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec!["old", "backlog, edited", "another backlog", "added"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_archive_appends_complete_tasks() {
+        let dir = temp_dir("archive");
+        let file_path = dir.join("arenta");
+        let complete_days_ago = |description: &str, days: i64| {
+            let mut task = Task::new_immediate_task(description);
+            task.complete();
+            task.actual_start = Some(clock::now() - Duration::days(days));
+            task.actual_complete = task.actual_start;
+            task
+        };
+        write_tasks_to_file(
+            &file_path,
+            &[
+                complete_days_ago("old", 40),
+                Task::new_backlog_task("backlog"),
+                complete_days_ago("recent", 3),
+                complete_days_ago("older", 50),
+            ],
+        )
+        .unwrap();
+        let month_ago = clock::today() - Duration::days(30);
+        let descriptions = |tasks: &[Task]| {
+            tasks
+                .iter()
+                .map(|task| task.description.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager
+            .archive_tasks(DateFilterOp::Earlier, month_ago)
+            .unwrap();
+        assert_eq!(descriptions(&manager.tasks), ["backlog", "recent"]);
+        let archive_file_path = get_sibling_file_path(&file_path, "archive");
+        assert_eq!(
+            descriptions(&load_tasks_from_file(&archive_file_path).tasks),
+            ["old", "older"]
+        );
+
+        // archiving again keeps what is archived before
+        let mut manager = load_manager(&file_path, false).unwrap();
+        assert_eq!(manager.tasks.len(), 2);
+        manager
+            .archive_tasks(DateFilterOp::LaterEqual, month_ago)
+            .unwrap();
+        assert_eq!(descriptions(&manager.tasks), ["backlog"]);
+        assert_eq!(
+            descriptions(&load_tasks_from_file(&archive_file_path).tasks),
+            ["old", "older", "recent"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_day_view_lists_what_ll_shows() {
+        let dir = temp_dir("day-view");
+        let file_path = dir.join("arenta");
+        let tasks = [
+            Task::new_immediate_task("write report"),
+            Task::new_backlog_task("someday"),
+            Task::new_immediate_task("water plants"),
+        ];
+        write_tasks_to_file(&file_path, &tasks).unwrap();
+        let mut manager = load_manager(&file_path, false).unwrap();
+        let mut option = ListOption {
+            has_timeline: true,
+            ..ListOption::default()
+        };
+        let view = manager.day_view(&option, Some(80));
+        assert_eq!(
+            view.rows
+                .iter()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>(),
+            [0, 2]
+        );
+        assert!(strip_ansi(&view.rows[1].1).starts_with("2(b)."));
+        assert!(strip_ansi(view.timeline.last().unwrap()).contains("b=2 water plants"));
+        assert_eq!(
+            view.summary,
+            summarize_statuses(&manager.timeline_tasks(&option))
+        );
+
+        option.text = Some("water".to_string());
+        let view = manager.day_view(&option, None);
+        assert_eq!(
+            view.rows
+                .iter()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // answers yes to every confirmation, and nothing else
+    struct Confirming;
+
+    impl Prompter for Confirming {
+        fn command_line(
+            &mut self,
+            prompt: &str,
+            tasks: &[Task],
+            history: &[String],
+        ) -> ArentaResult<String> {
+            NoPrompter.command_line(prompt, tasks, history)
+        }
+
+        fn text(&mut self, prompt: TextPrompt) -> ArentaResult<String> {
+            NoPrompter.text(prompt)
+        }
+
+        fn confirm(&mut self, _: &str, _: bool) -> ArentaResult<bool> {
+            Ok(true)
+        }
+
+        fn select(&mut self, prompt: SelectPrompt) -> ArentaResult<usize> {
+            NoPrompter.select(prompt)
+        }
+
+        fn date(&mut self, message: &str) -> ArentaResult<NaiveDate> {
+            NoPrompter.date(message)
+        }
+
+        fn count_down(&mut self, duration: std::time::Duration, label: &str) -> bool {
+            NoPrompter.count_down(duration, label)
+        }
+    }
+
+    // answers every text prompt with the same input, keeping what it's echoed as
+    struct Answering {
+        input: &'static str,
+        echoed: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Prompter for Answering {
+        fn command_line(
+            &mut self,
+            prompt: &str,
+            tasks: &[Task],
+            history: &[String],
+        ) -> ArentaResult<String> {
+            NoPrompter.command_line(prompt, tasks, history)
+        }
+
+        fn text(&mut self, prompt: TextPrompt) -> ArentaResult<String> {
+            if let Some(validator) = &prompt.validator {
+                validator(self.input).map_err(ArentaError::validation)?;
+            }
+            let echoed = match &prompt.formatter {
+                Some(formatter) => formatter(self.input),
+                None => self.input.to_string(),
+            };
+            self.echoed.borrow_mut().push(echoed);
+            Ok(self.input.to_string())
+        }
+
+        fn confirm(&mut self, message: &str, default: bool) -> ArentaResult<bool> {
+            NoPrompter.confirm(message, default)
+        }
+
+        fn select(&mut self, prompt: SelectPrompt) -> ArentaResult<usize> {
+            NoPrompter.select(prompt)
+        }
+
+        fn date(&mut self, message: &str) -> ArentaResult<NaiveDate> {
+            NoPrompter.date(message)
+        }
+
+        fn count_down(&mut self, duration: std::time::Duration, label: &str) -> bool {
+            NoPrompter.count_down(duration, label)
+        }
+    }
+
+    #[test]
+    fn test_date_filter_prompt_echoes_the_date() {
+        let _frozen = crate::clock::freeze("2024-03-09 10:00");
+        let dir = temp_dir("date-filter-prompt");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(&file_path, &[]).unwrap();
+        let echoed = Rc::new(RefCell::new(vec![]));
+        let prompter = Answering {
+            input: ">=-1",
+            echoed: Rc::clone(&echoed),
+        };
+        let mut manager = Manager::load(
+            file_path,
+            Config::default(),
+            EventBus::new(),
+            false,
+            Box::new(prompter),
+        )
+        .unwrap();
+        assert!(!manager.execute_line("ls someday").unwrap());
+        assert_eq!(*echoed.borrow(), [">= 2024-03-08 (Fri)"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_complete_untracked_task_starts_it_at_its_completion() {
+        let dir = temp_dir("untracked");
+        let file_path = dir.join("arenta");
+        let mut task = Task::new_backlog_task("completed by hand");
+        task.actual_complete = Some(Local.with_ymd_and_hms(2024, 3, 4, 10, 30, 0).unwrap());
+        write_tasks_to_file(&file_path, &[task]).unwrap();
+        let load = |prompter: Box<dyn Prompter>| {
+            Manager::load(
+                file_path.clone(),
+                Config::default(),
+                EventBus::new(),
+                false,
+                prompter,
+            )
+            .unwrap()
+        };
+
+        // nobody to confirm, so the completion stays where it was
+        let mut manager = load(Box::new(NoPrompter));
+        assert!(manager.run_command(&Command::Complete(0)).is_err());
+        assert!(manager.tasks[0].is_untracked());
+
+        let mut manager = load(Box::new(Confirming));
+        manager.run_command(&Command::Complete(0)).unwrap();
+        let task = &load(Box::new(NoPrompter)).tasks[0];
+        assert_eq!(task.actual_start, task.actual_complete);
+        assert_eq!(
+            task.actual_complete.unwrap().format("%R").to_string(),
+            "10:30"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sort_keeps_the_order_in_file() {
+        let dir = temp_dir("sort");
+        let file_path = dir.join("arenta");
+        let mut urgent = Task::new_backlog_task("urgent");
+        urgent.priority = PRIORITY_HIGH;
+        write_tasks_to_file(&file_path, &[Task::new_backlog_task("later"), urgent]).unwrap();
+        let descriptions = |tasks: &[Task]| {
+            tasks
+                .iter()
+                .map(|task| task.description.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.run_command(&Command::Sort(false)).unwrap();
+        assert!(manager.is_listed_by_priority);
+        let mut listed: Vec<(usize, &Task)> = manager.tasks.iter().enumerate().collect();
+        sort_by_priority(&mut listed);
+        assert_eq!(
+            listed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            [1, 0]
+        );
+        assert_eq!(
+            descriptions(&load_manager(&file_path, false).unwrap().tasks),
+            ["later", "urgent"]
+        );
+
+        manager.run_command(&Command::Sort(true)).unwrap();
+        assert_eq!(
+            descriptions(&load_manager(&file_path, false).unwrap().tasks),
+            ["urgent", "later"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_deleted_tasks_are_kept_until_purged() {
+        let dir = temp_dir("trash");
+        let file_path = dir.join("arenta");
+        write_tasks_to_file(
+            &file_path,
+            &[Task::new_backlog_task("a"), Task::new_backlog_task("b")],
+        )
+        .unwrap();
+
+        let mut manager = load_manager(&file_path, false).unwrap();
+        manager.delete_task(0).unwrap();
+        manager.sort_tasks().unwrap();
+        let mut manager = load_manager(&file_path, false).unwrap();
+        assert_eq!(manager.tasks[0].description, "b");
+        assert!(manager.tasks[1].is_deleted);
+        assert!(matches!(
+            manager.restore_task(0),
+            Err(ArentaError::Validation(_))
+        ));
+        manager.restore_task(1).unwrap();
+        assert!(!load_manager(&file_path, false).unwrap().tasks[1].is_deleted);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_malformed_rows_are_skipped() {
+        let dir = temp_dir("malformed");
+        let file_path = dir.join("arenta");
+        fs::write(&file_path, "first,,,,,false\nsecond,yesterday,,,,false\n").unwrap();
+        let skipped = load_tasks_from_file(&file_path).skipped;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            skipped[0].0.to_string(),
+            "line 2: malformed timestamp `yesterday`"
+        );
+        let manager = load_manager(&file_path, true).unwrap();
+        assert_eq!(manager.tasks.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failing_to_save_fails_only_the_command() {
+        let dir = temp_dir("unsavable");
+        // the data file can't be written, as its parent is not a directory
+        fs::write(dir.join("file"), "").unwrap();
+        let file_path = dir.join("file").join("arenta");
+        let mut manager = load_manager(&file_path, false).unwrap();
+        let error = manager.capture_task("a").unwrap_err();
+        assert!(matches!(error, ArentaError::Io { .. }));
+        assert!(error.to_string().starts_with(&format!(
+            "failed to save tasks, {} is left untouched: ",
+            file_path.display()
+        )));
+        assert!(!error.is_fatal());
+        // the change is kept, so that the next command retries saving it
+        assert_eq!(manager.tasks.len(), 1);
+        assert!(matches!(
+            manager.start_task(1),
+            Err(ArentaError::Validation(_))
+        ));
+        assert!(!manager.execute_once(&Command::Flush));
+
+        let mut manager = load_manager(&dir.join("arenta"), true).unwrap();
+        assert_eq!(
+            manager.capture_task("a").unwrap_err().to_string(),
+            "cannot save tasks in read-only mode"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_timeline_rejects_date_comparison() {
+        let Some(Command::List(mut option)) = parse_command("ls >=-1") else {
+            panic!("`ls >=-1` should be a list command");
+        };
+        option.has_timeline = true;
+        let dir = temp_dir("timeline");
+        let mut manager = load_manager(&dir.join("arenta"), true).unwrap();
+        assert!(matches!(
+            manager.list_tasks_with_timeline(&option),
+            Err(ArentaError::Validation(_))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_local_datetime() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 3, 31)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        assert_eq!(
+            resolve_local_datetime(LocalResult::None, datetime)
+                .unwrap_err()
+                .to_string(),
+            "2024-03-31 02:30 doesn't exist in the local time zone"
+        );
+        let early = Local
+            .with_ymd_and_hms(2024, 10, 27, 2, 30, 0)
+            .earliest()
+            .unwrap();
+        let late = early + Duration::hours(1);
+        assert_eq!(
+            resolve_local_datetime(LocalResult::Ambiguous(early, late), datetime).unwrap(),
+            early
+        );
+    }
+
+    #[test]
+    fn test_parse_planned_start() {
+        let now = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        assert_eq!(parse_planned_start_at("", now), Ok(None));
+        assert_eq!(parse_planned_start_at(" cal ", now), Ok(None));
+        assert_eq!(
+            parse_planned_start_at("14:30", now),
+            Ok(Local.with_ymd_and_hms(2024, 3, 4, 14, 30, 0).earliest())
+        );
+        assert_eq!(
+            parse_planned_start_at("2pm", now),
+            Ok(Local.with_ymd_and_hms(2024, 3, 4, 14, 0, 0).earliest())
+        );
+        assert_eq!(
+            parse_planned_start_at("+30m", now),
+            Ok(Some(now + Duration::minutes(30)))
+        );
+        assert_eq!(
+            parse_planned_start_at("+1h30m", now),
+            Ok(Some(now + Duration::minutes(90)))
+        );
+        assert!(parse_planned_start_at("+", now).is_err());
+        assert!(parse_planned_start_at("tomorrow", now).is_err());
+    }
+
+    #[test]
+    fn test_pick_candidates() {
+        let mut ongoing = Task::new_immediate_task("write report");
+        ongoing.update_status();
+        let mut complete = Task::new_immediate_task("read papers");
+        complete.complete();
+        complete.update_status();
+        let mut deleted = Task::new_backlog_task("old");
+        deleted.delete();
+        let backlog = Task::new_backlog_task("plan trip");
+        let tasks = [ongoing, complete, deleted, backlog];
+        let candidates = |action| -> Vec<usize> {
+            tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| is_candidate(action, task))
+                .map(|(index, _)| index)
+                .collect()
+        };
+        assert_eq!(candidates(TaskAction::Start), vec![3]);
+        assert_eq!(candidates(TaskAction::Complete), vec![0, 3]);
+        assert_eq!(candidates(TaskAction::Edit), vec![0, 1, 3]);
+        assert_eq!(
+            Candidate::new(3, &tasks[0]).to_string(),
+            "3. write report [ongoing]"
+        );
+    }
+
+    #[test]
+    fn test_summarize_statuses() {
+        let mut ongoing = Task::new_immediate_task("a");
+        ongoing.update_status();
+        let mut complete = Task::new_immediate_task("b");
+        complete.complete();
+        let mut deleted = Task::new_backlog_task("c");
+        deleted.delete();
+        let backlog = Task::new_backlog_task("d");
+        let tasks = [&ongoing, &ongoing, &complete, &deleted, &backlog];
+        let tasks: Vec<(usize, &Task)> = tasks.into_iter().enumerate().collect();
+        assert_eq!(
+            summarize_statuses(&tasks),
+            "5 tasks: 2 ongoing, 1 complete, 1 backlog, 1 deleted"
+        );
+        assert_eq!(summarize_statuses(&tasks[..1]), "1 task: 1 ongoing");
+    }
+
+    #[test]
+    fn test_merged_task() {
+        let now = clock::now();
+        let mut first =
+            Task::new_planned_task("draft", now - Duration::hours(3), now - Duration::hours(2));
+        first.tags = vec!["work".to_string()];
+        first.priority = 1;
+        first.start();
+        first.complete();
+        let mut second =
+            Task::new_planned_task("review", now - Duration::hours(1), now + Duration::hours(1));
+        second.tags = vec!["work".to_string(), "docs".to_string()];
+        second.priority = 2;
+        second.notes = "see the comments".to_string();
+
+        let merged = merged_task(&first, &second);
+        assert_eq!(merged.description, "draft + review");
+        assert_eq!(merged.planned_start, first.planned_start);
+        assert_eq!(merged.planned_complete, second.planned_complete);
+        assert_eq!(merged.actual_start, first.actual_start);
+        // the second part isn't done yet
+        assert_eq!(merged.actual_complete, None);
+        assert_eq!(merged.status, TaskStatus::Paused);
+        assert_eq!(merged.tags, ["work", "docs"]);
+        assert_eq!(merged.priority, 2);
+        assert_eq!(merged.notes, "see the comments");
+
+        second.start();
+        second.complete();
+        let merged = merged_task(&first, &second);
+        assert_eq!(merged.actual_complete, second.actual_complete);
+        assert_eq!(merged.status, TaskStatus::Complete);
+    }
+
+    #[test]
+    fn test_split_window() {
+        let start = clock::now();
+        let complete = start + Duration::hours(2);
+        let hour_later = start + Duration::hours(1);
+        assert_eq!(
+            split_window(start, complete, hour_later),
+            ((start, hour_later), (hour_later, complete))
+        );
+        // an expired window is all done, and one not started yet is all left
+        assert_eq!(
+            split_window(start, complete, complete + Duration::hours(1)),
+            ((start, complete), (complete, complete))
+        );
+        assert_eq!(
+            split_window(start, complete, start - Duration::hours(1)),
+            ((start, start), (start, complete))
+        );
+    }
+
+    #[test]
+    fn test_parse_split_percent() {
+        assert_eq!(parse_split_percent("60"), Ok(60));
+        assert_eq!(parse_split_percent(" 25 % "), Ok(25));
+        assert!(parse_split_percent("0").is_err());
+        assert!(parse_split_percent("100").is_err());
+        assert!(parse_split_percent("half").is_err());
+    }
+
+    #[test]
+    fn test_pick_next_task() {
+        let now = clock::now();
+        let planned = |description, hours: i64| {
+            let start = now + Duration::hours(hours);
+            Task::new_planned_task(description, start, start + Duration::minutes(30))
+        };
+        let backlog = |description, days: i64| {
+            let mut task = Task::new_backlog_task(description);
+            task.created_at = now - Duration::days(days);
+            task
+        };
+        let mut tasks = vec![
+            backlog("newer backlog", 1),
+            backlog("older backlog", 3),
+            planned("far ahead", 5),
+            planned("soon", 1),
+        ];
+        let update = |tasks: &mut Vec<Task>| tasks.iter_mut().for_each(Task::update_status);
+        update(&mut tasks);
+        assert_eq!(pick_next_task(&tasks, now), Some(3));
+        // overdue tasks come before planned ones
+        tasks.push(planned("missed", -1));
+        update(&mut tasks);
+        assert_eq!(pick_next_task(&tasks, now), Some(4));
+        tasks[4].delete();
+        tasks[3].complete();
+        assert_eq!(pick_next_task(&tasks, now), Some(1));
+        assert_eq!(pick_next_task(&tasks[2..3], now), None);
+    }
+
+    #[test]
+    fn test_page_range() {
+        assert_eq!(page_range(120, 20, 1), 0..20);
+        assert_eq!(page_range(120, 20, 6), 100..120);
+        assert_eq!(page_range(45, 20, 3), 40..45);
+        assert!(page_range(45, 20, 4).is_empty());
+        assert_eq!(page_range(5, 20, 1), 0..5);
+        assert!(page_range(0, 20, 1).is_empty());
+    }
+
+    #[test]
+    fn test_digest_of_today() {
+        let today = clock::today();
+        let at = |days: i64, hour: u32| {
+            let date = today + Duration::days(days);
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+                .earliest()
+                .unwrap()
+        };
+        let mut tasks = vec![
+            Task::new_planned_task("yesterday", at(-1, 9), at(-1, 10)),
+            Task::new_planned_task("later today", at(0, 23), at(0, 23) + Duration::minutes(30)),
+            Task::new_planned_task("tomorrow", at(1, 9), at(1, 10)),
+            Task::new_immediate_task("ongoing"),
+            Task::new_backlog_task("backlog"),
+            Task::new_backlog_task("another backlog"),
+        ];
+        let mut deleted = Task::new_backlog_task("deleted");
+        deleted.delete();
+        tasks.push(deleted);
+        tasks.iter_mut().for_each(Task::update_status);
+        let digest = digest_of_today(&tasks, today);
+        // the task later today may have already started to be overdue just before midnight
+        assert!(digest.starts_with("1 overdue, 1 ongoing, "));
+        assert!(digest.ends_with("2 in backlog"));
+        assert_eq!(digest_of_today(&[], today), "nothing to do today");
     }
 }
@@ -0,0 +1,130 @@
+use crate::display::humanize_duration;
+use crate::task::{Task, TaskStatus};
+use chrono::{DateTime, Duration, Local, NaiveDate};
+
+// the plan of the date as a checklist ordered by start, for pasting into chats; deleted tasks
+// are left out, and backlog tasks get their own section if asked for
+pub fn plan_to_markdown(
+    tasks: &[Task],
+    date: NaiveDate,
+    include_backlog: bool,
+    redact: bool,
+) -> (String, usize) {
+    let mut planned: Vec<(&Task, DateTime<Local>)> = tasks
+        .iter()
+        .filter(|task| !task.is_deleted)
+        .filter_map(|task| Some((task, task.planned_start.or(task.actual_start)?)))
+        .filter(|(_, start)| start.date_naive() == date)
+        .collect();
+    planned.sort_by_key(|(_, start)| *start);
+    let mut lines = vec![format!("## {date}"), String::new()];
+    let mut planned_duration = Duration::zero();
+    for (task, start) in &planned {
+        let complete = match task.planned_start {
+            Some(_) => task.planned_complete,
+            None => task.actual_complete,
+        };
+        if let (Some(_), Some(complete)) = (task.planned_start, task.planned_complete) {
+            planned_duration = planned_duration + (complete - *start);
+        }
+        lines.push(format!(
+            "- {} {}–{} {}",
+            checkbox(task),
+            start.format("%H:%M"),
+            complete.map_or(String::new(), |complete| complete
+                .format("%H:%M")
+                .to_string()),
+            task.shared_description(redact)
+        ));
+    }
+    let done = planned
+        .iter()
+        .filter(|(task, _)| task.status == TaskStatus::Complete)
+        .count();
+    if !planned.is_empty() {
+        lines.push(String::new());
+    }
+    lines.push(format!(
+        "{} tasks, {done} done, {} planned",
+        planned.len(),
+        humanize_duration(planned_duration)
+    ));
+    let mut exported = planned.len();
+    if include_backlog {
+        let backlog: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| !task.is_deleted && task.status == TaskStatus::Backlog)
+            .collect();
+        lines.push(String::new());
+        lines.push("### Backlog".to_string());
+        lines.push(String::new());
+        for task in &backlog {
+            lines.push(format!("- [ ] {}", task.shared_description(redact)));
+        }
+        exported += backlog.len();
+    }
+    let mut markdown = lines.join("\n");
+    markdown.push('\n');
+    (markdown, exported)
+}
+
+fn checkbox(task: &Task) -> &'static str {
+    match task.status {
+        TaskStatus::Complete => "[x]",
+        _ => "[ ]",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::at;
+
+    #[test]
+    fn test_plan_to_markdown() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let mut review = Task::new_planned_task("review", at(4, 14), at(4, 15));
+        review.is_private = true;
+        let mut standup = Task::new_planned_task("standup", at(4, 9), at(4, 10));
+        standup.actual_start = Some(at(4, 9));
+        standup.actual_complete = Some(at(4, 10));
+        standup.status = TaskStatus::Complete;
+        let mut hotfix = Task::new_immediate_task("hotfix");
+        hotfix.actual_start = Some(at(4, 11));
+        hotfix.work_intervals.clear();
+        let mut deleted = Task::new_planned_task("deleted", at(4, 8), at(4, 9));
+        deleted.delete();
+        let tasks = [
+            review,
+            Task::new_planned_task("tomorrow", at(5, 9), at(5, 10)),
+            standup,
+            Task::new_backlog_task("read"),
+            hotfix,
+            deleted,
+        ];
+
+        let (markdown, exported) = plan_to_markdown(&tasks, date, false, true);
+        assert_eq!(exported, 3);
+        assert_eq!(
+            markdown,
+            "## 2024-03-04\n\
+             \n\
+             - [x] 09:00–10:00 standup\n\
+             - [ ] 11:00– hotfix\n\
+             - [ ] 14:00–15:00 private task\n\
+             \n\
+             3 tasks, 1 done, 2 hours planned\n"
+        );
+
+        let (markdown, exported) = plan_to_markdown(&tasks, date, true, false);
+        assert_eq!(exported, 4);
+        assert!(markdown.contains("- [ ] 14:00–15:00 review\n"));
+        assert!(markdown.ends_with("### Backlog\n\n- [ ] read\n"));
+
+        let empty = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+        assert_eq!(
+            plan_to_markdown(&tasks, empty, false, false).0,
+            "## 2024-03-06\n\n0 tasks, 0 done, 0 minutes planned\n"
+        );
+    }
+}
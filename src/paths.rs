@@ -0,0 +1,67 @@
+use crate::error::{ArentaError, ArentaResult};
+use std::env;
+use std::ffi::OsString;
+use std::io;
+use std::path::PathBuf;
+
+// where arenta keeps its data, resolved here only so that tests can redirect all of it with
+// `ARENTA_HOME`
+pub fn data_dir() -> ArentaResult<PathBuf> {
+    data_dir_from(arenta_home(), dirs::data_dir())
+}
+
+// set by tests and the like, which trumps the platform data directory and the config
+pub fn arenta_home() -> Option<PathBuf> {
+    non_empty(env::var_os("ARENTA_HOME"))
+}
+
+// the home directory older versions kept their data in, which is left alone with
+// `ARENTA_HOME` set
+pub fn legacy_home() -> Option<PathBuf> {
+    match arenta_home() {
+        Some(_) => None,
+        None => dirs::home_dir(),
+    }
+}
+
+fn non_empty(value: Option<OsString>) -> Option<PathBuf> {
+    value.filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+// e.g. `$XDG_DATA_HOME/arenta`, falling back to `~/.local/share/arenta` on linux
+fn data_dir_from(
+    arenta_home: Option<PathBuf>,
+    platform_data_dir: Option<PathBuf>,
+) -> ArentaResult<PathBuf> {
+    match (arenta_home, platform_data_dir) {
+        (Some(arenta_home), _) => Ok(arenta_home),
+        (None, Some(platform_data_dir)) => Ok(platform_data_dir.join("arenta")),
+        (None, None) => Err(ArentaError::io(
+            "cannot find the data directory",
+            io::ErrorKind::NotFound.into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_dir_from() {
+        let share = PathBuf::from("/home/me/.local/share");
+        assert_eq!(
+            data_dir_from(None, Some(share.clone())).unwrap(),
+            share.join("arenta")
+        );
+        assert_eq!(
+            data_dir_from(Some(PathBuf::from("/tmp/a")), Some(share)).unwrap(),
+            PathBuf::from("/tmp/a")
+        );
+        assert!(matches!(
+            data_dir_from(None, None),
+            Err(ArentaError::Io { .. })
+        ));
+        assert_eq!(non_empty(Some(OsString::new())), None);
+    }
+}
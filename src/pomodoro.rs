@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+pub const DEFAULT_MINUTES: u32 = 25;
+
+// `mm:ss`, rounded up so that `00:00` is only shown at the end
+pub fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_millis().div_ceil(1000);
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(25 * 60)), "25:00");
+        assert_eq!(format_remaining(Duration::from_millis(59_001)), "01:00");
+        assert_eq!(format_remaining(Duration::from_millis(400)), "00:01");
+        assert_eq!(format_remaining(Duration::ZERO), "00:00");
+        assert_eq!(format_remaining(Duration::from_secs(120 * 60)), "120:00");
+    }
+}
@@ -0,0 +1,248 @@
+use crate::config::Config;
+use crate::error::{ArentaError, ArentaResult};
+use crate::lock::Lock;
+use crate::manager::get_sibling_file_path;
+use crate::paths;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+const PROFILE_NAME_MAX_LEN: usize = 32;
+
+// files kept next to the data file, which are copied along with it into the default profile
+const SIBLING_SUFFIXES: [&str; 7] = [
+    "journal",
+    "archive",
+    "history",
+    "log",
+    "templates",
+    "corrupt",
+    "maintained",
+];
+
+// a separate task list, kept in `<data dir>/<name>.csv` with its own lock file
+pub struct Profile {
+    // none if `data_file` is set in the config, which leaves no other profiles
+    pub name: Option<String>,
+    pub data_file: PathBuf,
+}
+
+impl Profile {
+    pub fn resolve(config: &Config, name: &str) -> ArentaResult<Self> {
+        validate_name(name)?;
+        if let (Some(data_file), None) = (&config.data_file, paths::arenta_home()) {
+            if name != DEFAULT_PROFILE {
+                return Err(ArentaError::validation(
+                    "profiles are not available while `data_file` is set in the config",
+                ));
+            }
+            return Ok(Profile {
+                name: None,
+                data_file: data_file.clone(),
+            });
+        }
+        Ok(Profile {
+            name: Some(name.to_string()),
+            data_file: data_file_path_in(&paths::data_dir()?, paths::legacy_home(), name)?,
+        })
+    }
+
+    pub fn lock_file(&self) -> PathBuf {
+        lock_file_path(&self.data_file)
+    }
+}
+
+// e.g. `work.csv` is guarded by `work.lock` next to it, and a legacy `~/.arenta` by
+// `~/.arenta.lock`
+pub fn lock_file_path(data_file: &Path) -> PathBuf {
+    data_file.with_extension("lock")
+}
+
+// names end up in file names, so only letters, digits, `-` and `_` are allowed, which rules
+// out separators and `..`
+pub fn validate_name(name: &str) -> ArentaResult<()> {
+    let is_valid = !name.is_empty()
+        && name.len() <= PROFILE_NAME_MAX_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    match is_valid {
+        true => Ok(()),
+        false => Err(ArentaError::validation(format!(
+            "invalid profile name `{name}`, use up to {PROFILE_NAME_MAX_LEN} letters, digits, `-` and `_`"
+        ))),
+    }
+}
+
+// the profiles with a data file in the directory, sorted by name
+pub fn list_profiles(data_file: &Path) -> Vec<String> {
+    let Some(dir) = data_file.parent() else {
+        return vec![];
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_stem()?.to_str()?.to_string();
+            let is_profile = path.extension()? == "csv" && validate_name(&name).is_ok();
+            is_profile.then_some(name)
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn data_file_path_in(
+    data_dir: &Path,
+    legacy_home: Option<PathBuf>,
+    name: &str,
+) -> ArentaResult<PathBuf> {
+    if let Some(home) = legacy_home {
+        if !migrate_legacy_file(&home, data_dir)? {
+            // an older session still works on it, so it's used as it is for now
+            let legacy = home.join(".arenta");
+            return match name == DEFAULT_PROFILE {
+                true => Ok(legacy),
+                false => Err(ArentaError::validation(format!(
+                    "profiles are set up once the session holding {} is closed",
+                    lock_file_path(&legacy).display()
+                ))),
+            };
+        }
+    }
+    let data_file = data_dir.join(format!("{name}.csv"));
+    // an empty data file holds no tasks, but lists the profile from now on
+    fs::create_dir_all(data_dir)
+        .and_then(|_| {
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&data_file)
+        })
+        .map_err(|e| ArentaError::io("failed to create the profile", e))?;
+    Ok(data_file)
+}
+
+// copies `~/.arenta` written by an older version into the default profile, the files next
+// to it first and the data file last, then leaves a breadcrumb behind telling where it went;
+// files already in the data directory are never overwritten, so an interrupted migration is
+// simply done again. returns false without copying anything if an older session holds the
+// lock
+fn migrate_legacy_file(home: &Path, data_dir: &Path) -> ArentaResult<bool> {
+    let legacy = home.join(".arenta");
+    let breadcrumb = home.join(".arenta.moved");
+    if !legacy.is_file() || breadcrumb.exists() {
+        return Ok(true);
+    }
+    let lock = match Lock::acquire(&lock_file_path(&legacy)) {
+        Ok(lock) => lock,
+        Err(ArentaError::Locked { .. }) => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let data_file = data_dir.join(format!("{DEFAULT_PROFILE}.csv"));
+    let copied = fs::create_dir_all(data_dir)
+        .and_then(|_| {
+            SIBLING_SUFFIXES
+                .iter()
+                .map(|suffix| {
+                    (
+                        get_sibling_file_path(&legacy, suffix),
+                        get_sibling_file_path(&data_file, suffix),
+                    )
+                })
+                .chain(iter::once((legacy.clone(), data_file.clone())))
+                .filter(|(from, to)| from.exists() && !to.exists())
+                .try_for_each(|(from, to)| fs::copy(from, to).map(|_| ()))
+        })
+        .and_then(|_| {
+            fs::write(
+                &breadcrumb,
+                format!(
+                    "arenta keeps its data in {} now, ~/.arenta is no longer used\n",
+                    data_dir.display()
+                ),
+            )
+        });
+    copied.map_err(|e| ArentaError::io("failed to copy ~/.arenta into the data directory", e))?;
+    lock.release()
+        .map_err(|e| ArentaError::io("failed to release the lock file", e))?;
+    // on stderr, as stdout may be piped, e.g. from `ls --json`
+    eprintln!(
+        "tasks copied into the `{DEFAULT_PROFILE}` profile, {}",
+        data_file.display()
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("side-project_2").is_ok());
+        for name in ["", "..", "../work", "a/b", "work.csv", "~", &"x".repeat(33)] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_legacy_file_is_migrated() {
+        let home = temp_dir("migrate");
+        let data_dir = home.join(".local/share/arenta");
+        fs::write(home.join(".arenta"), "a,,,,,false\n").unwrap();
+        fs::write(home.join(".arenta.journal"), "base,1\n").unwrap();
+        fs::write(home.join(".arenta.hooks"), "").unwrap();
+
+        let data_file = data_file_path_in(&data_dir, Some(home.clone()), "work").unwrap();
+        assert_eq!(data_file, data_dir.join("work.csv"));
+        assert_eq!(
+            fs::read_to_string(data_dir.join("default.csv")).unwrap(),
+            "a,,,,,false\n"
+        );
+        assert!(data_dir.join("default.csv.journal").exists());
+        // the originals are left behind, along with the breadcrumb
+        assert!(home.join(".arenta.journal").exists());
+        assert!(home.join(".arenta.moved").exists());
+        assert!(!home.join(".arenta.lock").exists());
+        // not a sibling of the data file
+        assert!(!data_dir.join("default.csv.hooks").exists());
+
+        // never copied again, even if changed meanwhile
+        fs::write(home.join(".arenta"), "b,,,,,false\n").unwrap();
+        assert_eq!(
+            data_file_path_in(&data_dir, Some(home.clone()), DEFAULT_PROFILE).unwrap(),
+            data_dir.join("default.csv")
+        );
+        assert_eq!(
+            fs::read_to_string(data_dir.join("default.csv")).unwrap(),
+            "a,,,,,false\n"
+        );
+        assert_eq!(list_profiles(&data_file), vec!["default", "work"]);
+        assert_eq!(lock_file_path(&data_file), data_dir.join("work.lock"));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn test_legacy_file_held_by_older_session_is_kept() {
+        let home = temp_dir("legacy-held");
+        let data_dir = home.join("data");
+        fs::write(home.join(".arenta"), "a,,,,,false\n").unwrap();
+        let lock = Lock::acquire(&home.join(".arenta.lock")).unwrap();
+        assert_eq!(
+            data_file_path_in(&data_dir, Some(home.clone()), DEFAULT_PROFILE).unwrap(),
+            home.join(".arenta")
+        );
+        assert!(data_file_path_in(&data_dir, Some(home.clone()), "work").is_err());
+        assert!(!data_dir.exists());
+        lock.release().unwrap();
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
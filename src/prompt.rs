@@ -0,0 +1,157 @@
+use crate::error::{ArentaError, ArentaResult, PromptError};
+use crate::task::Task;
+use chrono::NaiveDate;
+use std::thread;
+use std::time::Duration;
+
+// everything a manager waits for the user for, which the terminal frontend answers with
+// inquire and a gui with dialogs; implementations should wait inside `timing::waiting`, so
+// that `timings` leaves the time out
+pub trait Prompter {
+    // the next line of an interactive session, which may be completed from the commands, the
+    // tasks and the lines typed before
+    fn command_line(
+        &mut self,
+        prompt: &str,
+        tasks: &[Task],
+        history: &[String],
+    ) -> ArentaResult<String>;
+
+    fn text(&mut self, prompt: TextPrompt) -> ArentaResult<String>;
+
+    fn confirm(&mut self, message: &str, default: bool) -> ArentaResult<bool>;
+
+    // returns the index of the option picked
+    fn select(&mut self, prompt: SelectPrompt) -> ArentaResult<usize>;
+
+    fn date(&mut self, message: &str) -> ArentaResult<NaiveDate>;
+
+    // counts down, returns whether it ran to the end rather than being stopped
+    fn count_down(&mut self, duration: Duration, label: &str) -> bool;
+
+    // lines typed ahead of the prompt, which is what a multi-line paste leaves behind after
+    // the first line has been submitted
+    fn pending_lines(&mut self) -> Vec<String> {
+        vec![]
+    }
+
+    // columns the timeline may fill, none to keep its default width
+    fn width(&self) -> Option<usize> {
+        None
+    }
+}
+
+pub type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+pub type Formatter = Box<dyn Fn(&str) -> String>;
+
+// a line of text, e.g. `description:`
+pub struct TextPrompt {
+    pub message: String,
+    pub help: Option<String>,
+    // shown dimmed while nothing is typed, e.g. the value being edited
+    pub placeholder: Option<String>,
+    // the prompt is answered again while the input is rejected with the reason
+    pub validator: Option<Validator>,
+    // what the answer is echoed as once it's submitted, e.g. the date a filter resolves to
+    pub formatter: Option<Formatter>,
+}
+
+impl TextPrompt {
+    pub fn new(message: &str) -> Self {
+        TextPrompt {
+            message: message.to_string(),
+            help: None,
+            placeholder: None,
+            validator: None,
+            formatter: None,
+        }
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    pub fn with_formatter(mut self, formatter: impl Fn(&str) -> String + 'static) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+}
+
+// one of a few options, e.g. `how to arrange this task`
+pub struct SelectPrompt {
+    pub message: String,
+    pub options: Vec<String>,
+    pub starting_cursor: usize,
+    pub help: Option<String>,
+}
+
+impl SelectPrompt {
+    pub fn new<T: ToString>(message: &str, options: &[T]) -> Self {
+        SelectPrompt {
+            message: message.to_string(),
+            options: options.iter().map(ToString::to_string).collect(),
+            starting_cursor: 0,
+            help: None,
+        }
+    }
+
+    pub fn with_starting_cursor(mut self, starting_cursor: usize) -> Self {
+        self.starting_cursor = starting_cursor;
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+}
+
+// answers nothing, for a manager nobody sits in front of, e.g. in tests or a program built on
+// the library; commands needing an answer fail as if stdin weren't a terminal
+pub struct NoPrompter;
+
+impl Prompter for NoPrompter {
+    fn command_line(&mut self, _: &str, _: &[Task], _: &[String]) -> ArentaResult<String> {
+        Err(no_answer())
+    }
+
+    fn text(&mut self, _: TextPrompt) -> ArentaResult<String> {
+        Err(no_answer())
+    }
+
+    fn confirm(&mut self, _: &str, _: bool) -> ArentaResult<bool> {
+        Err(no_answer())
+    }
+
+    fn select(&mut self, _: SelectPrompt) -> ArentaResult<usize> {
+        Err(no_answer())
+    }
+
+    fn date(&mut self, _: &str) -> ArentaResult<NaiveDate> {
+        Err(no_answer())
+    }
+
+    // nobody can stop it
+    fn count_down(&mut self, duration: Duration, _: &str) -> bool {
+        thread::sleep(duration);
+        true
+    }
+}
+
+fn no_answer() -> ArentaError {
+    ArentaError::Prompt(PromptError::NotTty)
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::PathBuf;
+
+pub const PROMPT_HISTORY_MAX_ENTRIES: usize = 500;
+
+// commands typed at the prompt, the latest last, kept across sessions
+pub struct PromptHistory {
+    path: PathBuf,
+    entries: Vec<String>,
+}
+
+impl PromptHistory {
+    // a missing or unreadable file is taken as an empty history
+    pub fn load(path: PathBuf) -> Self {
+        let entries = fs::read_to_string(&path)
+            .map(|content| content.lines().map(String::from).collect())
+            .unwrap_or_default();
+        PromptHistory { path, entries }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    // repeating the latest command adds nothing, and the oldest ones are dropped over the limit
+    pub fn push(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        let excess = self
+            .entries
+            .len()
+            .saturating_sub(PROMPT_HISTORY_MAX_ENTRIES);
+        self.entries.drain(..excess);
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        fs::write(&self.path, content)
+            .map_err(|e| format!("failed to save {}: {e}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_reload() {
+        let path = std::env::temp_dir().join(format!("arenta-prompt-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut history = PromptHistory::load(path.clone());
+        assert!(history.entries().is_empty());
+        for line in ["ls", "ls", " ", "c 1", "ls"] {
+            history.push(line);
+        }
+        assert_eq!(history.entries(), ["ls", "c 1", "ls"]);
+        history.save().unwrap();
+        assert_eq!(
+            PromptHistory::load(path.clone()).entries(),
+            history.entries()
+        );
+
+        for index in 0..PROMPT_HISTORY_MAX_ENTRIES {
+            history.push(&format!("s {index}"));
+        }
+        assert_eq!(history.entries().len(), PROMPT_HISTORY_MAX_ENTRIES);
+        assert_eq!(history.entries()[0], "s 0");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+use crate::task::{Task, TaskStatus};
+use chrono::{DateTime, Local, NaiveDate};
+use colored::Colorize;
+
+#[derive(Debug, PartialEq)]
+pub struct DayReport {
+    pub date: NaiveDate,
+    pub completed: usize,
+    pub overdue: usize,
+    pub actual_minutes: i64,
+    pub planned_minutes: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub days: Vec<DayReport>,
+    // index of the task and how many minutes it took longer than planned
+    pub biggest_overrun: Option<(usize, i64)>,
+}
+
+fn minutes_between(start: Option<DateTime<Local>>, end: Option<DateTime<Local>>) -> i64 {
+    match (start, end) {
+        (Some(start), Some(end)) => (end - start).num_minutes().max(0),
+        _ => 0,
+    }
+}
+
+// a task is attributed to the day it actually started, or was planned to start,
+// even if it spans midnight
+fn date_of(task: &Task) -> Option<NaiveDate> {
+    task.actual_start
+        .or(task.planned_start)
+        .map(|dt| dt.date_naive())
+}
+
+pub fn build_report(tasks: &[Task], first: NaiveDate, last: NaiveDate) -> Report {
+    let mut report = Report {
+        days: first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .map(|date| DayReport {
+                date,
+                completed: 0,
+                overdue: 0,
+                actual_minutes: 0,
+                planned_minutes: 0,
+            })
+            .collect(),
+        biggest_overrun: None,
+    };
+    for (index, task) in tasks.iter().enumerate() {
+        if task.is_deleted {
+            continue;
+        }
+        let Some(day) =
+            date_of(task).and_then(|date| report.days.iter_mut().find(|day| day.date == date))
+        else {
+            continue;
+        };
+        match task.status {
+            TaskStatus::Complete => day.completed += 1,
+            TaskStatus::Overdue => day.overdue += 1,
+            _ => {}
+        }
+        let actual_minutes = match task.actual_complete {
+            Some(complete) => task.worked_duration(complete).num_minutes(),
+            None => 0,
+        };
+        let planned_minutes = minutes_between(task.planned_start, task.planned_complete);
+        day.actual_minutes += actual_minutes;
+        day.planned_minutes += planned_minutes;
+        if task.actual_complete.is_some() && task.planned_start.is_some() {
+            let overrun = actual_minutes - planned_minutes;
+            if overrun > 0 && report.biggest_overrun.is_none_or(|(_, max)| overrun > max) {
+                report.biggest_overrun = Some((index, overrun));
+            }
+        }
+    }
+    report
+}
+
+pub fn format_minutes(minutes: i64) -> String {
+    format!("{}h{:02}m", minutes / 60, minutes % 60)
+}
+
+impl Report {
+    pub fn render(&self, tasks: &[Task]) {
+        println!(
+            "{: <18}{: >6}{: >9}{: >10}{: >10}",
+            "date", "done", "overdue", "actual", "planned"
+        );
+        println!("{}", "-".repeat(53));
+        let render_row = |label: &str, completed: usize, overdue: usize, actual, planned| {
+            println!(
+                "{: <18}{}{}{: >10}{: >10}",
+                label,
+                format!("{completed: >6}").color(TaskStatus::Complete.color()),
+                format!("{overdue: >9}").color(TaskStatus::Overdue.color()),
+                format_minutes(actual),
+                format_minutes(planned)
+            );
+        };
+        self.days.iter().for_each(|day| {
+            render_row(
+                &day.date.format("%F (%a)").to_string(),
+                day.completed,
+                day.overdue,
+                day.actual_minutes,
+                day.planned_minutes,
+            )
+        });
+        if self.days.len() > 1 {
+            println!("{}", "-".repeat(53));
+            render_row(
+                "total",
+                self.days.iter().map(|day| day.completed).sum(),
+                self.days.iter().map(|day| day.overdue).sum(),
+                self.days.iter().map(|day| day.actual_minutes).sum(),
+                self.days.iter().map(|day| day.planned_minutes).sum(),
+            );
+        }
+        if let Some((index, overrun)) = self.biggest_overrun {
+            println!(
+                "biggest overrun: {} longer than planned, {index}. {}",
+                format_minutes(overrun).color(TaskStatus::Overdue.color()),
+                tasks[index].description
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDateTime, TimeZone};
+
+    fn at(datetime: &str) -> Option<DateTime<Local>> {
+        let datetime = NaiveDateTime::parse_from_str(datetime, "%F %R").unwrap();
+        Local.from_local_datetime(&datetime).single()
+    }
+
+    fn task(
+        status: TaskStatus,
+        planned: Option<(&str, &str)>,
+        actual: (Option<&str>, Option<&str>),
+    ) -> Task {
+        let mut task = Task::new_backlog_task("task");
+        task.status = status;
+        task.planned_start = planned.and_then(|(start, _)| at(start));
+        task.planned_complete = planned.and_then(|(_, end)| at(end));
+        task.actual_start = actual.0.and_then(at);
+        task.actual_complete = actual.1.and_then(at);
+        task
+    }
+
+    #[test]
+    fn test_build_report() {
+        let tasks = vec![
+            task(
+                TaskStatus::Complete,
+                Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+                (Some("2024-03-04 09:00"), Some("2024-03-04 10:30")),
+            ),
+            // spans midnight, attributed to the day it started
+            task(
+                TaskStatus::Complete,
+                None,
+                (Some("2024-03-05 23:00"), Some("2024-03-06 01:00")),
+            ),
+            task(
+                TaskStatus::Overdue,
+                Some(("2024-03-05 14:00", "2024-03-05 15:00")),
+                (None, None),
+            ),
+            // completed without ever being started
+            task(
+                TaskStatus::Complete,
+                Some(("2024-03-06 09:00", "2024-03-06 09:30")),
+                (None, Some("2024-03-06 10:00")),
+            ),
+            task(
+                TaskStatus::Complete,
+                Some(("2024-03-11 09:00", "2024-03-11 10:00")),
+                (Some("2024-03-11 09:00"), Some("2024-03-11 12:00")),
+            ),
+        ];
+        let first = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let last = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let report = build_report(&tasks, first, last);
+        assert_eq!(report.days.len(), 7);
+        assert_eq!(
+            report.days[0],
+            DayReport {
+                date: first,
+                completed: 1,
+                overdue: 0,
+                actual_minutes: 90,
+                planned_minutes: 60,
+            }
+        );
+        assert_eq!(
+            report.days[1],
+            DayReport {
+                date: NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+                completed: 1,
+                overdue: 1,
+                actual_minutes: 120,
+                planned_minutes: 60,
+            }
+        );
+        assert_eq!(
+            report.days[2],
+            DayReport {
+                date: NaiveDate::from_ymd_opt(2024, 3, 6).unwrap(),
+                completed: 1,
+                overdue: 0,
+                actual_minutes: 0,
+                planned_minutes: 30,
+            }
+        );
+        assert_eq!(report.biggest_overrun, Some((0, 30)));
+    }
+
+    #[test]
+    fn test_format_minutes() {
+        assert_eq!(format_minutes(0), "0h00m");
+        assert_eq!(format_minutes(95), "1h35m");
+        assert_eq!(format_minutes(600), "10h00m");
+    }
+}
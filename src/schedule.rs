@@ -0,0 +1,136 @@
+use chrono::{DateTime, Duration, Local};
+
+// from the start to the end, which is never earlier
+pub type Interval = (DateTime<Local>, DateTime<Local>);
+
+// the gaps within the window which no busy interval covers, the earliest first; busy
+// intervals may overlap each other or stick out of the window
+pub fn free_slots(window: Interval, busy: &[Interval]) -> Vec<Interval> {
+    let mut busy: Vec<Interval> = busy
+        .iter()
+        .filter(|(start, end)| start < end)
+        .copied()
+        .collect();
+    busy.sort();
+    let mut slots = vec![];
+    let mut cursor = window.0;
+    for (start, end) in busy {
+        if start >= window.1 {
+            break;
+        }
+        if start > cursor {
+            slots.push((cursor, start));
+        }
+        cursor = cursor.max(end);
+    }
+    if cursor < window.1 {
+        slots.push((cursor, window.1));
+    }
+    slots
+}
+
+// assigns every duration in order the start of the earliest slot long enough for it, which
+// is shrunk or removed then; a duration fitting no slot gets none, while later shorter ones
+// may still fit
+pub fn pack_into_slots(slots: &mut Vec<Interval>, durations: &[Duration]) -> Vec<Option<Interval>> {
+    durations
+        .iter()
+        .map(|duration| {
+            let position = slots
+                .iter()
+                .position(|(start, end)| *end - *start >= *duration)?;
+            let start = slots[position].0;
+            slots[position].0 = start + *duration;
+            if slots[position].0 == slots[position].1 {
+                slots.remove(position);
+            }
+            Some((start, start + *duration))
+        })
+        .collect()
+}
+
+// schedules start at whole multiples of 5 minutes
+pub fn round_up_to_five_minutes(time: DateTime<Local>) -> DateTime<Local> {
+    let time = time - Duration::nanoseconds(time.timestamp_subsec_nanos() as i64);
+    let seconds = time.timestamp();
+    time + Duration::seconds((seconds + 299).div_euclid(300) * 300 - seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_free_slots() {
+        let window = (at(9, 0), at(18, 0));
+        assert_eq!(free_slots(window, &[]), vec![window]);
+        assert_eq!(
+            free_slots(
+                window,
+                &[
+                    (at(13, 0), at(14, 0)),
+                    (at(8, 0), at(10, 0)),
+                    (at(13, 30), at(15, 0)),
+                    (at(17, 0), at(19, 0)),
+                ]
+            ),
+            vec![(at(10, 0), at(13, 0)), (at(15, 0), at(17, 0))]
+        );
+        // back to back, and entirely outside the window
+        assert_eq!(
+            free_slots(
+                window,
+                &[
+                    (at(9, 0), at(12, 0)),
+                    (at(12, 0), at(18, 0)),
+                    (at(19, 0), at(20, 0))
+                ]
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_pack_into_slots() {
+        let mut slots = vec![(at(10, 0), at(10, 30)), (at(13, 0), at(15, 0))];
+        let packed = pack_into_slots(
+            &mut slots,
+            &[
+                Duration::minutes(60),
+                Duration::minutes(30),
+                Duration::minutes(90),
+                Duration::minutes(60),
+            ],
+        );
+        assert_eq!(
+            packed,
+            vec![
+                Some((at(13, 0), at(14, 0))),
+                // the short slot skipped by the first one still fits this
+                Some((at(10, 0), at(10, 30))),
+                None,
+                Some((at(14, 0), at(15, 0))),
+            ]
+        );
+        assert!(slots.is_empty());
+        assert_eq!(
+            pack_into_slots(&mut slots, &[Duration::minutes(5)]),
+            vec![None]
+        );
+    }
+
+    #[test]
+    fn test_round_up_to_five_minutes() {
+        assert_eq!(round_up_to_five_minutes(at(9, 0)), at(9, 0));
+        assert_eq!(round_up_to_five_minutes(at(9, 1)), at(9, 5));
+        assert_eq!(
+            round_up_to_five_minutes(at(9, 57) + Duration::seconds(30)),
+            at(10, 0)
+        );
+    }
+}
@@ -0,0 +1,662 @@
+use crate::command::DateFilter;
+use crate::display::is_plain;
+use crate::report::format_minutes;
+use crate::task::{compare_date, Task, TaskStatus};
+use crate::working::WorkingHours;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
+use colored::{Color, Colorize};
+use csv::WriterBuilder;
+use unicode_width::UnicodeWidthStr;
+
+const WORST_OVERRUNS: usize = 5;
+pub const DEFAULT_HEATMAP_WEEKS: u32 = 12;
+// from none to the most, each cell is drawn as a char of the bucket followed by a space
+const HEATMAP_CELLS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const HEATMAP_COLORS: [Color; 4] = [
+    Color::TrueColor {
+        r: 14,
+        g: 68,
+        b: 41,
+    },
+    Color::TrueColor {
+        r: 0,
+        g: 109,
+        b: 50,
+    },
+    Color::TrueColor {
+        r: 38,
+        g: 166,
+        b: 65,
+    },
+    Color::TrueColor {
+        r: 57,
+        g: 211,
+        b: 83,
+    },
+];
+
+// started up to this many minutes after the planned start is on time, and up to the latter
+// is late; any later is very late
+const ON_TIME_MINUTES: i64 = 5;
+const LATE_MINUTES: i64 = 30;
+const PUNCTUALITY_BAR_WIDTH: usize = 30;
+const TAG_BAR_WIDTH: usize = 30;
+const UNTAGGED: &str = "(untagged)";
+
+#[derive(Debug, PartialEq)]
+pub struct EstimateStats {
+    pub count: usize,
+    // of the ratios of actual to planned durations
+    pub median: f64,
+    pub mean: f64,
+    // how many took no longer than planned
+    pub within_plan: usize,
+    // index of the task, the ratio and how many minutes it took longer than planned
+    pub worst_overruns: Vec<(usize, f64, i64)>,
+    // completed with a plan but never started, so how long they took is unknown
+    pub untracked: usize,
+}
+
+// over completed tasks having both a planned and an actual period, `None` if there's none;
+// tasks planned to take no time at all are left out, as there's no ratio to them
+pub fn estimate_stats(tasks: &[Task]) -> Option<EstimateStats> {
+    let mut estimates: Vec<(usize, f64, i64)> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| !task.is_deleted && task.status == TaskStatus::Complete)
+        .filter_map(|(index, task)| {
+            let planned = (task.planned_complete? - task.planned_start?).num_minutes();
+            let complete = task.actual_complete?;
+            task.actual_start?;
+            let actual = task.worked_duration(complete).num_minutes();
+            (planned > 0).then(|| (index, actual as f64 / planned as f64, actual - planned))
+        })
+        .collect();
+    if estimates.is_empty() {
+        return None;
+    }
+    let untracked = tasks
+        .iter()
+        .filter(|task| !task.is_deleted && task.planned_start.is_some() && task.is_untracked())
+        .count();
+    let mut ratios: Vec<f64> = estimates.iter().map(|(_, ratio, _)| *ratio).collect();
+    ratios.sort_by(f64::total_cmp);
+    let count = ratios.len();
+    let median = match count % 2 {
+        0 => (ratios[count / 2 - 1] + ratios[count / 2]) / 2.0,
+        _ => ratios[count / 2],
+    };
+    estimates.sort_by(|(_, a, _), (_, b, _)| b.total_cmp(a));
+    Some(EstimateStats {
+        count,
+        median,
+        mean: ratios.iter().sum::<f64>() / count as f64,
+        within_plan: ratios.iter().filter(|ratio| **ratio <= 1.0).count(),
+        worst_overruns: estimates
+            .into_iter()
+            .filter(|(_, ratio, _)| *ratio > 1.0)
+            .take(WORST_OVERRUNS)
+            .collect(),
+        untracked,
+    })
+}
+
+impl EstimateStats {
+    pub fn render(&self, tasks: &[Task]) {
+        println!("{: <14}{: >8}", "tasks", self.count);
+        println!("{: <14}{: >8}", "median", format!("{:.2}x", self.median));
+        println!("{: <14}{: >8}", "mean", format!("{:.2}x", self.mean));
+        println!(
+            "{: <14}{: >8}",
+            "within plan",
+            format!("{}%", self.within_plan * 100 / self.count)
+        );
+        if self.untracked > 0 {
+            println!("{: <14}{: >8}", "untracked", self.untracked);
+        }
+        if self.worst_overruns.is_empty() {
+            return;
+        }
+        println!("worst overruns");
+        for (index, ratio, overrun) in &self.worst_overruns {
+            println!(
+                "  {: >7}{: >8}  {index}. {}",
+                format!("{ratio:.2}x"),
+                format!("+{}", format_minutes(*overrun)).color(TaskStatus::Overdue.color()),
+                tasks[*index].description
+            );
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PunctualityStats {
+    // of tasks started, in minutes of actual starts behind planned ones, negative if early
+    pub mean_delay: i64,
+    pub median_delay: i64,
+    // early, on time, late and very late
+    pub buckets: [usize; 4],
+    // planned to start by now but never did
+    pub never_started: usize,
+    // completed without ever being started, so when they were started is unknown
+    pub untracked: usize,
+    // index of the task started the latest and its delay
+    pub worst: (usize, i64),
+}
+
+// over tasks planned to start on the dates of the filter, all of them if none; `None` if none
+// of them has been started; delays count only working time if working hours are given
+pub fn punctuality_stats(
+    tasks: &[Task],
+    filter: Option<DateFilter>,
+    now: DateTime<Local>,
+    working_hours: Option<&WorkingHours>,
+) -> Option<PunctualityStats> {
+    let planned: Vec<(usize, &Task)> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| !task.is_deleted && task.planned_start.is_some())
+        .filter(|(_, task)| {
+            filter.is_none_or(|DateFilter(op, date)| compare_date(&task.planned_start, op, &date))
+        })
+        .collect();
+    let mut delays: Vec<(usize, i64)> = planned
+        .iter()
+        .filter_map(|(index, task)| {
+            let (planned, actual) = (task.planned_start?, task.actual_start?);
+            let delay = match working_hours {
+                Some(working_hours) => working_hours.signed_duration_between(planned, actual),
+                None => actual - planned,
+            };
+            Some((*index, delay.num_minutes()))
+        })
+        .collect();
+    let untracked = planned
+        .iter()
+        .filter(|(_, task)| task.is_untracked())
+        .count();
+    let never_started = planned
+        .iter()
+        .filter(|(_, task)| task.actual_start.is_none() && !task.is_untracked())
+        .filter(|(_, task)| task.planned_start.unwrap() <= now)
+        .count();
+    if delays.is_empty() {
+        return None;
+    }
+    delays.sort_by_key(|(_, delay)| *delay);
+    let count = delays.len();
+    let median_delay = match count % 2 {
+        0 => (delays[count / 2 - 1].1 + delays[count / 2].1) / 2,
+        _ => delays[count / 2].1,
+    };
+    let mut buckets = [0; 4];
+    for (_, delay) in &delays {
+        buckets[punctuality_bucket(*delay)] += 1;
+    }
+    Some(PunctualityStats {
+        mean_delay: delays.iter().map(|(_, delay)| delay).sum::<i64>() / count as i64,
+        median_delay,
+        buckets,
+        never_started,
+        untracked,
+        worst: delays[count - 1],
+    })
+}
+
+fn punctuality_bucket(delay: i64) -> usize {
+    if delay < 0 {
+        0
+    } else if delay <= ON_TIME_MINUTES {
+        1
+    } else if delay <= LATE_MINUTES {
+        2
+    } else {
+        3
+    }
+}
+
+// e.g. `+1h05m`, or `-0h10m` if early
+fn format_delay(minutes: i64) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    format!("{sign}{}", format_minutes(minutes.abs()))
+}
+
+impl PunctualityStats {
+    pub fn render(&self, tasks: &[Task]) {
+        let started: usize = self.buckets.iter().sum();
+        println!("{: <14}{: >8}", "started", started);
+        println!("{: <14}{: >8}", "mean delay", format_delay(self.mean_delay));
+        println!(
+            "{: <14}{: >8}",
+            "median delay",
+            format_delay(self.median_delay)
+        );
+        let mut rows = vec![
+            ("early", self.buckets[0], TaskStatus::Planned),
+            ("on time", self.buckets[1], TaskStatus::Complete),
+            ("late", self.buckets[2], TaskStatus::Ongoing),
+            ("very late", self.buckets[3], TaskStatus::Overdue),
+            ("never started", self.never_started, TaskStatus::Backlog),
+        ];
+        // only tasks completed by hand are, so it's left out unless there's any
+        if self.untracked > 0 {
+            rows.push(("untracked", self.untracked, TaskStatus::Complete));
+        }
+        let total = started + self.never_started + self.untracked;
+        for (label, count, status) in rows {
+            let bar = "█".repeat((count * PUNCTUALITY_BAR_WIDTH).div_ceil(total));
+            let bar = match is_plain() {
+                true => bar,
+                false => bar.color(status.color()).to_string(),
+            };
+            println!(
+                "{label: <14}{count: >8}  {: >3}%  {bar}",
+                count * 100 / total
+            );
+        }
+        let (index, delay) = self.worst;
+        if delay > ON_TIME_MINUTES {
+            println!(
+                "latest start  {: >8}  {index}. {}",
+                format_delay(delay).color(TaskStatus::Overdue.color()),
+                tasks[index].description
+            );
+        }
+    }
+}
+
+// minutes worked per tag over tasks started on the dates of the filter, all of them if none,
+// the most first; the time of a task with several tags is split evenly across them, and
+// ongoing tasks count until now
+pub fn time_per_tag(
+    tasks: &[Task],
+    filter: Option<DateFilter>,
+    now: DateTime<Local>,
+) -> Vec<(String, f64)> {
+    let mut times: Vec<(String, f64)> = vec![];
+    for task in tasks.iter().filter(|task| !task.is_deleted) {
+        if task.actual_start.is_none()
+            || filter
+                .is_some_and(|DateFilter(op, date)| !compare_date(&task.actual_start, op, &date))
+        {
+            continue;
+        }
+        let minutes = task.worked_duration(now).num_minutes() as f64;
+        let tags: Vec<&str> = match task.tags.is_empty() {
+            true => vec![UNTAGGED],
+            false => task.tags.iter().map(String::as_str).collect(),
+        };
+        for tag in &tags {
+            let share = minutes / tags.len() as f64;
+            match times.iter_mut().find(|(name, _)| name == tag) {
+                Some((_, sum)) => *sum += share,
+                None => times.push((tag.to_string(), share)),
+            }
+        }
+    }
+    times.sort_by(|(a_tag, a), (b_tag, b)| b.total_cmp(a).then_with(|| a_tag.cmp(b_tag)));
+    times
+}
+
+pub fn render_tag_times(times: &[(String, f64)]) {
+    let total: f64 = times.iter().map(|(_, minutes)| minutes).sum();
+    let width = times
+        .iter()
+        .map(|(tag, _)| tag.width())
+        .chain(["total".len()])
+        .max()
+        .unwrap();
+    for (tag, minutes) in times {
+        let share = match total > 0.0 {
+            true => minutes / total,
+            false => 0.0,
+        };
+        let bar = "█".repeat((share * TAG_BAR_WIDTH as f64).round() as usize);
+        println!(
+            "{tag}{}{: >8}{: >6}  {bar}",
+            " ".repeat(width - tag.width()),
+            format_minutes(minutes.round() as i64),
+            format!("{:.0}%", share * 100.0),
+        );
+    }
+    println!(
+        "{: <width$}{: >8}",
+        "total",
+        format_minutes(total.round() as i64)
+    );
+}
+
+// `tag,minutes` lines under a header, for spreadsheets
+pub fn tag_times_to_csv(times: &[(String, f64)]) -> String {
+    let mut writer = WriterBuilder::new().from_writer(vec![]);
+    writer.write_record(["tag", "minutes"]).unwrap();
+    for (tag, minutes) in times {
+        writer
+            .write_record([tag, &(minutes.round() as i64).to_string()])
+            .unwrap();
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+// the completions of every day in the weeks up to the one of today, a row per weekday from
+// monday and a column per week; days after today are `None`
+pub fn heatmap_grid(tasks: &[Task], today: NaiveDate, weeks: u32) -> Vec<Vec<Option<usize>>> {
+    let first_monday = first_monday(today, weeks);
+    (0..7)
+        .map(|weekday| {
+            (0..weeks as i64)
+                .map(|week| first_monday + Duration::days(week * 7 + weekday))
+                .map(|date| {
+                    (date <= today).then(|| {
+                        tasks
+                            .iter()
+                            .filter(|task| !task.is_deleted && task.status == TaskStatus::Complete)
+                            .filter(|task| {
+                                task.actual_complete
+                                    .is_some_and(|complete| complete.date_naive() == date)
+                            })
+                            .count()
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn first_monday(today: NaiveDate, weeks: u32) -> NaiveDate {
+    today.week(Weekday::Mon).first_day() - Duration::weeks(weeks as i64 - 1)
+}
+
+// 0 for no completions, otherwise 1 to 4 relative to the busiest day
+fn heatmap_bucket(count: usize, max: usize) -> usize {
+    match count {
+        0 => 0,
+        count => (count * 4).div_ceil(max.max(1)).clamp(1, 4),
+    }
+}
+
+// the abbreviated month above the first week starting in it, if there's room for it
+fn month_labels(first_monday: NaiveDate, weeks: u32) -> String {
+    let mut labels = String::new();
+    let mut last_month = None;
+    for week in 0..weeks as usize {
+        let monday = first_monday + Duration::weeks(week as i64);
+        if last_month == Some(monday.month()) {
+            continue;
+        }
+        last_month = Some(monday.month());
+        let column = week * 2;
+        if column < labels.chars().count() + usize::from(!labels.is_empty()) {
+            continue;
+        }
+        labels.push_str(&" ".repeat(column - labels.chars().count()));
+        labels.push_str(&monday.format("%b").to_string());
+    }
+    labels
+}
+
+pub fn render_heatmap(tasks: &[Task], today: NaiveDate, weeks: u32) {
+    let grid = heatmap_grid(tasks, today, weeks);
+    let max = grid.iter().flatten().flatten().copied().max().unwrap_or(0);
+    println!("    {}", month_labels(first_monday(today, weeks), weeks));
+    for (weekday, row) in grid.iter().enumerate() {
+        // every other weekday is labelled, like mon, wed and fri
+        let label = match weekday % 2 {
+            0 => (first_monday(today, weeks) + Duration::days(weekday as i64))
+                .format("%a")
+                .to_string(),
+            _ => String::new(),
+        };
+        let cells: String = row
+            .iter()
+            .map_while(|count| *count)
+            .map(|count| {
+                let bucket = heatmap_bucket(count, max);
+                let cell = HEATMAP_CELLS[bucket].to_string();
+                match (bucket, is_plain()) {
+                    (_, true) => cell,
+                    (0, false) => cell.dimmed().to_string(),
+                    (bucket, false) => cell.color(HEATMAP_COLORS[bucket - 1]).to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("{label: <4}{cells}");
+    }
+    println!(
+        "{} tasks completed in {weeks} weeks, {max} at most a day",
+        grid.iter().flatten().flatten().sum::<usize>()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::DateFilterOp;
+    use crate::testing::at;
+    use chrono::TimeZone;
+
+    fn complete(planned_minutes: i64, actual_minutes: i64) -> Task {
+        let start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let mut task = Task::new_planned_task(
+            &format!("{planned_minutes} vs {actual_minutes}"),
+            start,
+            start + Duration::minutes(planned_minutes),
+        );
+        task.actual_start = Some(start);
+        task.actual_complete = Some(start + Duration::minutes(actual_minutes));
+        task.status = TaskStatus::Complete;
+        task
+    }
+
+    #[test]
+    fn test_estimate_stats() {
+        let mut deleted = complete(60, 600);
+        deleted.delete();
+        let mut ongoing = complete(60, 600);
+        ongoing.actual_complete = None;
+        ongoing.status = TaskStatus::Ongoing;
+        let mut untracked = complete(60, 60);
+        untracked.actual_start = None;
+        let tasks = vec![
+            complete(60, 30),
+            complete(60, 90),
+            complete(0, 30),
+            deleted,
+            complete(30, 90),
+            ongoing,
+            complete(60, 60),
+            Task::new_backlog_task("backlog"),
+            untracked.clone(),
+        ];
+        let stats = estimate_stats(&tasks).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.untracked, 1);
+        assert_eq!(stats.median, 1.25);
+        assert_eq!(stats.mean, 1.5);
+        assert_eq!(stats.within_plan, 2);
+        assert_eq!(stats.worst_overruns, vec![(4, 3.0, 60), (1, 1.5, 30)]);
+
+        assert_eq!(estimate_stats(&tasks[2..4]), None);
+        assert_eq!(estimate_stats(&tasks[..1]).unwrap().median, 0.5);
+        assert_eq!(estimate_stats(&[untracked]), None);
+    }
+
+    #[test]
+    fn test_punctuality_stats() {
+        let at = |day: u32, hour: u32, minute: u32| {
+            Local
+                .with_ymd_and_hms(2024, 3, day, hour, minute, 0)
+                .unwrap()
+        };
+        let started = |day: u32, delay: i64| {
+            let mut task =
+                Task::new_planned_task(&format!("{delay}"), at(day, 9, 0), at(day, 10, 0));
+            task.actual_start = Some(at(day, 9, 0) + Duration::minutes(delay));
+            task
+        };
+        let mut deleted = started(4, 600);
+        deleted.delete();
+        // completed by hand, and planned later than now
+        let mut untracked = Task::new_planned_task("untracked", at(6, 9, 0), at(6, 10, 0));
+        untracked.actual_complete = Some(at(4, 11, 0));
+        let tasks = vec![
+            started(4, -10),
+            started(4, 0),
+            started(4, 5),
+            started(4, 6),
+            started(4, 30),
+            started(5, 31),
+            deleted,
+            Task::new_planned_task("missed", at(4, 14, 0), at(4, 15, 0)),
+            Task::new_planned_task("upcoming", at(5, 14, 0), at(5, 15, 0)),
+            Task::new_backlog_task("backlog"),
+            untracked,
+        ];
+        let now = at(5, 12, 0);
+        let stats = punctuality_stats(&tasks, None, now, None).unwrap();
+        assert_eq!(stats.buckets, [1, 2, 2, 1]);
+        assert_eq!(stats.never_started, 1);
+        assert_eq!(stats.untracked, 1);
+        assert_eq!(stats.mean_delay, 10);
+        assert_eq!(stats.median_delay, 5);
+        assert_eq!(stats.worst, (5, 31));
+
+        let march_4th = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let filter = DateFilter(DateFilterOp::Equal, march_4th);
+        let stats = punctuality_stats(&tasks, Some(filter), now, None).unwrap();
+        assert_eq!(stats.buckets, [1, 2, 2, 0]);
+        assert_eq!(stats.worst, (4, 30));
+
+        let filter = DateFilter(DateFilterOp::Later, march_4th);
+        assert_eq!(
+            punctuality_stats(&tasks[7..], Some(filter), now, None),
+            None
+        );
+
+        // planned on a friday evening and started on monday morning
+        let mut task = started(8, 0);
+        task.planned_start = Some(at(8, 17, 30));
+        task.actual_start = Some(at(11, 9, 15));
+        let hours = WorkingHours {
+            hours: (9, 18),
+            days: crate::working::DEFAULT_WORKING_DAYS.to_vec(),
+        };
+        let stats = punctuality_stats(&[task], None, now, Some(&hours)).unwrap();
+        assert_eq!(stats.worst, (0, 45));
+    }
+
+    #[test]
+    fn test_punctuality_bucket() {
+        assert_eq!(punctuality_bucket(-1), 0);
+        assert_eq!(punctuality_bucket(0), 1);
+        assert_eq!(punctuality_bucket(ON_TIME_MINUTES), 1);
+        assert_eq!(punctuality_bucket(ON_TIME_MINUTES + 1), 2);
+        assert_eq!(punctuality_bucket(LATE_MINUTES), 2);
+        assert_eq!(punctuality_bucket(LATE_MINUTES + 1), 3);
+    }
+
+    #[test]
+    fn test_time_per_tag() {
+        let worked = |day: u32, hours: i64, tags: &[&str]| {
+            let mut task = Task::new_backlog_task("worked");
+            task.actual_start = Some(at(day, 9));
+            task.actual_complete = Some(at(day, 9) + Duration::hours(hours));
+            task.status = TaskStatus::Complete;
+            task.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            task
+        };
+        let mut ongoing = worked(5, 0, &["work"]);
+        ongoing.actual_complete = None;
+        ongoing.status = TaskStatus::Ongoing;
+        let mut deleted = worked(4, 5, &["work"]);
+        deleted.delete();
+        let tasks = vec![
+            worked(4, 2, &["work"]),
+            worked(4, 3, &["work", "reading"]),
+            worked(4, 1, &[]),
+            ongoing,
+            deleted,
+            Task::new_backlog_task("backlog"),
+        ];
+        let now = at(5, 10);
+        assert_eq!(
+            time_per_tag(&tasks, None, now),
+            vec![
+                ("work".to_string(), 270.0),
+                ("reading".to_string(), 90.0),
+                (UNTAGGED.to_string(), 60.0),
+            ]
+        );
+        let filter = DateFilter(
+            DateFilterOp::Later,
+            NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+        );
+        assert_eq!(
+            time_per_tag(&tasks, Some(filter), now),
+            vec![("work".to_string(), 60.0)]
+        );
+        assert_eq!(
+            tag_times_to_csv(&time_per_tag(&tasks, None, now)),
+            "tag,minutes\nwork,270\nreading,90\n(untagged),60\n"
+        );
+    }
+
+    #[test]
+    fn test_heatmap_grid() {
+        let completed_on = |day: u32, hour: u32| {
+            let mut task = complete(60, 60);
+            task.actual_complete = Local.with_ymd_and_hms(2024, 3, day, hour, 0, 0).single();
+            task
+        };
+        let mut deleted = completed_on(4, 9);
+        deleted.delete();
+        let tasks = vec![
+            completed_on(4, 9),
+            completed_on(4, 18),
+            completed_on(13, 9),
+            deleted,
+            Task::new_backlog_task("backlog"),
+        ];
+        // a wednesday, so the rest of its week is yet to come
+        let today = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let grid = heatmap_grid(&tasks, today, 2);
+        assert_eq!(grid.len(), 7);
+        assert_eq!(grid[0], vec![Some(2), Some(0)]);
+        assert_eq!(grid[2], vec![Some(0), Some(1)]);
+        assert_eq!(grid[3], vec![Some(0), None]);
+        assert_eq!(grid[6], vec![Some(0), None]);
+    }
+
+    #[test]
+    fn test_heatmap_bucket() {
+        assert_eq!(heatmap_bucket(0, 8), 0);
+        assert_eq!(heatmap_bucket(1, 8), 1);
+        assert_eq!(heatmap_bucket(3, 8), 2);
+        assert_eq!(heatmap_bucket(6, 8), 3);
+        assert_eq!(heatmap_bucket(8, 8), 4);
+        assert_eq!(heatmap_bucket(1, 1), 4);
+    }
+
+    #[test]
+    fn test_month_labels() {
+        // mondays of jan 22 and 29, feb 5 to 26, and mar 4
+        let first_monday = NaiveDate::from_ymd_opt(2024, 1, 22).unwrap();
+        assert_eq!(month_labels(first_monday, 7), "Jan Feb     Mar");
+        // no room for january before february
+        let first_monday = NaiveDate::from_ymd_opt(2024, 1, 29).unwrap();
+        assert_eq!(month_labels(first_monday, 2), "Jan");
+    }
+
+    #[test]
+    fn test_worst_overruns_are_capped() {
+        let tasks: Vec<Task> = (1..=7).map(|hours| complete(60, 60 + hours * 60)).collect();
+        let stats = estimate_stats(&tasks).unwrap();
+        let indices: Vec<usize> = stats
+            .worst_overruns
+            .iter()
+            .map(|(index, ..)| *index)
+            .collect();
+        assert_eq!(indices, vec![6, 5, 4, 3, 2]);
+    }
+}
@@ -0,0 +1,634 @@
+use crate::config::StorageKind;
+use crate::error::{ArentaError, ArentaResult};
+use crate::journal::Journal;
+use crate::manager::{
+    load_tasks_from_file, record_to_task, task_to_row, write_tasks_to_file, LoadResult,
+    KNOWN_COLUMNS,
+};
+use crate::task::Task;
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// where a list of tasks is kept; a task is told by its index, i.e. its position in the list
+pub trait TaskStore {
+    fn load_all(&mut self) -> ArentaResult<Vec<Task>>;
+
+    // puts each task at its index, replacing the task there or appending it right after the
+    // last one; either all of them are saved or none
+    fn upsert(&mut self, tasks: &[(usize, &Task)]) -> ArentaResult<()>;
+
+    // removes the task at the index, moving those after it up
+    fn delete(&mut self, index: usize) -> ArentaResult<()>;
+
+    fn append(&mut self, tasks: &[Task]) -> ArentaResult<()>;
+
+    // replaces whatever is kept with the tasks
+    fn save_all(&mut self, tasks: &[Task]) -> ArentaResult<()>;
+
+    // changes kept apart from the bulk of the tasks, e.g. in the journal of a data file, which
+    // `save_all` folds in
+    fn pending(&self) -> usize {
+        0
+    }
+}
+
+// the file tasks are kept in for the data file, which is the data file itself for csv
+pub fn store_file_path(kind: StorageKind, data_file: &Path) -> PathBuf {
+    match kind {
+        StorageKind::Csv => data_file.to_path_buf(),
+        StorageKind::Sqlite => data_file.with_extension("db"),
+    }
+}
+
+// the store of the kind kept in the file, e.g. a backup of it
+pub fn open_store(kind: StorageKind, path: &Path) -> ArentaResult<Box<dyn TaskStore>> {
+    Ok(match kind {
+        StorageKind::Csv => Box::new(CsvFile::new(path)),
+        StorageKind::Sqlite => Box::new(SqliteFile::new(path)?),
+    })
+}
+
+fn check_index(index: usize, len: usize, can_append: bool) -> ArentaResult<()> {
+    match index < len || (can_append && index == len) {
+        true => Ok(()),
+        false => Err(ArentaError::validation(format!(
+            "index {index} out of range, {len} task(s) kept"
+        ))),
+    }
+}
+
+// a data file as a session leaves it, which the journal next to it is applied onto; changes
+// are appended to the journal, and the data file is only written by `save_all`. It must not
+// be written while a session holds its lock
+pub struct CsvFile {
+    path: PathBuf,
+    // none until the file is loaded, as the journal applies to what the data file holds then
+    journal: Option<Journal>,
+    len: usize,
+}
+
+impl CsvFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        CsvFile {
+            path: path.into(),
+            journal: None,
+            len: 0,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // loads tasks the way a session does, i.e. corrupt lines are skipped and come along, and
+    // records of the journal after a damaged one are ignored with a warning
+    pub(crate) fn load(&mut self) -> (LoadResult, Option<String>) {
+        let mut result = load_tasks_from_file(&self.path);
+        let mut journal = Journal::new(&self.path, result.base_hash);
+        let warning = journal.replay(&mut result.tasks);
+        self.journal = Some(journal);
+        self.len = result.tasks.len();
+        (result, warning)
+    }
+
+    fn journal(&mut self) -> ArentaResult<&mut Journal> {
+        if self.journal.is_none() {
+            self.load_all()?;
+        }
+        Ok(self.journal.as_mut().expect("journal is set by loading"))
+    }
+
+    fn save_error(&self, e: io::Error) -> ArentaError {
+        ArentaError::io(
+            format!(
+                "failed to save tasks, {} is left untouched",
+                self.path.display()
+            ),
+            e,
+        )
+    }
+}
+
+impl TaskStore for CsvFile {
+    // corrupt lines fail the load rather than being skipped, as a session would ask about them;
+    // a damaged rest of the journal is dropped by the next change, as a session would do
+    fn load_all(&mut self) -> ArentaResult<Vec<Task>> {
+        let (result, _) = self.load();
+        result.check_version()?;
+        let LoadResult {
+            tasks, mut skipped, ..
+        } = result;
+        if !skipped.is_empty() {
+            return Err(skipped.swap_remove(0).0);
+        }
+        Ok(tasks)
+    }
+
+    fn upsert(&mut self, tasks: &[(usize, &Task)]) -> ArentaResult<()> {
+        self.journal()?;
+        let mut len = self.len;
+        for (index, _) in tasks {
+            check_index(*index, len, true)?;
+            len = len.max(index + 1);
+        }
+        let rows: Vec<(usize, Vec<String>)> = tasks
+            .iter()
+            .map(|(index, task)| (*index, task_to_row(task)))
+            .collect();
+        let result = self.journal()?.append(&rows);
+        result.map_err(|e| self.save_error(e))?;
+        self.len = len;
+        Ok(())
+    }
+
+    fn delete(&mut self, index: usize) -> ArentaResult<()> {
+        self.journal()?;
+        check_index(index, self.len, false)?;
+        let result = self.journal()?.delete(index);
+        result.map_err(|e| self.save_error(e))?;
+        self.len -= 1;
+        Ok(())
+    }
+
+    fn append(&mut self, tasks: &[Task]) -> ArentaResult<()> {
+        self.journal()?;
+        let first = self.len;
+        let indexed: Vec<(usize, &Task)> = tasks
+            .iter()
+            .enumerate()
+            .map(|(offset, task)| (first + offset, task))
+            .collect();
+        self.upsert(&indexed)
+    }
+
+    fn save_all(&mut self, tasks: &[Task]) -> ArentaResult<()> {
+        let base_hash = write_tasks_to_file(&self.path, tasks).map_err(|e| self.save_error(e))?;
+        let mut journal = Journal::new(&self.path, base_hash);
+        journal.reset(base_hash).map_err(|e| self.save_error(e))?;
+        self.journal = Some(journal);
+        self.len = tasks.len();
+        Ok(())
+    }
+
+    fn pending(&self) -> usize {
+        self.journal.as_ref().map_or(0, |journal| journal.records)
+    }
+}
+
+// how a column of the data file is kept in the database
+#[derive(Copy, Clone)]
+enum ColumnType {
+    Text,
+    // empty for none, e.g. a task not started yet
+    OptionalText,
+    Flag,
+    Number,
+}
+
+// the columns of the tasks table, in the order of those of the data file
+const COLUMNS: [(&str, ColumnType); KNOWN_COLUMNS] = [
+    ("description", ColumnType::Text),
+    ("planned_start", ColumnType::OptionalText),
+    ("planned_complete", ColumnType::OptionalText),
+    ("actual_start", ColumnType::OptionalText),
+    ("actual_complete", ColumnType::OptionalText),
+    ("is_deleted", ColumnType::Flag),
+    ("tags", ColumnType::Text),
+    ("postponed_count", ColumnType::Number),
+    ("priority", ColumnType::Number),
+    ("is_private", ColumnType::Flag),
+    ("work_intervals", ColumnType::Text),
+    ("notes", ColumnType::Text),
+    ("created_at", ColumnType::Text),
+];
+
+// a sqlite database with a `tasks` table, talked to through the sqlite3 binary rather than a
+// linked library, so values go into the sql as quoted literals; every change runs in a
+// transaction, which sqlite rolls back if the binary is stopped halfway
+pub struct SqliteFile {
+    path: PathBuf,
+    // none until the tasks are loaded or counted
+    len: Option<usize>,
+}
+
+impl SqliteFile {
+    // fails right away if the sqlite3 binary can't be run, rather than at the first load
+    pub fn new(path: impl Into<PathBuf>) -> ArentaResult<Self> {
+        Command::new("sqlite3")
+            .arg("-version")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(spawn_error)?;
+        Ok(SqliteFile {
+            path: path.into(),
+            len: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn len(&mut self) -> ArentaResult<usize> {
+        if let Some(len) = self.len {
+            return Ok(len);
+        }
+        let len = match self.path.exists() {
+            true => {
+                let output = self.run(&["-readonly"], "SELECT count(*) FROM tasks;")?;
+                let count = String::from_utf8_lossy(&output).trim().parse::<usize>();
+                count.map_err(|e| self.error("count tasks in", e.to_string()))?
+            }
+            false => 0,
+        };
+        self.len = Some(len);
+        Ok(len)
+    }
+
+    // the statements are run in a transaction, after the table is created if it's missing
+    fn transact(&self, statements: &[String]) -> ArentaResult<()> {
+        let mut sql = schema();
+        sql.push_str("BEGIN IMMEDIATE;\n");
+        statements.iter().for_each(|statement| {
+            sql.push_str(statement);
+            sql.push('\n');
+        });
+        sql.push_str("COMMIT;\n");
+        self.run(&[], &sql).map(|_| ())
+    }
+
+    // the output of the statements in csv, or the first line of what sqlite3 complained about
+    fn run(&self, options: &[&str], sql: &str) -> ArentaResult<Vec<u8>> {
+        let spawned = Command::new("sqlite3")
+            .args(["-bail", "-batch", "-csv"])
+            .args(options)
+            .arg(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = spawned.map_err(spawn_error)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(sql.as_bytes())
+                .map_err(|e| ArentaError::io("failed to run sqlite3", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| ArentaError::io("failed to run sqlite3", e))?;
+        if output.status.success() {
+            return Ok(output.stdout);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("sqlite3 failed");
+        Err(self.error("access", reason.trim().to_string()))
+    }
+
+    fn error(&self, doing: &str, reason: String) -> ArentaError {
+        ArentaError::validation(format!(
+            "failed to {doing} {}: {reason}",
+            self.path.display()
+        ))
+    }
+}
+
+impl TaskStore for SqliteFile {
+    fn load_all(&mut self) -> ArentaResult<Vec<Task>> {
+        if !self.path.exists() {
+            self.len = Some(0);
+            return Ok(vec![]);
+        }
+        let columns: Vec<String> = COLUMNS
+            .iter()
+            .map(|(name, column_type)| match column_type {
+                ColumnType::Flag => format!("CASE WHEN {name} THEN 'true' ELSE 'false' END"),
+                _ => name.to_string(),
+            })
+            .collect();
+        let sql = format!(
+            "SELECT {}, extras FROM tasks ORDER BY position;",
+            columns.join(", ")
+        );
+        let output = self.run(&["-readonly"], &sql)?;
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(output.as_slice());
+        let mut tasks = vec![];
+        for (index, record) in reader.records().enumerate() {
+            let task = record
+                .map_err(|e| e.to_string())
+                .and_then(|record| {
+                    let extras = parse_extras(record.get(KNOWN_COLUMNS).unwrap_or_default())?;
+                    let row: StringRecord = record
+                        .iter()
+                        .take(KNOWN_COLUMNS)
+                        .chain(extras.iter().map(String::as_str))
+                        .collect();
+                    record_to_task(&row)
+                })
+                .map_err(|reason| self.error("load", format!("task {index}: {reason}")))?;
+            tasks.push(task);
+        }
+        self.len = Some(tasks.len());
+        Ok(tasks)
+    }
+
+    // a task already there keeps its id
+    fn upsert(&mut self, tasks: &[(usize, &Task)]) -> ArentaResult<()> {
+        let mut len = self.len()?;
+        for (index, _) in tasks {
+            check_index(*index, len, true)?;
+            len = len.max(index + 1);
+        }
+        let updates: Vec<String> = COLUMNS
+            .iter()
+            .map(|(name, _)| name)
+            .chain([&"extras"])
+            .map(|name| format!("{name} = excluded.{name}"))
+            .collect();
+        let statements: Vec<String> = tasks
+            .iter()
+            .map(|(index, task)| {
+                format!(
+                    "{} ON CONFLICT (position) DO UPDATE SET {};",
+                    insert_statement(*index, task),
+                    updates.join(", ")
+                )
+            })
+            .collect();
+        self.transact(&statements)?;
+        self.len = Some(len);
+        Ok(())
+    }
+
+    fn delete(&mut self, index: usize) -> ArentaResult<()> {
+        let len = self.len()?;
+        check_index(index, len, false)?;
+        // positions are unique, so those after it are moved out of the way first
+        self.transact(&[
+            format!("DELETE FROM tasks WHERE position = {index};"),
+            format!("UPDATE tasks SET position = -position WHERE position > {index};"),
+            "UPDATE tasks SET position = -position - 1 WHERE position < 0;".to_string(),
+        ])?;
+        self.len = Some(len - 1);
+        Ok(())
+    }
+
+    fn append(&mut self, tasks: &[Task]) -> ArentaResult<()> {
+        let first = self.len()?;
+        let statements: Vec<String> = tasks
+            .iter()
+            .enumerate()
+            .map(|(offset, task)| format!("{};", insert_statement(first + offset, task)))
+            .collect();
+        self.transact(&statements)?;
+        self.len = Some(first + tasks.len());
+        Ok(())
+    }
+
+    fn save_all(&mut self, tasks: &[Task]) -> ArentaResult<()> {
+        let statements: Vec<String> = ["DELETE FROM tasks;".to_string()]
+            .into_iter()
+            .chain(
+                tasks
+                    .iter()
+                    .enumerate()
+                    .map(|(index, task)| format!("{};", insert_statement(index, task))),
+            )
+            .collect();
+        self.transact(&statements)?;
+        self.len = Some(tasks.len());
+        Ok(())
+    }
+}
+
+// every column of a task has one of the table, along with an id and the position in the list
+fn schema() -> String {
+    let columns: Vec<String> = COLUMNS
+        .iter()
+        .map(|(name, column_type)| match column_type {
+            ColumnType::Text => format!("{name} TEXT NOT NULL"),
+            ColumnType::OptionalText => format!("{name} TEXT"),
+            ColumnType::Flag | ColumnType::Number => format!("{name} INTEGER NOT NULL"),
+        })
+        .collect();
+    format!(
+        "CREATE TABLE IF NOT EXISTS tasks (id INTEGER PRIMARY KEY, position INTEGER NOT NULL UNIQUE, {}, extras TEXT NOT NULL);\n",
+        columns.join(", ")
+    )
+}
+
+fn insert_statement(index: usize, task: &Task) -> String {
+    let row = task_to_row(task);
+    let values: Vec<String> = COLUMNS
+        .iter()
+        .zip(&row)
+        .map(|((_, column_type), value)| match column_type {
+            ColumnType::OptionalText if value.is_empty() => "NULL".to_string(),
+            ColumnType::Text | ColumnType::OptionalText => quote(value),
+            ColumnType::Flag => match value.as_str() {
+                "true" => "1".to_string(),
+                _ => "0".to_string(),
+            },
+            ColumnType::Number => value.parse::<u64>().unwrap_or_default().to_string(),
+        })
+        .collect();
+    let names: Vec<&str> = COLUMNS.iter().map(|(name, _)| *name).collect();
+    format!(
+        "INSERT INTO tasks (position, {}, extras) VALUES ({index}, {}, {})",
+        names.join(", "),
+        values.join(", "),
+        quote(&format_extras(&row[KNOWN_COLUMNS..]))
+    )
+}
+
+fn spawn_error(e: io::Error) -> ArentaError {
+    match e.kind() {
+        io::ErrorKind::NotFound => ArentaError::validation(
+            "sqlite3 is not installed, or not on PATH, which `storage = \"sqlite\"` needs",
+        ),
+        _ => ArentaError::io("failed to run sqlite3", e),
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// columns added by a newer version are kept as a csv line of their own
+fn format_extras(extras: &[String]) -> String {
+    if extras.is_empty() {
+        return String::new();
+    }
+    let mut writer = WriterBuilder::new().from_writer(vec![]);
+    let _ = writer.write_record(extras);
+    let line = writer.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&line).trim_end().to_string()
+}
+
+fn parse_extras(line: &str) -> Result<Vec<String>, String> {
+    if line.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(line.as_bytes());
+    match reader.records().next() {
+        Some(Ok(record)) => Ok(record.iter().map(String::from).collect()),
+        _ => Err(format!("invalid extra columns `{line}`")),
+    }
+}
+
+// keeps tasks for as long as it lives, e.g. in tests
+#[derive(Default)]
+pub struct MemoryStore {
+    tasks: Vec<Task>,
+}
+
+impl TaskStore for MemoryStore {
+    fn load_all(&mut self) -> ArentaResult<Vec<Task>> {
+        Ok(self.tasks.clone())
+    }
+
+    fn upsert(&mut self, tasks: &[(usize, &Task)]) -> ArentaResult<()> {
+        let mut len = self.tasks.len();
+        for (index, _) in tasks {
+            check_index(*index, len, true)?;
+            len = len.max(index + 1);
+        }
+        for (index, task) in tasks {
+            match self.tasks.get_mut(*index) {
+                Some(kept) => *kept = (*task).clone(),
+                None => self.tasks.push((*task).clone()),
+            }
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, index: usize) -> ArentaResult<()> {
+        check_index(index, self.tasks.len(), false)?;
+        self.tasks.remove(index);
+        Ok(())
+    }
+
+    fn append(&mut self, tasks: &[Task]) -> ArentaResult<()> {
+        self.tasks.extend_from_slice(tasks);
+        Ok(())
+    }
+
+    fn save_all(&mut self, tasks: &[Task]) -> ArentaResult<()> {
+        self.tasks = tasks.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+    use std::fs;
+
+    fn descriptions(tasks: &[Task]) -> Vec<String> {
+        tasks.iter().map(|task| task.description.clone()).collect()
+    }
+
+    // makes the same changes to every store, returning the task upserted
+    fn change_store(store: &mut dyn TaskStore) -> Task {
+        assert!(store.load_all().unwrap().is_empty());
+        let mut a = Task::new_backlog_task("a, 'quoted'");
+        a.tags = vec!["work".to_string()];
+        a.notes = "first line\nsecond line".to_string();
+        a.extras = vec!["from a newer version".to_string(), "".to_string()];
+        store.save_all(&[a, Task::new_immediate_task("b")]).unwrap();
+        store
+            .append(&[Task::new_backlog_task("c"), Task::new_backlog_task("d")])
+            .unwrap();
+        let mut b = Task::new_immediate_task("b, started");
+        b.start();
+        b.priority = 2;
+        store
+            .upsert(&[(1, &b), (4, &Task::new_backlog_task("e"))])
+            .unwrap();
+        store.delete(2).unwrap();
+        assert!(store.delete(4).is_err());
+        assert!(store.upsert(&[(5, &Task::new_backlog_task("f"))]).is_err());
+        b
+    }
+
+    fn check_tasks(tasks: &[Task], b: &Task) {
+        assert_eq!(
+            descriptions(tasks),
+            vec!["a, 'quoted'", "b, started", "d", "e"]
+        );
+        assert_eq!(tasks[0].tags, vec!["work"]);
+        assert_eq!(tasks[0].notes, "first line\nsecond line");
+        assert_eq!(tasks[0].extras, vec!["from a newer version", ""]);
+        assert_eq!(task_to_row(&tasks[1]), task_to_row(b));
+    }
+
+    #[test]
+    fn test_csv_file() {
+        let dir = temp_dir("csv-store");
+        let path = dir.join("default.csv");
+        let mut store = CsvFile::new(&path);
+        let b = change_store(&mut store);
+        check_tasks(&store.load_all().unwrap(), &b);
+        assert_eq!(store.pending(), 5);
+        check_tasks(&CsvFile::new(&path).load_all().unwrap(), &b);
+
+        fs::write(store.path(), "a,b\n").unwrap();
+        assert!(store.load_all().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sqlite_file() {
+        let dir = temp_dir("sqlite-store");
+        let path = dir.join("default.db");
+        let Ok(mut store) = SqliteFile::new(&path) else {
+            eprintln!("sqlite3 is missing, skipped");
+            fs::remove_dir_all(&dir).unwrap();
+            return;
+        };
+        let b = change_store(&mut store);
+        check_tasks(&store.load_all().unwrap(), &b);
+        check_tasks(&SqliteFile::new(&path).unwrap().load_all().unwrap(), &b);
+
+        // a change failing halfway leaves nothing of it behind
+        assert!(store
+            .transact(&[
+                "DELETE FROM tasks;".to_string(),
+                "INSERT INTO tasks (position) VALUES (0);".to_string(),
+            ])
+            .is_err());
+        check_tasks(&SqliteFile::new(&path).unwrap().load_all().unwrap(), &b);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let mut store = MemoryStore::default();
+        let b = change_store(&mut store);
+        check_tasks(&store.load_all().unwrap(), &b);
+    }
+
+    #[test]
+    fn test_extras() {
+        for extras in [
+            vec![],
+            vec!["x".to_string()],
+            vec!["".to_string()],
+            vec!["".to_string(), "a,b".to_string()],
+        ] {
+            assert_eq!(parse_extras(&format_extras(&extras)).unwrap(), extras);
+        }
+    }
+}
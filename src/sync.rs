@@ -0,0 +1,300 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// the git work tree the data file lives in, talked to through the git binary
+pub struct Repo {
+    // the directory of the data file, which git commands run in so that paths are relative
+    // to it
+    dir: PathBuf,
+    root: PathBuf,
+}
+
+// how a pull went wrong
+pub enum PullError {
+    // files with conflicts; the rebase is left going
+    Conflicts(Vec<PathBuf>),
+    Failed(String),
+}
+
+impl Repo {
+    // refuses a work tree on a detached HEAD or amid a rebase or merge, as committing there
+    // would end up on no branch or in the middle of someone else's work
+    pub fn discover(data_file: &Path) -> Result<Self, String> {
+        let dir = data_file
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let root = git(&dir, &["rev-parse", "--show-toplevel"])
+            .map(PathBuf::from)
+            .map_err(|e| match e.starts_with("fatal: not a git repository") {
+                true => format!("{} is not inside a git work tree", dir.display()),
+                false => e,
+            })?;
+        let repo = Repo { dir, root };
+        if !repo.succeeds(&["symbolic-ref", "-q", "HEAD"])? {
+            return Err(format!(
+                "HEAD is detached in {}, check out a branch to sync",
+                repo.root.display()
+            ));
+        }
+        for name in ["rebase-merge", "rebase-apply", "MERGE_HEAD"] {
+            let path = git(&repo.dir, &["rev-parse", "--git-path", name])?;
+            if repo.dir.join(path).exists() {
+                return Err(format!(
+                    "a rebase or merge is in progress in {}, finish it first",
+                    repo.root.display()
+                ));
+            }
+        }
+        Ok(repo)
+    }
+
+    // the file as of the latest commit, none if it's not committed yet
+    pub fn committed(&self, path: &Path) -> Option<Vec<u8>> {
+        let spec = format!("HEAD:./{}", self.relative(path));
+        let output = self.run(&["show", &spec]).ok()?;
+        output.status.success().then_some(output.stdout)
+    }
+
+    // stages and commits the files, returns false if none of them has changed
+    pub fn commit(&self, paths: &[PathBuf], message: &str) -> Result<bool, String> {
+        let paths: Vec<String> = paths.iter().map(|path| self.relative(path)).collect();
+        let with_paths = |args: &[&str]| -> Vec<String> {
+            args.iter()
+                .map(|arg| arg.to_string())
+                .chain(["--".to_string()])
+                .chain(paths.iter().cloned())
+                .collect()
+        };
+        git(&self.dir, &as_strs(&with_paths(&["add", "-A"])))?;
+        if self.succeeds(&as_strs(&with_paths(&["diff", "--cached", "--quiet"])))? {
+            return Ok(false);
+        }
+        git(
+            &self.dir,
+            &as_strs(&with_paths(&["commit", "-q", "-m", message])),
+        )?;
+        Ok(true)
+    }
+
+    // e.g. `origin/main`, none if the branch tracks nothing
+    pub fn upstream(&self) -> Option<String> {
+        git(
+            &self.dir,
+            &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
+        )
+        .ok()
+    }
+
+    // other changes in the work tree, e.g. dotfiles being edited, are stashed meanwhile
+    pub fn pull_rebase(&self) -> Result<(), PullError> {
+        match git(&self.dir, &["pull", "-q", "--rebase", "--autostash"]) {
+            Ok(_) => Ok(()),
+            Err(e) => match self.conflicts() {
+                conflicts if !conflicts.is_empty() => Err(PullError::Conflicts(conflicts)),
+                _ => Err(PullError::Failed(e)),
+            },
+        }
+    }
+
+    // files with unresolved conflicts
+    fn conflicts(&self) -> Vec<PathBuf> {
+        let files =
+            git(&self.root, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+        files.lines().map(|file| self.root.join(file)).collect()
+    }
+
+    // during a rebase, `ours` is the upstream being built upon and `theirs` is the local
+    // commit being replayed; an absent side, e.g. a deleted file, is empty
+    pub fn conflict_sides(&self, path: &Path) -> (Vec<u8>, Vec<u8>) {
+        let side = |stage: u32| {
+            let spec = format!(":{stage}:./{}", self.relative(path));
+            self.run(&["show", &spec])
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| output.stdout)
+                .unwrap_or_default()
+        };
+        (side(3), side(2))
+    }
+
+    // stages the resolved file and carries on, which may stop at the next conflict
+    pub fn continue_rebase(&self, path: &Path) -> Result<(), PullError> {
+        git(&self.dir, &["add", "--", &self.relative(path)]).map_err(PullError::Failed)?;
+        let output = self
+            .run_with_env(&["rebase", "--continue"], ("GIT_EDITOR", "true"))
+            .map_err(PullError::Failed)?;
+        if output.status.success() {
+            return Ok(());
+        }
+        match self.conflicts() {
+            conflicts if !conflicts.is_empty() => Err(PullError::Conflicts(conflicts)),
+            _ => Err(PullError::Failed(first_line(&output.stderr))),
+        }
+    }
+
+    pub fn abort_rebase(&self) {
+        let _ = git(&self.dir, &["rebase", "--abort"]);
+    }
+
+    pub fn push(&self) -> Result<(), String> {
+        git(&self.dir, &["push", "-q"]).map(|_| ())
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn succeeds(&self, args: &[&str]) -> Result<bool, String> {
+        self.run(args)
+            .map(|output| output.status.success())
+            .map_err(describe_spawn_error)
+    }
+
+    fn run(&self, args: &[&str]) -> io::Result<Output> {
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+    }
+
+    fn run_with_env(&self, args: &[&str], env: (&str, &str)) -> Result<Output, String> {
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .env(env.0, env.1)
+            .output()
+            .map_err(describe_spawn_error)
+    }
+}
+
+fn as_strs(args: &[String]) -> Vec<&str> {
+    args.iter().map(String::as_str).collect()
+}
+
+// the output of a successful git command, or the first line of what it complained about
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(describe_spawn_error)?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string()),
+        false => Err(first_line(&output.stderr)),
+    }
+}
+
+fn describe_spawn_error(e: io::Error) -> String {
+    match e.kind() {
+        io::ErrorKind::NotFound => "git is not installed, or not on PATH".to_string(),
+        _ => format!("failed to run git: {e}"),
+    }
+}
+
+fn first_line(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    match stderr.lines().find(|line| !line.trim().is_empty()) {
+        Some(line) => line.trim().to_string(),
+        None => "git failed".to_string(),
+    }
+}
+
+// the two sides of a file with git conflict markers together with their labels, e.g. `HEAD`;
+// lines outside of conflicts go to both sides, and the base of a diff3 style conflict to
+// neither. none if there are no markers
+pub fn split_conflict(content: &str) -> Option<[(String, String); 2]> {
+    #[derive(PartialEq)]
+    enum Side {
+        Both,
+        First,
+        Base,
+        Second,
+    }
+    let mut side = Side::Both;
+    let mut sides = [
+        (String::new(), String::new()),
+        (String::new(), String::new()),
+    ];
+    let mut has_markers = false;
+    for line in content.split_inclusive('\n') {
+        let label = |marker: &str| line[marker.len()..].trim().to_string();
+        if line.starts_with("<<<<<<<") && side == Side::Both {
+            has_markers = true;
+            side = Side::First;
+            if sides[0].0.is_empty() {
+                sides[0].0 = label("<<<<<<<");
+            }
+        } else if line.starts_with("|||||||") && side == Side::First {
+            side = Side::Base;
+        } else if line.starts_with("=======") && matches!(side, Side::First | Side::Base) {
+            side = Side::Second;
+        } else if line.starts_with(">>>>>>>") && side == Side::Second {
+            side = Side::Both;
+            if sides[1].0.is_empty() {
+                sides[1].0 = label(">>>>>>>");
+            }
+        } else {
+            match side {
+                Side::Both => {
+                    sides[0].1.push_str(line);
+                    sides[1].1.push_str(line);
+                }
+                Side::First => sides[0].1.push_str(line),
+                Side::Second => sides[1].1.push_str(line),
+                Side::Base => {}
+            }
+        }
+    }
+    has_markers.then_some(sides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    #[test]
+    fn test_split_conflict() {
+        assert!(split_conflict("a\nb\n").is_none());
+        let content = "\
+#arenta,2
+a
+<<<<<<< HEAD
+b
+=======
+c
+>>>>>>> origin/main
+d
+<<<<<<< HEAD
+e
+||||||| base
+f
+=======
+>>>>>>> origin/main
+";
+        let [(first_label, first), (second_label, second)] = split_conflict(content).unwrap();
+        assert_eq!(first_label, "HEAD");
+        assert_eq!(first, "#arenta,2\na\nb\nd\ne\n");
+        assert_eq!(second_label, "origin/main");
+        assert_eq!(second, "#arenta,2\na\nc\nd\n");
+    }
+
+    #[test]
+    fn test_discover_outside_work_tree() {
+        let dir = temp_dir("no-repo");
+        // also an error if git is missing
+        assert!(Repo::discover(&dir.join("default.csv")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,8 +1,22 @@
 use chrono::offset::Local;
 use chrono::{DateTime, Duration, NaiveDate};
 use colored::{Color, Colorize};
+use std::cmp::Ordering;
+use std::ops::Range;
 
+use crate::clock;
 use crate::command::{DateFilterOp, ListOption};
+use crate::display::{
+    backlog_stale_days, humanize_duration, is_plain, pad_to_width, strip_ansi, time_format,
+};
+use crate::hyperlink::linkify_if_enabled;
+use crate::working::{humanize_working_duration, working_hours, WorkingHours};
+
+pub const PRIORITY_HIGH: u8 = 3;
+pub const PRIORITY_NAMES: [&str; 4] = ["none", "low", "medium", "high"];
+
+// a period worked on a task, with no end while it's still going on
+pub type WorkInterval = (DateTime<Local>, Option<DateTime<Local>>);
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TaskStatus {
@@ -10,10 +24,65 @@ pub enum TaskStatus {
     Planned,
     Overdue,
     Ongoing,
+    Paused,
     Complete,
 }
 
-#[derive(Debug)]
+impl TaskStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskStatus::Backlog => "backlog",
+            TaskStatus::Planned => "planned",
+            TaskStatus::Overdue => "overdue",
+            TaskStatus::Ongoing => "ongoing",
+            TaskStatus::Paused => "paused",
+            TaskStatus::Complete => "complete",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        const COLOR_GREY: Color = Color::TrueColor {
+            r: 128,
+            g: 128,
+            b: 128,
+        };
+        const COLOR_CYAN: Color = Color::TrueColor {
+            r: 51,
+            g: 255,
+            b: 255,
+        };
+        const COLOR_RED: Color = Color::TrueColor {
+            r: 255,
+            g: 102,
+            b: 102,
+        };
+        const COLOR_YELLOW: Color = Color::TrueColor {
+            r: 255,
+            g: 255,
+            b: 102,
+        };
+        const COLOR_GREEN: Color = Color::TrueColor {
+            r: 51,
+            g: 255,
+            b: 51,
+        };
+        const COLOR_GREY_BLUE: Color = Color::TrueColor {
+            r: 112,
+            g: 144,
+            b: 176,
+        };
+        match self {
+            TaskStatus::Backlog => COLOR_GREY,
+            TaskStatus::Planned => COLOR_CYAN,
+            TaskStatus::Overdue => COLOR_RED,
+            TaskStatus::Ongoing => COLOR_YELLOW,
+            TaskStatus::Paused => COLOR_GREY_BLUE,
+            TaskStatus::Complete => COLOR_GREEN,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Task {
     pub description: String,
     pub planned_start: Option<DateTime<Local>>,
@@ -22,18 +91,43 @@ pub struct Task {
     pub actual_complete: Option<DateTime<Local>>,
     pub status: TaskStatus,
     pub is_deleted: bool,
+    pub tags: Vec<String>,
+    // how many times the planned start has been moved later
+    pub postponed_count: u32,
+    // 0 means no priority, 1 to 3 means low, medium and high
+    pub priority: u8,
+    // private descriptions are replaced when exporting with redaction
+    pub is_private: bool,
+    // periods actually worked on, split by pauses, the last one is open while ongoing;
+    // empty for tasks started before pausing was supported
+    pub work_intervals: Vec<WorkInterval>,
+    // free text, which may span multiple lines
+    pub notes: String,
+    // when the task was loaded for the first time if it was created before this was kept
+    pub created_at: DateTime<Local>,
+    // trailing columns unknown to this version, written back as they are
+    pub extras: Vec<String>,
 }
 
 impl Task {
     pub fn new_immediate_task(description: &str) -> Self {
+        let now = clock::now();
         Task {
             description: description.to_string(),
             planned_start: None,
             planned_complete: None,
-            actual_start: Some(Local::now()),
+            actual_start: Some(now),
             actual_complete: None,
             status: TaskStatus::Ongoing,
             is_deleted: false,
+            tags: vec![],
+            postponed_count: 0,
+            priority: 0,
+            is_private: false,
+            work_intervals: vec![(now, None)],
+            notes: String::new(),
+            created_at: now,
+            extras: vec![],
         }
     }
 
@@ -48,12 +142,20 @@ impl Task {
             planned_complete: Some(end),
             actual_start: None,
             actual_complete: None,
-            status: if Local::now() > start {
+            status: if clock::now() > start {
                 TaskStatus::Overdue
             } else {
                 TaskStatus::Planned
             },
             is_deleted: false,
+            tags: vec![],
+            postponed_count: 0,
+            priority: 0,
+            is_private: false,
+            work_intervals: vec![],
+            notes: String::new(),
+            created_at: clock::now(),
+            extras: vec![],
         }
     }
 
@@ -66,33 +168,146 @@ impl Task {
             actual_complete: None,
             status: TaskStatus::Backlog,
             is_deleted: false,
+            tags: vec![],
+            postponed_count: 0,
+            priority: 0,
+            is_private: false,
+            work_intervals: vec![],
+            notes: String::new(),
+            created_at: clock::now(),
+            extras: vec![],
         }
     }
 
+    // a paused task goes on instead, keeping its start and the time worked before
     pub fn start(&mut self) {
-        self.actual_start = Some(Local::now());
+        if self.paused_at().is_some() {
+            return self.resume();
+        }
+        let now = clock::now();
+        self.actual_start = Some(now);
+        self.work_intervals = vec![(now, None)];
         self.status = TaskStatus::Ongoing;
     }
 
     pub fn complete(&mut self) {
+        let now = clock::now();
         if self.actual_start.is_none() {
-            self.actual_start = Some(Local::now());
+            self.actual_start = Some(now);
         }
-        self.actual_complete = Some(Local::now());
+        self.close_work_interval(now);
+        self.actual_complete = Some(now);
         self.status = TaskStatus::Complete;
     }
 
+    // completed without ever being started, e.g. by setting only the actual complete time with
+    // `edit`, so how long it took is unknown
+    pub fn is_untracked(&self) -> bool {
+        self.actual_complete.is_some() && self.actual_start.is_none()
+    }
+
+    pub fn pause(&mut self) {
+        self.close_work_interval(clock::now());
+        self.status = TaskStatus::Paused;
+    }
+
+    pub fn resume(&mut self) {
+        self.work_intervals.push((clock::now(), None));
+        self.status = TaskStatus::Ongoing;
+    }
+
+    fn close_work_interval(&mut self, now: DateTime<Local>) {
+        // a task started before pausing was supported worked since its actual start
+        if let Some(start) = self.actual_start.filter(|_| self.work_intervals.is_empty()) {
+            self.work_intervals.push((start, None));
+        }
+        if let Some((_, end @ None)) = self.work_intervals.last_mut() {
+            *end = Some(now);
+        }
+    }
+
+    // when the task was paused, if it's paused
+    pub fn paused_at(&self) -> Option<DateTime<Local>> {
+        match self.work_intervals.last() {
+            Some((_, end)) if self.actual_complete.is_none() => *end,
+            _ => None,
+        }
+    }
+
+    // time actually worked on the task leaving out pauses, up to `now` if still ongoing
+    pub fn worked_duration(&self, now: DateTime<Local>) -> Duration {
+        if self.work_intervals.is_empty() {
+            return self.actual_start.map_or(Duration::zero(), |start| {
+                get_duration(&start, &self.actual_complete.unwrap_or(now))
+            });
+        }
+        self.work_intervals
+            .iter()
+            .map(|(start, end)| get_duration(start, &end.unwrap_or(now)))
+            .fold(Duration::zero(), |sum, duration| sum + duration)
+    }
+
+    // sets the planned pair, counting it as a postponement if the planned start moves later
+    pub fn reschedule(
+        &mut self,
+        planned_start: Option<DateTime<Local>>,
+        planned_complete: Option<DateTime<Local>>,
+    ) {
+        if let (Some(old_start), Some(new_start)) = (self.planned_start, planned_start) {
+            if new_start > old_start {
+                self.postponed_count += 1;
+            }
+        }
+        self.planned_start = planned_start;
+        self.planned_complete = planned_complete;
+    }
+
+    // a period may be empty, but never ends before it starts
+    pub fn validate_periods(&self) -> Result<(), String> {
+        for (name, start, complete) in [
+            ("planned", self.planned_start, self.planned_complete),
+            ("actual", self.actual_start, self.actual_complete),
+        ] {
+            if let (Some(start), Some(complete)) = (start, complete) {
+                if start > complete {
+                    return Err(format!(
+                        "{name} start {} is later than {name} complete {}",
+                        start.format("%Y-%m-%d %H:%M"),
+                        complete.format("%Y-%m-%d %H:%M")
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn delete(&mut self) {
         self.is_deleted = true;
     }
 
+    pub fn restore(&mut self) {
+        self.is_deleted = false;
+    }
+
+    // the description to put in shared output, hiding it for private tasks if `redact` is set
+    pub fn shared_description(&self, redact: bool) -> &str {
+        if redact && self.is_private {
+            "private task"
+        } else {
+            &self.description
+        }
+    }
+
     pub fn update_status(&mut self) {
         self.status = {
-            let now = Local::now();
+            let now = clock::now();
             if self.actual_complete.map(|dt| dt < now).unwrap_or(false) {
                 TaskStatus::Complete
             } else if self.actual_start.map(|dt| dt < now).unwrap_or(false) {
-                TaskStatus::Ongoing
+                match self.paused_at() {
+                    Some(_) => TaskStatus::Paused,
+                    None => TaskStatus::Ongoing,
+                }
             } else if self.planned_start.map(|dt| dt < now).unwrap_or(false) {
                 TaskStatus::Overdue
             } else if self.planned_start.is_some() {
@@ -104,9 +319,44 @@ impl Task {
     }
 
     pub fn satisfy(&self, option: &ListOption) -> bool {
+        if self.is_deleted && !option.include_deleted {
+            return false;
+        }
+        if let Some(tag) = &option.tag {
+            if !self.has_tag(tag) {
+                return false;
+            }
+        }
+        if option.only_prioritized && self.priority == 0 {
+            return false;
+        }
+        if let Some(text) = &option.text {
+            if !self.matches(text) {
+                return false;
+            }
+        }
+        if let Some(status) = option.status {
+            if self.is_deleted || self.status != status {
+                return false;
+            }
+        }
         match self.status {
-            TaskStatus::Backlog => option.include_backlog,
+            TaskStatus::Backlog => option.include_backlog || option.status.is_some(),
             TaskStatus::Overdue => true,
+            // what is being worked on is never out of sight
+            TaskStatus::Ongoing | TaskStatus::Paused if option.reaches_today() => true,
+            _ if option.end_date.is_some() => {
+                let range = option.date_filter.1..=option.end_date.unwrap();
+                [
+                    self.planned_start,
+                    self.planned_complete,
+                    self.actual_start,
+                    self.actual_complete,
+                ]
+                .iter()
+                .flatten()
+                .any(|dt| range.contains(&dt.date_naive()))
+            }
             _ => {
                 let (op, date) = &option.date_filter;
                 compare_date(&self.planned_start, *op, date)
@@ -117,170 +367,446 @@ impl Task {
         }
     }
 
+    // a backlog task which has been there for the days or longer
+    pub fn is_stale(&self, now: DateTime<Local>, stale_days: u32) -> bool {
+        self.status == TaskStatus::Backlog
+            && !self.is_deleted
+            && now - self.created_at >= Duration::days(stale_days as i64)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    #[cfg(test)]
     pub fn has_higher_priority_than(&self, task: &Task) -> bool {
-        match self.status {
-            TaskStatus::Overdue => {
-                if task.status == TaskStatus::Overdue {
-                    self.planned_start.unwrap() < task.planned_start.unwrap()
-                } else {
-                    true
-                }
+        self.compare_priority(task) == Ordering::Less
+    }
+
+    // a total order in which tasks of higher priority come first: by status, then by the
+    // priority set on tasks of the same status, then by the time relevant to the status,
+    // and at last by description
+    pub fn compare_priority(&self, task: &Task) -> Ordering {
+        fn rank(status: TaskStatus) -> u8 {
+            match status {
+                TaskStatus::Overdue => 0,
+                TaskStatus::Ongoing => 1,
+                TaskStatus::Paused => 2,
+                TaskStatus::Planned => 3,
+                TaskStatus::Complete => 4,
+                TaskStatus::Backlog => 5,
             }
-            TaskStatus::Ongoing => {
-                if task.status == TaskStatus::Ongoing {
-                    self.actual_start.unwrap() > task.actual_start.unwrap()
-                } else {
-                    task.status != TaskStatus::Overdue
+        }
+        rank(self.status)
+            .cmp(&rank(task.status))
+            .then_with(|| task.priority.cmp(&self.priority))
+            .then_with(|| match self.status {
+                // the earliest planned first
+                TaskStatus::Overdue | TaskStatus::Planned => {
+                    self.planned_start.cmp(&task.planned_start)
                 }
-            }
-            TaskStatus::Planned => {
-                if task.status == TaskStatus::Planned {
-                    self.planned_start.unwrap() < task.planned_start.unwrap()
-                } else {
-                    task.status == TaskStatus::Complete || task.status == TaskStatus::Backlog
+                // the latest started first
+                TaskStatus::Ongoing | TaskStatus::Paused => {
+                    task.actual_start.cmp(&self.actual_start)
                 }
+                // the latest completed first
+                TaskStatus::Complete => task.actual_complete.cmp(&self.actual_complete),
+                TaskStatus::Backlog => Ordering::Equal,
+            })
+            .then_with(|| self.description.cmp(&task.description))
+    }
+
+    pub fn render(&self, index: usize, timeline_index: Option<char>, is_verbose: bool) -> String {
+        self.render_with_highlight(index, timeline_index, is_verbose, None)
+    }
+
+    // the line `ls` prints for the task, index included
+    pub fn render_with_highlight(
+        &self,
+        index: usize,
+        timeline_index: Option<char>,
+        is_verbose: bool,
+        highlight: Option<&str>,
+    ) -> String {
+        let padding = if index >= 10 { " " } else { "  " };
+        let prefix = match timeline_index {
+            Some(timeline_index) => format!("{index}({timeline_index}).{padding}"),
+            None => format!("{index}.{padding}"),
+        };
+        prefix + &self.format_row(is_verbose, highlight, is_plain())
+    }
+
+    // archived tasks have no index, as they cannot be referred to by commands
+    pub fn render_archived(&self, is_verbose: bool, highlight: Option<&str>) -> String {
+        format!(
+            "{}   {}",
+            "-".dimmed(),
+            self.format_row(is_verbose, highlight, is_plain())
+        )
+    }
+
+    // plain rows carry no escape sequences at all, so the status is told by a trailing tag
+    // such as `[overdue]` instead of colors
+    fn format_row(&self, is_verbose: bool, highlight: Option<&str>, plain: bool) -> String {
+        let mut row = String::new();
+        if self.is_deleted {
+            let grey = Color::TrueColor {
+                r: 100,
+                g: 100,
+                b: 100,
+            };
+            row.push_str(&format!(
+                "{}  {}",
+                "(deleted)".color(grey),
+                self.description.color(grey)
+            ));
+        } else {
+            row.push_str(&match is_verbose {
+                true => self.format_time_verbose(),
+                false => self.format_time_simple(),
+            });
+            row.push_str(&match highlight {
+                Some(pattern) => self.render_highlighted_description(pattern),
+                None => linkify_if_enabled(&self.description).bold().to_string(),
+            });
+            if self.postponed_count >= 2 {
+                row.push_str(&format!(
+                    "  {}",
+                    format!("↻{}", self.postponed_count).yellow()
+                ));
             }
-            TaskStatus::Complete => {
-                if task.status == TaskStatus::Complete {
-                    self.actual_complete.unwrap() > task.actual_complete.unwrap()
-                } else {
-                    task.status == TaskStatus::Backlog
-                }
+            if !self.tags.is_empty() {
+                row.push_str(&format!("  {}", self.render_tags_string().dimmed()));
+            }
+            if is_verbose && !self.notes.is_empty() {
+                row.push_str(&format!("  {}", "[n]".dimmed()));
+            }
+            if let Some(bar) = self.progress_bar(clock::now()).filter(|_| is_verbose) {
+                row.push_str(&format!("  {bar}"));
             }
-            TaskStatus::Backlog => false,
         }
+        if !plain {
+            return row;
+        }
+        let tag = match self.is_deleted {
+            true => "deleted",
+            false => self.status.name(),
+        };
+        format!("{}  [{tag}]", strip_ansi(&row))
     }
 
-    pub fn render(&self, index: usize, timeline_index: Option<char>, is_verbose: bool) {
-        let padding = if index >= 10 { " " } else { "  " };
-        if let Some(timeline_index) = timeline_index {
-            print!("{index}({timeline_index}).{padding}");
-        } else {
-            print!("{index}.{padding}");
+    // every attribute of the task which is set, with the notes at last, one per line
+    pub fn render_detail(&self, index: usize) -> String {
+        let mut detail = format!(
+            "{index}. {}\n",
+            linkify_if_enabled(&self.description).bold()
+        );
+        let mut field = |name: &str, value: String| {
+            detail.push_str(&format!("  {}{value}\n", pad_to_width(name, 12).dimmed()));
+        };
+        field(
+            "status",
+            match self.is_deleted {
+                true => "deleted".to_string(),
+                false => self.get_render_status_string(true),
+            },
+        );
+        if self.planned_start.is_some() {
+            field(
+                "planned",
+                format!(
+                    "{} ~ {}",
+                    format_datetime_opt(&self.planned_start),
+                    format_datetime_opt(&self.planned_complete)
+                ),
+            );
+        }
+        if self.actual_start.is_some() {
+            field(
+                "actual",
+                format!(
+                    "{} ~ {}",
+                    format_datetime_opt(&self.actual_start),
+                    format_datetime_opt(&self.actual_complete)
+                ),
+            );
+        }
+        if !self.tags.is_empty() {
+            field("tags", self.render_tags_string());
+        }
+        if self.priority > 0 {
+            field(
+                "priority",
+                PRIORITY_NAMES[self.priority as usize].to_string(),
+            );
+        }
+        if self.postponed_count > 0 {
+            field("postponed", format!("{} times", self.postponed_count));
+        }
+        if self.is_private {
+            field("private", "yes".to_string());
         }
+        if !self.notes.is_empty() {
+            detail.push_str(&format!("  {}\n", "notes".dimmed()));
+            for line in self.notes.lines() {
+                detail.push_str(&format!("    {}\n", linkify_if_enabled(line)));
+            }
+        }
+        detail
+    }
 
-        if self.is_deleted {
-            println!(
-                "{}",
-                "(deleted)".color(Color::TrueColor {
-                    r: 100,
-                    g: 100,
-                    b: 100
-                })
+    pub fn matches(&self, pattern: &str) -> bool {
+        !find_matches(&self.description, pattern).is_empty()
+    }
+
+    fn render_highlighted_description(&self, pattern: &str) -> String {
+        let mut rendered = String::new();
+        let mut last = 0;
+        for range in find_matches(&self.description, pattern) {
+            rendered.push_str(&self.description[last..range.start].bold().to_string());
+            rendered.push_str(
+                &self.description[range.clone()]
+                    .bold()
+                    .underline()
+                    .color(Color::Magenta)
+                    .to_string(),
             );
-            return;
+            last = range.end;
         }
+        rendered.push_str(&self.description[last..].bold().to_string());
+        rendered
+    }
 
-        if is_verbose {
-            self.render_time_verbose();
-        } else {
-            self.render_time_simple();
+    fn render_tags_string(&self) -> String {
+        self.tags
+            .iter()
+            .map(|tag| format!("+{tag}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // in working time if working hours are set, together with the wall clock time if verbose
+    fn format_overdue(
+        &self,
+        now: DateTime<Local>,
+        working_hours: Option<&WorkingHours>,
+        is_verbose: bool,
+    ) -> String {
+        let planned_start = self.planned_start.unwrap();
+        let gap = humanize_duration(get_duration(&planned_start, &now));
+        let overdue = "overdue".color(self.color_of_status());
+        let Some(working_hours) = working_hours else {
+            return format!("{gap} {overdue}");
+        };
+        let working = humanize_working_duration(working_hours.duration_between(planned_start, now));
+        match is_verbose {
+            true => format!("{working} {overdue}, {gap} wall clock"),
+            false => format!("{working} {overdue}"),
         }
-        println!("{}", self.description.bold());
     }
 
-    pub fn render_time_simple(&self) {
-        print!("{}  ", self.get_render_status_string());
+    fn format_time_simple(&self) -> String {
+        let mut time = String::new();
+        if self.priority >= PRIORITY_HIGH {
+            time.push_str(&format!("{} ", "!!".red().bold()));
+        }
+        time.push_str(&format!("{}  ", self.get_render_status_string(false)));
+        time
     }
 
-    pub fn render_time_verbose(&self) {
-        print!("{: <56}", self.get_render_status_string());
-        print!("{}", self.get_render_status_padding());
-        fn datetime_opt_to_str(datetime_opt: &Option<DateTime<Local>>) -> String {
-            match datetime_opt {
-                Some(dt) => dt.format("%F %R").to_string(),
-                None => "-".to_string(),
-            }
+    fn format_time_verbose(&self) -> String {
+        let mut row = pad_to_width(&self.get_render_status_string(true), STATUS_COLUMN_WIDTH);
+        for datetime_opt in [
+            &self.planned_start,
+            &self.planned_complete,
+            &self.actual_start,
+            &self.actual_complete,
+        ] {
+            row.push_str(&pad_to_width(
+                &format_datetime_opt(datetime_opt),
+                TIME_COLUMN_WIDTH,
+            ));
         }
-        print!("{: <20}", datetime_opt_to_str(&self.planned_start));
-        print!("{: <20}", datetime_opt_to_str(&self.planned_complete));
-        print!("{: <20}", datetime_opt_to_str(&self.actual_start));
-        print!("{: <20}", datetime_opt_to_str(&self.actual_complete));
+        row.push_str(&pad_to_width(&self.render_overrun(), OVERRUN_COLUMN_WIDTH));
+        row
     }
 
-    fn get_render_status_string(&self) -> String {
+    fn get_render_status_string(&self, is_verbose: bool) -> String {
         match self.status {
-            TaskStatus::Backlog => format!("in {}", "backlog".color(self.color_of_status())),
-            TaskStatus::Planned => {
-                let gap = get_duration(&Local::now(), &self.planned_start.unwrap());
+            TaskStatus::Backlog => {
+                let age = get_duration(&self.created_at, &clock::now());
                 format!(
-                    "{} to start in {} minutes",
-                    "planned".color(self.color_of_status()),
-                    gap.num_minutes()
+                    "in {} for {}",
+                    "backlog".color(backlog_age_color(age, backlog_stale_days())),
+                    humanize_duration(age)
                 )
             }
-            TaskStatus::Overdue => {
-                let gap = get_duration(&self.planned_start.unwrap(), &Local::now());
+            TaskStatus::Planned => {
+                let gap = get_duration(&clock::now(), &self.planned_start.unwrap());
                 format!(
-                    "{} minutes {}",
-                    gap.num_minutes(),
-                    "overdue".color(self.color_of_status())
+                    "{} to start in {}",
+                    "planned".color(self.color_of_status()),
+                    humanize_duration(gap)
                 )
             }
+            TaskStatus::Overdue => self.format_overdue(clock::now(), working_hours(), is_verbose),
             TaskStatus::Ongoing => {
-                let gap = get_duration(&self.actual_start.unwrap(), &Local::now());
+                let worked = self.worked_duration(clock::now());
+                let Some(planned) = self.planned_duration() else {
+                    return format!(
+                        "{} for {}",
+                        "ongoing".color(self.color_of_status()),
+                        humanize_duration(worked)
+                    );
+                };
+                let elapsed = match worked > planned {
+                    true => humanize_duration(worked)
+                        .color(TaskStatus::Overdue.color())
+                        .to_string(),
+                    false => humanize_duration(worked),
+                };
                 format!(
-                    "{} for {} minutes",
+                    "{} for {elapsed} of {} planned",
                     "ongoing".color(self.color_of_status()),
-                    gap.num_minutes()
+                    humanize_duration(planned)
                 )
             }
+            TaskStatus::Paused => format!(
+                "{} after {}",
+                "paused".color(self.color_of_status()),
+                humanize_duration(self.worked_duration(clock::now()))
+            ),
             TaskStatus::Complete => {
-                let gap = get_duration(&self.actual_complete.unwrap(), &Local::now());
-                format!(
-                    "{} {} minutes ago",
-                    "complete".color(self.color_of_status()),
-                    gap.num_minutes()
-                )
+                let gap = get_duration(&self.actual_complete.unwrap(), &clock::now());
+                let ago = match gap.num_minutes() {
+                    0 => "just now".to_string(),
+                    _ => format!("{} ago", humanize_duration(gap)),
+                };
+                // the time worked is only worth telling if it differs from the actual period
+                let worked = match self.work_intervals.len() {
+                    0 | 1 => String::new(),
+                    _ => format!(
+                        " ({})",
+                        humanize_duration(self.worked_duration(clock::now()))
+                    ),
+                };
+                format!("{} {ago}{worked}", "complete".color(self.color_of_status()))
             }
         }
     }
 
-    fn get_render_status_padding(&self) -> String {
+    pub fn color_of_status(&self) -> Color {
+        self.status.color()
+    }
+
+    fn planned_duration(&self) -> Option<Duration> {
+        Some(get_duration(&self.planned_start?, &self.planned_complete?))
+    }
+
+    // how much longer than planned the task has been worked on, negative if shorter, for
+    // started tasks with a planned period only
+    pub fn overrun(&self, now: DateTime<Local>) -> Option<Duration> {
         match self.status {
-            TaskStatus::Backlog | TaskStatus::Overdue | TaskStatus::Ongoing => "  ".to_string(),
-            TaskStatus::Planned => " ".to_string(),
-            TaskStatus::Complete => "".to_string(),
+            TaskStatus::Ongoing | TaskStatus::Paused | TaskStatus::Complete => {
+                self.actual_start?;
+                Some(self.worked_duration(now) - self.planned_duration()?)
+            }
+            _ => None,
         }
     }
 
-    pub fn color_of_status(&self) -> Color {
-        const COLOR_GREY: Color = Color::TrueColor {
-            r: 128,
-            g: 128,
-            b: 128,
-        };
-        const COLOR_CYAN: Color = Color::TrueColor {
-            r: 51,
-            g: 255,
-            b: 255,
-        };
-        const COLOR_RED: Color = Color::TrueColor {
-            r: 255,
-            g: 102,
-            b: 102,
-        };
-        const COLOR_YELLOW: Color = Color::TrueColor {
-            r: 255,
-            g: 255,
-            b: 102,
-        };
-        const COLOR_GREEN: Color = Color::TrueColor {
-            r: 51,
-            g: 255,
-            b: 51,
-        };
-        match self.status {
-            TaskStatus::Backlog => COLOR_GREY,
-            TaskStatus::Planned => COLOR_CYAN,
-            TaskStatus::Overdue => COLOR_RED,
-            TaskStatus::Ongoing => COLOR_YELLOW,
-            TaskStatus::Complete => COLOR_GREEN,
+    // how far an ongoing task is into its planned period, by the time since its actual start
+    fn progress_bar(&self, now: DateTime<Local>) -> Option<String> {
+        if self.status != TaskStatus::Ongoing {
+            return None;
+        }
+        let elapsed = get_duration(&self.actual_start?, &now);
+        Some(render_progress_bar(
+            elapsed,
+            self.planned_duration()?,
+            PROGRESS_BAR_WIDTH,
+        ))
+    }
+
+    fn render_overrun(&self) -> String {
+        match self.overrun(clock::now()) {
+            None => "-".to_string(),
+            Some(overrun) if overrun.num_minutes() > 0 => {
+                format!("+{}", humanize_duration(overrun))
+                    .color(TaskStatus::Overdue.color())
+                    .to_string()
+            }
+            Some(overrun) if overrun.num_minutes() < 0 => {
+                format!("-{}", humanize_duration(-overrun))
+                    .color(TaskStatus::Complete.color())
+                    .to_string()
+            }
+            Some(_) => "0 minutes".to_string(),
         }
     }
 }
 
-fn compare_date(self_dt: &Option<DateTime<Local>>, op: DateFilterOp, date: &NaiveDate) -> bool {
+// the columns of verbose listings, after the index
+const STATUS_COLUMN_WIDTH: usize = 45;
+const TIME_COLUMN_WIDTH: usize = 20;
+const OVERRUN_COLUMN_WIDTH: usize = 14;
+
+const PROGRESS_BAR_WIDTH: usize = 10;
+// the bar grows past its width up to this ratio, in red
+const PROGRESS_MAX_RATIO: f64 = 1.5;
+
+// e.g. `[=====>    ] 55%`, the width is what 100% takes; a zero-length plan is exceeded as soon
+// as any time has passed
+pub fn render_progress_bar(elapsed: Duration, planned: Duration, width: usize) -> String {
+    let ratio = match (planned.num_seconds(), elapsed.num_seconds().max(0)) {
+        (_, 0) => 0.0,
+        (planned, _) if planned <= 0 => PROGRESS_MAX_RATIO,
+        (planned, elapsed) => elapsed as f64 / planned as f64,
+    }
+    .min(PROGRESS_MAX_RATIO);
+    let filled = (ratio.min(1.0) * width as f64).round() as usize;
+    let mut bar = match filled {
+        0 => String::new(),
+        filled if filled < width => "=".repeat(filled - 1) + ">",
+        _ => "=".repeat(width),
+    };
+    bar.push_str(&" ".repeat(width - filled));
+    let overflow = ((ratio - 1.0).max(0.0) * width as f64).round() as usize;
+    if overflow > 0 {
+        let overflow = "=".repeat(overflow).color(TaskStatus::Overdue.color());
+        bar.push_str(&overflow.to_string());
+    }
+    format!("[{bar}] {}%", (ratio * 100.0).round())
+}
+
+// the column names above verbose rows and the rule under them, `indent` being the width of
+// the index column
+pub fn render_verbose_header(indent: usize) -> String {
+    let mut header = " ".repeat(indent) + &pad_to_width("status", STATUS_COLUMN_WIDTH);
+    for column in [
+        "planned start",
+        "planned complete",
+        "actual start",
+        "actual complete",
+    ] {
+        header.push_str(&pad_to_width(column, TIME_COLUMN_WIDTH));
+    }
+    header.push_str(&pad_to_width("overrun", OVERRUN_COLUMN_WIDTH));
+    format!(
+        "{}\n{}",
+        format!("{header}description").dimmed(),
+        "-".repeat(
+            indent
+                + STATUS_COLUMN_WIDTH
+                + 4 * TIME_COLUMN_WIDTH
+                + OVERRUN_COLUMN_WIDTH
+                + "description".len()
+        )
+        .dimmed()
+    )
+}
+
+pub fn compare_date(self_dt: &Option<DateTime<Local>>, op: DateFilterOp, date: &NaiveDate) -> bool {
     self_dt.is_some()
         && match op {
             DateFilterOp::Earlier => self_dt.unwrap().date_naive() < *date,
@@ -291,15 +817,268 @@ fn compare_date(self_dt: &Option<DateTime<Local>>, op: DateFilterOp, date: &Naiv
         }
 }
 
+// find all non-overlapping case-insensitive occurrences of `pattern`,
+// returned as byte ranges into `haystack`
+fn find_matches(haystack: &str, pattern: &str) -> Vec<Range<usize>> {
+    let mut matches = vec![];
+    if pattern.is_empty() {
+        return matches;
+    }
+    let mut start = 0;
+    while start < haystack.len() {
+        let mut needle = pattern.chars().flat_map(char::to_lowercase);
+        let mut end = start;
+        let mut next_needle = needle.next();
+        for (offset, c) in haystack[start..].char_indices() {
+            let mut lowered = c.to_lowercase();
+            let matched = lowered.all(|lc| {
+                let ok = next_needle == Some(lc);
+                if ok {
+                    next_needle = needle.next();
+                }
+                ok
+            });
+            if !matched {
+                break;
+            }
+            end = start + offset + c.len_utf8();
+            if next_needle.is_none() {
+                break;
+            }
+        }
+        if next_needle.is_none() {
+            matches.push(start..end);
+            start = end;
+        } else {
+            start += haystack[start..].chars().next().unwrap().len_utf8();
+        }
+    }
+    matches
+}
+
+fn format_datetime_opt(datetime_opt: &Option<DateTime<Local>>) -> String {
+    match datetime_opt {
+        Some(dt) => dt.format(time_format()).to_string(),
+        None => "-".to_string(),
+    }
+}
+
+// saturates to zero if `t1` is not after `t0`, e.g. a task completed in the same instant as
+// rendering, or a timestamp edited into the future
 fn get_duration(t0: &DateTime<Local>, t1: &DateTime<Local>) -> Duration {
-    assert!(*t1 > *t0);
-    *t1 - *t0
+    (*t1 - *t0).max(Duration::zero())
+}
+
+// shifts from the grey of backlog toward the red of overdue as the task gets stale
+fn backlog_age_color(age: Duration, stale_days: u32) -> Color {
+    let ratio = (age.num_minutes() as f64 / (stale_days as f64 * 24.0 * 60.0)).clamp(0.0, 1.0);
+    match (TaskStatus::Backlog.color(), TaskStatus::Overdue.color()) {
+        (
+            Color::TrueColor {
+                r: r0,
+                g: g0,
+                b: b0,
+            },
+            Color::TrueColor {
+                r: r1,
+                g: g1,
+                b: b1,
+            },
+        ) => {
+            let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * ratio) as u8;
+            Color::TrueColor {
+                r: mix(r0, r1),
+                g: mix(g0, g1),
+                b: mix(b0, b1),
+            }
+        }
+        (grey, _) => grey,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::display::display_width;
     use chrono::naive::Days;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_status_string_of_timestamps_not_in_the_past() {
+        let _frozen = clock::freeze("2024-03-04 23:59");
+        let mut task = Task::new_immediate_task("just started");
+        assert_eq!(
+            strip_ansi(&task.get_render_status_string(false)),
+            "ongoing for 0 minutes"
+        );
+        task.complete();
+        assert_eq!(
+            strip_ansi(&task.get_render_status_string(false)),
+            "complete just now"
+        );
+
+        // edited by hand into the future
+        let tomorrow = clock::now() + Duration::days(1);
+        task.actual_complete = Some(tomorrow);
+        assert_eq!(
+            strip_ansi(&task.get_render_status_string(false)),
+            "complete just now"
+        );
+        task.actual_start = Some(tomorrow);
+        task.actual_complete = None;
+        task.status = TaskStatus::Ongoing;
+        assert_eq!(
+            strip_ansi(&task.get_render_status_string(false)),
+            "ongoing for 0 minutes"
+        );
+    }
+
+    #[test]
+    fn test_overdue_in_working_time() {
+        // planned for friday 16:00 and looked at on monday 9:00
+        let friday = Local.with_ymd_and_hms(2024, 3, 8, 16, 0, 0).unwrap();
+        let monday = Local.with_ymd_and_hms(2024, 3, 11, 9, 0, 0).unwrap();
+        let mut task = Task::new_planned_task("overdue", friday, friday + Duration::hours(1));
+        task.status = TaskStatus::Overdue;
+        let hours = WorkingHours {
+            hours: (9, 18),
+            days: crate::working::DEFAULT_WORKING_DAYS.to_vec(),
+        };
+        let overdue = |working_hours, is_verbose| {
+            strip_ansi(&task.format_overdue(monday, working_hours, is_verbose))
+        };
+        assert_eq!(overdue(None, false), "3 days overdue");
+        assert_eq!(overdue(Some(&hours), false), "2 working hours overdue");
+        assert_eq!(
+            overdue(Some(&hours), true),
+            "2 working hours overdue, 3 days wall clock"
+        );
+    }
+
+    #[test]
+    fn test_ongoing_status_string_and_overrun() {
+        colored::control::set_override(true);
+        let _frozen = clock::freeze("2024-03-04 10:00");
+        let now = clock::now();
+        let at = |minutes| now + Duration::minutes(minutes);
+        let mut task = Task::new_planned_task("review", at(-30), at(30));
+        task.start();
+        task.actual_start = Some(at(-45));
+        task.work_intervals = vec![(at(-45), None)];
+        task.update_status();
+        let status = task.get_render_status_string(false);
+        assert_eq!(
+            strip_ansi(&status),
+            "ongoing for 45 minutes of 60 minutes planned"
+        );
+        assert!(!status.contains("45 minutes\x1b"));
+        assert_eq!(task.overrun(now).unwrap().num_minutes(), -15);
+
+        task.planned_complete = Some(at(0));
+        let status = task.get_render_status_string(false);
+        assert_eq!(
+            strip_ansi(&status),
+            "ongoing for 45 minutes of 30 minutes planned"
+        );
+        let red = "45 minutes".color(TaskStatus::Overdue.color()).to_string();
+        assert!(status.contains(&red));
+        assert_eq!(strip_ansi(&task.render_overrun()), "+15 minutes");
+
+        task.complete();
+        task.actual_complete = Some(now);
+        task.work_intervals = vec![(at(-45), Some(now))];
+        task.update_status();
+        assert_eq!(strip_ansi(&task.render_overrun()), "+15 minutes");
+        task.planned_complete = Some(at(60));
+        assert_eq!(strip_ansi(&task.render_overrun()), "-45 minutes");
+
+        // no plan to compare with
+        let task = Task::new_immediate_task("unplanned");
+        assert_eq!(task.overrun(now), None);
+        assert_eq!(task.render_overrun(), "-");
+    }
+
+    #[test]
+    fn test_start_paused_task_resumes_it() {
+        let mut task = Task::new_immediate_task("write report");
+        task.start();
+        let started = task.actual_start;
+        task.pause();
+        let worked = task.work_intervals.clone();
+        task.start();
+        assert_eq!(task.status, TaskStatus::Ongoing);
+        assert_eq!(task.actual_start, started);
+        assert_eq!(task.work_intervals.len(), 2);
+        assert_eq!(task.work_intervals[0], worked[0]);
+        assert_eq!(task.work_intervals[1].1, None);
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let now = clock::now();
+        let at = |minutes| now - Duration::minutes(minutes);
+        let mut task = Task::new_immediate_task("write report");
+        task.actual_start = Some(at(180));
+        task.work_intervals = vec![(at(180), Some(at(120))), (at(60), None)];
+        task.update_status();
+        assert_eq!(task.status, TaskStatus::Ongoing);
+        assert_eq!(task.worked_duration(at(0)), Duration::minutes(120));
+
+        task.pause();
+        task.update_status();
+        assert_eq!(task.status, TaskStatus::Paused);
+        let paused_at = task.paused_at().unwrap();
+        assert_eq!(
+            task.worked_duration(paused_at + Duration::hours(1)),
+            task.worked_duration(paused_at)
+        );
+        assert!(
+            strip_ansi(&task.get_render_status_string(false)).starts_with("paused after 2 hours")
+        );
+
+        task.resume();
+        assert_eq!(task.status, TaskStatus::Ongoing);
+        assert_eq!(task.work_intervals.len(), 3);
+        task.complete();
+        assert_eq!(task.paused_at(), None);
+        assert!(task.work_intervals.iter().all(|(_, end)| end.is_some()));
+        assert!(
+            strip_ansi(&task.get_render_status_string(false)).starts_with("complete just now (2")
+        );
+
+        // started before pausing was supported
+        let mut legacy = Task::new_immediate_task("legacy");
+        legacy.actual_start = Some(at(30));
+        legacy.work_intervals.clear();
+        assert_eq!(legacy.worked_duration(at(0)).num_minutes(), 30);
+        legacy.pause();
+        assert_eq!(legacy.work_intervals[0].0, at(30));
+        assert!(legacy.paused_at().is_some());
+    }
+
+    #[test]
+    fn test_render_detail() {
+        let task = Task {
+            description: "write report".to_string(),
+            status: TaskStatus::Backlog,
+            tags: vec!["work".to_string()],
+            priority: PRIORITY_HIGH,
+            notes: "outline:\n- numbers".to_string(),
+            ..task_template()
+        };
+        assert_eq!(
+            strip_ansi(&task.render_detail(3)),
+            concat!(
+                "3. write report\n",
+                "  status      in backlog for 0 minutes\n",
+                "  tags        +work\n",
+                "  priority    high\n",
+                "  notes\n",
+                "    outline:\n",
+                "    - numbers\n",
+            )
+        );
+    }
 
     #[test]
     fn test_new_immediate_task() {
@@ -345,6 +1124,37 @@ mod tests {
         assert_eq!(task.status, TaskStatus::Overdue);
     }
 
+    #[test]
+    fn test_backlog_aging() {
+        let now = clock::now();
+        let mut task = Task::new_backlog_task("read");
+        task.created_at = now - Duration::days(12);
+        assert!(!task.is_stale(now, 30));
+        assert!(task.is_stale(now, 12));
+        assert!(task
+            .get_render_status_string(false)
+            .ends_with(" for 12 days"));
+        task.status = TaskStatus::Planned;
+        assert!(!task.is_stale(now, 12));
+
+        assert_eq!(
+            backlog_age_color(Duration::zero(), 30),
+            TaskStatus::Backlog.color()
+        );
+        assert_eq!(
+            backlog_age_color(Duration::days(45), 30),
+            TaskStatus::Overdue.color()
+        );
+        assert_eq!(
+            backlog_age_color(Duration::days(15), 30),
+            Color::TrueColor {
+                r: 191,
+                g: 115,
+                b: 115
+            }
+        );
+    }
+
     fn task_template() -> Task {
         Task {
             description: "".to_string(),
@@ -354,9 +1164,88 @@ mod tests {
             actual_complete: None,
             status: TaskStatus::Planned,
             is_deleted: false,
+            tags: vec![],
+            postponed_count: 0,
+            priority: 0,
+            is_private: false,
+            work_intervals: vec![],
+            notes: String::new(),
+            created_at: clock::now(),
+            extras: vec![],
         }
     }
 
+    #[test]
+    fn test_validate_periods() {
+        let at = |hour: u32| Some(Local.with_ymd_and_hms(2024, 3, 4, hour, 0, 0).unwrap());
+        let task = |planned: (u32, u32), actual: (u32, u32)| Task {
+            planned_start: at(planned.0),
+            planned_complete: at(planned.1),
+            actual_start: at(actual.0),
+            actual_complete: at(actual.1),
+            ..task_template()
+        };
+        assert_eq!(task((9, 10), (9, 11)).validate_periods(), Ok(()));
+        // equal timestamps make an empty period, which is fine
+        assert_eq!(task((9, 9), (10, 10)).validate_periods(), Ok(()));
+        assert_eq!(
+            task((10, 9), (9, 11)).validate_periods(),
+            Err(
+                "planned start 2024-03-04 10:00 is later than planned complete 2024-03-04 09:00"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            task((9, 10), (11, 9)).validate_periods(),
+            Err(
+                "actual start 2024-03-04 11:00 is later than actual complete 2024-03-04 09:00"
+                    .to_string()
+            )
+        );
+        // only one end set is not a period yet
+        let ongoing = Task {
+            actual_start: at(11),
+            ..task_template()
+        };
+        assert_eq!(ongoing.validate_periods(), Ok(()));
+    }
+
+    #[test]
+    fn test_render_progress_bar() {
+        colored::control::set_override(true);
+        let minutes = Duration::minutes;
+        assert_eq!(
+            render_progress_bar(minutes(0), minutes(60), 10),
+            "[          ] 0%"
+        );
+        assert_eq!(
+            render_progress_bar(minutes(33), minutes(60), 10),
+            "[=====>    ] 55%"
+        );
+        assert_eq!(
+            render_progress_bar(minutes(60), minutes(60), 10),
+            "[==========] 100%"
+        );
+        let over = render_progress_bar(minutes(72), minutes(60), 10);
+        assert_eq!(strip_ansi(&over), "[============] 120%");
+        let red = "==".color(TaskStatus::Overdue.color()).to_string();
+        assert_eq!(over, format!("[=========={red}] 120%"));
+        // capped
+        assert_eq!(
+            strip_ansi(&render_progress_bar(minutes(600), minutes(60), 10)),
+            "[===============] 150%"
+        );
+        // a zero-length plan
+        assert_eq!(
+            render_progress_bar(minutes(0), minutes(0), 10),
+            "[          ] 0%"
+        );
+        assert_eq!(
+            strip_ansi(&render_progress_bar(minutes(1), minutes(0), 10)),
+            "[===============] 150%"
+        );
+    }
+
     #[test]
     fn test_render_simple() {
         let task = Task {
@@ -364,38 +1253,262 @@ mod tests {
             status: TaskStatus::Backlog,
             ..task_template()
         };
-        task.render(0, None, false);
+        let row = strip_ansi(&task.render(0, None, false));
+        assert!(
+            row.starts_with("0.  ") && row.ends_with("planned but no schedule"),
+            "{row}"
+        );
 
         let task = Task {
             description: "planned but no schedule".to_string(),
-            planned_start: Local::now().checked_add_days(Days::new(1)),
+            planned_start: clock::now().checked_add_days(Days::new(1)),
             ..task_template()
         };
-        task.render(1, None, false);
+        let row = strip_ansi(&task.render(1, None, false));
+        assert!(
+            row.starts_with("1.  ") && row.ends_with("planned but no schedule"),
+            "{row}"
+        );
 
         let task = Task {
             description: "overdue".to_string(),
-            planned_start: Local::now().checked_sub_days(Days::new(1)),
+            planned_start: clock::now().checked_sub_days(Days::new(1)),
             status: TaskStatus::Overdue,
             ..task_template()
         };
-        task.render(2, None, false);
+        let row = strip_ansi(&task.render(2, None, false));
+        assert!(row.starts_with("2.  ") && row.ends_with("overdue"), "{row}");
 
         let task = Task {
             description: "ongoing".to_string(),
-            actual_start: Local::now().checked_sub_days(Days::new(1)),
+            actual_start: clock::now().checked_sub_days(Days::new(1)),
             status: TaskStatus::Ongoing,
             ..task_template()
         };
-        task.render(3, None, false);
+        let row = strip_ansi(&task.render(3, None, false));
+        assert!(row.starts_with("3.  ") && row.ends_with("ongoing"), "{row}");
 
         let task = Task {
             description: "complete".to_string(),
-            actual_complete: Local::now().checked_sub_days(Days::new(1)),
+            actual_complete: clock::now().checked_sub_days(Days::new(1)),
             status: TaskStatus::Complete,
             ..task_template()
         };
-        task.render(4, None, false);
+        let row = strip_ansi(&task.render(4, None, false));
+        assert!(
+            row.starts_with("4.  ") && row.ends_with("complete"),
+            "{row}"
+        );
+    }
+
+    #[test]
+    fn test_verbose_columns_align_across_statuses() {
+        colored::control::set_override(true);
+        let day = Days::new(1);
+        let tasks = [
+            Task {
+                status: TaskStatus::Backlog,
+                ..task_template()
+            },
+            Task {
+                planned_start: clock::now().checked_add_days(day),
+                planned_complete: clock::now().checked_add_days(day),
+                ..task_template()
+            },
+            Task {
+                planned_start: clock::now().checked_sub_days(day),
+                planned_complete: clock::now().checked_sub_days(day),
+                status: TaskStatus::Overdue,
+                ..task_template()
+            },
+            Task {
+                actual_start: clock::now().checked_sub_days(day),
+                status: TaskStatus::Ongoing,
+                ..task_template()
+            },
+            Task {
+                actual_start: clock::now().checked_sub_days(day),
+                actual_complete: clock::now().checked_sub_days(day),
+                status: TaskStatus::Complete,
+                ..task_template()
+            },
+        ];
+        for task in &tasks {
+            let row = task.format_time_verbose();
+            assert!(row.contains('\x1b'));
+            let row = strip_ansi(&row);
+            assert_eq!(
+                display_width(&row),
+                STATUS_COLUMN_WIDTH + 4 * TIME_COLUMN_WIDTH + OVERRUN_COLUMN_WIDTH
+            );
+            // every column starts with either a time, an overrun or `-`
+            for column in 0..5 {
+                let offset = STATUS_COLUMN_WIDTH + column * TIME_COLUMN_WIDTH;
+                assert_eq!(row.as_bytes()[offset - 1], b' ');
+                assert_ne!(row.as_bytes()[offset], b' ', "{row}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_plain_rows_have_no_escapes() {
+        colored::control::set_override(true);
+        let now = clock::now();
+        let hour = Duration::hours(1);
+        let tasks = [
+            (TaskStatus::Backlog, None, None),
+            (TaskStatus::Planned, Some(now + hour), None),
+            (TaskStatus::Overdue, Some(now - hour), None),
+            (TaskStatus::Ongoing, None, Some(now - hour)),
+            (TaskStatus::Paused, None, Some(now - hour)),
+            (TaskStatus::Complete, None, Some(now - hour)),
+        ]
+        .map(|(status, planned, actual)| Task {
+            description: "see https://example.com".to_string(),
+            planned_start: planned,
+            planned_complete: planned.map(|dt| dt + hour),
+            actual_start: actual,
+            actual_complete: actual.filter(|_| status == TaskStatus::Complete),
+            status,
+            tags: vec!["work".to_string()],
+            postponed_count: 3,
+            priority: PRIORITY_HIGH,
+            notes: "a note".to_string(),
+            ..task_template()
+        });
+        for task in &tasks {
+            for is_verbose in [false, true] {
+                for highlight in [None, Some("example")] {
+                    let row = task.format_row(is_verbose, highlight, true);
+                    assert!(!row.contains('\x1b'), "{row:?}");
+                    assert!(row.ends_with(&format!("[{}]", task.status.name())), "{row}");
+                }
+            }
+            assert!(task.format_row(false, None, false).contains('\x1b'));
+        }
+        let deleted = Task {
+            is_deleted: true,
+            ..task_template()
+        };
+        let row = deleted.format_row(false, None, true);
+        assert!(!row.contains('\x1b'));
+        assert!(row.ends_with("[deleted]"));
+    }
+
+    #[test]
+    fn test_satisfy_status_filter() {
+        use crate::command::{parse_command, Command};
+        let list_option = |line: &str| match parse_command(line) {
+            Some(Command::List(option)) => option,
+            _ => panic!("`{line}` should be a list command"),
+        };
+        let overdue_last_week = Task {
+            planned_start: clock::now().checked_sub_days(Days::new(7)),
+            planned_complete: clock::now().checked_sub_days(Days::new(7)),
+            status: TaskStatus::Overdue,
+            ..task_template()
+        };
+        let ongoing = Task {
+            actual_start: Some(clock::now()),
+            status: TaskStatus::Ongoing,
+            ..task_template()
+        };
+        let backlog = Task {
+            status: TaskStatus::Backlog,
+            ..task_template()
+        };
+        assert!(overdue_last_week.satisfy(&list_option("ls @overdue 0")));
+        assert!(!ongoing.satisfy(&list_option("ls @overdue")));
+        assert!(ongoing.satisfy(&list_option("ls @ongoing")));
+        assert!(!ongoing.satisfy(&list_option("ls @ongoing -1")));
+        assert!(backlog.satisfy(&list_option("ls @backlog")));
+        let deleted = Task {
+            is_deleted: true,
+            ..ongoing
+        };
+        assert!(!deleted.satisfy(&list_option("ls @ongoing")));
+        assert!(!deleted.satisfy(&list_option("ls")));
+        let ongoing_for_days = Task {
+            status: TaskStatus::Ongoing,
+            actual_start: Some(clock::now() - Duration::days(2)),
+            ..task_template()
+        };
+        let paused_for_days = Task {
+            status: TaskStatus::Paused,
+            actual_start: ongoing_for_days.actual_start,
+            ..task_template()
+        };
+        for task in [&ongoing_for_days, &paused_for_days] {
+            assert!(task.satisfy(&list_option("ls")));
+            assert!(task.satisfy(&list_option("ls >=1")));
+            assert!(task.satisfy(&list_option("ls -1..1")));
+        }
+        assert!(!ongoing_for_days.satisfy(&list_option("ls -1")));
+        assert!(!ongoing_for_days.satisfy(&list_option("ls -3")));
+        assert!(deleted.satisfy(&list_option("ls d")));
+    }
+
+    #[test]
+    fn test_satisfy_date_range() {
+        use crate::command::{parse_command, Command};
+        let list_option = |line: &str| match parse_command(line) {
+            Some(Command::List(option)) => option,
+            _ => panic!("`{line}` should be a list command"),
+        };
+        let complete_yesterday = Task {
+            actual_start: clock::now().checked_sub_days(Days::new(1)),
+            actual_complete: clock::now().checked_sub_days(Days::new(1)),
+            status: TaskStatus::Complete,
+            ..task_template()
+        };
+        assert!(complete_yesterday.satisfy(&list_option("ls -1..1")));
+        assert!(complete_yesterday.satisfy(&list_option("ls -3..-1")));
+        assert!(!complete_yesterday.satisfy(&list_option("ls 0..2")));
+        // a later date filter replaces the range
+        assert!(!complete_yesterday.satisfy(&list_option("ls -1..1 0")));
+    }
+
+    #[test]
+    fn test_satisfy_text_filter() {
+        use crate::command::{parse_command, Command};
+        let list_option = |line: &str| match parse_command(line) {
+            Some(Command::List(option)) => option,
+            _ => panic!("`{line}` should be a list command"),
+        };
+        let backlog = Task {
+            description: "Send the March Invoice".to_string(),
+            status: TaskStatus::Backlog,
+            ..task_template()
+        };
+        assert!(backlog.satisfy(&list_option("ls b /invoice")));
+        assert!(!backlog.satisfy(&list_option("ls b /receipt")));
+        // the other filters still apply
+        assert!(!backlog.satisfy(&list_option("ls /invoice")));
+    }
+
+    #[test]
+    fn test_reschedule() {
+        let now = clock::now();
+        let mut task = Task::new_planned_task("task", now, now + Duration::hours(1));
+        task.reschedule(Some(now), Some(now + Duration::hours(2)));
+        assert_eq!(task.postponed_count, 0);
+        task.reschedule(Some(now - Duration::hours(1)), Some(now));
+        assert_eq!(task.postponed_count, 0);
+        task.reschedule(Some(now + Duration::days(1)), Some(now + Duration::days(1)));
+        assert_eq!(task.postponed_count, 1);
+        task.reschedule(None, None);
+        task.reschedule(Some(now + Duration::days(2)), Some(now + Duration::days(2)));
+        assert_eq!(task.postponed_count, 1);
+        assert_eq!(task.planned_start, Some(now + Duration::days(2)));
+    }
+
+    #[test]
+    fn test_find_matches() {
+        assert_eq!(find_matches("Pay the Invoice", "invoice"), vec![8..15]);
+        assert_eq!(find_matches("abcABCabc", "ABC"), vec![0..3, 3..6, 6..9]);
+        assert_eq!(find_matches("写周报 report", "周报"), vec![3..9]);
+        assert_eq!(find_matches("invoice", "invoices"), vec![]);
+        assert_eq!(find_matches("invoice", ""), vec![]);
     }
 
     #[test]
@@ -421,36 +1534,33 @@ mod tests {
     }
 
     #[test]
-    #[cfg_attr(target_os = "windows", ignore)]
-    // on windows this test case can panic at
-    // 'SystemTimeToFileTime failed with: The parameter is incorrect. (os error 87)'
-    // don't know why.. let's ignore it for now
     fn test_has_higher_priority_than() {
+        let _frozen = clock::freeze("2024-03-04 12:00");
         fn overdue_task(gap: i64) -> Task {
             Task {
                 status: TaskStatus::Overdue,
-                planned_start: Some(Local::now() + Duration::minutes(gap)),
+                planned_start: Some(clock::now() + Duration::minutes(gap)),
                 ..task_template()
             }
         }
         fn ongoing_task(gap: i64) -> Task {
             Task {
                 status: TaskStatus::Ongoing,
-                actual_start: Some(Local::now() + Duration::minutes(gap)),
+                actual_start: Some(clock::now() + Duration::minutes(gap)),
                 ..task_template()
             }
         }
         fn planned_task(gap: i64) -> Task {
             Task {
                 status: TaskStatus::Planned,
-                planned_start: Some(Local::now() + Duration::minutes(gap)),
+                planned_start: Some(clock::now() + Duration::minutes(gap)),
                 ..task_template()
             }
         }
         fn done_task(gap: i64) -> Task {
             Task {
                 status: TaskStatus::Complete,
-                actual_complete: Some(Local::now() + Duration::minutes(gap)),
+                actual_complete: Some(clock::now() + Duration::minutes(gap)),
                 ..task_template()
             }
         }
@@ -468,5 +1578,85 @@ mod tests {
         assert!(ongoing_task(-1).has_higher_priority_than(&planned_task(2)));
         assert!(planned_task(1).has_higher_priority_than(&done_task(-2)));
         assert!(done_task(-1).has_higher_priority_than(&backlog_task()));
+        let paused_task = || Task {
+            status: TaskStatus::Paused,
+            actual_start: Some(clock::now()),
+            ..task_template()
+        };
+        assert!(ongoing_task(-1).has_higher_priority_than(&paused_task()));
+        assert!(!paused_task().has_higher_priority_than(&ongoing_task(-1)));
+        assert!(paused_task().has_higher_priority_than(&planned_task(1)));
+        assert!(!planned_task(1).has_higher_priority_than(&paused_task()));
+
+        let prioritized = |mut task: Task, priority: u8| {
+            task.priority = priority;
+            task
+        };
+        assert!(prioritized(overdue_task(-1), 2).has_higher_priority_than(&overdue_task(-2)));
+        assert!(prioritized(planned_task(2), 1).has_higher_priority_than(&planned_task(1)));
+        assert!(!planned_task(1).has_higher_priority_than(&prioritized(planned_task(2), 3)));
+        assert!(overdue_task(-1).has_higher_priority_than(&prioritized(ongoing_task(-1), 3)));
+    }
+
+    #[test]
+    fn test_compare_priority_is_a_total_order() {
+        let now = clock::now();
+        let mut tasks = vec![];
+        for (status, description, priority, minutes) in [
+            (TaskStatus::Overdue, "b", 0, -30),
+            (TaskStatus::Overdue, "a", 0, -30),
+            (TaskStatus::Overdue, "c", 2, -10),
+            (TaskStatus::Ongoing, "a", 0, -20),
+            (TaskStatus::Ongoing, "a", 0, -20),
+            (TaskStatus::Paused, "d", 1, -40),
+            (TaskStatus::Planned, "e", 0, 10),
+            (TaskStatus::Planned, "a", 3, 20),
+            (TaskStatus::Complete, "f", 0, -5),
+            (TaskStatus::Complete, "g", 0, -5),
+            (TaskStatus::Backlog, "b", 0, 0),
+            (TaskStatus::Backlog, "a", 0, 0),
+            (TaskStatus::Backlog, "a", 1, 0),
+        ] {
+            let time = Some(now + Duration::minutes(minutes));
+            tasks.push(Task {
+                description: description.to_string(),
+                status,
+                priority,
+                planned_start: time,
+                actual_start: time,
+                actual_complete: time,
+                ..task_template()
+            });
+        }
+        for a in &tasks {
+            assert_eq!(a.compare_priority(a), Ordering::Equal);
+            for b in &tasks {
+                assert_eq!(a.compare_priority(b), b.compare_priority(a).reverse());
+                for c in &tasks {
+                    if a.compare_priority(b) != Ordering::Greater
+                        && b.compare_priority(c) != Ordering::Greater
+                    {
+                        assert_ne!(a.compare_priority(c), Ordering::Greater);
+                    }
+                }
+            }
+        }
+        // ties on everything but the description are broken by it
+        assert!(tasks[1].has_higher_priority_than(&tasks[0]));
+        assert!(tasks[11].has_higher_priority_than(&tasks[10]));
+
+        let descriptions = |tasks: &[Task]| {
+            tasks
+                .iter()
+                .map(|task| format!("{}{}", task.status.name(), task.description))
+                .collect::<Vec<String>>()
+        };
+        tasks.reverse();
+        tasks.sort_by(Task::compare_priority);
+        let sorted = descriptions(&tasks);
+        tasks.sort_by(Task::compare_priority);
+        assert_eq!(descriptions(&tasks), sorted);
+        assert_eq!(sorted[0], "overduec");
+        assert_eq!(sorted[12], "backlogb");
     }
 }
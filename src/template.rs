@@ -0,0 +1,180 @@
+use crate::command::parse_tags;
+use crate::error::{ArentaError, ArentaResult};
+use crate::task::Task;
+use chrono::{DateTime, Duration, Local};
+use csv::{ReaderBuilder, WriterBuilder};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// a task to plan again and again, keeping how long it takes instead of when
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub priority: u8,
+    pub minutes: i64,
+}
+
+impl Template {
+    pub fn from_task(name: &str, task: &Task) -> Result<Template, String> {
+        let (Some(start), Some(complete)) = (task.planned_start, task.planned_complete) else {
+            return Err("only a task with a planned time could be saved as a template".to_string());
+        };
+        Ok(Template {
+            name: name.to_string(),
+            description: task.description.clone(),
+            tags: task.tags.clone(),
+            priority: task.priority,
+            minutes: (complete - start).num_minutes(),
+        })
+    }
+
+    pub fn instantiate(&self, start: DateTime<Local>) -> Task {
+        let complete = start + Duration::minutes(self.minutes);
+        let mut task = Task::new_planned_task(&self.description, start, complete);
+        task.tags = self.tags.clone();
+        task.priority = self.priority;
+        task
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.description.clone(),
+            self.tags.join(";"),
+            self.priority.to_string(),
+            self.minutes.to_string(),
+        ]
+    }
+
+    fn from_row(row: &csv::StringRecord) -> Result<Template, String> {
+        let [name, description, tags, priority, minutes] =
+            [0, 1, 2, 3, 4].map(|index| row.get(index).ok_or("missing columns".to_string()));
+        Ok(Template {
+            name: name?.to_string(),
+            description: description?.to_string(),
+            tags: parse_tags(tags?),
+            priority: priority?
+                .parse()
+                .map_err(|_| "invalid priority".to_string())?,
+            minutes: minutes?
+                .parse()
+                .map_err(|_| "invalid minutes".to_string())?,
+        })
+    }
+}
+
+// names are typed after `n @`, so they are kept to a single plain word
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name == "now" || name == "backlog" {
+        return Err(format!("`{name}` is reserved by `n @{name}`"));
+    }
+    match name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        true => Ok(()),
+        false => Err(format!(
+            "invalid template name `{name}`, use letters, digits, `-` and `_` only"
+        )),
+    }
+}
+
+// no file means no templates yet, and a broken row is skipped with a warning
+pub fn load_templates(path: &Path) -> ArentaResult<Vec<Template>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(ArentaError::io("failed to read templates", e)),
+    };
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut templates = vec![];
+    for (index, row) in reader.records().enumerate() {
+        match row
+            .map_err(|e| e.to_string())
+            .and_then(|row| Template::from_row(&row))
+        {
+            Ok(template) => templates.push(template),
+            Err(reason) => eprintln!("line {} of {} skipped: {reason}", index + 1, path.display()),
+        }
+    }
+    Ok(templates)
+}
+
+pub fn save_templates(path: &Path, templates: &[Template]) -> ArentaResult<()> {
+    let mut writer = WriterBuilder::new().from_writer(vec![]);
+    for template in templates {
+        writer
+            .write_record(template.to_row())
+            .map_err(|e| ArentaError::io("failed to save templates", e.into()))?;
+    }
+    let content = writer
+        .into_inner()
+        .map_err(|e| io::Error::new(e.error().kind(), e.error().to_string()))
+        .map_err(|e| ArentaError::io("failed to save templates", e))?;
+    fs::write(path, content).map_err(|e| ArentaError::io("failed to save templates", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn template() -> Template {
+        Template {
+            name: "alerts".to_string(),
+            description: "review on-call alerts, then triage".to_string(),
+            tags: vec!["oncall".to_string()],
+            priority: 2,
+            minutes: 30,
+        }
+    }
+
+    #[test]
+    fn test_template_from_task_and_back() {
+        let at =
+            |hour: u32, minute: u32| Local.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap();
+        let mut task =
+            Task::new_planned_task("review on-call alerts, then triage", at(9, 30), at(10, 0));
+        task.tags = vec!["oncall".to_string()];
+        task.priority = 2;
+        assert_eq!(Template::from_task("alerts", &task), Ok(template()));
+        assert!(Template::from_task("read", &Task::new_backlog_task("read")).is_err());
+
+        let task = template().instantiate(at(14, 0));
+        assert_eq!(task.planned_start, Some(at(14, 0)));
+        assert_eq!(task.planned_complete, Some(at(14, 30)));
+        assert_eq!(task.tags, vec!["oncall"]);
+        assert_eq!(task.priority, 2);
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("standup").is_ok());
+        assert!(validate_name("on-call_2").is_ok());
+        assert!(validate_name("now").is_err());
+        assert!(validate_name("14:00+45").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_templates() {
+        let path = std::env::temp_dir().join(format!("arenta-{}-templates", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(load_templates(&path).unwrap(), vec![]);
+        let untagged = Template {
+            name: "read".to_string(),
+            tags: vec![],
+            ..template()
+        };
+        save_templates(&path, &[template(), untagged.clone()]).unwrap();
+        assert_eq!(load_templates(&path).unwrap(), vec![template(), untagged]);
+        fs::write(&path, "broken,row\nread,read,,0,45\n").unwrap();
+        assert_eq!(load_templates(&path).unwrap().len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
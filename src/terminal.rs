@@ -0,0 +1,207 @@
+use crate::completion::CommandCompleter;
+use arenta::error::{ArentaError, ArentaResult, PromptError};
+use arenta::pomodoro::format_remaining;
+use arenta::prompt::{Prompter, SelectPrompt, TextPrompt};
+use arenta::task::Task;
+use arenta::timing::waiting;
+use chrono::NaiveDate;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use inquire::error::InquireError;
+use inquire::ui::{RenderConfig, Styled};
+use inquire::validator::Validation;
+use inquire::{Confirm, DateSelect, Select, Text};
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// answers prompts on the terminal with inquire, counting the time as waiting for the user
+pub struct TerminalPrompter;
+
+impl TerminalPrompter {
+    pub fn new() -> Self {
+        inquire::set_global_render_config(get_render_config());
+        TerminalPrompter
+    }
+}
+
+impl Prompter for TerminalPrompter {
+    fn command_line(
+        &mut self,
+        prompt: &str,
+        tasks: &[Task],
+        history: &[String],
+    ) -> ArentaResult<String> {
+        let text = Text::new(prompt).with_autocomplete(CommandCompleter::new(tasks, history));
+        waiting(|| text.prompt()).map_err(prompt_error)
+    }
+
+    fn text(&mut self, prompt: TextPrompt) -> ArentaResult<String> {
+        let mut text = Text::new(&prompt.message);
+        if let Some(help) = &prompt.help {
+            text = text.with_help_message(help);
+        }
+        if let Some(placeholder) = &prompt.placeholder {
+            text = text.with_placeholder(placeholder);
+        }
+        if let Some(validator) = prompt.validator {
+            let validator = Rc::new(validator);
+            text = text.with_validator(move |input: &str| {
+                Ok(match validator(input) {
+                    Ok(()) => Validation::Valid,
+                    Err(reason) => Validation::Invalid(reason.into()),
+                })
+            });
+        }
+        if let Some(formatter) = &prompt.formatter {
+            text = text.with_formatter(formatter.as_ref());
+        }
+        waiting(|| text.prompt()).map_err(prompt_error)
+    }
+
+    fn confirm(&mut self, message: &str, default: bool) -> ArentaResult<bool> {
+        let confirm = Confirm::new(message).with_default(default);
+        waiting(|| confirm.prompt()).map_err(prompt_error)
+    }
+
+    fn select(&mut self, prompt: SelectPrompt) -> ArentaResult<usize> {
+        let mut select = Select::new(&prompt.message, prompt.options)
+            .with_starting_cursor(prompt.starting_cursor);
+        select = match &prompt.help {
+            Some(help) => select.with_help_message(help),
+            None => select.without_help_message(),
+        };
+        waiting(|| select.raw_prompt())
+            .map(|picked| picked.index)
+            .map_err(prompt_error)
+    }
+
+    fn date(&mut self, message: &str) -> ArentaResult<NaiveDate> {
+        let date = DateSelect::new(message).with_help_message("select a date");
+        waiting(|| date.prompt()).map_err(prompt_error)
+    }
+
+    fn count_down(&mut self, duration: Duration, label: &str) -> bool {
+        waiting(|| count_down(duration, label))
+    }
+
+    fn pending_lines(&mut self) -> Vec<String> {
+        read_pending_lines()
+    }
+
+    fn width(&self) -> Option<usize> {
+        terminal::size().ok().map(|(width, _)| width as usize)
+    }
+}
+
+fn prompt_error(e: InquireError) -> ArentaError {
+    ArentaError::Prompt(match e {
+        InquireError::OperationCanceled => PromptError::Canceled,
+        InquireError::OperationInterrupted => PromptError::Interrupted,
+        InquireError::NotTTY => PromptError::NotTty,
+        e => PromptError::Failed(e.to_string()),
+    })
+}
+
+fn get_render_config() -> RenderConfig {
+    RenderConfig {
+        prompt_prefix: Styled::new(""),
+        answered_prompt_prefix: Styled::new(""),
+        ..RenderConfig::default()
+    }
+}
+
+// counts down on a line updated in place, returns whether it ran to the end rather than being
+// stopped by ctrl-c, `q` or esc; keys are only read in raw mode, so ctrl-c never reaches the
+// process as a signal while it's counting
+fn count_down(duration: Duration, label: &str) -> bool {
+    let end = Instant::now() + duration;
+    let is_raw = terminal::enable_raw_mode().is_ok();
+    let finished = loop {
+        let remaining = end.saturating_duration_since(Instant::now());
+        print!("\r{} left {label}", format_remaining(remaining));
+        let _ = io::stdout().flush();
+        if remaining.is_zero() {
+            break true;
+        }
+        // wakes up whenever the shown second changes
+        let tick = match remaining.subsec_millis() {
+            0 => Duration::from_secs(1),
+            millis => Duration::from_millis(millis as u64),
+        };
+        if !is_raw {
+            thread::sleep(tick);
+        } else if event::poll(tick).unwrap_or(false) {
+            if let Ok(Event::Key(key)) = event::read() {
+                if is_stop_key(&key) {
+                    break false;
+                }
+            }
+        }
+    };
+    if is_raw {
+        let _ = terminal::disable_raw_mode();
+    }
+    println!();
+    finished
+}
+
+fn is_stop_key(key: &KeyEvent) -> bool {
+    key.kind != KeyEventKind::Release
+        && match key.code {
+            KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+            KeyCode::Char('q') | KeyCode::Esc => true,
+            _ => false,
+        }
+}
+
+// lines typed ahead of the prompt, which is what a multi-line paste leaves behind
+// after the first line has been submitted
+fn read_pending_lines() -> Vec<String> {
+    let mut lines = vec![];
+    if terminal::enable_raw_mode().is_err() {
+        return lines;
+    }
+    let mut current = String::new();
+    while let Ok(true) = event::poll(Duration::from_millis(10)) {
+        match event::read() {
+            Ok(Event::Key(KeyEvent { code, kind, .. })) if kind != KeyEventKind::Release => {
+                match code {
+                    KeyCode::Char(c) => current.push(c),
+                    KeyCode::Tab => current.push(' '),
+                    KeyCode::Enter => lines.push(std::mem::take(&mut current)),
+                    _ => (),
+                }
+            }
+            Ok(_) => (),
+            Err(_) => break,
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    let _ = terminal::disable_raw_mode();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stop_key() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert!(is_stop_key(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(is_stop_key(&key(KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert!(is_stop_key(&key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(!is_stop_key(&key(KeyCode::Char('c'), KeyModifiers::NONE)));
+        assert!(!is_stop_key(&key(KeyCode::Enter, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn test_prompt_error() {
+        assert!(!prompt_error(InquireError::OperationCanceled).is_fatal());
+        assert!(prompt_error(InquireError::NotTTY).is_fatal());
+    }
+}
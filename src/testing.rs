@@ -0,0 +1,33 @@
+use crate::config::Config;
+use crate::error::ArentaResult;
+use crate::event::EventBus;
+use crate::manager::Manager;
+use crate::prompt::NoPrompter;
+use chrono::{DateTime, Local, TimeZone};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// an empty directory of its own for the test, under the temp dir and named after the process,
+// so that runs side by side don't step on each other; the test removes it when it's done
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("arenta-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// a manager of the data file with the default config, nothing listening and no one to prompt
+pub fn load_manager(file_path: &Path, is_read_only: bool) -> ArentaResult<Manager> {
+    Manager::load(
+        file_path.to_path_buf(),
+        Config::default(),
+        EventBus::new(),
+        is_read_only,
+        Box::new(NoPrompter),
+    )
+}
+
+// on the hour of a day in March 2024, which tests of fixed times share
+pub fn at(day: u32, hour: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap()
+}
@@ -1,10 +1,21 @@
 use std::{cmp::Ordering, vec};
 
-use crate::{manager::timeline_index_to_char, task::Task};
+use crate::clock;
+use crate::display::is_plain;
+use crate::{
+    manager::timeline_index_to_char,
+    task::{Task, TaskStatus},
+};
 use chrono::{DateTime, Local, NaiveDate, NaiveTime};
 use colored::{Color, Colorize};
 
-const UI_MAX_WIDTH: usize = 73;
+// the canvas consists of a left gutter holding task indices and continuation markers,
+// followed by the plotting area, whose column 0 corresponds to the start hour
+const GUTTER_WIDTH: usize = 2;
+// one column per 10 minutes, which is also the layout used if the terminal is too narrow
+const DEFAULT_COLUMNS_PER_HOUR: usize = 6;
+const MAX_COLUMNS_PER_HOUR: usize = 60;
+const LEGEND_DESCRIPTION_MAX_LEN: usize = 20;
 
 #[derive(Clone, Debug, Copy)]
 struct Pixel {
@@ -31,89 +42,398 @@ impl Pixel {
         self.content == ' '
     }
 
-    fn render(&self) {
-        if let Some(color) = self.color {
-            print!("{}", self.content.to_string().color(color));
-        } else {
-            print!("{}", self.content);
+    fn render(&self) -> String {
+        match (self.color, is_plain()) {
+            (Some(color), false) => self.content.to_string().color(color).to_string(),
+            _ => self.content.to_string(),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BarKind {
+    Planned,
+    Actual,
+    // the completion of a task never started, a single column
+    Untracked,
+}
+
+// the start and end columns, which may fall outside the plotting area
+type Period = (i64, i64);
+
+#[derive(Clone, Copy, Debug)]
+struct Bar {
+    row: usize,
+    start_pos: usize,
+    end_pos: usize,
+    index: char,
+    kind: BarKind,
+    color: Color,
+}
+
 pub struct Timeline<'a> {
     tasks: &'a Vec<(usize, &'a Task)>,
     canvas: Vec<Vec<Pixel>>,
+    bars: Vec<Bar>,
     date: NaiveDate,
+    start_hour: u32,
+    end_hour: u32,
+    columns_per_hour: usize,
+    // width of the plotting area, one column per tick with both ends included
+    width: usize,
+    // set by a fixed tick, otherwise the columns are fitted to the available width
+    is_resolution_fixed: bool,
+    // e.g. of the terminal, none to keep the default columns per hour
+    available_width: Option<usize>,
+    // every task gets a row for its planned period and one beneath for its actual period
+    is_paired: bool,
+    // taken when the timeline is made, so that the cursor and ongoing tasks agree
+    now: DateTime<Local>,
     pos_of_now: Option<i64>,
 }
 
 impl<'a> Timeline<'a> {
-    pub fn new(tasks: &'a Vec<(usize, &'a Task)>, date: NaiveDate) -> Self {
+    pub fn new(tasks: &'a Vec<(usize, &'a Task)>, date: NaiveDate, hours: (u32, u32)) -> Self {
         assert!(tasks.len() <= 26);
+        let (start_hour, end_hour) = hours;
+        assert!(start_hour < end_hour && end_hour <= 24);
         let mut timeline = Timeline {
             tasks,
             canvas: vec![],
+            bars: vec![],
             date,
+            start_hour,
+            end_hour,
+            columns_per_hour: 0,
+            width: 0,
+            is_resolution_fixed: false,
+            available_width: None,
+            is_paired: false,
+            now: clock::now(),
             pos_of_now: None,
         };
-        if Local::now().date_naive() == date {
-            timeline.pos_of_now = Some(timeline.get_pos_in_row(&Local::now()));
-        }
+        timeline.set_columns_per_hour(DEFAULT_COLUMNS_PER_HOUR);
         timeline
     }
 
+    fn set_columns_per_hour(&mut self, columns_per_hour: usize) {
+        self.columns_per_hour = columns_per_hour;
+        self.width = (self.end_hour - self.start_hour) as usize * columns_per_hour + 1;
+        self.pos_of_now =
+            (self.now.date_naive() == self.date).then(|| self.get_pos_in_row(&self.now));
+    }
+
+    // one column per `minutes`, which should divide an hour
+    pub fn set_tick_minutes(&mut self, minutes: u32) {
+        self.set_columns_per_hour(60 / minutes as usize);
+        self.is_resolution_fixed = true;
+    }
+
+    pub fn set_available_width(&mut self, width: usize) {
+        self.available_width = Some(width);
+    }
+
+    pub fn set_paired_rows(&mut self) {
+        self.is_paired = true;
+    }
+
+    // widens the plotting area to fill the width, but never narrower than the default
+    fn fit_width(&mut self, width: usize) {
+        let hours = (self.end_hour - self.start_hour) as usize;
+        let columns_per_hour = (width.saturating_sub(GUTTER_WIDTH + 1) / hours)
+            .clamp(DEFAULT_COLUMNS_PER_HOUR, MAX_COLUMNS_PER_HOUR);
+        self.set_columns_per_hour(columns_per_hour);
+    }
+
     pub fn draw(&mut self) {
+        self.render().iter().for_each(|line| println!("{line}"));
+    }
+
+    // the lines `draw` prints, for a frontend laying them out on its own
+    pub fn render(&mut self) -> Vec<String> {
+        if let (Some(width), false) = (self.available_width, self.is_resolution_fixed) {
+            self.fit_width(width);
+        }
+        self.layout();
+        self.populate_scale_line();
+        let overlaps = find_overlaps(&self.bars, self.width);
+        self.populate_overlaps(&overlaps);
+        self.populate_now_cursor();
+        let mut lines = vec![self
+            .date
+            .format("%F")
+            .to_string()
+            .bold()
+            .underline()
+            .to_string()];
+        lines.extend(
+            self.canvas
+                .iter()
+                .map(|row| row.iter().map(Pixel::render).collect::<String>()),
+        );
+        if !overlaps.is_empty() {
+            lines.push(format!(
+                "{} {}",
+                "conflicts:".red(),
+                self.describe_overlaps(&overlaps)
+            ));
+        }
+        let legend: Vec<String> = self
+            .legend()
+            .into_iter()
+            .map(|(entry, color)| match is_plain() {
+                true => entry,
+                false => entry.color(color).to_string(),
+            })
+            .collect();
+        if !legend.is_empty() {
+            lines.push(legend.join(" · "));
+        }
+        lines
+    }
+
+    // maps every letter drawn on the canvas to the task index and a truncated description,
+    // e.g. `a=3 write report`
+    fn legend(&self) -> Vec<(String, Color)> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .map(|(timeline_index, &(index, task))| {
+                (timeline_index_to_char(timeline_index), index, task)
+            })
+            .filter(|(letter, ..)| self.bars.iter().any(|bar| bar.index == *letter))
+            .map(|(letter, index, task)| {
+                let mut description: String = task
+                    .description
+                    .chars()
+                    .take(LEGEND_DESCRIPTION_MAX_LEN)
+                    .collect();
+                if task.description.chars().count() > LEGEND_DESCRIPTION_MAX_LEN {
+                    description = format!("{}…", description.trim_end());
+                }
+                (
+                    format!("{letter}={index} {description}"),
+                    task.color_of_status(),
+                )
+            })
+            .collect()
+    }
+
+    // if `redact` is set, descriptions of private tasks are hidden in the legend
+    pub fn render_svg(&mut self, redact: bool) -> String {
+        const CELL_WIDTH: usize = 10;
+        const ROW_HEIGHT: usize = 20;
+        const MARGIN: usize = 20;
+        const DESCRIPTION_MAX_LEN: usize = 40;
+
+        self.layout();
+        let rows = self.canvas.len();
+        let chart_top = MARGIN + 2 * ROW_HEIGHT;
+        let chart_bottom = chart_top + rows * ROW_HEIGHT;
+        let legend_top = chart_bottom + ROW_HEIGHT;
+        let width = self.width * CELL_WIDTH + 2 * MARGIN;
+        let height = legend_top + self.tasks.len() * ROW_HEIGHT + MARGIN;
+        let x_of = |pos: usize| MARGIN + pos * CELL_WIDTH;
+
+        let mut svg = String::new();
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             font-family=\"monospace\" font-size=\"12\">\n"
+        ));
+        svg.push_str(&format!(
+            "  <rect width=\"{width}\" height=\"{height}\" fill=\"#ffffff\"/>\n"
+        ));
+        svg.push_str(&format!(
+            "  <text x=\"{MARGIN}\" y=\"{}\" font-weight=\"bold\">{}</text>\n",
+            MARGIN + 12,
+            self.date.format("%F")
+        ));
+
+        for (hour, pos) in
+            (self.start_hour..=self.end_hour).zip((0..self.width).step_by(self.columns_per_hour))
+        {
+            let x = x_of(pos);
+            svg.push_str(&format!(
+                "  <text x=\"{x}\" y=\"{}\">{hour}</text>\n",
+                chart_top - 6
+            ));
+            svg.push_str(&format!(
+                "  <line x1=\"{x}\" y1=\"{chart_top}\" x2=\"{x}\" y2=\"{chart_bottom}\" \
+                 stroke=\"#cccccc\"/>\n"
+            ));
+        }
+
+        for bar in &self.bars {
+            let y = chart_top + bar.row * ROW_HEIGHT;
+            let fill = color_to_hex(bar.color);
+            let (bar_y, bar_height, opacity) = match bar.kind {
+                BarKind::Planned => (y + 7, 6, "0.5"),
+                BarKind::Actual | BarKind::Untracked => (y + 4, 12, "1"),
+            };
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{bar_y}\" width=\"{}\" height=\"{bar_height}\" \
+                 fill=\"{fill}\" fill-opacity=\"{opacity}\"/>\n",
+                x_of(bar.start_pos),
+                (bar.end_pos - bar.start_pos + 1) * CELL_WIDTH
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" fill=\"{fill}\">{}</text>\n",
+                x_of(bar.start_pos) - CELL_WIDTH,
+                y + 14,
+                bar.index
+            ));
+        }
+
+        if let Some(pos) = self.pos_of_now {
+            let x = x_of(pos.clamp(0, self.width as i64 - 1) as usize);
+            svg.push_str(&format!(
+                "  <line x1=\"{x}\" y1=\"{chart_top}\" x2=\"{x}\" y2=\"{chart_bottom}\" \
+                 stroke=\"#ff0000\"/>\n"
+            ));
+        }
+
+        self.tasks
+            .iter()
+            .enumerate()
+            .for_each(|(timeline_index, &(index, task))| {
+                let shared_description = task.shared_description(redact);
+                let mut description: String = shared_description
+                    .chars()
+                    .take(DESCRIPTION_MAX_LEN)
+                    .collect();
+                if shared_description.chars().count() > DESCRIPTION_MAX_LEN {
+                    description.push_str("...");
+                }
+                svg.push_str(&format!(
+                    "  <text x=\"{MARGIN}\" y=\"{}\" fill=\"{}\">{}({}) {}</text>\n",
+                    legend_top + timeline_index * ROW_HEIGHT + 12,
+                    color_to_hex(task.color_of_status()),
+                    index,
+                    timeline_index_to_char(timeline_index),
+                    escape_xml(&description)
+                ));
+            });
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn layout(&mut self) {
         self.tasks
             .iter()
             .enumerate()
             .for_each(|(timeline_index, &(_, task))| {
                 self.populate_task(task, timeline_index_to_char(timeline_index))
             });
-        self.populate_scale_line();
-        self.populate_now_cursor();
-        println!("{}", self.date.format("%F").to_string().bold().underline());
-        self.canvas.iter().for_each(|row| {
-            row.iter().for_each(|p| p.render());
-            println!();
-        });
+        self.populate_bridges();
+    }
+
+    // a dotted bridge from the end of the planned bar to the actual one starting later, on the
+    // row of the latter, to tell how much a task slipped; drawn once all tasks have found their
+    // rows, and only on empty cells, so that it never takes room from another task
+    fn populate_bridges(&mut self) {
+        let bridges: Vec<(usize, usize, usize)> = self
+            .bars
+            .iter()
+            .filter(|bar| bar.kind == BarKind::Actual)
+            .filter_map(|actual| {
+                let planned = self
+                    .bars
+                    .iter()
+                    .find(|bar| bar.kind == BarKind::Planned && bar.index == actual.index)?;
+                (planned.end_pos + 1 < actual.start_pos).then_some((
+                    actual.row,
+                    planned.end_pos + 1,
+                    actual.start_pos,
+                ))
+            })
+            .collect();
+        for (row, start_pos, end_pos) in bridges {
+            for pos in start_pos..end_pos {
+                if (pos - start_pos) % 3 < 2 {
+                    self.canvas[row][GUTTER_WIDTH + pos]
+                        .set_if_empty(Pixel::new('·', Some(Color::BrightBlack)));
+                }
+            }
+        }
     }
 
     fn populate_scale_line(&mut self) {
-        self.canvas.insert(
-            0,
-            "8     9     10    11    12    13    14    15    16    17    18    19    20"
-                .chars()
-                .map(|content| Pixel::new(content, None))
-                .collect(),
-        );
-        self.canvas.insert(
-            1,
-            "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|"
-                .chars()
-                .map(|content| Pixel::new(content, None))
-                .collect(),
-        );
-        self.canvas.insert(
-            self.canvas.len(),
-            "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|"
-                .chars()
-                .map(|content| Pixel::new(content, None))
-                .collect(),
-        );
-        self.canvas.insert(
-            self.canvas.len(),
-            "8     9     10    11    12    13    14    15    16    17    18    19    20"
+        let mut label_line = String::new();
+        let mut tick_line = String::new();
+        let label_step = self.label_step();
+        for (hour, pos) in
+            (self.start_hour..=self.end_hour).zip((0..self.width).step_by(self.columns_per_hour))
+        {
+            if (hour - self.start_hour).is_multiple_of(label_step) {
+                label_line.push_str(&" ".repeat(pos.saturating_sub(label_line.len())));
+                label_line.push_str(&hour.to_string());
+            }
+            tick_line.push('|');
+            if pos + 1 < self.width {
+                tick_line.push_str(&"-".repeat(self.columns_per_hour - 1));
+            }
+        }
+        let to_row = |line: &str| -> Vec<Pixel> {
+            " ".repeat(GUTTER_WIDTH)
                 .chars()
+                .chain(line.chars())
                 .map(|content| Pixel::new(content, None))
-                .collect(),
-        );
+                .collect()
+        };
+        self.canvas.insert(0, to_row(&label_line));
+        self.canvas.insert(1, to_row(&tick_line));
+        self.canvas.push(to_row(&tick_line));
+        self.canvas.push(to_row(&label_line));
+    }
+
+    // a row of `^` right under the scale, marking where planned periods overlap
+    fn populate_overlaps(&mut self, overlaps: &[Overlap]) {
+        if overlaps.is_empty() {
+            return;
+        }
+        let mut row = vec![Pixel::default(); GUTTER_WIDTH + self.width];
+        for (start_pos, end_pos, _) in overlaps {
+            row[GUTTER_WIDTH + start_pos..GUTTER_WIDTH + end_pos]
+                .fill(Pixel::new('^', Some(Color::Red)));
+        }
+        self.canvas.insert(2, row);
+    }
+
+    // e.g. `13:00–13:30 (a,d), 15:00–15:20 (b,c)`
+    fn describe_overlaps(&self, overlaps: &[Overlap]) -> String {
+        let time_of = |pos: usize| {
+            let minutes = self.start_hour as usize * 60 + pos * 60 / self.columns_per_hour;
+            format!("{:02}:{:02}", minutes / 60, minutes % 60)
+        };
+        overlaps
+            .iter()
+            .map(|(start_pos, end_pos, letters)| {
+                let letters: Vec<String> = letters.iter().map(char::to_string).collect();
+                format!(
+                    "{}–{} ({})",
+                    time_of(*start_pos),
+                    time_of(*end_pos),
+                    letters.join(",")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // every how many hours to label, so that labels of two digits never run into each other
+    // at a coarse resolution
+    fn label_step(&self) -> u32 {
+        [1, 2, 3, 4, 6, 12]
+            .into_iter()
+            .find(|step| *step as usize * self.columns_per_hour >= 3)
+            .unwrap_or(24)
     }
 
     fn populate_now_cursor(&mut self) {
         if self.pos_of_now.is_none() {
             return;
         }
-        let pos = self.pos_of_now.unwrap().clamp(0, UI_MAX_WIDTH as i64 - 1) as usize;
+        let pos = GUTTER_WIDTH + self.pos_of_now.unwrap().clamp(0, self.width as i64 - 1) as usize;
         let bottom = self.canvas.len() - 2;
         self.canvas[1][pos] = Pixel::new('v', Some(Color::Red));
         self.canvas[bottom][pos] = Pixel::new('^', Some(Color::Red));
@@ -126,74 +446,917 @@ impl<'a> Timeline<'a> {
         if task.is_deleted {
             return;
         }
-        if self.date_includes(&task.planned_start) || self.date_includes(&task.planned_complete) {
-            let start_pos = self.get_pos_in_row(&task.planned_start.unwrap());
-            let end_pos = self.get_pos_in_row(&task.planned_complete.unwrap());
-            self.populate_index_and_line(
-                start_pos,
-                end_pos,
-                index,
-                Pixel::new('-', Some(task.color_of_status())),
-            );
-        }
-        if self.date_includes(&task.actual_start) || self.date_includes(&task.actual_complete) {
-            let start_pos = self.get_pos_in_row(&task.actual_start.unwrap());
-            let end_pos = task
-                .actual_complete
-                .map_or(self.pos_of_now.unwrap_or(UI_MAX_WIDTH as i64 - 1), |dt| {
-                    self.get_pos_in_row(&dt)
-                });
-            self.populate_index_and_line(
-                start_pos,
-                end_pos,
-                index,
-                Pixel::new('=', Some(task.color_of_status())),
-            );
+        let (planned, actual) = self.periods_on_date(task);
+        let color = task.color_of_status();
+        let actual_kind = match task.is_untracked() {
+            true => BarKind::Untracked,
+            false => BarKind::Actual,
+        };
+        if !self.is_paired {
+            for (period, kind) in [(planned, BarKind::Planned), (actual, actual_kind)] {
+                if let Some((start_pos, end_pos)) = period {
+                    self.populate_index_and_line(start_pos, end_pos, index, kind, color, None);
+                }
+            }
+            return;
+        }
+        if planned.is_none() && actual.is_none() {
+            return;
+        }
+        // the index stays on the planned row, even if there's only an actual period
+        let planned_row = self.new_row();
+        let actual_row = self.new_row();
+        if let Some((start_pos, end_pos)) = planned {
+            let rows = Some((planned_row, Some(planned_row)));
+            self.populate_index_and_line(start_pos, end_pos, index, BarKind::Planned, color, rows);
+        }
+        if let Some((start_pos, end_pos)) = actual {
+            let index_row = planned.is_none().then_some(planned_row);
+            let rows = Some((actual_row, index_row));
+            self.populate_index_and_line(start_pos, end_pos, index, actual_kind, color, rows);
         }
     }
 
+    // the columns of the planned and the actual period which fall on the date, if any; a task
+    // completed without a start has its completion as the actual period
+    fn periods_on_date(&self, task: &Task) -> (Option<Period>, Option<Period>) {
+        let planned = (self.date_includes(&task.planned_start)
+            || self.date_includes(&task.planned_complete))
+        .then(|| {
+            (
+                self.get_pos_in_row(&task.planned_start.unwrap()),
+                self.get_pos_in_row(&task.planned_complete.unwrap()),
+            )
+        });
+        // the actual period is drawn on every day it covers, e.g. a task ongoing for days
+        let actual_end = task.actual_complete.or(task.paused_at());
+        let covers_date = task.actual_start.is_some_and(|start| {
+            start.date_naive() <= self.date
+                && actual_end.unwrap_or(self.now).date_naive() >= self.date
+        });
+        if task.is_untracked() {
+            let actual = self.date_includes(&task.actual_complete).then(|| {
+                let pos = self.get_pos_in_row(&task.actual_complete.unwrap());
+                (pos, pos)
+            });
+            return (planned, actual);
+        }
+        let actual = covers_date.then(|| {
+            (
+                self.get_pos_in_row(&task.actual_start.unwrap()),
+                actual_end.map_or(self.pos_of_now.unwrap_or(self.width as i64 - 1), |dt| {
+                    self.get_pos_in_row(&dt)
+                }),
+            )
+        });
+        (planned, actual)
+    }
+
     fn date_includes(&self, datetime: &Option<DateTime<Local>>) -> bool {
         datetime.is_some() && datetime.unwrap().date_naive() == self.date
     }
 
-    fn populate_index_and_line(&mut self, start_pos: i64, end_pos: i64, index: char, pixel: Pixel) {
-        let start_pos = start_pos.clamp(1, UI_MAX_WIDTH as i64 - 1) as usize;
-        let end_pos = end_pos.clamp(1, UI_MAX_WIDTH as i64 - 1) as usize;
-        let row_opt = self
-            .canvas
-            .iter()
-            .position(|row| can_put_in_row(row, start_pos, end_pos));
-        let row = row_opt.unwrap_or_else(|| self.new_row());
+    // `rows` are the row of the line and that of the index, if it's put at all, otherwise both
+    // go to the first row with room for them
+    fn populate_index_and_line(
+        &mut self,
+        start_pos: i64,
+        end_pos: i64,
+        index: char,
+        kind: BarKind,
+        color: Color,
+        rows: Option<(usize, Option<usize>)>,
+    ) {
+        let content = match kind {
+            BarKind::Planned => '-',
+            BarKind::Actual => '=',
+            BarKind::Untracked => 'x',
+        };
+        let pixel = Pixel::new(content, Some(color));
+        let starts_before = start_pos < 0;
+        let ends_after = end_pos >= self.width as i64 && end_pos >= start_pos;
+        let start_pos = start_pos.clamp(0, self.width as i64 - 1) as usize;
+        // an inverted range (e.g. an ongoing task whose actual start is later than now)
+        // collapses into a single pixel at the start position
+        let end_pos = (end_pos.clamp(0, self.width as i64 - 1) as usize).max(start_pos);
+        // the index sits right before the line, or in the gutter if the line starts at column 0
+        let index_col = if start_pos == 0 {
+            0
+        } else {
+            GUTTER_WIDTH + start_pos - 1
+        };
+        let (row, index_row) = rows.unwrap_or_else(|| {
+            let row_opt = self
+                .canvas
+                .iter()
+                .position(|row| can_put_in_row(row, index_col, GUTTER_WIDTH + end_pos));
+            let row = row_opt.unwrap_or_else(|| self.new_row());
+            (row, Some(row))
+        });
         self.put_in_row(row, start_pos, end_pos, pixel);
-        assert!(start_pos >= 1);
-        self.canvas[row][start_pos - 1] = Pixel::new(index, pixel.color);
+        if let Some(index_row) = index_row {
+            self.canvas[index_row][index_col] = Pixel::new(index, pixel.color);
+        }
+        if starts_before {
+            self.canvas[row][GUTTER_WIDTH - 1] = Pixel::new('<', pixel.color);
+        }
+        if ends_after {
+            self.canvas[row][GUTTER_WIDTH + end_pos] = Pixel::new('>', pixel.color);
+        }
+        self.bars.push(Bar {
+            row,
+            start_pos,
+            end_pos,
+            index,
+            kind,
+            color,
+        });
     }
 
     fn new_row(&mut self) -> usize {
-        self.canvas.push(vec![Pixel::default(); UI_MAX_WIDTH]);
+        self.canvas
+            .push(vec![Pixel::default(); GUTTER_WIDTH + self.width]);
         self.canvas.len() - 1
     }
 
     fn put_in_row(&mut self, row: usize, start_pos: usize, end_pos: usize, pixel: Pixel) {
-        self.canvas[row].splice(start_pos..=end_pos, vec![pixel; end_pos - start_pos + 1]);
+        self.canvas[row].splice(
+            GUTTER_WIDTH + start_pos..=GUTTER_WIDTH + end_pos,
+            vec![pixel; end_pos - start_pos + 1],
+        );
     }
 
+    // returns the column in the plotting area, which is negative or beyond the width
+    // if the datetime falls before or after the plotted range
     fn get_pos_in_row(&self, dt: &DateTime<Local>) -> i64 {
         match dt.date_naive().cmp(&self.date) {
-            Ordering::Less => 1,
-            Ordering::Greater => UI_MAX_WIDTH as i64 - 1,
+            Ordering::Less => -1,
+            Ordering::Greater => self.width as i64,
             Ordering::Equal => {
-                const START_HOUR: u32 = 8;
-                const TIMELINE_TICK: usize = 10;
-                let offset = dt.time() - NaiveTime::from_hms_opt(START_HOUR, 0, 0).unwrap();
-                offset.num_minutes() / TIMELINE_TICK as i64
+                let offset = dt.time() - NaiveTime::from_hms_opt(self.start_hour, 0, 0).unwrap();
+                offset.num_minutes() * self.columns_per_hour as i64 / 60
             }
         }
     }
 }
 
-fn can_put_in_row(row: &[Pixel], start_pos: usize, end_pos: usize) -> bool {
-    row[start_pos - 1..=end_pos]
+// columns `start..end` where planned bars of two or more tasks overlap, with their letters
+type Overlap = (usize, usize, Vec<char>);
+
+// a bar covers its columns but the last for this, so that a task planned right after another
+// doesn't overlap with it, and an actual bar never overlaps with the planned one of its task
+fn find_overlaps(bars: &[Bar], width: usize) -> Vec<Overlap> {
+    let mut letters_of_column: Vec<Vec<char>> = vec![vec![]; width];
+    for bar in bars.iter().filter(|bar| bar.kind == BarKind::Planned) {
+        for letters in &mut letters_of_column[bar.start_pos..bar.end_pos] {
+            if !letters.contains(&bar.index) {
+                letters.push(bar.index);
+            }
+        }
+    }
+    let mut overlaps: Vec<Overlap> = vec![];
+    for (pos, letters) in letters_of_column.into_iter().enumerate() {
+        if letters.len() < 2 {
+            continue;
+        }
+        match overlaps.last_mut() {
+            Some((_, end_pos, overlapping)) if *end_pos == pos => {
+                *end_pos = pos + 1;
+                overlapping.extend(letters);
+                overlapping.sort();
+                overlapping.dedup();
+            }
+            _ => {
+                let mut letters = letters;
+                letters.sort();
+                overlaps.push((pos, pos + 1, letters));
+            }
+        }
+    }
+    overlaps
+}
+
+// what the colors of bars stand for, each in its own color
+pub fn render_color_key() -> String {
+    [
+        ("red", TaskStatus::Overdue),
+        ("yellow", TaskStatus::Ongoing),
+        ("blue", TaskStatus::Paused),
+        ("green", TaskStatus::Complete),
+        ("cyan", TaskStatus::Planned),
+    ]
+    .iter()
+    .map(|(name, status)| {
+        format!("{name}={}", status.name())
+            .color(status.color())
+            .to_string()
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn can_put_in_row(row: &[Pixel], start_col: usize, end_col: usize) -> bool {
+    row[start_col..=end_col]
         .iter()
         .all(|pixel| pixel.is_empty())
 }
+
+fn color_to_hex(color: Color) -> String {
+    match color {
+        Color::TrueColor { r, g, b } => format!("#{r:02x}{g:02x}{b:02x}"),
+        Color::Red => "#ff0000".to_string(),
+        _ => "#000000".to_string(),
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_TIMELINE_HOURS;
+    use crate::display::strip_ansi;
+    use chrono::TimeZone;
+
+    fn task_at(
+        description: &str,
+        status: TaskStatus,
+        planned: Option<(&str, &str)>,
+        actual: Option<(&str, &str)>,
+    ) -> Task {
+        let dt = |s: &str| {
+            let naive = chrono::NaiveDateTime::parse_from_str(s, "%F %R").unwrap();
+            Local.from_local_datetime(&naive).unwrap()
+        };
+        Task {
+            description: description.to_string(),
+            planned_start: planned.map(|(s, _)| dt(s)),
+            planned_complete: planned.map(|(_, c)| dt(c)),
+            actual_start: actual.map(|(s, _)| dt(s)),
+            actual_complete: actual.map(|(_, c)| dt(c)),
+            status,
+            is_deleted: false,
+            tags: vec![],
+            postponed_count: 0,
+            priority: 0,
+            is_private: false,
+            work_intervals: vec![],
+            notes: String::new(),
+            created_at: clock::now(),
+            extras: vec![],
+        }
+    }
+
+    fn draw_plain(timeline: &mut Timeline) -> String {
+        timeline.layout();
+        timeline.populate_scale_line();
+        timeline.populate_now_cursor();
+        timeline
+            .canvas
+            .iter()
+            .map(|row| row.iter().map(|pixel| pixel.content).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn with_gutter(rows: &[&str]) -> String {
+        rows.iter()
+            .map(|row| format!("{}{row}", " ".repeat(GUTTER_WIDTH)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn bar(start_pos: usize, end_pos: usize, index: char, kind: BarKind) -> Bar {
+        Bar {
+            row: 0,
+            start_pos,
+            end_pos,
+            index,
+            kind,
+            color: Color::White,
+        }
+    }
+
+    #[test]
+    fn test_find_overlaps() {
+        use BarKind::{Actual, Planned};
+        // a: 0-6, b: 6-9 right after a, c: 4-8 over both, d: 12-15 with its actual bar over it
+        let bars = [
+            bar(0, 6, 'a', Planned),
+            bar(6, 9, 'b', Planned),
+            bar(4, 8, 'c', Planned),
+            bar(12, 15, 'd', Planned),
+            bar(13, 16, 'd', Actual),
+            bar(10, 14, 'e', Actual),
+        ];
+        assert_eq!(find_overlaps(&bars, 20), vec![(4, 8, vec!['a', 'b', 'c'])]);
+        assert_eq!(find_overlaps(&bars[..2], 20), vec![]);
+        let bars = [
+            bar(0, 3, 'b', Planned),
+            bar(2, 5, 'a', Planned),
+            bar(10, 12, 'c', Planned),
+            bar(10, 12, 'a', Planned),
+        ];
+        assert_eq!(
+            find_overlaps(&bars, 20),
+            vec![(2, 3, vec!['a', 'b']), (10, 12, vec!['a', 'c'])]
+        );
+    }
+
+    #[test]
+    fn test_draw_overlaps() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let review = task_at(
+            "review",
+            TaskStatus::Planned,
+            Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+            None,
+        );
+        let standup = task_at(
+            "standup",
+            TaskStatus::Complete,
+            Some(("2024-03-04 09:30", "2024-03-04 10:30")),
+            Some(("2024-03-04 09:30", "2024-03-04 10:00")),
+        );
+        let tasks = vec![(0, &review), (1, &standup)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.layout();
+        timeline.populate_scale_line();
+        let overlaps = find_overlaps(&timeline.bars, timeline.width);
+        timeline.populate_overlaps(&overlaps);
+        let rows: Vec<String> = timeline
+            .canvas
+            .iter()
+            .map(|row| row.iter().map(|pixel| pixel.content).collect())
+            .collect();
+        assert_eq!(
+            rows[2].trim_end(),
+            format!("{}^^^", " ".repeat(GUTTER_WIDTH + 9))
+        );
+        assert_eq!(timeline.describe_overlaps(&overlaps), "09:30–10:00 (a,b)");
+    }
+
+    #[test]
+    fn test_legend_maps_drawn_letters_to_indices() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let report = task_at(
+            "write the quarterly report",
+            TaskStatus::Ongoing,
+            None,
+            Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+        );
+        let tomorrow = task_at(
+            "tomorrow",
+            TaskStatus::Planned,
+            Some(("2024-03-05 09:00", "2024-03-05 10:00")),
+            None,
+        );
+        let one_on_one = task_at(
+            "1:1 with Sam",
+            TaskStatus::Planned,
+            Some(("2024-03-04 14:00", "2024-03-04 14:30")),
+            None,
+        );
+        let tasks = vec![(3, &report), (5, &tomorrow), (7, &one_on_one)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.layout();
+        assert_eq!(
+            timeline.legend(),
+            vec![
+                (
+                    "a=3 write the quarterly…".to_string(),
+                    TaskStatus::Ongoing.color()
+                ),
+                ("c=7 1:1 with Sam".to_string(), TaskStatus::Planned.color()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_lines() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let report = task_at(
+            "write report",
+            TaskStatus::Complete,
+            None,
+            Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+        );
+        let tasks = vec![(2, &report)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        let lines: Vec<String> = timeline
+            .render()
+            .iter()
+            .map(|line| strip_ansi(line))
+            .collect();
+        assert_eq!(lines[0], "2024-03-04");
+        assert_eq!(lines.len(), timeline.canvas.len() + 2);
+        assert_eq!(lines.last().unwrap(), "a=2 write report");
+    }
+
+    #[test]
+    fn test_draw_simple_cases() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let planned = task_at(
+            "planned",
+            TaskStatus::Planned,
+            Some(("2024-03-04 09:00", "2024-03-04 10:30")),
+            None,
+        );
+        let complete = task_at(
+            "complete",
+            TaskStatus::Complete,
+            Some(("2024-03-04 13:00", "2024-03-04 14:00")),
+            Some(("2024-03-04 13:20", "2024-03-04 15:10")),
+        );
+        let overlapping = task_at(
+            "overlapping",
+            TaskStatus::Ongoing,
+            None,
+            Some(("2024-03-04 10:00", "2024-03-04 11:00")),
+        );
+        let tasks = vec![(0, &planned), (1, &complete), (2, &overlapping)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+                "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|",
+                "     a----------             b-------                                    ",
+                "           c=======            b============                             ",
+                "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|",
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+            ])
+        );
+
+        let tasks = vec![(0, &planned)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.pos_of_now = Some(24);
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+                "|-----|-----|-----|-----v-----|-----|-----|-----|-----|-----|-----|-----|",
+                "     a----------        |                                                ",
+                "|-----|-----|-----|-----^-----|-----|-----|-----|-----|-----|-----|-----|",
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_draw_task_ongoing_for_days() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let mut ongoing = task_at(
+            "ongoing",
+            TaskStatus::Ongoing,
+            None,
+            Some(("2024-03-02 10:00", "2024-03-02 10:00")),
+        );
+        ongoing.actual_complete = None;
+        let tasks = vec![(0, &ongoing)];
+        // drawn from the left edge to now, as if it's today
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.pos_of_now = Some(24);
+        assert_eq!(
+            draw_plain(&mut timeline),
+            [
+                "  8     9     10    11    12    13    14    15    16    17    18    19    20",
+                "  |-----|-----|-----|-----v-----|-----|-----|-----|-----|-----|-----|-----|",
+                "a<=========================                                                ",
+                "  |-----|-----|-----|-----^-----|-----|-----|-----|-----|-----|-----|-----|",
+                "  8     9     10    11    12    13    14    15    16    17    18    19    20",
+            ]
+            .join("\n")
+        );
+
+        // nothing before it started
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        assert!(!draw_plain(&mut timeline).contains('='));
+    }
+
+    #[test]
+    fn test_draw_fitting_terminal_width() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let planned = task_at(
+            "planned",
+            TaskStatus::Planned,
+            Some(("2024-03-04 09:00", "2024-03-04 10:30")),
+            None,
+        );
+        let tasks = vec![(0, &planned)];
+        let mut timeline = Timeline::new(&tasks, date, (8, 12));
+        // 4 hours in 45 columns leave 10 columns per hour
+        timeline.fit_width(45);
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "8         9         10        11        12",
+                "|---------|---------|---------|---------|",
+                "         a----------------               ",
+                "|---------|---------|---------|---------|",
+                "8         9         10        11        12",
+            ])
+        );
+
+        // too narrow, falls back to the default layout
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.fit_width(40);
+        assert_eq!(timeline.width, 73);
+        timeline.fit_width(200);
+        assert_eq!(timeline.columns_per_hour, 16);
+        assert!(GUTTER_WIDTH + timeline.width <= 200);
+    }
+
+    #[test]
+    fn test_draw_gutter_and_continuation_markers() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let from_yesterday = task_at(
+            "from yesterday",
+            TaskStatus::Complete,
+            None,
+            Some(("2024-03-03 22:00", "2024-03-04 09:00")),
+        );
+        let at_start_hour = task_at(
+            "at start hour",
+            TaskStatus::Planned,
+            Some(("2024-03-04 08:00", "2024-03-04 08:30")),
+            None,
+        );
+        let to_tomorrow = task_at(
+            "to tomorrow",
+            TaskStatus::Complete,
+            None,
+            Some(("2024-03-04 19:00", "2024-03-05 01:00")),
+        );
+        let tasks = vec![(0, &from_yesterday), (1, &at_start_hour), (2, &to_tomorrow)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        let canvas = draw_plain(&mut timeline);
+        let rows: Vec<&str> = canvas.lines().collect();
+        assert_eq!(
+            rows[2],
+            format!("a<{}{}c{}>", "=".repeat(7), " ".repeat(58), "=".repeat(6))
+        );
+        assert_eq!(rows[3], format!("b {}{}", "-".repeat(4), " ".repeat(69)));
+    }
+
+    #[test]
+    fn test_draw_scale_line_for_custom_hours() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let evening = task_at(
+            "evening",
+            TaskStatus::Planned,
+            Some(("2024-03-04 21:00", "2024-03-04 22:00")),
+            None,
+        );
+        let tasks = vec![(0, &evening)];
+        let mut timeline = Timeline::new(&tasks, date, (18, 24));
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "18    19    20    21    22    23    24",
+                "|-----|-----|-----|-----|-----|-----|",
+                "                 a-------            ",
+                "|-----|-----|-----|-----|-----|-----|",
+                "18    19    20    21    22    23    24",
+            ])
+        );
+
+        let tasks = vec![];
+        let mut timeline = Timeline::new(&tasks, date, (0, 24));
+        let canvas = draw_plain(&mut timeline);
+        let rows: Vec<&str> = canvas.lines().collect();
+        assert_eq!(rows[0].find("12"), Some(GUTTER_WIDTH + 12 * 6));
+        assert_eq!(rows[0].find("24"), Some(GUTTER_WIDTH + 24 * 6));
+        assert_eq!(rows[1].len(), GUTTER_WIDTH + 24 * 6 + 1);
+    }
+
+    #[test]
+    fn test_draw_with_fixed_tick() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let task = task_at(
+            "planned",
+            TaskStatus::Planned,
+            Some(("2024-03-04 09:00", "2024-03-04 10:30")),
+            None,
+        );
+        let tasks = vec![(0, &task)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.set_tick_minutes(30);
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "8   10  12  14  16  18  20",
+                "|-|-|-|-|-|-|-|-|-|-|-|-|",
+                " a----                   ",
+                "|-|-|-|-|-|-|-|-|-|-|-|-|",
+                "8   10  12  14  16  18  20",
+            ])
+        );
+
+        let mut timeline = Timeline::new(&tasks, date, (8, 12));
+        timeline.set_tick_minutes(5);
+        assert_eq!(timeline.width, 4 * 12 + 1);
+        assert_eq!(timeline.get_pos_in_row(&task.planned_complete.unwrap()), 30);
+        timeline.set_tick_minutes(60);
+        assert_eq!(timeline.label_step(), 3);
+    }
+
+    #[test]
+    fn test_draw_paired_rows() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let complete = task_at(
+            "complete",
+            TaskStatus::Complete,
+            Some(("2024-03-04 09:00", "2024-03-04 10:30")),
+            Some(("2024-03-04 09:20", "2024-03-04 10:00")),
+        );
+        let tomorrow = task_at(
+            "tomorrow",
+            TaskStatus::Planned,
+            Some(("2024-03-05 09:00", "2024-03-05 10:00")),
+            None,
+        );
+        let unplanned = task_at(
+            "unplanned",
+            TaskStatus::Complete,
+            None,
+            Some(("2024-03-04 11:00", "2024-03-04 12:00")),
+        );
+        let tasks = vec![(0, &complete), (1, &tomorrow), (2, &unplanned)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.set_paired_rows();
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+                "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|",
+                "     a----------                                                         ",
+                "        =====                                                            ",
+                "                 c                                                       ",
+                "                  =======                                                ",
+                "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|",
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_draw_bridge_of_slipped_task() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let slipped = task_at(
+            "slipped",
+            TaskStatus::Complete,
+            Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+            Some(("2024-03-04 12:00", "2024-03-04 13:00")),
+        );
+        let between = task_at(
+            "between",
+            TaskStatus::Planned,
+            Some(("2024-03-04 10:40", "2024-03-04 11:00")),
+            None,
+        );
+        let tasks = vec![(0, &slipped), (1, &between)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        assert_eq!(
+            draw_plain(&mut timeline),
+            with_gutter(&[
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+                "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|",
+                "     a-------··b---·· ·a=======                                          ",
+                "|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|",
+                "8     9     10    11    12    13    14    15    16    17    18    19    20",
+            ])
+        );
+
+        let tasks = vec![(0, &slipped)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.set_paired_rows();
+        timeline.layout();
+        let rows: Vec<String> = timeline
+            .canvas
+            .iter()
+            .map(|row| row.iter().map(|pixel| pixel.content).collect())
+            .collect();
+        assert_eq!(
+            rows[1].trim_end(),
+            format!("{}·· ·· ·· ··=======", " ".repeat(GUTTER_WIDTH + 13))
+        );
+    }
+
+    #[test]
+    fn test_draw_periods_outside_hours() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let overnight = task_at(
+            "overnight",
+            TaskStatus::Complete,
+            None,
+            Some(("2024-03-03 23:00", "2024-03-04 07:30")),
+        );
+        let early = task_at(
+            "early",
+            TaskStatus::Complete,
+            Some(("2024-03-04 06:00", "2024-03-04 07:00")),
+            Some(("2024-03-04 07:00", "2024-03-04 09:00")),
+        );
+        let late = task_at(
+            "late",
+            TaskStatus::Planned,
+            Some(("2024-03-04 20:30", "2024-03-04 22:00")),
+            None,
+        );
+        let tasks = vec![(0, &overnight), (1, &early), (2, &late)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        let canvas = draw_plain(&mut timeline);
+        let rows: Vec<&str> = canvas.lines().collect();
+        assert_eq!(rows[2], format!("a<={}c>", " ".repeat(70)));
+        assert_eq!(rows[3], format!("b<-{}", " ".repeat(72)));
+        assert_eq!(rows[4], format!("b<{}{}", "=".repeat(7), " ".repeat(66)));
+    }
+
+    #[test]
+    fn test_draw_inverted_range_as_single_pixel() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let started_in_future = task_at(
+            "started in future",
+            TaskStatus::Ongoing,
+            None,
+            Some(("2024-03-04 15:00", "2024-03-04 15:00")),
+        );
+        let tasks = vec![(0, &started_in_future)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.populate_index_and_line(42, 12, 'a', BarKind::Actual, Color::Yellow, None);
+        timeline.populate_index_and_line(-5, -3, 'b', BarKind::Actual, Color::Yellow, None);
+        timeline.populate_index_and_line(80, 75, 'c', BarKind::Actual, Color::Yellow, None);
+        let rows: Vec<String> = timeline
+            .canvas
+            .iter()
+            .map(|row| row.iter().map(|pixel| pixel.content).collect())
+            .collect();
+        assert_eq!(
+            rows[0],
+            format!("b<={}a={}c=", " ".repeat(40), " ".repeat(28))
+        );
+    }
+
+    #[test]
+    fn test_draw_untracked_completion_as_marker() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let untracked = Task {
+            actual_start: None,
+            ..task_at(
+                "completed by hand",
+                TaskStatus::Complete,
+                Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+                Some(("2024-03-04 10:30", "2024-03-04 10:30")),
+            )
+        };
+        let tasks = vec![(0, &untracked)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        let rows: Vec<String> = draw_plain(&mut timeline)
+            .lines()
+            .map(|row| row.trim_end().to_string())
+            .collect();
+        assert_eq!(
+            rows[2],
+            format!("{}a------- ax", " ".repeat(GUTTER_WIDTH + 5))
+        );
+        assert_eq!(timeline.bars[1].kind, BarKind::Untracked);
+
+        let tasks = vec![(0, &untracked)];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.set_paired_rows();
+        let rows: Vec<String> = draw_plain(&mut timeline)
+            .lines()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            rows[3].trim_end(),
+            format!("{}x", " ".repeat(GUTTER_WIDTH + 15))
+        );
+    }
+
+    // the lines of a day with a task of every status, looked at 11:20
+    fn render_snapshot(paired: bool) -> String {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let report = task_at(
+            "write weekly report",
+            TaskStatus::Complete,
+            Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+            Some(("2024-03-04 09:10", "2024-03-04 10:20")),
+        );
+        let review = Task {
+            actual_complete: None,
+            ..task_at(
+                "review PRs",
+                TaskStatus::Ongoing,
+                None,
+                Some(("2024-03-04 10:30", "2024-03-04 10:30")),
+            )
+        };
+        let bank = task_at(
+            "call the bank",
+            TaskStatus::Overdue,
+            Some(("2024-03-04 10:00", "2024-03-04 11:00")),
+            None,
+        );
+        let one_on_one = task_at(
+            "1:1 with Sam",
+            TaskStatus::Planned,
+            Some(("2024-03-04 14:00", "2024-03-04 15:30")),
+            None,
+        );
+        let tasks = vec![(0, &report), (1, &review), (4, &bank), (7, &one_on_one)];
+        let _frozen = clock::freeze("2024-03-04 11:20");
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        timeline.set_available_width(100);
+        if paired {
+            timeline.set_paired_rows();
+        }
+        let lines: Vec<String> = timeline
+            .render()
+            .iter()
+            .map(|line| strip_ansi(line))
+            .collect();
+        lines.join("\n") + "\n"
+    }
+
+    #[test]
+    fn test_render_matches_snapshots() {
+        assert_eq!(
+            render_snapshot(false),
+            include_str!("testdata/timeline.txt")
+        );
+        assert_eq!(
+            render_snapshot(true),
+            include_str!("testdata/timeline_paired.txt")
+        );
+    }
+
+    #[test]
+    fn test_render_is_colored_like_the_canvas() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let tasks = vec![];
+        let mut timeline = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS);
+        let lines = timeline.render();
+        assert_eq!(lines.len(), timeline.canvas.len() + 1);
+        for (line, row) in lines[1..].iter().zip(&timeline.canvas) {
+            assert_eq!(line, &row.iter().map(Pixel::render).collect::<String>());
+        }
+    }
+
+    #[test]
+    fn test_render_svg_matches_golden_file() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let planned = task_at(
+            "write weekly report",
+            TaskStatus::Overdue,
+            Some(("2024-03-04 09:00", "2024-03-04 10:30")),
+            None,
+        );
+        let complete = task_at(
+            "review PR <arenta> & reply to comments on the timeline refactoring",
+            TaskStatus::Complete,
+            Some(("2024-03-04 13:00", "2024-03-04 14:00")),
+            Some(("2024-03-04 13:20", "2024-03-04 15:10")),
+        );
+        let tasks = vec![(0, &planned), (3, &complete)];
+        let svg = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS).render_svg(false);
+        assert_eq!(svg, include_str!("testdata/timeline.svg"));
+    }
+
+    #[test]
+    fn test_render_svg_redacts_private_tasks() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let mut private = task_at(
+            "call ACME Corp about renewal",
+            TaskStatus::Complete,
+            Some(("2024-03-04 09:00", "2024-03-04 10:00")),
+            Some(("2024-03-04 09:10", "2024-03-04 10:20")),
+        );
+        private.is_private = true;
+        let public = task_at(
+            "write weekly report",
+            TaskStatus::Overdue,
+            Some(("2024-03-04 11:00", "2024-03-04 12:00")),
+            None,
+        );
+        let tasks = vec![(0, &private), (1, &public)];
+        let redacted = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS).render_svg(true);
+        let plain = Timeline::new(&tasks, date, DEFAULT_TIMELINE_HOURS).render_svg(false);
+        assert!(!redacted.contains("ACME"));
+        assert!(redacted.contains("0(a) private task"));
+        assert!(redacted.contains("1(b) write weekly report"));
+        assert!(plain.contains("0(a) call ACME Corp about renewal"));
+        // timings are kept intact
+        assert_eq!(
+            redacted.matches("<rect").count(),
+            plain.matches("<rect").count()
+        );
+    }
+}
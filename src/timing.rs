@@ -0,0 +1,65 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    // total time spent waiting for the user at prompts
+    static WAITING: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+// runs something that waits for the user, e.g. a prompt or a countdown
+pub fn waiting<T>(prompt: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = prompt();
+    WAITING.with(|waiting| waiting.set(waiting.get() + start.elapsed()));
+    result
+}
+
+// measures wall time excluding the time spent waiting at prompts
+pub struct Stopwatch {
+    start: Instant,
+    waiting_before: Duration,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Stopwatch {
+            start: Instant::now(),
+            waiting_before: WAITING.with(Cell::get),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        let waited = WAITING.with(Cell::get) - self.waiting_before;
+        self.start.elapsed().saturating_sub(waited)
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_stopwatch_excludes_waiting() {
+        let stopwatch = Stopwatch::start();
+        thread::sleep(Duration::from_millis(20));
+        waiting(|| thread::sleep(Duration::from_millis(200)));
+        let elapsed = stopwatch.elapsed();
+        assert!(elapsed >= Duration::from_millis(20));
+        assert!(elapsed < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(42)), "42ms");
+        assert_eq!(format_duration(Duration::from_millis(1340)), "1.3s");
+    }
+}
@@ -0,0 +1,166 @@
+use crate::clock;
+use crate::task::{Task, PRIORITY_HIGH};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+
+// turns a line of todo.txt into a task: `x <date>` completes it at noon of the date,
+// `(A)` to `(Z)` set its priority, `due:<date>` plans it within the timeline hours of the
+// date, and `+project` or `@context` become tags; a line which cannot be made sense of is put
+// into backlog as a whole, together with the reason
+pub fn line_to_task(line: &str, hours: (u32, u32)) -> (Task, Option<String>) {
+    let line = line.trim();
+    match parse_line(line, hours) {
+        Ok(task) => (task, None),
+        Err(reason) => (Task::new_backlog_task(line), Some(reason)),
+    }
+}
+
+fn parse_line(line: &str, hours: (u32, u32)) -> Result<Task, String> {
+    let mut words = line.split_whitespace().peekable();
+    let mut actual_complete = None;
+    if words.next_if_eq(&"x").is_some() {
+        // the completion date is optional, in which case it's taken as completed just now
+        actual_complete = match words.peek().and_then(|word| parse_date(word).ok()) {
+            Some(date) => {
+                words.next();
+                Some(local_datetime(date, 12)?)
+            }
+            None => Some(clock::now()),
+        };
+    }
+    let mut priority = words
+        .peek()
+        .and_then(|word| parse_priority(word.strip_prefix('(')?.strip_suffix(')')?));
+    if priority.is_some() {
+        words.next();
+    }
+    // the creation date, which arenta doesn't keep
+    if words
+        .peek()
+        .and_then(|word| parse_date(word).ok())
+        .is_some()
+    {
+        words.next();
+    }
+    let mut description = vec![];
+    let mut tags = vec![];
+    let mut due = None;
+    for word in words {
+        if let Some(date) = word.strip_prefix("due:") {
+            due = Some(parse_date(date)?);
+        } else if let Some(pri) = word.strip_prefix("pri:") {
+            // where completed tasks keep their priority
+            priority =
+                Some(parse_priority(pri).ok_or_else(|| format!("invalid priority `{pri}`"))?);
+        } else if let Some(tag) = word
+            .strip_prefix('+')
+            .or_else(|| word.strip_prefix('@'))
+            .filter(|tag| !tag.is_empty())
+        {
+            tags.push(tag.to_string());
+        } else {
+            description.push(word);
+        }
+    }
+    if description.is_empty() {
+        return Err("no description".to_string());
+    }
+    let mut task = Task::new_backlog_task(&description.join(" "));
+    task.tags = tags;
+    task.priority = priority.unwrap_or(0);
+    if let Some(date) = due {
+        task.planned_start = Some(local_datetime(date, hours.0)?);
+        task.planned_complete = Some(local_datetime(date, hours.1)?);
+    }
+    task.actual_complete = actual_complete;
+    task.update_status();
+    Ok(task)
+}
+
+// `A` is high, `B` medium, and everything from `C` on is low
+fn parse_priority(letter: &str) -> Option<u8> {
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some('A'), None) => Some(PRIORITY_HIGH),
+        (Some('B'), None) => Some(2),
+        (Some('C'..='Z'), None) => Some(1),
+        _ => None,
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("invalid date `{date}`"))
+}
+
+// the hour may be 24, i.e. the midnight ending the date
+fn local_datetime(date: NaiveDate, hour: u32) -> Result<DateTime<Local>, String> {
+    let naive = date.and_hms_opt(0, 0, 0).unwrap() + Duration::hours(hour as i64);
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("{naive} doesn't exist in the local timezone"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskStatus;
+
+    const HOURS: (u32, u32) = (8, 20);
+
+    fn at(date: &str, hour: u32) -> Option<DateTime<Local>> {
+        Some(local_datetime(parse_date(date).unwrap(), hour).unwrap())
+    }
+
+    #[test]
+    fn test_line_to_task() {
+        let (task, malformed) = line_to_task("(A) 2024-03-01 call mom +family @phone", HOURS);
+        assert_eq!(malformed, None);
+        assert_eq!(task.description, "call mom");
+        assert_eq!(task.priority, PRIORITY_HIGH);
+        assert_eq!(task.tags, vec!["family", "phone"]);
+        assert_eq!(task.status, TaskStatus::Backlog);
+
+        let (task, _) = line_to_task("x 2024-03-04 2024-03-01 file taxes pri:B", HOURS);
+        assert_eq!(task.description, "file taxes");
+        assert_eq!(task.priority, 2);
+        assert_eq!(task.actual_complete, at("2024-03-04", 12));
+        assert_eq!(task.status, TaskStatus::Complete);
+
+        let (task, _) = line_to_task("x done without a date", HOURS);
+        assert_eq!(task.description, "done without a date");
+        assert_eq!(task.status, TaskStatus::Complete);
+
+        let (task, _) = line_to_task("(D) pay rent due:2024-03-05", HOURS);
+        assert_eq!(task.priority, 1);
+        assert_eq!(task.planned_start, at("2024-03-05", 8));
+        assert_eq!(task.planned_complete, at("2024-03-05", 20));
+        let (task, _) = line_to_task("late one due:2024-03-05", (20, 24));
+        assert_eq!(task.planned_complete, at("2024-03-06", 0));
+
+        // `x` and priorities only count at the start
+        let (task, _) = line_to_task("fix (A) in x mode", HOURS);
+        assert_eq!(task.description, "fix (A) in x mode");
+        assert_eq!(task.priority, 0);
+        assert_eq!(task.actual_complete, None);
+        let (task, _) = line_to_task("xylophone lesson", HOURS);
+        assert_eq!(task.description, "xylophone lesson");
+        assert_eq!(task.actual_complete, None);
+    }
+
+    #[test]
+    fn test_malformed_line_is_put_into_backlog() {
+        let (task, malformed) = line_to_task("  pay rent due:2024-13-05 ", HOURS);
+        assert_eq!(malformed, Some("invalid date `2024-13-05`".to_string()));
+        assert_eq!(task.description, "pay rent due:2024-13-05");
+        assert_eq!(task.status, TaskStatus::Backlog);
+        assert_eq!(task.planned_start, None);
+
+        let (task, malformed) = line_to_task("(A) +work", HOURS);
+        assert_eq!(malformed, Some("no description".to_string()));
+        assert_eq!(task.description, "(A) +work");
+        assert_eq!(task.tags, Vec::<String>::new());
+
+        let (_, malformed) = line_to_task("x task pri:a", HOURS);
+        assert_eq!(malformed, Some("invalid priority `a`".to_string()));
+    }
+}
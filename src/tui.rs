@@ -0,0 +1,303 @@
+use arenta::command::ListOption;
+use arenta::display::{display_width, strip_ansi};
+use arenta::manager::{DayView, Manager};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{
+    self, Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
+
+// often enough for the now cursor to move along the timeline
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const HINTS: &str = "j/k select · s start · c complete · d delete · / filter · q quit";
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Up,
+    Down,
+    Start,
+    Complete,
+    Delete,
+    EditFilter,
+    Quit,
+    None,
+}
+
+fn key_to_action(key: &KeyEvent) -> Action {
+    if key.kind == KeyEventKind::Release {
+        return Action::None;
+    }
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Char('k') | KeyCode::Up => Action::Up,
+        KeyCode::Char('j') | KeyCode::Down => Action::Down,
+        KeyCode::Char('s') => Action::Start,
+        KeyCode::Char('c') => Action::Complete,
+        KeyCode::Char('d') => Action::Delete,
+        KeyCode::Char('/') => Action::EditFilter,
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        _ => Action::None,
+    }
+}
+
+// the first of `height` rows to show so that the selected one stays in sight
+fn scroll_offset(selected: usize, height: usize, offset: usize) -> usize {
+    if height == 0 || selected < offset {
+        selected
+    } else if selected >= offset + height {
+        selected + 1 - height
+    } else {
+        offset
+    }
+}
+
+// cuts a line to the columns of the width, dropping its escape sequences if it's too wide to
+// keep them; a wide character which would straddle the edge is left out
+fn fit_line(line: &str, width: usize) -> String {
+    if display_width(line) <= width {
+        return line.to_string();
+    }
+    let mut columns = 0;
+    strip_ansi(line)
+        .chars()
+        .take_while(|c| {
+            columns += c.width().unwrap_or(0);
+            columns <= width
+        })
+        .collect()
+}
+
+struct View {
+    selected: usize,
+    offset: usize,
+    filter: String,
+    is_editing_filter: bool,
+    // what the latest action did, shown instead of the hints
+    message: Option<String>,
+}
+
+impl View {
+    fn list_option(&self) -> ListOption {
+        ListOption {
+            has_timeline: true,
+            text: Some(self.filter.clone()).filter(|filter| !filter.is_empty()),
+            ..ListOption::default()
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write, day: &DayView) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+        queue!(out, Clear(ClearType::All))?;
+        let mut lines: Vec<String> = day.timeline.clone();
+        lines.push("─".repeat(width));
+        // the task list takes what the timeline leaves, less the status line
+        let list_height = height.saturating_sub(lines.len() + 1);
+        self.offset = scroll_offset(self.selected, list_height, self.offset);
+        if day.rows.is_empty() {
+            lines.push("no tasks match".to_string());
+        }
+        for (position, (_, row)) in day.rows.iter().enumerate().skip(self.offset) {
+            if position >= self.offset + list_height {
+                break;
+            }
+            let marker = if position == self.selected {
+                "> "
+            } else {
+                "  "
+            };
+            lines.push(format!("{marker}{row}"));
+        }
+        for (y, line) in lines.iter().take(height.saturating_sub(1)).enumerate() {
+            queue!(out, MoveTo(0, y as u16), Print(fit_line(line, width)))?;
+        }
+        let status = match (&self.message, self.is_editing_filter) {
+            (_, true) => format!("/{}", self.filter),
+            (Some(message), false) => message.clone(),
+            (None, false) => format!("{} · {HINTS}", day.summary),
+        };
+        queue!(
+            out,
+            MoveTo(0, height.saturating_sub(1) as u16),
+            SetAttribute(Attribute::Reverse),
+            Print(fit_line(&status, width)),
+            SetAttribute(Attribute::Reset)
+        )?;
+        out.flush()
+    }
+
+    // returns whether the view is left
+    fn handle_key(&mut self, key: &KeyEvent, manager: &mut Manager, day: &DayView) -> bool {
+        if self.is_editing_filter {
+            self.edit_filter(key);
+            return false;
+        }
+        self.message = None;
+        let selected = day.rows.get(self.selected).map(|(index, _)| *index);
+        match (key_to_action(key), selected) {
+            (Action::Quit, _) => return true,
+            (Action::Up, _) => self.selected = self.selected.saturating_sub(1),
+            (Action::Down, _) if self.selected + 1 < day.rows.len() => self.selected += 1,
+            (Action::EditFilter, _) => self.is_editing_filter = true,
+            (Action::Start, Some(index)) => {
+                self.message = Some(report(
+                    manager.start(index),
+                    format!("task {index} started"),
+                ))
+            }
+            (Action::Complete, Some(index)) => {
+                self.message = Some(report(
+                    manager.complete(index),
+                    format!("task {index} completed"),
+                ))
+            }
+            (Action::Delete, Some(index)) => {
+                self.message = Some(report(
+                    manager.delete(index),
+                    format!("task {index} deleted, `restore {index}` to bring it back"),
+                ))
+            }
+            _ => (),
+        }
+        false
+    }
+
+    // the list follows the filter as it's typed; enter keeps it, esc drops it
+    fn edit_filter(&mut self, key: &KeyEvent) {
+        if key.kind == KeyEventKind::Release {
+            return;
+        }
+        match key.code {
+            KeyCode::Enter => self.is_editing_filter = false,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.is_editing_filter = false;
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        self.selected = 0;
+        self.offset = 0;
+    }
+}
+
+fn report<E: std::fmt::Display>(result: Result<(), E>, done: String) -> String {
+    match result {
+        Ok(()) => done,
+        Err(e) => e.to_string(),
+    }
+}
+
+// puts the terminal back however the view is left, panics included
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = Screen;
+        execute!(io::stdout(), EnterAlternateScreen, DisableLineWrap, Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, EnableLineWrap, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// the full-screen view of `tui`, the timeline of today on top and its tasks below, until
+// it's quit
+pub fn run(manager: &mut Manager) -> io::Result<()> {
+    let _screen = Screen::enter()?;
+    let mut out = io::stdout();
+    let mut view = View {
+        selected: 0,
+        offset: 0,
+        filter: String::new(),
+        is_editing_filter: false,
+        message: None,
+    };
+    loop {
+        let width = terminal::size()?.0 as usize;
+        let day = manager.day_view(&view.list_option(), Some(width));
+        view.selected = view.selected.min(day.rows.len().saturating_sub(1));
+        view.draw(&mut out, &day)?;
+        let refresh_at = Instant::now() + REFRESH_INTERVAL;
+        // waits for a key which changes something, or the next refresh
+        loop {
+            let timeout = refresh_at.saturating_duration_since(Instant::now());
+            if !event::poll(timeout)? {
+                break;
+            }
+            match event::read()? {
+                Event::Key(key) => {
+                    if view.handle_key(&key, manager, &day) {
+                        return Ok(());
+                    }
+                    break;
+                }
+                Event::Resize(..) => break,
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_to_action() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert_eq!(
+            key_to_action(&key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Action::Down
+        );
+        assert_eq!(
+            key_to_action(&key(KeyCode::Up, KeyModifiers::NONE)),
+            Action::Up
+        );
+        assert_eq!(
+            key_to_action(&key(KeyCode::Char('c'), KeyModifiers::NONE)),
+            Action::Complete
+        );
+        assert_eq!(
+            key_to_action(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+        assert_eq!(
+            key_to_action(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+            Action::None
+        );
+    }
+
+    #[test]
+    fn test_scroll_offset() {
+        assert_eq!(scroll_offset(0, 5, 0), 0);
+        assert_eq!(scroll_offset(4, 5, 0), 0);
+        assert_eq!(scroll_offset(5, 5, 0), 1);
+        assert_eq!(scroll_offset(2, 5, 3), 2);
+        assert_eq!(scroll_offset(3, 0, 0), 3);
+    }
+
+    #[test]
+    fn test_fit_line() {
+        assert_eq!(fit_line("\x1b[1mabc\x1b[0m", 5), "\x1b[1mabc\x1b[0m");
+        assert_eq!(fit_line("\x1b[1mabcdef\x1b[0m", 3), "abc");
+        assert_eq!(fit_line("写周报", 6), "写周报");
+        assert_eq!(fit_line("写周报", 5), "写周");
+        assert_eq!(fit_line("a写周报", 4), "a写");
+    }
+}
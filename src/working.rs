@@ -0,0 +1,212 @@
+use chrono::{DateTime, Datelike, Duration, Local, Weekday};
+use std::sync::OnceLock;
+
+use crate::display::humanize_duration;
+
+pub const DEFAULT_WORKING_DAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+// like hyperlinks, decided once on startup; durations are wall clock ones if not configured
+static WORKING_HOURS: OnceLock<WorkingHours> = OnceLock::new();
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkingHours {
+    // from the start hour to the end hour of every working day, e.g. 9 to 18
+    pub hours: (u32, u32),
+    pub days: Vec<Weekday>,
+}
+
+pub fn init(working_hours: Option<WorkingHours>) {
+    if let Some(working_hours) = working_hours {
+        let _ = WORKING_HOURS.set(working_hours);
+    }
+}
+
+pub fn working_hours() -> Option<&'static WorkingHours> {
+    WORKING_HOURS.get()
+}
+
+impl WorkingHours {
+    // only the time within working hours of working days counts, zero if `to` isn't later;
+    // reckoned in local time, so a change of daylight saving time is left out
+    pub fn duration_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Duration {
+        let (from, to) = (from.naive_local(), to.naive_local());
+        let mut duration = Duration::zero();
+        let mut date = from.date();
+        while date <= to.date() {
+            if self.days.contains(&date.weekday()) {
+                let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+                let start = from.max(midnight + Duration::hours(self.hours.0 as i64));
+                let end = to.min(midnight + Duration::hours(self.hours.1 as i64));
+                if end > start {
+                    duration = duration + (end - start);
+                }
+            }
+            date = date.succ_opt().unwrap();
+        }
+        duration
+    }
+
+    // like the former, but negative if `to` is earlier
+    pub fn signed_duration_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Duration {
+        match to < from {
+            true => -self.duration_between(to, from),
+            false => self.duration_between(from, to),
+        }
+    }
+}
+
+// e.g. `2 working hours` or `45 working minutes`
+pub fn humanize_working_duration(duration: Duration) -> String {
+    let humanized = humanize_duration(duration);
+    match humanized.split_once(' ') {
+        Some((count, unit)) => format!("{count} working {unit}"),
+        None => humanized,
+    }
+}
+
+// e.g. `mon-fri`, or `mon,wed,fri` if the days aren't consecutive
+pub fn format_days(days: &[Weekday]) -> String {
+    let name = |day: &Weekday| day.to_string().to_lowercase();
+    let consecutive = days.windows(2).all(|pair| pair[0].succ() == pair[1]);
+    match (days.first(), days.last()) {
+        (Some(first), Some(last)) if consecutive && days.len() > 2 => {
+            format!("{}-{}", name(first), name(last))
+        }
+        _ => days.iter().map(name).collect::<Vec<_>>().join(","),
+    }
+}
+
+// `mon-fri` or `mon,wed,fri`, and a range may wrap around the week, e.g. `sat-mon`
+pub fn parse_days(value: &str) -> Result<Vec<Weekday>, String> {
+    let parse_day = |day: &str| {
+        day.trim()
+            .parse::<Weekday>()
+            .map_err(|_| format!("invalid weekday `{}`", day.trim()))
+    };
+    let mut days = vec![];
+    for part in value.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (mut day, last) = (parse_day(first)?, parse_day(last)?);
+                days.push(day);
+                while day != last {
+                    day = day.succ();
+                    days.push(day);
+                }
+            }
+            None => days.push(parse_day(part)?),
+        }
+    }
+    days.dedup();
+    match days.is_empty() {
+        true => Err("expected at least one weekday".to_string()),
+        false => Ok(days),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn nine_to_six() -> WorkingHours {
+        WorkingHours {
+            hours: (9, 18),
+            days: DEFAULT_WORKING_DAYS.to_vec(),
+        }
+    }
+
+    // march 4th of 2024 is a monday
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 3, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_duration_within_a_day() {
+        let hours = nine_to_six();
+        let minutes = |from, to| hours.duration_between(from, to).num_minutes();
+        assert_eq!(minutes(at(4, 10, 0), at(4, 11, 30)), 90);
+        // starting before or ending after working hours
+        assert_eq!(minutes(at(4, 7, 0), at(4, 10, 0)), 60);
+        assert_eq!(minutes(at(4, 17, 0), at(4, 21, 0)), 60);
+        assert_eq!(minutes(at(4, 6, 0), at(4, 22, 0)), 9 * 60);
+        // entirely outside working hours
+        assert_eq!(minutes(at(4, 19, 0), at(4, 23, 0)), 0);
+        assert_eq!(minutes(at(4, 11, 0), at(4, 10, 0)), 0);
+    }
+
+    #[test]
+    fn test_duration_across_days_and_weekends() {
+        let hours = nine_to_six();
+        let minutes = |from, to| hours.duration_between(from, to).num_minutes();
+        // from a monday evening to the next morning
+        assert_eq!(minutes(at(4, 17, 0), at(5, 10, 0)), 120);
+        // from friday 16:00 to monday 9:00, as the weekend doesn't count
+        assert_eq!(minutes(at(8, 16, 0), at(11, 9, 0)), 120);
+        assert_eq!(minutes(at(8, 16, 0), at(11, 11, 0)), 240);
+        // within the weekend
+        assert_eq!(minutes(at(9, 10, 0), at(10, 17, 0)), 0);
+        // from a saturday to the next saturday
+        assert_eq!(minutes(at(9, 12, 0), at(16, 12, 0)), 5 * 9 * 60);
+        // two whole weeks
+        assert_eq!(minutes(at(4, 0, 0), at(18, 0, 0)), 10 * 9 * 60);
+    }
+
+    #[test]
+    fn test_duration_with_custom_days_and_hours() {
+        let hours = WorkingHours {
+            hours: (0, 24),
+            days: vec![Weekday::Sat, Weekday::Sun],
+        };
+        assert_eq!(
+            hours
+                .duration_between(at(8, 12, 0), at(11, 12, 0))
+                .num_hours(),
+            48
+        );
+        assert_eq!(
+            hours
+                .signed_duration_between(at(11, 12, 0), at(8, 12, 0))
+                .num_hours(),
+            -48
+        );
+    }
+
+    #[test]
+    fn test_humanize_working_duration() {
+        assert_eq!(
+            humanize_working_duration(Duration::minutes(120)),
+            "2 working hours"
+        );
+        assert_eq!(
+            humanize_working_duration(Duration::minutes(1)),
+            "1 working minute"
+        );
+    }
+
+    #[test]
+    fn test_parse_and_format_days() {
+        assert_eq!(parse_days("mon-fri"), Ok(DEFAULT_WORKING_DAYS.to_vec()));
+        assert_eq!(
+            parse_days("sat-mon"),
+            Ok(vec![Weekday::Sat, Weekday::Sun, Weekday::Mon])
+        );
+        assert_eq!(
+            parse_days("mon, wed,fri"),
+            Ok(vec![Weekday::Mon, Weekday::Wed, Weekday::Fri])
+        );
+        assert!(parse_days("mon-funday").is_err());
+        assert!(parse_days("").is_err());
+        assert_eq!(format_days(&DEFAULT_WORKING_DAYS), "mon-fri");
+        assert_eq!(format_days(&[Weekday::Mon, Weekday::Wed]), "mon,wed");
+        assert_eq!(format_days(&[Weekday::Sat, Weekday::Sun]), "sat,sun");
+    }
+}
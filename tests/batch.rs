@@ -0,0 +1,129 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+fn temp_home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("arenta-batch-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("default.csv"), "Read papers,,,,,false,,0,0\n").unwrap();
+    dir
+}
+
+fn arenta_batch(home: &Path, commands: &str, args: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_arenta"))
+        .env("HOME", home)
+        .env("ARENTA_HOME", home)
+        .args(["--plain", "--batch", "-"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(commands.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn listed(home: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_arenta"))
+        .env("HOME", home)
+        .env("ARENTA_HOME", home)
+        .args(["--plain", "-e", "ls b"])
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_batch_runs_every_command() {
+    let home = temp_home("all");
+    let output = arenta_batch(
+        &home,
+        "# morning routine\nn standup @09:30+15\n\nb Write report\ne 2 tags work\n",
+        &[],
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("arenta> n standup @09:30+15\ntask 1 created"));
+    assert!(stdout.contains("arenta> e 2 tags work\ntask 2 edited"));
+    assert!(!stdout.contains("morning routine"));
+    let tasks = listed(&home);
+    assert!(tasks.contains("standup"));
+    assert!(tasks.contains("Write report  +work"));
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_batch_stops_at_first_failure() {
+    let home = temp_home("stop");
+    let output = arenta_batch(&home, "b First\nbogus\nb Second\n", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 2: invalid command"));
+    let tasks = listed(&home);
+    assert!(tasks.contains("First"));
+    assert!(!tasks.contains("Second"));
+
+    let output = arenta_batch(&home, "e 0\nb Second\n", &["--keep-going"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("line 1: `edit` asks for input, which batch mode cannot answer"));
+    assert!(listed(&home).contains("Second"));
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_batch_reorders_tasks() {
+    let home = temp_home("reorder");
+    let output = arenta_batch(&home, "b A\nb B\nb C\nmv 3 1\nswap 0 3\nmv 9 0\n", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("task 3 moved to 1\n  1. C\n  2. A\n  3. B\n"));
+    assert!(stdout.contains("tasks 0 and 3 swapped\n  0. B\n  3. Read papers\n"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 6: index out of range"));
+    let tasks = listed(&home);
+    let position = |description: &str| tasks.find(description).unwrap();
+    assert!(position("B") < position("C"));
+    assert!(position("C") < position("A"));
+    assert!(position("A") < position("Read papers"));
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_keep_going_without_batch_fails() {
+    let home = temp_home("keep-going");
+    let output = Command::new(env!("CARGO_BIN_EXE_arenta"))
+        .env("HOME", &home)
+        .env("ARENTA_HOME", &home)
+        .args(["--keep-going", "-e", "ls"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("arenta - "));
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_sqlite_storage_without_sqlite3_fails() {
+    let home = temp_home("no-sqlite3");
+    let config_dir = home.join(".config/arenta");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join("config.toml"), "storage = \"sqlite\"\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_arenta"))
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("ARENTA_HOME", &home)
+        .env("PATH", "")
+        .args(["--plain", "-e", "ls"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("sqlite3 is not installed"));
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,100 @@
+// a stale lock is only told apart from a live one by the pid on unix, and the lock holders
+// are stood in for by `sleep` and `true`
+#![cfg(unix)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output};
+
+// holds the lock file on behalf of a live process, just like an interactive session does
+struct FakeLockHolder {
+    child: Child,
+    lock_file: PathBuf,
+}
+
+impl FakeLockHolder {
+    fn spawn(home: &Path) -> Self {
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let lock_file = home.join("default.lock");
+        fs::write(&lock_file, child.id().to_string()).unwrap();
+        FakeLockHolder { child, lock_file }
+    }
+}
+
+impl Drop for FakeLockHolder {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.lock_file);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn temp_home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("arenta-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("default.csv"), "Read papers,,,,,false,,0,0\n").unwrap();
+    dir
+}
+
+fn arenta(home: &Path, command: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_arenta"))
+        .env("HOME", home)
+        .env("ARENTA_HOME", home)
+        .args(["-e", command])
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_read_only_command_bypasses_lock() {
+    let home = temp_home("read");
+    let holder = FakeLockHolder::spawn(&home);
+
+    let output = arenta(&home, "ls b");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Read papers"));
+    assert_eq!(
+        fs::read_to_string(home.join("default.lock")).unwrap(),
+        holder.child.id().to_string()
+    );
+
+    drop(holder);
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_mutating_command_fails_while_locked() {
+    let home = temp_home("write");
+    let holder = FakeLockHolder::spawn(&home);
+
+    let output = arenta(&home, "b Write report");
+    assert_eq!(output.status.code(), Some(75));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!("pid {}", holder.child.id())));
+    assert!(!String::from_utf8_lossy(&arenta(&home, "ls b").stdout).contains("Write report"));
+
+    drop(holder);
+    let output = arenta(&home, "b Write report");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&arenta(&home, "ls b").stdout).contains("Write report"));
+    assert!(!home.join("default.lock").exists());
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_stale_lock_is_reclaimed() {
+    let home = temp_home("stale");
+    let mut exited = Command::new("true").spawn().unwrap();
+    exited.wait().unwrap();
+    fs::write(home.join("default.lock"), exited.id().to_string()).unwrap();
+
+    let output = arenta(&home, "b Write report");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "stale lock file left behind by process {}",
+        exited.id()
+    )));
+    assert!(!home.join("default.lock").exists());
+    fs::remove_dir_all(&home).unwrap();
+}